@@ -4,7 +4,7 @@ use util::direction::DirectionFlags;
 
 use interfaces::scheduling::{LevelLoadState, LevelSystemSet};
 use physics::{
-    collision::{Aabb, BlockPhysics, CollidingDirections},
+    collision::{Aabb, BlockPhysics, CollidingDirections, CompoundCollider},
     movement::Velocity,
     PhysicsBundle,
 };
@@ -17,6 +17,8 @@ use world::{
 
 const HALF_SIDE: f32 = 0.45;
 const MAX_PLANAR_VELOCITY: f32 = 0.25;
+//furthest an attached block is moved to find room when the spot it would land in is taken
+const MAX_RELOCATE_DIST: i32 = 2;
 
 pub struct BlockActorPlugin;
 
@@ -37,6 +39,8 @@ pub struct SpawnFallingBlockEvent {
     pub position: Vec3,
     pub initial_velocity: Vec3,
     pub falling_block: FallingBlock,
+    //other blocks that fall as one body with this one, offset from its position
+    pub attached: Vec<(BlockCoord, Entity)>,
 }
 
 #[derive(Event)]
@@ -53,36 +57,83 @@ pub struct FallingBlock {
     pub impact_direcitons: DirectionFlags,
 }
 
+//blocks attached to a FallingBlock, placed at the same offsets when it lands
+#[derive(Component, Clone, Default)]
+pub struct FallingStructure {
+    pub attached: Vec<(BlockCoord, Entity)>,
+}
+
 fn falling_block_spawner(
     mut reader: EventReader<SpawnFallingBlockEvent>,
     mut commands: Commands,
     mesh_query: Query<(&BlockMesh, Option<&BlockPhysics>)>,
     materials: Res<ChunkMaterial>,
 ) {
-    const COLLIDER_SQUISH_FACTOR: f32 = 0.9; //squish the collider a bit so the collider can fall down 1x1 tunnels
+    const COLLIDER_SQUISH: f32 = 0.1; //squish the collider a bit so the collider can fall down 1x1 tunnels
+    let material = |block_mesh: &BlockMesh| {
+        MeshMaterial3d(if block_mesh.use_transparent_shader {
+            materials.transparent_material.clone().unwrap()
+        } else {
+            materials.opaque_material.clone().unwrap()
+        })
+    };
     for event in reader.read() {
-        if let Ok((block_mesh, opt_physics)) = mesh_query.get(event.falling_block.block) {
-            if let Some(collider) = Aabb::from_block(opt_physics.unwrap_or(&BlockPhysics::Solid)) {
-                if let Some(mesh) = block_mesh.single_mesh.clone() {
-                    commands.spawn((
-                        StateScoped(LevelLoadState::Loaded),
-                        PhysicsBundle {
-                            velocity: Velocity(event.initial_velocity),
-                            collider: collider.scale(Vec3::ONE * COLLIDER_SQUISH_FACTOR),
-                            ..default()
-                        },
-                        Transform::from_translation(event.position),
-                        Mesh3d(mesh.0),
-                        MeshMaterial3d(if block_mesh.use_transparent_shader {
-                            materials.transparent_material.clone().unwrap()
-                        } else {
-                            materials.opaque_material.clone().unwrap()
-                        }),
-                        event.falling_block,
-                    ));
-                }
+        //one box per block in the structure, so shapes that aren't boxes don't land on empty air
+        let parts: Vec<Aabb> =
+            std::iter::once((BlockCoord::new(0, 0, 0), event.falling_block.block))
+                .chain(event.attached.iter().copied())
+                .filter_map(|(offset, block)| {
+                    let (_, physics) = mesh_query.get(block).ok()?;
+                    Aabb::from_block(physics.unwrap_or(&BlockPhysics::Solid)).map(|aabb| {
+                        aabb.add_offset(offset.to_vec3())
+                            .grow(Vec3::splat(-COLLIDER_SQUISH))
+                    })
+                })
+                .collect();
+        //the bounding box is still used to find nearby blocks and for everything but terrain collision
+        let Some(collider) = parts.iter().copied().reduce(|a, b| {
+            let min = a.min().min(b.min());
+            Aabb::new(a.max().max(b.max()) - min, min)
+        }) else {
+            continue;
+        };
+        let mut ec = commands.spawn((
+            StateScoped(LevelLoadState::Loaded),
+            PhysicsBundle {
+                velocity: Velocity(event.initial_velocity),
+                collider,
+                ..default()
+            },
+            Transform::from_translation(event.position),
+            Visibility::default(),
+            event.falling_block,
+            FallingStructure {
+                attached: event.attached.clone(),
+            },
+        ));
+        if parts.len() > 1 {
+            ec.insert(CompoundCollider(parts));
+        }
+        if let Ok((block_mesh, _)) = mesh_query.get(event.falling_block.block) {
+            if let Some(mesh) = block_mesh.single_mesh.clone() {
+                ec.insert((Mesh3d(mesh.0), material(block_mesh)));
             }
         }
+        ec.with_children(|parent| {
+            for (offset, block) in event.attached.iter() {
+                let Ok((block_mesh, _)) = mesh_query.get(*block) else {
+                    continue;
+                };
+                let Some(mesh) = block_mesh.single_mesh.clone() else {
+                    continue;
+                };
+                parent.spawn((
+                    Transform::from_translation(offset.to_vec3()),
+                    Mesh3d(mesh.0),
+                    material(block_mesh),
+                ));
+            }
+        });
     }
 }
 
@@ -121,6 +172,7 @@ fn on_block_landed(
     mut reader: EventReader<LandedFallingBlockEvent>,
     level: Res<Level>,
     id_query: Query<&BlockId>,
+    structure_query: Query<&FallingStructure>,
     mut commands: Commands,
     mut update_writer: EventWriter<ChunkUpdatedEvent>,
 ) {
@@ -128,12 +180,26 @@ fn on_block_landed(
         let mut exists = false;
         if let Some(mut ec) = commands.get_entity(event.faller) {
             exists = true;
-            ec.despawn();
+            ec.despawn_recursive();
         }
         if exists && event.falling_block.place_on_landing {
-            level.set_block_entity(
-                event.position,
-                BlockType::Filled(event.falling_block.block),
+            let mut placed = vec![(event.position, BlockType::Filled(event.falling_block.block))];
+            let attached = structure_query
+                .get(event.faller)
+                .map(|structure| structure.attached.as_slice())
+                .unwrap_or_default();
+            //attached blocks whose spot is taken go in the nearest free one instead
+            for (offset, block) in attached.iter() {
+                match nearest_empty(&level, event.position + *offset, &placed) {
+                    Some(coord) => placed.push((coord, BlockType::Filled(*block))),
+                    None => warn!(
+                        "no room to place falling block near {:?}",
+                        event.position + *offset
+                    ),
+                }
+            }
+            level.batch_set_block_entities(
+                placed.into_iter(),
                 &id_query,
                 &mut update_writer,
                 &mut commands,
@@ -141,3 +207,33 @@ fn on_block_landed(
         }
     }
 }
+
+//closest empty position to coord that isn't already taken, preferring higher positions at the same distance
+fn nearest_empty(
+    level: &Level,
+    coord: BlockCoord,
+    taken: &[(BlockCoord, BlockType)],
+) -> Option<BlockCoord> {
+    let r = MAX_RELOCATE_DIST;
+    let mut offsets = Vec::with_capacity((2 * r as usize + 1).pow(3));
+    for x in -r..=r {
+        for y in -r..=r {
+            for z in -r..=r {
+                offsets.push(BlockCoord::new(x, y, z));
+            }
+        }
+    }
+    offsets.sort_by_key(|offset| {
+        (
+            offset.x * offset.x + offset.y * offset.y + offset.z * offset.z,
+            -offset.y,
+        )
+    });
+    offsets
+        .into_iter()
+        .map(|offset| coord + offset)
+        .find(|candidate| {
+            !taken.iter().any(|(placed, _)| placed == candidate)
+                && matches!(level.get_block(*candidate), Some(BlockType::Empty))
+        })
+}
@@ -0,0 +1,284 @@
+use std::{f32::consts::PI, sync::Arc};
+
+use bevy::{prelude::*, reflect::TypePath};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use engine::actors::Combatant;
+use interfaces::{
+    components::Id,
+    scheduling::{LevelLoadState, LevelSystemSet},
+};
+use world::{
+    block::{BlockCoord, BlockId, BlockName, BlockResources},
+    events::{ChunkUpdatedEvent, ExplosionEvent},
+    level::Level,
+};
+
+use crate::spawning::{DefaultSpawnArgs, SpawnActorEvent};
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BossPhaseChangedEvent>()
+            .init_resource::<BossRng>()
+            .add_systems(OnEnter(LevelLoadState::Loading), seed_boss_rng)
+            .add_systems(
+                FixedUpdate,
+                (update_boss_phases, do_phase_actions)
+                    .chain()
+                    .in_set(LevelSystemSet::PostTick),
+            )
+            .register_type::<BossPhases>()
+            .register_type::<BossPhase>()
+            .register_type::<BossMinionSpawn>()
+            .register_type::<BossTerrainEffect>();
+    }
+}
+
+//marks the root combatant of a boss. the boss health bar looks for this
+#[derive(Component, Clone)]
+pub struct Boss {
+    pub display_name: String,
+}
+
+//loaded from the boss's scene, see BossPhases::from_scene
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component, FromWorld)]
+pub struct BossPhases {
+    //sorted by descending health_fraction, the first phase is active on spawn and its actions happen then
+    phases: Vec<BossPhase>,
+    #[reflect(ignore)]
+    current: usize,
+}
+
+impl Default for BossPhases {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl BossPhases {
+    pub fn new(mut phases: Vec<BossPhase>) -> Self {
+        phases.sort_by(|a, b| b.health_fraction.total_cmp(&a.health_fraction));
+        Self { phases, current: 0 }
+    }
+    //the first BossPhases component in the scene, None if the scene doesn't have one
+    pub fn from_scene(scene: &DynamicScene) -> Option<Self> {
+        scene
+            .entities
+            .iter()
+            .flat_map(|entity| entity.components.iter())
+            .filter(|component| {
+                component
+                    .get_represented_type_info()
+                    .is_some_and(|info| info.type_path() == Self::type_path())
+            })
+            .find_map(|component| Self::from_reflect(component.as_ref()))
+            .map(|loaded| Self::new(loaded.phases))
+    }
+    pub fn current(&self) -> usize {
+        self.current
+    }
+    pub fn current_phase(&self) -> Option<&BossPhase> {
+        self.phases.get(self.current)
+    }
+    pub fn get(&self, phase: usize) -> Option<&BossPhase> {
+        self.phases.get(phase)
+    }
+    pub fn len(&self) -> usize {
+        self.phases.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.phases.is_empty()
+    }
+}
+
+#[derive(Clone, Default, Reflect)]
+pub struct BossPhase {
+    pub name: String,
+    //the phase starts once the boss's health drops to this fraction of its max
+    pub health_fraction: f32,
+    pub minions: Vec<BossMinionSpawn>,
+    pub terrain: Vec<BossTerrainEffect>,
+}
+
+#[derive(Clone, Reflect)]
+pub struct BossMinionSpawn {
+    pub actor: String,
+    pub count: u32,
+    //minions are spread out on a circle around the boss
+    pub radius: f32,
+}
+
+#[derive(Clone, Reflect)]
+pub enum BossTerrainEffect {
+    //blows a hole in the terrain around the boss
+    Crater {
+        radius: f32,
+    },
+    //raises a ring of blocks around the boss, centered on the ground below it
+    Wall {
+        radius: f32,
+        height: i32,
+        block: BlockName,
+    },
+}
+
+//sent when a boss enters a new phase (not sent for the starting phase)
+//bosses listen to this to swap out their behaviors
+#[derive(Event, Clone, Copy)]
+pub struct BossPhaseChangedEvent {
+    pub boss: Entity,
+    pub phase: usize,
+}
+
+//minion placement goes through this, seeded from the level like LootRng
+#[derive(Resource)]
+pub struct BossRng(pub StdRng);
+
+impl BossRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for BossRng {
+    fn default() -> Self {
+        Self::seeded(0)
+    }
+}
+
+fn seed_boss_rng(level: Res<Level>, mut commands: Commands) {
+    commands.insert_resource(BossRng::seeded(level.seed ^ 0xB055_B055_B055_B055));
+}
+
+fn update_boss_phases(
+    mut query: Query<(Entity, &mut BossPhases, &Combatant)>,
+    mut writer: EventWriter<BossPhaseChangedEvent>,
+) {
+    for (boss, mut phases, combatant) in query.iter_mut() {
+        let Combatant::Root { health, .. } = combatant else {
+            continue;
+        };
+        if health.max <= 0.0 {
+            continue;
+        }
+        let fraction = health.current / health.max;
+        //big hits can skip past a phase, so go through each one we crossed so their actions still happen
+        while let Some(next) = phases.phases.get(phases.current + 1)
+            && fraction <= next.health_fraction
+        {
+            phases.current += 1;
+            info!(
+                "boss {:?} entered phase {} ({})",
+                boss, phases.current, phases.phases[phases.current].name
+            );
+            writer.send(BossPhaseChangedEvent {
+                boss,
+                phase: phases.current,
+            });
+        }
+    }
+}
+
+fn do_phase_actions(
+    mut reader: EventReader<BossPhaseChangedEvent>,
+    spawned_query: Query<Entity, Added<BossPhases>>,
+    query: Query<(&BossPhases, &GlobalTransform)>,
+    mut rng: ResMut<BossRng>,
+    level: Res<Level>,
+    block_resources: Res<BlockResources>,
+    id_query: Query<&BlockId>,
+    mut spawn_writer: EventWriter<SpawnActorEvent>,
+    mut explosion_writer: EventWriter<ExplosionEvent>,
+    mut update_writer: EventWriter<ChunkUpdatedEvent>,
+    mut commands: Commands,
+) {
+    //there's no changed event for the starting phase, so its actions happen when the boss spawns
+    let started = spawned_query.iter().map(|boss| (boss, 0));
+    let changed = reader.read().map(|event| (event.boss, event.phase));
+    for (boss, phase) in started.chain(changed) {
+        let Ok((phases, gtf)) = query.get(boss) else {
+            continue;
+        };
+        let Some(phase) = phases.get(phase) else {
+            continue;
+        };
+        let origin = gtf.translation();
+        for minion in phase.minions.iter() {
+            let actor = Arc::new(minion.actor.clone());
+            let angle_offset = rng.0.gen_range(0.0..2.0 * PI);
+            for i in 0..minion.count {
+                let angle = angle_offset + i as f32 * 2.0 * PI / minion.count as f32;
+                let offset = minion.radius * Vec3::new(angle.cos(), 0.0, angle.sin());
+                spawn_writer.send(SpawnActorEvent {
+                    name: actor.clone(),
+                    args: DefaultSpawnArgs {
+                        transform: Transform::from_translation(origin + offset),
+                    },
+                });
+            }
+        }
+        for effect in phase.terrain.iter() {
+            match effect {
+                BossTerrainEffect::Crater { radius } => {
+                    explosion_writer.send(ExplosionEvent {
+                        radius: *radius,
+                        origin: BlockCoord::from(origin),
+                    });
+                }
+                BossTerrainEffect::Wall {
+                    radius,
+                    height,
+                    block,
+                } => {
+                    let id = block_resources.registry.get_id(block);
+                    if id == BlockId(Id::Empty) {
+                        continue;
+                    }
+                    let ground = find_ground(BlockCoord::from(origin), &level);
+                    let size = radius.ceil() as i32;
+                    let mut changes = Vec::new();
+                    for x in -size..=size {
+                        for z in -size..=size {
+                            //only the outer shell of the circle
+                            let dist = ((x * x + z * z) as f32).sqrt();
+                            if (dist - radius).abs() > 0.5 {
+                                continue;
+                            }
+                            for y in 0..*height {
+                                let coord = ground + BlockCoord::new(x, y, z);
+                                if level.get_block_entity(coord).is_none() {
+                                    changes.push((coord, id));
+                                }
+                            }
+                        }
+                    }
+                    level.batch_set_block(
+                        changes.into_iter(),
+                        &block_resources.registry,
+                        &id_query,
+                        &mut update_writer,
+                        &mut commands,
+                    );
+                }
+            }
+        }
+    }
+}
+
+//first empty block above solid ground, searching down from origin
+fn find_ground(origin: BlockCoord, level: &Level) -> BlockCoord {
+    const MAX_SEARCH: i32 = 64;
+    for dy in 0..MAX_SEARCH {
+        let coord = origin - BlockCoord::new(0, dy, 0);
+        if level
+            .get_block_entity(coord - BlockCoord::new(0, 1, 0))
+            .is_some()
+        {
+            return coord;
+        }
+    }
+    origin
+}
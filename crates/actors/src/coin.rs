@@ -1,14 +1,10 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
-use engine::{all_teams_function, all_teams_system};
 use interfaces::scheduling::LevelLoadState;
 use physics::{collision::Aabb, PhysicsBundle};
 
-use engine::actors::{
-    projectile::{Projectile, ProjectileBundle},
-    team::*,
-};
+use engine::actors::projectile::{Projectile, ProjectileBundle};
 
 use crate::spawning::{BuildProjectileRegistry, DefaultSpawnArgs, ProjectileSpawnArgs};
 
@@ -25,20 +21,20 @@ pub struct CoinPlugin;
 impl Plugin for CoinPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, load_resources)
-            .add_systems(Update, all_teams_system!(spawn_coin));
-        all_teams_function!(app, add_event, SpawnCoinEvent);
-        all_teams_function!(app, add_projectile, SpawnCoinEvent, "coin".to_string());
+            .add_systems(Update, spawn_coin)
+            .add_event::<SpawnCoinEvent>()
+            .add_projectile::<SpawnCoinEvent>("coin".to_string());
     }
 }
 
 #[derive(Event)]
-pub struct SpawnCoinEvent<T: Team> {
-    pub projectile_args: ProjectileSpawnArgs<T>,
+pub struct SpawnCoinEvent {
+    pub projectile_args: ProjectileSpawnArgs,
     pub default_args: DefaultSpawnArgs,
 }
 
-impl<T: Team> From<(DefaultSpawnArgs, ProjectileSpawnArgs<T>)> for SpawnCoinEvent<T> {
-    fn from(value: (DefaultSpawnArgs, ProjectileSpawnArgs<T>)) -> Self {
+impl From<(DefaultSpawnArgs, ProjectileSpawnArgs)> for SpawnCoinEvent {
+    fn from(value: (DefaultSpawnArgs, ProjectileSpawnArgs)) -> Self {
         Self {
             projectile_args: value.1,
             default_args: value.0,
@@ -52,10 +48,10 @@ pub fn load_resources(mut commands: Commands, assets: Res<AssetServer>) {
     });
 }
 
-pub fn spawn_coin<T: Team>(
+pub fn spawn_coin(
     mut commands: Commands,
     res: Res<CoinResources>,
-    mut spawn_requests: EventReader<SpawnCoinEvent<T>>,
+    mut spawn_requests: EventReader<SpawnCoinEvent>,
     time: Res<Time>,
 ) {
     const LIFETIME: Duration = Duration::from_secs(10);
@@ -84,7 +80,8 @@ pub fn spawn_coin<T: Team>(
                 damage: projectile_args.damage,
                 hit_behavior: engine::actors::projectile::ProjecileHitBehavior::Despawn,
                 on_hit: None,
-            }),
+            })
+            .with_behaviors(projectile_args.behaviors),
             //no UninitializedActor b/c we don't have to do any setup
         ));
     }
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use big_brain::{prelude::Highest, scorers::FixedScore, thinker::Thinker};
 use engine::{
     actors::{
-        ai::FlyToCurrentTargetAction, team::EnemyTeam, AggroPlayer, AggroTargets, Combatant,
+        ai::FlyToCurrentTargetAction, team::TeamId, AggroPlayer, AggroTargets, Combatant,
         CombatantBundle, Defense, Health, MoveSpeed,
     },
     controllers::ControllableBundle,
@@ -10,6 +10,7 @@ use engine::{
 use interfaces::scheduling::LevelSystemSet;
 use physics::{
     collision::{Aabb, IgnoreTerrainCollision},
+    entity_collision::CollisionLayers,
     movement::{GravityMult, Mass},
     PhysicsBundle,
 };
@@ -98,17 +99,19 @@ fn spawn_eye_balloon(
                 mass: Mass(0.5),
                 ..default()
             },
+            CollisionLayers::ENEMY,
             ControllableBundle {
                 mode: engine::controllers::MovementMode::Flying,
                 move_speed: MoveSpeed::new(0.1, 0.1, 0.05),
                 ..default()
             },
             AggroPlayer::default(),
-            CombatantBundle::<EnemyTeam> {
+            CombatantBundle {
                 combatant: Combatant::Root {
                     health: Health::new(10.),
                     defense: Defense::new(0.),
                 },
+                team: TeamId::ENEMY,
                 ..default()
             },
             AggroTargets::default(),
@@ -1,12 +1,17 @@
+#![feature(let_chains)]
+
 pub mod block_actors;
+pub mod boss;
 pub mod coin;
 mod eye_balloon;
 pub mod skeleton_pirate;
 pub mod slither_spine;
 pub mod spawning;
 pub mod spike_ball;
+#[cfg(test)]
+mod test;
 mod util;
-mod wisp;
+pub mod wisp;
 
 use bevy::prelude::*;
 
@@ -24,6 +29,7 @@ impl Plugin for ActorsPlugin {
             eye_balloon::EyeBalloonPlugin,
             util::ActorUtilPlugin,
             block_actors::BlockActorPlugin,
+            boss::BossPlugin,
         ));
     }
 }
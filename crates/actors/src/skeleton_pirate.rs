@@ -5,7 +5,7 @@ use engine::{
     actors::{
         ai::{scorers::AggroScorer, AttackAction, WalkToCurrentTargetAction},
         damage::KillOnSunrise,
-        team::EnemyTeam,
+        team::TeamId,
         world_anchor::WorldAnchor,
         ActorName, ActorResources, AggroPlayer, AggroTargets, Combatant, CombatantBundle, Damage,
         DefaultAnimation, Jump, MoveSpeed, UninitializedActor,
@@ -13,7 +13,12 @@ use engine::{
     controllers::{ControllableBundle, JumpBundle},
 };
 use interfaces::scheduling::LevelLoadState;
-use physics::{collision::Aabb, movement::Velocity, PhysicsBundle, GRAVITY};
+use physics::{
+    collision::{Aabb, StepUp},
+    entity_collision::CollisionLayers,
+    movement::Velocity,
+    PhysicsBundle, GRAVITY,
+};
 use util::{physics::aim_projectile_straight_fallback, plugin::SmoothLookTo, SendEventCommand};
 
 use crate::spawning::{DefaultSpawnArgs, ProjectileSpawnArgs};
@@ -92,14 +97,17 @@ pub fn spawn_skeleton_pirate(
             spawn.location,
             Name::new("SkeletonPirate"),
             (
-                CombatantBundle::<EnemyTeam> {
+                CombatantBundle {
                     combatant: Combatant::new(10.0, 0.0),
+                    team: TeamId::ENEMY,
                     ..default()
                 },
                 PhysicsBundle {
                     collider: Aabb::new(Vec3::new(0.8, 1.6, 0.8), Vec3::new(-0.4, 0., -0.4)),
                     ..default()
                 },
+                CollisionLayers::ENEMY,
+                StepUp::default(),
                 ControllableBundle {
                     move_speed: MoveSpeed::new(0.5, 0.5, 0.10),
                     ..default()
@@ -202,28 +210,32 @@ fn attack(
             &GlobalTransform,
             &AggroTargets,
             &Velocity,
+            &TeamId,
             Option<&mut DefaultAnimation>,
         ),
         With<SkeletonPirate>,
     >,
     aggro_query: Query<(&GlobalTransform, Option<&Velocity>)>,
-    mut spawn_coin: EventWriter<SpawnCoinEvent<EnemyTeam>>,
+    mut spawn_coin: EventWriter<SpawnCoinEvent>,
     time: Res<Time>,
 ) {
     const COIN_OFFSET: Vec3 = Vec3::new(0.0, 2.0, 0.0);
     const THROW_IMPULSE: f32 = 0.5;
-    let combat = CombatantBundle::default();
     let damage = Damage::new(1.0);
     for (&Actor(actor), mut state) in action_query.iter_mut() {
         match *state {
             ActionState::Requested => {
                 *state = ActionState::Executing;
-                if let Ok((_, _, _, Some(mut anim))) = skele_query.get_mut(actor) {
+                if let Ok((_, _, _, _, Some(mut anim))) = skele_query.get_mut(actor) {
                     anim.reset();
                 }
             }
             ActionState::Executing => {
-                if let Ok((tf, targets, v, anim_opt)) = skele_query.get_mut(actor) {
+                if let Ok((tf, targets, v, team, anim_opt)) = skele_query.get_mut(actor) {
+                    let combat = CombatantBundle {
+                        team: *team,
+                        ..default()
+                    };
                     if let Some((target, target_v_opt)) = targets
                         .current_target()
                         .and_then(|t| aggro_query.get(t).ok())
@@ -233,7 +245,7 @@ fn attack(
                             Some(mut anim) => {
                                 anim.tick(time.delta_secs());
                                 if anim.just_acted() {
-                                    spawn_coin.send(SpawnCoinEvent::<EnemyTeam> {
+                                    spawn_coin.send(SpawnCoinEvent {
                                         default_args: DefaultSpawnArgs {
                                             transform: Transform::from_translation(spawn_point),
                                         },
@@ -259,7 +271,7 @@ fn attack(
                             }
                             None => {
                                 //no anim so gogogogogogogogogogogogo
-                                spawn_coin.send(SpawnCoinEvent::<EnemyTeam> {
+                                spawn_coin.send(SpawnCoinEvent {
                                     default_args: DefaultSpawnArgs {
                                         transform: Transform::from_translation(spawn_point),
                                     },
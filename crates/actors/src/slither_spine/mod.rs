@@ -1,12 +1,15 @@
 use core::f32;
 use std::f32::consts::PI;
 
-use super::spawning::*;
+use super::{
+    boss::{Boss, BossPhaseChangedEvent, BossPhases},
+    spawning::*,
+};
 use bevy::prelude::*;
 use engine::{
     actors::{
-        team::EnemyTeam, AggroPlayer, AggroTargets, Combatant, CombatantBundle, ContactDamage,
-        Damage, MoveSpeed,
+        team::TeamId, AggroPlayer, AggroTargets, Combatant, CombatantBundle, ContactDamage, Damage,
+        MoveSpeed,
     },
     controllers::{ControllableBundle, TickMovement},
     items::{
@@ -18,6 +21,8 @@ use engine::{
 use interfaces::scheduling::{LevelLoadState, LevelSystemSet, PhysicsLevelSet};
 use physics::{
     collision::{Aabb, CollidingDirections, IgnoreTerrainCollision, TerrainQueryPoint},
+    constraint::{Constraint, ConstraintAnchor},
+    entity_collision::CollisionLayers,
     movement::{Drag, GravityMult, LookInMovementDirection, Velocity},
     PhysicsBundle,
 };
@@ -30,13 +35,20 @@ impl Plugin for SlitherSpinePlugin {
         app.add_systems(Startup, load_resources)
             .add_systems(
                 FixedUpdate,
-                (spawn_handler, update_segments)
+                (
+                    spawn_handler,
+                    boss_spawn_handler,
+                    orient_segments,
+                    change_boss_behavior,
+                )
                     .chain()
                     .in_set(LevelSystemSet::PostTick),
             )
             .add_systems(FixedUpdate, move_head.in_set(PhysicsLevelSet::Main))
             .add_event::<SpawnSlitherSpineEvent>()
-            .add_actor::<SpawnSlitherSpineEvent>("slither_spine".to_string());
+            .add_event::<SpawnSlitherSpineBossEvent>()
+            .add_actor::<SpawnSlitherSpineEvent>("slither_spine".to_string())
+            .add_actor::<SpawnSlitherSpineBossEvent>("slither_spine_boss".to_string());
     }
 }
 
@@ -68,18 +80,47 @@ impl From<DefaultSpawnArgs> for SpawnSlitherSpineEvent {
     }
 }
 
+#[derive(Event)]
+pub struct SpawnSlitherSpineBossEvent {
+    default: DefaultSpawnArgs,
+}
+
+impl From<DefaultSpawnArgs> for SpawnSlitherSpineBossEvent {
+    fn from(value: DefaultSpawnArgs) -> Self {
+        Self { default: value }
+    }
+}
+
 #[derive(Resource)]
 struct SlitherSpineResources {
     spine_scene: Handle<Scene>,
     head_scene: Handle<Scene>,
+    //the boss's phases
+    boss_scene: Handle<DynamicScene>,
 }
 
+//most a segment can turn relative to the one in front of it
+const MAX_SEGMENT_BEND: f32 = PI / 2.0;
+
 #[derive(Component)]
 struct SlitherSpineSegment {
-    target_dist: f32,
     parent: Entity,
 }
 
+//how the boss moves and hits in each phase, indexed by phase
+#[derive(Component)]
+struct SlitherSpineBossBehavior {
+    phases: Vec<SlitherSpinePhaseBehavior>,
+}
+
+#[derive(Clone, Copy)]
+struct SlitherSpinePhaseBehavior {
+    accel: f32,
+    max_speed: f32,
+    exit_ground_speed: f32,
+    contact_damage: f32,
+}
+
 #[derive(Component)]
 struct SlitherSpineHead {
     in_ground_gravity_mult: f32,
@@ -92,36 +133,24 @@ fn load_resources(mut commands: Commands, assets: Res<AssetServer>) {
     commands.insert_resource(SlitherSpineResources {
         spine_scene: assets.load("actors/slither_spine/spine_segment.glb#Scene0"),
         head_scene: assets.load("actors/slither_spine/spine_head.glb#Scene0"),
+        boss_scene: assets.load("actors/slither_spine/slither_spine_boss.scn.ron"),
     });
 }
 
-fn update_segments(
-    mut set: ParamSet<(
-        (
-            Query<(Entity, &Transform, &SlitherSpineSegment)>,
-            Query<&Transform>,
-        ),
-        Query<&mut Transform>,
-    )>,
+//segments are pulled along by their constraints, this only turns them to face the one in front
+fn orient_segments(
+    mut tf_query: Query<&mut Transform>,
+    segment_query: Query<(Entity, &SlitherSpineSegment)>,
+    mut targets: Local<Vec<(Entity, Transform)>>,
 ) {
-    let mut new_translations = Vec::with_capacity(set.p0().0.iter().len());
-    {
-        let (segments, attachments) = set.p0();
-        for (entity, tf, segment) in segments.iter() {
-            if let Ok(attach_tf) = attachments.get(segment.parent) {
-                let delta = attach_tf.translation - tf.translation;
-                new_translations.push((
-                    entity,
-                    delta - delta.normalize_or_zero() * segment.target_dist,
-                    *attach_tf,
-                ));
-            }
-        }
-    }
-    let mut tf_query = set.p1();
-    for (entity, dx, target) in new_translations.into_iter() {
-        if let Ok(mut tf) = tf_query.get_mut(entity) {
-            tf.translation += dx;
+    targets.clear();
+    targets.extend(
+        segment_query
+            .iter()
+            .filter_map(|(entity, segment)| Some((entity, *tf_query.get(segment.parent).ok()?))),
+    );
+    for (entity, target) in targets.iter() {
+        if let Ok(mut tf) = tf_query.get_mut(*entity) {
             tf.look_at(target.translation, target.up());
         }
     }
@@ -133,40 +162,141 @@ fn spawn_handler(
     mut events: EventReader<SpawnSlitherSpineEvent>,
 ) {
     for spawn_event in events.read() {
-        let segment_length = spawn_event.segment_offset.length();
-        let mut prev: Option<(Entity, Entity)> = None;
-        for i in 0..spawn_event.segment_count {
-            let offset = i as f32 * spawn_event.segment_offset;
-            prev = Some(if let Some((prev_segment, head)) = prev {
-                (
-                    spawn_segement(
-                        &mut commands,
-                        resources.spine_scene.clone(),
-                        spawn_event
-                            .default
-                            .transform
-                            .with_translation(spawn_event.default.transform.translation + offset),
-                        SlitherSpineSegment {
-                            target_dist: segment_length,
-                            parent: prev_segment,
-                        },
-                        head,
-                    ),
-                    head,
-                )
-            } else {
-                let head = spawn_head(
-                    &mut commands,
-                    resources.head_scene.clone(),
+        spawn_slither_spine(&mut commands, &resources, spawn_event);
+    }
+}
+
+fn boss_spawn_handler(
+    mut commands: Commands,
+    resources: Res<SlitherSpineResources>,
+    scenes: Res<Assets<DynamicScene>>,
+    mut events: EventReader<SpawnSlitherSpineBossEvent>,
+) {
+    const SCALE: f32 = 2.0;
+    for spawn_event in events.read() {
+        let phases = scenes
+            .get(&resources.boss_scene)
+            .and_then(BossPhases::from_scene)
+            .unwrap_or_else(|| {
+                warn!("slither spine boss phases aren't loaded, it won't change phases");
+                BossPhases::default()
+            });
+        let head = spawn_slither_spine(
+            &mut commands,
+            &resources,
+            &SpawnSlitherSpineEvent {
+                default: DefaultSpawnArgs {
+                    transform: spawn_event.default.transform.with_scale(Vec3::splat(SCALE)),
+                },
+                segment_count: 30,
+                segment_offset: Vec3::Z * SCALE,
+            },
+        );
+        commands.entity(head).insert((
+            Name::new("slither_spine_boss"),
+            Boss {
+                display_name: "Slither Spine Matriarch".to_string(),
+            },
+            Combatant::new(150., 2.),
+            phases,
+            SlitherSpineBossBehavior {
+                phases: vec![
+                    SlitherSpinePhaseBehavior {
+                        accel: 0.005,
+                        max_speed: 0.5,
+                        exit_ground_speed: 2.0,
+                        contact_damage: 5.0,
+                    },
+                    SlitherSpinePhaseBehavior {
+                        accel: 0.008,
+                        max_speed: 0.7,
+                        exit_ground_speed: 3.0,
+                        contact_damage: 6.0,
+                    },
+                    SlitherSpinePhaseBehavior {
+                        accel: 0.012,
+                        max_speed: 0.9,
+                        exit_ground_speed: 4.0,
+                        contact_damage: 8.0,
+                    },
+                ],
+            },
+        ));
+    }
+}
+
+fn change_boss_behavior(
+    mut reader: EventReader<BossPhaseChangedEvent>,
+    mut query: Query<(
+        &SlitherSpineBossBehavior,
+        &mut SlitherSpineHead,
+        &mut MoveSpeed,
+        &mut ContactDamage,
+    )>,
+) {
+    for BossPhaseChangedEvent { boss, phase } in reader.read().copied() {
+        let Ok((behavior, mut head, mut move_speed, mut contact_damage)) = query.get_mut(boss)
+        else {
+            continue;
+        };
+        let Some(phase_behavior) = behavior.phases.get(phase) else {
+            continue;
+        };
+        move_speed.grounded_accel = phase_behavior.accel;
+        move_speed.aerial_accel = phase_behavior.accel;
+        move_speed.max_speed = phase_behavior.max_speed;
+        head.exit_ground_speed = phase_behavior.exit_ground_speed;
+        contact_damage.damage = Damage::new(phase_behavior.contact_damage);
+    }
+}
+
+//returns the head
+fn spawn_slither_spine(
+    commands: &mut Commands,
+    resources: &SlitherSpineResources,
+    spawn_event: &SpawnSlitherSpineEvent,
+) -> Entity {
+    let segment_length = spawn_event.segment_offset.length();
+    let mut prev: Option<(Entity, Entity)> = None;
+    let mut anchors = Vec::with_capacity(spawn_event.segment_count);
+    for i in 0..spawn_event.segment_count {
+        let offset = i as f32 * spawn_event.segment_offset;
+        prev = Some(if let Some((prev_segment, head)) = prev {
+            (
+                spawn_segement(
+                    commands,
+                    resources.spine_scene.clone(),
                     spawn_event
                         .default
                         .transform
                         .with_translation(spawn_event.default.transform.translation + offset),
-                );
-                (head, head)
-            });
+                    SlitherSpineSegment {
+                        parent: prev_segment,
+                    },
+                    head,
+                ),
+                head,
+            )
+        } else {
+            let head = spawn_head(
+                commands,
+                resources.head_scene.clone(),
+                spawn_event
+                    .default
+                    .transform
+                    .with_translation(spawn_event.default.transform.translation + offset),
+            );
+            (head, head)
+        });
+        if let Some((segment, _)) = prev {
+            anchors.push(ConstraintAnchor::entity(segment));
         }
     }
+    //each segment follows the one in front without pulling on it
+    for constraint in Constraint::chain(&anchors, segment_length, Some(MAX_SEGMENT_BEND)) {
+        commands.spawn((StateScoped(LevelLoadState::Loaded), constraint.one_way()));
+    }
+    prev.map(|(_, head)| head).unwrap_or(Entity::PLACEHOLDER)
 }
 
 fn spawn_head(commands: &mut Commands, scene: Handle<Scene>, transform: Transform) -> Entity {
@@ -175,15 +305,18 @@ fn spawn_head(commands: &mut Commands, scene: Handle<Scene>, transform: Transfor
             StateScoped(LevelLoadState::Loaded),
             SceneRoot(scene),
             transform,
-            PhysicsBundle {
-                gravity: GravityMult::new(1.0),
-                collider: Aabb::new(
-                    Vec3::new(1.8, 1.0, 1.3) * transform.scale,
-                    Vec3::new(-0.9, 0.2, -0.7) * transform.scale,
-                ),
-                drag: Drag(0.01),
-                ..default()
-            },
+            (
+                PhysicsBundle {
+                    gravity: GravityMult::new(1.0),
+                    collider: Aabb::new(
+                        Vec3::new(1.8, 1.0, 1.3) * transform.scale,
+                        Vec3::new(-0.9, 0.2, -0.7) * transform.scale,
+                    ),
+                    drag: Drag(0.01),
+                    ..default()
+                },
+                CollisionLayers::ENEMY,
+            ),
             Name::new("slither_spine_head"),
             SlitherSpineHead {
                 in_ground_gravity_mult: -0.7,
@@ -208,8 +341,9 @@ fn spawn_head(commands: &mut Commands, scene: Handle<Scene>, transform: Transfor
                 lod_levels: 0,
                 mesh: false,
             },
-            CombatantBundle::<EnemyTeam> {
+            CombatantBundle {
                 combatant: Combatant::new(10., 1.),
+                team: TeamId::ENEMY,
                 ..default()
             },
             ContactDamage::new(Damage::new(5.0)),
@@ -237,11 +371,13 @@ fn spawn_segement(
                 ),
                 ..default()
             },
+            CollisionLayers::ENEMY,
             Name::new("slither_spine_segment"),
             segment,
             IgnoreTerrainCollision,
-            CombatantBundle::<EnemyTeam> {
+            CombatantBundle {
                 combatant: Combatant::new_child(head, 0.),
+                team: TeamId::ENEMY,
                 ..default()
             },
             ContactDamage::new(Damage::new(1.0)),
@@ -250,7 +386,9 @@ fn spawn_segement(
                     item: ItemName::core("tnt"),
                     drop_chance: 1.0,
                     drop_count_range: (1, 5),
+                    ..default()
                 }],
+                ..default()
             },
         ))
         .id()
@@ -2,10 +2,7 @@ use std::sync::Arc;
 
 use ahash::HashMap;
 use bevy::prelude::*;
-use engine::{
-    actors::{team::*, Combatant, CombatantBundle, Damage},
-    all_teams_function, all_teams_system,
-};
+use engine::actors::{projectile::ProjectileBehaviors, Combatant, CombatantBundle, Damage};
 use interfaces::scheduling::LevelSystemSet;
 use physics::movement::Velocity;
 use util::SendEventCommand;
@@ -14,15 +11,12 @@ pub struct SpawningPlugin;
 
 impl Plugin for SpawningPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SpawnActorEvent>().add_systems(
-            FixedUpdate,
-            (
-                actor_spawn_handler,
-                all_teams_system!(projectile_spawn_handler),
-            )
-                .in_set(LevelSystemSet::PostTick),
-        );
-        all_teams_function!(app, add_event, SpawnProjectileEvent);
+        app.add_event::<SpawnActorEvent>()
+            .add_event::<SpawnProjectileEvent>()
+            .add_systems(
+                FixedUpdate,
+                (actor_spawn_handler, projectile_spawn_handler).in_set(LevelSystemSet::PostTick),
+            );
     }
 }
 
@@ -32,29 +26,32 @@ pub struct DefaultSpawnArgs {
 }
 
 #[derive(Clone)]
-pub struct ProjectileSpawnArgs<T: Team> {
+pub struct ProjectileSpawnArgs {
     pub velocity: Velocity,
-    pub combat: CombatantBundle<T>,
+    //projectile's team is combat.team
+    pub combat: CombatantBundle,
     pub owner: Entity,
     pub damage: Damage,
     pub lifetime_mult: f32,
     pub knockback_mult: f32,
     pub terrain_damage_mult: f32,
+    pub behaviors: ProjectileBehaviors,
 }
 
-impl<T: Team> ProjectileSpawnArgs<T> {
+impl ProjectileSpawnArgs {
     pub fn new(owner: Entity) -> Self {
         Self {
             owner,
             velocity: default(),
             damage: default(),
-            combat: CombatantBundle::<T> {
+            combat: CombatantBundle {
                 combatant: Combatant::new(10.0, 0.),
                 ..default()
             },
             lifetime_mult: 1.,
             knockback_mult: 1.,
             terrain_damage_mult: 1.,
+            behaviors: default(),
         }
     }
 }
@@ -66,10 +63,10 @@ pub struct SpawnActorEvent {
 }
 
 #[derive(Event)]
-pub struct SpawnProjectileEvent<T: Team> {
+pub struct SpawnProjectileEvent {
     pub name: Arc<String>,
     pub default: DefaultSpawnArgs,
-    pub projectile: ProjectileSpawnArgs<T>,
+    pub projectile: ProjectileSpawnArgs,
 }
 
 #[derive(Resource, Default)]
@@ -81,16 +78,16 @@ trait ActorSpawner: Fn(DefaultSpawnArgs, &mut Commands) + Sync + Send {}
 impl<T: Fn(DefaultSpawnArgs, &mut Commands) + Sync + Send> ActorSpawner for T {}
 
 #[derive(Resource, Default)]
-pub struct ProjectileRegistry<T: Team> {
-    spawners: HashMap<String, Box<dyn ProjectileSpawner<T>>>,
+pub struct ProjectileRegistry {
+    spawners: HashMap<String, Box<dyn ProjectileSpawner>>,
 }
 
-trait ProjectileSpawner<T>:
-    Fn(DefaultSpawnArgs, ProjectileSpawnArgs<T>, &mut Commands) + Sync + Send
+trait ProjectileSpawner:
+    Fn(DefaultSpawnArgs, ProjectileSpawnArgs, &mut Commands) + Sync + Send
 {
 }
-impl<T: Team, S: Fn(DefaultSpawnArgs, ProjectileSpawnArgs<T>, &mut Commands) + Sync + Send>
-    ProjectileSpawner<T> for S
+impl<S: Fn(DefaultSpawnArgs, ProjectileSpawnArgs, &mut Commands) + Sync + Send> ProjectileSpawner
+    for S
 {
 }
 
@@ -132,18 +129,16 @@ fn actor_spawn_handler(
     }
 }
 
-pub trait BuildProjectileRegistry<T: Team> {
-    fn add_projectile<
-        Event: From<(DefaultSpawnArgs, ProjectileSpawnArgs<T>)> + bevy::prelude::Event,
-    >(
+pub trait BuildProjectileRegistry {
+    fn add_projectile<Event: From<(DefaultSpawnArgs, ProjectileSpawnArgs)> + bevy::prelude::Event>(
         &mut self,
         name: String,
     ) -> &mut Self;
 }
 
-impl<T: Team> BuildProjectileRegistry<T> for App {
+impl BuildProjectileRegistry for App {
     fn add_projectile<
-        Event: From<(DefaultSpawnArgs, ProjectileSpawnArgs<T>)> + bevy::prelude::Event,
+        Event: From<(DefaultSpawnArgs, ProjectileSpawnArgs)> + bevy::prelude::Event,
     >(
         &mut self,
         name: String,
@@ -154,9 +149,7 @@ impl<T: Team> BuildProjectileRegistry<T> for App {
         registry.spawners.insert(
             name,
             Box::new(
-                |default: DefaultSpawnArgs,
-                 event: ProjectileSpawnArgs<T>,
-                 commands: &mut Commands| {
+                |default: DefaultSpawnArgs, event: ProjectileSpawnArgs, commands: &mut Commands| {
                     commands.queue(SendEventCommand(Event::from((default, event))));
                 },
             ),
@@ -165,10 +158,10 @@ impl<T: Team> BuildProjectileRegistry<T> for App {
     }
 }
 
-fn projectile_spawn_handler<T: Team>(
-    mut events: EventReader<SpawnProjectileEvent<T>>,
+fn projectile_spawn_handler(
+    mut events: EventReader<SpawnProjectileEvent>,
     mut commands: Commands,
-    registry: Res<ProjectileRegistry<T>>,
+    registry: Res<ProjectileRegistry>,
 ) {
     for SpawnProjectileEvent {
         name,
@@ -1,11 +1,7 @@
 use std::time::Duration;
 
 use bevy::prelude::*;
-use engine::actors::{
-    projectile::{Projectile, ProjectileBundle},
-    team::*,
-};
-use engine::{all_teams_function, all_teams_system};
+use engine::actors::projectile::{ProjecileHitBehavior, Projectile, ProjectileBundle};
 use interfaces::scheduling::LevelLoadState;
 use physics::{
     collision::Aabb,
@@ -29,25 +25,38 @@ pub struct SpikeBallPlugin;
 impl Plugin for SpikeBallPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, load_resources)
-            .add_systems(Update, all_teams_system!(spawn_spike_ball));
-        all_teams_function!(app, add_event, SpawnSpikeBallEvent);
-        all_teams_function!(
-            app,
-            add_projectile,
-            SpawnSpikeBallEvent,
-            "spike_ball".to_string()
-        );
+            .add_systems(Update, (spawn_spike_ball, spawn_bomb_ball))
+            .add_event::<SpawnSpikeBallEvent>()
+            .add_event::<SpawnBombBallEvent>()
+            .add_projectile::<SpawnSpikeBallEvent>("spike_ball".to_string())
+            .add_projectile::<SpawnBombBallEvent>("bomb_ball".to_string());
+    }
+}
+
+#[derive(Event)]
+pub struct SpawnSpikeBallEvent {
+    pub projectile_args: ProjectileSpawnArgs,
+    pub default_args: DefaultSpawnArgs,
+}
+
+impl From<(DefaultSpawnArgs, ProjectileSpawnArgs)> for SpawnSpikeBallEvent {
+    fn from(value: (DefaultSpawnArgs, ProjectileSpawnArgs)) -> Self {
+        Self {
+            projectile_args: value.1,
+            default_args: value.0,
+        }
     }
 }
 
+//a spike ball that is used up when it hits something, so its explosion goes off on impact
 #[derive(Event)]
-pub struct SpawnSpikeBallEvent<T: Team> {
-    pub projectile_args: ProjectileSpawnArgs<T>,
+pub struct SpawnBombBallEvent {
+    pub projectile_args: ProjectileSpawnArgs,
     pub default_args: DefaultSpawnArgs,
 }
 
-impl<T: Team> From<(DefaultSpawnArgs, ProjectileSpawnArgs<T>)> for SpawnSpikeBallEvent<T> {
-    fn from(value: (DefaultSpawnArgs, ProjectileSpawnArgs<T>)) -> Self {
+impl From<(DefaultSpawnArgs, ProjectileSpawnArgs)> for SpawnBombBallEvent {
+    fn from(value: (DefaultSpawnArgs, ProjectileSpawnArgs)) -> Self {
         Self {
             projectile_args: value.1,
             default_args: value.0,
@@ -62,10 +71,10 @@ fn load_resources(mut commands: Commands, assets: Res<AssetServer>) {
     });
 }
 
-fn spawn_spike_ball<T: Team>(
+fn spawn_spike_ball(
     mut commands: Commands,
     res: Res<SpikeBallResources>,
-    mut spawn_requests: EventReader<SpawnSpikeBallEvent<T>>,
+    mut spawn_requests: EventReader<SpawnSpikeBallEvent>,
     time: Res<Time>,
 ) {
     const LIFETIME: Duration = Duration::from_secs(5);
@@ -94,9 +103,52 @@ fn spawn_spike_ball<T: Team>(
                 terrain_damage: 0.5 * projectile_args.terrain_damage_mult,
                 despawn_time: curr_time + LIFETIME.mul_f32(projectile_args.lifetime_mult),
                 damage: projectile_args.damage,
-                hit_behavior: engine::actors::projectile::ProjecileHitBehavior::None,
+                hit_behavior: ProjecileHitBehavior::None,
+                on_hit: None,
+            })
+            .with_behaviors(projectile_args.behaviors),
+            AudioPlayer(res.spawn_audio.clone()),
+            PlaybackSettings::ONCE,
+        ));
+    }
+}
+
+fn spawn_bomb_ball(
+    mut commands: Commands,
+    res: Res<SpikeBallResources>,
+    mut spawn_requests: EventReader<SpawnBombBallEvent>,
+    time: Res<Time>,
+) {
+    const LIFETIME: Duration = Duration::from_secs(5);
+    let curr_time = time.elapsed();
+    for SpawnBombBallEvent {
+        projectile_args,
+        default_args,
+    } in spawn_requests.read()
+    {
+        commands.spawn((
+            StateScoped(LevelLoadState::Loaded),
+            SceneRoot(res.scene.clone_weak()),
+            default_args.transform.with_scale(Vec3::splat(0.5)),
+            Name::new("bomb_ball"),
+            projectile_args.combat.clone(),
+            PhysicsBundle {
+                collider: Aabb::centered(0.75 * Vec3::ONE),
+                velocity: projectile_args.velocity,
+                drag: Drag(0.),
+                ..default()
+            },
+            //bounces come from the launcher's ProjectileBehaviors, after those run out it detonates
+            ProjectileBundle::new(Projectile {
+                owner: projectile_args.owner,
+                knockback_mult: 1.0 * projectile_args.knockback_mult,
+                terrain_damage: 0.0,
+                despawn_time: curr_time + LIFETIME.mul_f32(projectile_args.lifetime_mult),
+                damage: projectile_args.damage,
+                hit_behavior: ProjecileHitBehavior::Despawn,
                 on_hit: None,
-            }),
+            })
+            .with_behaviors(projectile_args.behaviors),
             AudioPlayer(res.spawn_audio.clone()),
             PlaybackSettings::ONCE,
         ));
@@ -0,0 +1,183 @@
+#[allow(unused_imports)]
+mod boss {
+    use std::sync::Arc;
+
+    use bevy::{prelude::*, scene::DynamicEntity};
+    use engine::actors::Combatant;
+    use interfaces::testing::{start_level, test_app};
+    use world::{
+        block::BlockResources,
+        events::{ChunkUpdatedEvent, ExplosionEvent},
+        level::{Level, LevelData},
+    };
+
+    use crate::{
+        boss::{
+            BossMinionSpawn, BossPhase, BossPhaseChangedEvent, BossPhases, BossPlugin,
+            BossTerrainEffect,
+        },
+        spawning::SpawnActorEvent,
+    };
+
+    fn phases() -> Vec<BossPhase> {
+        vec![
+            BossPhase {
+                name: "frenzy".to_string(),
+                health_fraction: 0.25,
+                terrain: vec![BossTerrainEffect::Crater { radius: 2.0 }],
+                ..default()
+            },
+            BossPhase {
+                name: "start".to_string(),
+                health_fraction: 1.0,
+                ..default()
+            },
+            BossPhase {
+                name: "minions".to_string(),
+                health_fraction: 0.5,
+                minions: vec![BossMinionSpawn {
+                    actor: "minion".to_string(),
+                    count: 3,
+                    radius: 5.0,
+                }],
+                ..default()
+            },
+        ]
+    }
+
+    fn boss_app() -> (App, Entity) {
+        boss_app_with(phases())
+    }
+
+    fn boss_app_with(phases: Vec<BossPhase>) -> (App, Entity) {
+        let mut app = test_app();
+        app.add_plugins(BossPlugin)
+            .add_event::<SpawnActorEvent>()
+            .add_event::<ExplosionEvent>()
+            .add_event::<ChunkUpdatedEvent>()
+            .init_resource::<BlockResources>()
+            .insert_resource(Level(Arc::new(LevelData::new("boss", 0))));
+        start_level(&mut app);
+        let boss = app
+            .world_mut()
+            .spawn((
+                BossPhases::new(phases),
+                Combatant::new(100.0, 0.0),
+                GlobalTransform::default(),
+            ))
+            .id();
+        (app, boss)
+    }
+
+    fn set_health(app: &mut App, boss: Entity, current: f32) {
+        let mut combatant = app.world_mut().get_mut::<Combatant>(boss).unwrap();
+        let Combatant::Root { health, .. } = combatant.as_mut() else {
+            panic!("bosses are root combatants");
+        };
+        health.current = current;
+    }
+
+    //(phases entered, minions spawned, explosions) this tick
+    fn tick(app: &mut App) -> (Vec<usize>, Vec<Vec3>, usize) {
+        app.world_mut().run_schedule(FixedUpdate);
+        let world = app.world_mut();
+        let phases = world
+            .resource_mut::<Events<BossPhaseChangedEvent>>()
+            .drain()
+            .map(|event| event.phase)
+            .collect();
+        let minions = world
+            .resource_mut::<Events<SpawnActorEvent>>()
+            .drain()
+            .map(|event| event.args.transform.translation)
+            .collect();
+        let explosions = world
+            .resource_mut::<Events<ExplosionEvent>>()
+            .drain()
+            .count();
+        (phases, minions, explosions)
+    }
+
+    #[test]
+    fn test_phases_are_sorted() {
+        let phases = BossPhases::new(phases());
+        assert_eq!(phases.current_phase().unwrap().name, "start");
+        assert_eq!(phases.get(1).unwrap().name, "minions");
+        assert_eq!(phases.get(2).unwrap().name, "frenzy");
+    }
+
+    #[test]
+    fn test_phases_from_scene() {
+        let scene = DynamicScene {
+            resources: Vec::new(),
+            entities: vec![DynamicEntity {
+                entity: Entity::from_raw(0),
+                components: vec![
+                    Box::new(Name::new("boss")),
+                    Box::new(BossPhases::new(phases())),
+                ],
+            }],
+        };
+        let phases = BossPhases::from_scene(&scene).unwrap();
+        assert_eq!(phases.len(), 3);
+        assert_eq!(phases.current(), 0);
+        assert_eq!(phases.get(2).unwrap().name, "frenzy");
+        assert!(BossPhases::from_scene(&DynamicScene::default()).is_none());
+    }
+
+    #[test]
+    fn test_starting_phase_actions() {
+        let mut phases = phases();
+        phases[1].minions.push(BossMinionSpawn {
+            actor: "escort".to_string(),
+            count: 2,
+            radius: 3.0,
+        });
+        let (mut app, _) = boss_app_with(phases);
+        //the starting phase isn't entered, but its minions still show up once
+        let (phases, minions, explosions) = tick(&mut app);
+        assert_eq!(phases, Vec::<usize>::new());
+        assert_eq!(minions.len(), 2);
+        assert_eq!(explosions, 0);
+        assert_eq!(tick(&mut app), (Vec::new(), Vec::new(), 0));
+    }
+
+    #[test]
+    fn test_phase_transition() {
+        let (mut app, boss) = boss_app();
+        //still in the first phase
+        set_health(&mut app, boss, 60.0);
+        assert_eq!(tick(&mut app), (Vec::new(), Vec::new(), 0));
+        set_health(&mut app, boss, 50.0);
+        let (phases, minions, explosions) = tick(&mut app);
+        assert_eq!(phases, vec![1]);
+        assert_eq!(minions.len(), 3);
+        for minion in minions {
+            assert!((minion.length() - 5.0).abs() < 1e-4);
+        }
+        assert_eq!(explosions, 0);
+        assert_eq!(app.world().get::<BossPhases>(boss).unwrap().current(), 1);
+        //phases are only entered once
+        assert_eq!(tick(&mut app).0, Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_big_hit_goes_through_every_phase() {
+        let (mut app, boss) = boss_app();
+        set_health(&mut app, boss, 10.0);
+        let (phases, minions, explosions) = tick(&mut app);
+        assert_eq!(phases, vec![1, 2]);
+        assert_eq!(minions.len(), 3);
+        assert_eq!(explosions, 1);
+    }
+
+    #[test]
+    fn test_minion_placement_is_seeded() {
+        let spawn = || {
+            let (mut app, boss) = boss_app();
+            set_health(&mut app, boss, 50.0);
+            tick(&mut app).1
+        };
+        assert_eq!(spawn(), spawn());
+    }
+}
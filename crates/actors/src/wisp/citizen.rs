@@ -0,0 +1,792 @@
+use std::time::Duration;
+
+use ahash::HashSet;
+use bevy::prelude::*;
+use big_brain::prelude::*;
+use leafwing_input_manager::prelude::ActionState as InputState;
+use serde::{Deserialize, Serialize};
+
+use engine::{
+    actors::{
+        team::{TeamId, TeamRelationships},
+        world_anchor::{CitizenCap, WorldAnchor},
+        AttackEvent, Combatant, Damage, IdleAction, LocalPlayer,
+    },
+    controllers::{player_controller::CursorLocked, Action, TickMovement},
+    items::{inventory::Inventory, DroppedItem, MaxStackSize, SpawnDroppedItemEvent},
+};
+use interfaces::scheduling::{LevelLoadState, LevelSystemSet};
+use physics::{
+    collision::{Aabb, BlockPhysics},
+    query::{self, Raycast, RaycastHit},
+};
+use util::plugin::SmoothLookTo;
+use world::{
+    block::{BlockCoord, BlockType, RemovedBlock},
+    events::DealBlockDamageEvent,
+    level::Level,
+};
+
+use super::SpawnWispEvent;
+
+pub struct CitizenPlugin;
+
+impl Plugin for CitizenPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MiningDesignations::default())
+            .add_event::<SetCitizenJobEvent>()
+            .add_event::<SpawnCitizenEvent>()
+            .add_event::<DesignateMiningAreaEvent>()
+            .add_systems(
+                Update,
+                (
+                    mark_mining_area,
+                    designate_mining_area,
+                    set_citizen_job,
+                    spawn_citizen,
+                    draw_mining_designations,
+                )
+                    .in_set(LevelSystemSet::Main),
+            )
+            .add_systems(
+                PreUpdate,
+                //LevelSystemSet::Main isn't configured in PreUpdate, so gate it on the level here
+                citizen_job_scorer
+                    .in_set(BigBrainSet::Scorers)
+                    .run_if(in_state(LevelLoadState::Loaded)),
+            )
+            .add_systems(
+                Update,
+                (mine_action, haul_action, repair_action, defend_action)
+                    .in_set(BigBrainSet::Actions)
+                    .in_set(LevelSystemSet::Main),
+            )
+            .register_type::<CitizenJob>()
+            .register_type::<CitizenStorage>();
+    }
+}
+
+//citizens only look for work within this distance of their anchor
+pub const WORK_RADIUS: f32 = 48.0;
+//distance the wisp has to be from its target to work on it
+const WORK_REACH: f32 = 2.0;
+
+//a friendly wisp bound to a world anchor
+#[derive(Component, Clone, Copy)]
+pub struct Citizen {
+    pub anchor: Entity,
+}
+
+#[derive(
+    Component, Clone, Copy, Default, PartialEq, Eq, Hash, Debug, Reflect, Serialize, Deserialize,
+)]
+#[reflect(Component)]
+pub enum CitizenJob {
+    #[default]
+    Idle,
+    //break blocks in MiningDesignations
+    Mine,
+    //bring dropped items back to the anchor
+    Haul,
+    //heal damaged blocks near the anchor
+    Repair,
+    //fight hostiles near the anchor
+    Defend,
+}
+
+impl CitizenJob {
+    pub const ALL: [CitizenJob; 5] = [
+        CitizenJob::Idle,
+        CitizenJob::Mine,
+        CitizenJob::Haul,
+        CitizenJob::Repair,
+        CitizenJob::Defend,
+    ];
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            CitizenJob::Idle => "Idle",
+            CitizenJob::Mine => "Mine",
+            CitizenJob::Haul => "Haul",
+            CitizenJob::Repair => "Repair",
+            CitizenJob::Defend => "Defend",
+        }
+    }
+}
+
+//put on a container block to let haulers store items in it
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component, FromWorld)]
+pub struct CitizenStorage;
+
+//blocks that miners should break
+#[derive(Resource, Default)]
+pub struct MiningDesignations(pub HashSet<BlockCoord>);
+
+#[derive(Event, Clone, Copy)]
+pub struct SetCitizenJobEvent {
+    pub citizen: Entity,
+    pub job: CitizenJob,
+}
+
+//spawns a citizen at the anchor, if the anchor's CitizenCap allows it
+#[derive(Event, Clone, Copy)]
+pub struct SpawnCitizenEvent {
+    pub anchor: Entity,
+}
+
+//corners are inclusive
+#[derive(Event, Clone, Copy)]
+pub struct DesignateMiningAreaEvent {
+    pub corner_a: BlockCoord,
+    pub corner_b: BlockCoord,
+    //false removes the designation
+    pub designate: bool,
+}
+
+#[derive(Component, Clone, Copy, Debug, ScorerBuilder)]
+pub struct CitizenJobScorer {
+    pub job: CitizenJob,
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, ActionBuilder)]
+pub struct MineAction {
+    target: Option<BlockCoord>,
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, ActionBuilder)]
+pub struct HaulAction {
+    target: Option<Entity>,
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, ActionBuilder)]
+pub struct RepairAction {
+    target: Option<BlockCoord>,
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, ActionBuilder)]
+pub struct DefendAction {
+    target: Option<Entity>,
+    next_attack: Duration,
+}
+
+pub fn citizen_thinker() -> ThinkerBuilder {
+    Thinker::build()
+        .label("citizen_thinker")
+        .picker(Highest)
+        .when(
+            CitizenJobScorer {
+                job: CitizenJob::Defend,
+            },
+            DefendAction::default(),
+        )
+        .when(
+            CitizenJobScorer {
+                job: CitizenJob::Mine,
+            },
+            MineAction::default(),
+        )
+        .when(
+            CitizenJobScorer {
+                job: CitizenJob::Haul,
+            },
+            HaulAction::default(),
+        )
+        .when(
+            CitizenJobScorer {
+                job: CitizenJob::Repair,
+            },
+            RepairAction::default(),
+        )
+        .otherwise(IdleAction { seconds: 1.0 })
+}
+
+fn spawn_citizen(
+    mut reader: EventReader<SpawnCitizenEvent>,
+    mut writer: EventWriter<SpawnWispEvent>,
+    anchor_query: Query<(&GlobalTransform, &CitizenCap), With<WorldAnchor>>,
+    citizen_query: Query<&Citizen>,
+) {
+    //count events from this frame too, so spamming the button can't go over the cap
+    let mut spawned_this_frame = Vec::new();
+    for SpawnCitizenEvent { anchor } in reader.read() {
+        let Ok((anchor_tf, cap)) = anchor_query.get(*anchor) else {
+            warn!("tried to spawn a citizen for an entity that isn't a world anchor");
+            continue;
+        };
+        let count = citizen_query.iter().filter(|c| c.anchor == *anchor).count()
+            + spawned_this_frame.iter().filter(|a| *a == anchor).count();
+        if count >= cap.0 as usize {
+            info!("anchor is at its citizen cap ({})", cap.0);
+            continue;
+        }
+        spawned_this_frame.push(*anchor);
+        writer.send(SpawnWispEvent {
+            location: Transform::from_translation(anchor_tf.translation() + Vec3::Y * 5.0),
+            citizen_of: Some(*anchor),
+        });
+    }
+}
+
+fn set_citizen_job(
+    mut reader: EventReader<SetCitizenJobEvent>,
+    mut query: Query<&mut CitizenJob, With<Citizen>>,
+) {
+    for SetCitizenJobEvent { citizen, job } in reader.read() {
+        if let Ok(mut current) = query.get_mut(*citizen) {
+            *current = *job;
+        }
+    }
+}
+
+//press once to set the first corner, again to set the second
+//designating an area that is already fully designated removes it
+fn mark_mining_area(
+    player_query: Query<(Entity, &GlobalTransform, &InputState<Action>), With<LocalPlayer>>,
+    level: Res<Level>,
+    block_physics_query: Query<&BlockPhysics>,
+    object_query: Query<(Entity, &GlobalTransform, &Aabb)>,
+    designations: Res<MiningDesignations>,
+    focused: Res<CursorLocked>,
+    mut writer: EventWriter<DesignateMiningAreaEvent>,
+    mut first_corner: Local<Option<BlockCoord>>,
+) {
+    let Ok((player, tf, action)) = player_query.get_single() else {
+        return;
+    };
+    if !focused.0 || !action.just_pressed(&Action::MarkArea) {
+        return;
+    }
+    let Some(RaycastHit::Block(coord, _)) = query::raycast(
+        Raycast::new(tf.translation(), tf.forward(), 32.0),
+        &level,
+        &block_physics_query,
+        &object_query,
+        &[player],
+    ) else {
+        return;
+    };
+    match first_corner.take() {
+        None => *first_corner = Some(coord),
+        Some(corner_a) => {
+            let min = IVec3::from(corner_a).min(coord.into());
+            let max = IVec3::from(corner_a).max(coord.into());
+            let already_designated = util::iterators::Volume::new_inclusive(min, max)
+                .iter()
+                .all(|pos| designations.0.contains(&BlockCoord::from(pos)));
+            writer.send(DesignateMiningAreaEvent {
+                corner_a,
+                corner_b: coord,
+                designate: !already_designated,
+            });
+        }
+    }
+}
+
+fn designate_mining_area(
+    mut reader: EventReader<DesignateMiningAreaEvent>,
+    mut designations: ResMut<MiningDesignations>,
+) {
+    //don't want someone to lag the game by marking a giant area
+    const MAX_DESIGNATION_VOLUME: i32 = 32 * 32 * 32;
+    for event in reader.read() {
+        let min = IVec3::from(event.corner_a).min(event.corner_b.into());
+        let max = IVec3::from(event.corner_a).max(event.corner_b.into());
+        let volume = util::iterators::Volume::new_inclusive(min, max);
+        if volume.volume() > MAX_DESIGNATION_VOLUME {
+            warn!("mining designation too large: {:?}", volume.size());
+            continue;
+        }
+        for pos in volume.iter() {
+            let coord = BlockCoord::from(pos);
+            if event.designate {
+                designations.0.insert(coord);
+            } else {
+                designations.0.remove(&coord);
+            }
+        }
+    }
+}
+
+fn draw_mining_designations(
+    designations: Res<MiningDesignations>,
+    level: Res<Level>,
+    mut gizmos: Gizmos,
+) {
+    for coord in designations.0.iter() {
+        if level.get_block_entity(*coord).is_some() {
+            gizmos.cuboid(
+                Transform::from_translation(coord.center()).with_scale(Vec3::splat(1.02)),
+                Color::srgb(1.0, 0.8, 0.2),
+            );
+        }
+    }
+}
+
+fn citizen_job_scorer(
+    citizen_query: Query<(&Citizen, &CitizenJob, &TeamId, &Inventory)>,
+    anchor_query: Query<&GlobalTransform>,
+    mut query: Query<(&Actor, &mut Score, &CitizenJobScorer)>,
+    designations: Res<MiningDesignations>,
+    level: Res<Level>,
+    dropped_query: Query<&GlobalTransform, With<DroppedItem>>,
+    target_query: Query<(&GlobalTransform, &TeamId), With<Combatant>>,
+    relationships: Res<TeamRelationships>,
+) {
+    for (&Actor(actor), mut score, scorer) in query.iter_mut() {
+        let Ok((citizen, job, team, inv)) = citizen_query.get(actor) else {
+            score.set(0.0);
+            continue;
+        };
+        if *job != scorer.job {
+            score.set(0.0);
+            continue;
+        }
+        let Ok(anchor_pos) = anchor_query.get(citizen.anchor).map(|tf| tf.translation()) else {
+            score.set(0.0);
+            continue;
+        };
+        let in_range = |pos: Vec3| pos.distance_squared(anchor_pos) <= WORK_RADIUS * WORK_RADIUS;
+        let has_work = match scorer.job {
+            CitizenJob::Idle => false,
+            CitizenJob::Mine => designations
+                .0
+                .iter()
+                .any(|coord| in_range(coord.center()) && level.get_block_entity(*coord).is_some()),
+            //still has to deliver what it's carrying even if there's nothing left to pick up
+            CitizenJob::Haul => {
+                inv.iter().any(|slot| slot.is_some())
+                    || dropped_query.iter().any(|tf| {
+                        in_range(tf.translation()) && !is_delivered(tf.translation(), anchor_pos)
+                    })
+            }
+            CitizenJob::Repair => level
+                .block_damages
+                .iter()
+                .any(|entry| in_range(entry.key().center())),
+            CitizenJob::Defend => target_query.iter().any(|(tf, target_team)| {
+                relationships.is_hostile(*team, *target_team) && in_range(tf.translation())
+            }),
+        };
+        score.set(if has_work { 0.8 } else { 0.0 });
+    }
+}
+
+//items dropped this close to the anchor count as stored, so haulers don't pick them back up
+fn is_delivered(item_pos: Vec3, anchor_pos: Vec3) -> bool {
+    const DELIVERED_RADIUS: f32 = 3.0;
+    item_pos.distance_squared(anchor_pos) <= DELIVERED_RADIUS * DELIVERED_RADIUS
+}
+
+//returns true when we've arrived
+fn fly_towards(
+    tf: &Transform,
+    target: Vec3,
+    stop_distance: f32,
+    fm: &mut TickMovement,
+    look_opt: Option<Mut<SmoothLookTo>>,
+) -> bool {
+    let delta = target - tf.translation;
+    if delta.length_squared() <= stop_distance * stop_distance {
+        fm.0 = Vec3::ZERO;
+        if let Some(mut look) = look_opt {
+            look.enabled = false;
+        }
+        return true;
+    }
+    let delta_normed = delta.normalize_or_zero();
+    fm.0 = delta_normed;
+    if let Some(mut look) = look_opt {
+        look.up = Vec3::Y;
+        look.forward = delta_normed;
+        look.enabled = true;
+    }
+    false
+}
+
+fn stop(fm: &mut TickMovement, look_opt: Option<Mut<SmoothLookTo>>) {
+    fm.0 = Vec3::ZERO;
+    if let Some(mut look) = look_opt {
+        look.enabled = false;
+    }
+}
+
+fn mine_action(
+    mut info: Query<(
+        Entity,
+        &Transform,
+        &Citizen,
+        &mut TickMovement,
+        Option<&mut SmoothLookTo>,
+    )>,
+    mut query: Query<(&Actor, &mut ActionState, &mut MineAction)>,
+    anchor_query: Query<&GlobalTransform>,
+    mut designations: ResMut<MiningDesignations>,
+    level: Res<Level>,
+    mut damage_writer: EventWriter<DealBlockDamageEvent>,
+    time: Res<Time>,
+) {
+    const DAMAGE_PER_SECOND: f32 = 0.5;
+    for (&Actor(actor), mut state, mut action) in query.iter_mut() {
+        let Ok((entity, tf, citizen, mut fm, look_opt)) = info.get_mut(actor) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+        match *state {
+            ActionState::Requested => {
+                *state = ActionState::Executing;
+                action.target = None;
+            }
+            ActionState::Executing => {
+                //drop designations for blocks that are already gone
+                if let Some(target) = action.target
+                    && level.get_block_entity(target).is_none()
+                {
+                    designations.0.remove(&target);
+                    action.target = None;
+                }
+                if action.target.is_none() {
+                    let Ok(anchor_pos) = anchor_query.get(citizen.anchor).map(|t| t.translation())
+                    else {
+                        *state = ActionState::Failure;
+                        continue;
+                    };
+                    designations
+                        .0
+                        .retain(|coord| level.get_block(*coord) != Some(BlockType::Empty));
+                    action.target = designations
+                        .0
+                        .iter()
+                        .filter(|coord| {
+                            coord.center().distance_squared(anchor_pos) <= WORK_RADIUS * WORK_RADIUS
+                                && level.get_block_entity(**coord).is_some()
+                        })
+                        .min_by(|a, b| {
+                            a.center()
+                                .distance_squared(tf.translation)
+                                .total_cmp(&b.center().distance_squared(tf.translation))
+                        })
+                        .copied();
+                }
+                let Some(target) = action.target else {
+                    stop(&mut fm, look_opt);
+                    *state = ActionState::Success;
+                    continue;
+                };
+                if fly_towards(tf, target.center(), WORK_REACH, &mut fm, look_opt) {
+                    damage_writer.send(DealBlockDamageEvent {
+                        block_position: target,
+                        damage: DAMAGE_PER_SECOND * time.delta_secs(),
+                        damager: Some(entity),
+                    });
+                }
+            }
+            ActionState::Cancelled => {
+                stop(&mut fm, look_opt);
+                *state = ActionState::Failure;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn haul_action(
+    mut info: Query<(
+        &Transform,
+        &Citizen,
+        &mut Inventory,
+        &mut TickMovement,
+        Option<&mut SmoothLookTo>,
+    )>,
+    mut query: Query<(&Actor, &mut ActionState, &mut HaulAction)>,
+    anchor_query: Query<&GlobalTransform>,
+    mut dropped_query: Query<(Entity, &GlobalTransform, &mut DroppedItem)>,
+    mut storage_query: Query<
+        (&BlockCoord, &mut Inventory),
+        (
+            With<CitizenStorage>,
+            Without<Citizen>,
+            Without<RemovedBlock>,
+        ),
+    >,
+    stack_query: Query<&MaxStackSize>,
+    mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
+    mut commands: Commands,
+) {
+    for (&Actor(actor), mut state, mut action) in query.iter_mut() {
+        let Ok((tf, citizen, mut inv, mut fm, look_opt)) = info.get_mut(actor) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+        let Ok(anchor_pos) = anchor_query.get(citizen.anchor).map(|t| t.translation()) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+        match *state {
+            ActionState::Requested => {
+                *state = ActionState::Executing;
+                action.target = None;
+            }
+            ActionState::Executing => {
+                let carrying = inv.iter().any(|slot| slot.is_some());
+                let inventory_full = inv.iter().all(|slot| slot.is_some());
+                if action.target.is_none() && !inventory_full {
+                    action.target = dropped_query
+                        .iter()
+                        .filter(|(_, item_tf, _)| {
+                            let pos = item_tf.translation();
+                            pos.distance_squared(anchor_pos) <= WORK_RADIUS * WORK_RADIUS
+                                && !is_delivered(pos, anchor_pos)
+                        })
+                        .min_by(|(_, a, _), (_, b, _)| {
+                            a.translation()
+                                .distance_squared(tf.translation)
+                                .total_cmp(&b.translation().distance_squared(tf.translation))
+                        })
+                        .map(|(entity, _, _)| entity);
+                }
+                match action.target.and_then(|t| dropped_query.get_mut(t).ok()) {
+                    Some((item_entity, item_tf, mut dropped)) => {
+                        if fly_towards(tf, item_tf.translation(), WORK_REACH, &mut fm, look_opt) {
+                            match inv.pickup_item(dropped.stack, &stack_query) {
+                                Some(remaining) => dropped.stack = remaining,
+                                None => commands.entity(item_entity).despawn(),
+                            }
+                            action.target = None;
+                        }
+                    }
+                    None if carrying => {
+                        action.target = None;
+                        //deliver to the closest storage, or the anchor if there is none
+                        let storage = storage_query
+                            .iter_mut()
+                            .filter(|(storage_coord, _)| {
+                                storage_coord.center().distance_squared(anchor_pos)
+                                    <= WORK_RADIUS * WORK_RADIUS
+                            })
+                            .min_by(|(a, _), (b, _)| {
+                                a.center()
+                                    .distance_squared(tf.translation)
+                                    .total_cmp(&b.center().distance_squared(tf.translation))
+                            });
+                        match storage {
+                            Some((storage_coord, mut storage_inv)) => {
+                                if fly_towards(
+                                    tf,
+                                    storage_coord.center(),
+                                    WORK_REACH,
+                                    &mut fm,
+                                    look_opt,
+                                ) {
+                                    for slot in 0..inv.len() {
+                                        let Some(stack) = inv.drop_slot(slot) else {
+                                            continue;
+                                        };
+                                        if let Some(remaining) =
+                                            storage_inv.pickup_item(stack, &stack_query)
+                                        {
+                                            //storage is full, so leave it on the ground next to it
+                                            drop_writer.send(SpawnDroppedItemEvent {
+                                                postion: storage_coord.center() + Vec3::Y,
+                                                velocity: Vec3::ZERO,
+                                                stack: remaining,
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                            None => {
+                                if fly_towards(tf, anchor_pos, WORK_REACH, &mut fm, look_opt) {
+                                    for slot in 0..inv.len() {
+                                        if let Some(stack) = inv.drop_slot(slot) {
+                                            drop_writer.send(SpawnDroppedItemEvent {
+                                                postion: anchor_pos + Vec3::Y,
+                                                velocity: Vec3::ZERO,
+                                                stack,
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    None => {
+                        //nothing to pick up and nothing to deliver
+                        stop(&mut fm, look_opt);
+                        *state = ActionState::Success;
+                    }
+                }
+            }
+            ActionState::Cancelled => {
+                stop(&mut fm, look_opt);
+                *state = ActionState::Failure;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn repair_action(
+    mut info: Query<(
+        Entity,
+        &Transform,
+        &Citizen,
+        &mut TickMovement,
+        Option<&mut SmoothLookTo>,
+    )>,
+    mut query: Query<(&Actor, &mut ActionState, &mut RepairAction)>,
+    anchor_query: Query<&GlobalTransform>,
+    level: Res<Level>,
+    mut damage_writer: EventWriter<DealBlockDamageEvent>,
+    time: Res<Time>,
+) {
+    const REPAIR_PER_SECOND: f32 = 0.5;
+    for (&Actor(actor), mut state, mut action) in query.iter_mut() {
+        let Ok((entity, tf, citizen, mut fm, look_opt)) = info.get_mut(actor) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+        match *state {
+            ActionState::Requested => {
+                *state = ActionState::Executing;
+                action.target = None;
+            }
+            ActionState::Executing => {
+                //block_damages entries are removed once they are fully healed
+                if let Some(target) = action.target
+                    && !level.block_damages.contains_key(&target)
+                {
+                    action.target = None;
+                }
+                if action.target.is_none() {
+                    let Ok(anchor_pos) = anchor_query.get(citizen.anchor).map(|t| t.translation())
+                    else {
+                        *state = ActionState::Failure;
+                        continue;
+                    };
+                    action.target = level
+                        .block_damages
+                        .iter()
+                        .map(|entry| *entry.key())
+                        .filter(|coord| {
+                            coord.center().distance_squared(anchor_pos) <= WORK_RADIUS * WORK_RADIUS
+                        })
+                        .min_by(|a, b| {
+                            a.center()
+                                .distance_squared(tf.translation)
+                                .total_cmp(&b.center().distance_squared(tf.translation))
+                        });
+                }
+                let Some(target) = action.target else {
+                    stop(&mut fm, look_opt);
+                    *state = ActionState::Success;
+                    continue;
+                };
+                if fly_towards(tf, target.center(), WORK_REACH, &mut fm, look_opt) {
+                    //negative damage heals the block
+                    damage_writer.send(DealBlockDamageEvent {
+                        block_position: target,
+                        damage: -REPAIR_PER_SECOND * time.delta_secs(),
+                        damager: Some(entity),
+                    });
+                }
+            }
+            ActionState::Cancelled => {
+                stop(&mut fm, look_opt);
+                *state = ActionState::Failure;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn defend_action(
+    mut info: Query<(
+        Entity,
+        &Transform,
+        &Citizen,
+        &TeamId,
+        &mut TickMovement,
+        Option<&mut SmoothLookTo>,
+    )>,
+    mut query: Query<(&Actor, &mut ActionState, &mut DefendAction)>,
+    anchor_query: Query<&GlobalTransform>,
+    target_query: Query<(Entity, &GlobalTransform, &TeamId), With<Combatant>>,
+    relationships: Res<TeamRelationships>,
+    mut attack_writer: EventWriter<AttackEvent>,
+    time: Res<Time>,
+) {
+    const ATTACK_COOLDOWN: Duration = Duration::from_secs(1);
+    const ATTACK_RANGE: f32 = 2.5;
+    const KNOCKBACK: f32 = 0.2;
+    let damage = Damage::new(1.0);
+    for (&Actor(actor), mut state, mut action) in query.iter_mut() {
+        let Ok((entity, tf, citizen, team, mut fm, look_opt)) = info.get_mut(actor) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+        let Ok(anchor_pos) = anchor_query.get(citizen.anchor).map(|t| t.translation()) else {
+            *state = ActionState::Failure;
+            continue;
+        };
+        let in_range = |pos: Vec3| pos.distance_squared(anchor_pos) <= WORK_RADIUS * WORK_RADIUS;
+        match *state {
+            ActionState::Requested => {
+                *state = ActionState::Executing;
+                action.target = None;
+            }
+            ActionState::Executing => {
+                //drop targets that died, left the area, or were charmed
+                if let Some(target) = action.target
+                    && !target_query
+                        .get(target)
+                        .is_ok_and(|(_, target_tf, target_team)| {
+                            relationships.is_hostile(*team, *target_team)
+                                && in_range(target_tf.translation())
+                        })
+                {
+                    action.target = None;
+                }
+                if action.target.is_none() {
+                    action.target = target_query
+                        .iter()
+                        .filter(|(_, target_tf, target_team)| {
+                            relationships.is_hostile(*team, **target_team)
+                                && in_range(target_tf.translation())
+                        })
+                        .min_by(|(_, a, _), (_, b, _)| {
+                            a.translation()
+                                .distance_squared(tf.translation)
+                                .total_cmp(&b.translation().distance_squared(tf.translation))
+                        })
+                        .map(|(target, _, _)| target);
+                }
+                let Some((target, target_tf, _)) =
+                    action.target.and_then(|t| target_query.get(t).ok())
+                else {
+                    stop(&mut fm, look_opt);
+                    *state = ActionState::Success;
+                    continue;
+                };
+                if fly_towards(tf, target_tf.translation(), ATTACK_RANGE, &mut fm, look_opt)
+                    && action.next_attack <= time.elapsed()
+                {
+                    action.next_attack = time.elapsed() + ATTACK_COOLDOWN;
+                    attack_writer.send(AttackEvent {
+                        attacker: entity,
+                        target,
+                        damage,
+                        knockback: (target_tf.translation() - tf.translation).normalize_or_zero()
+                            * KNOCKBACK,
+                        melee: true,
+                    });
+                }
+            }
+            ActionState::Cancelled => {
+                stop(&mut fm, look_opt);
+                *state = ActionState::Failure;
+            }
+            _ => {}
+        }
+    }
+}
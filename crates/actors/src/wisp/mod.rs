@@ -1,13 +1,21 @@
 use bevy::prelude::*;
 
 use interfaces::scheduling::LevelLoadState;
-use physics::{collision::Aabb, movement::GravityMult, PhysicsBundle};
+use physics::{
+    collision::Aabb, entity_collision::CollisionLayers, movement::GravityMult, PhysicsBundle,
+};
 use util::{plugin::SmoothLookTo, SendEventCommand};
 
-use engine::actors::{
-    team::PlayerTeam, ActorName, ActorResources, Combatant, CombatantBundle, Idler,
+use engine::{
+    actors::{
+        team::TeamId, ActorName, ActorResources, Combatant, CombatantBundle, Idler, MoveSpeed,
+    },
+    controllers::{ControllableBundle, MovementMode},
+    items::inventory::Inventory,
 };
 
+pub mod citizen;
+
 #[derive(Resource)]
 pub struct WispResources {
     pub mesh: Handle<Mesh>,
@@ -20,13 +28,16 @@ pub struct Wisp;
 #[derive(Event)]
 pub struct SpawnWispEvent {
     pub location: Transform,
+    //world anchor to bind the wisp to as a citizen
+    pub citizen_of: Option<Entity>,
 }
 
 pub struct WispPlugin;
 
 impl Plugin for WispPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, (load_resources, add_to_registry))
+        app.add_plugins(citizen::CitizenPlugin)
+            .add_systems(Startup, (load_resources, add_to_registry))
             // .add_systems(OnEnter(LevelLoadState::Loaded), trigger_spawning)
             .add_systems(Update, spawn_wisp)
             .add_event::<SpawnWispEvent>();
@@ -59,7 +70,12 @@ pub fn load_resources(
 fn add_to_registry(mut res: ResMut<ActorResources>) {
     res.registry.add_dynamic(
         ActorName::core("wisp"),
-        Box::new(|commands, tf| commands.queue(SendEventCommand(SpawnWispEvent { location: tf }))),
+        Box::new(|commands, tf| {
+            commands.queue(SendEventCommand(SpawnWispEvent {
+                location: tf,
+                citizen_of: None,
+            }))
+        }),
     );
 }
 
@@ -69,14 +85,15 @@ fn spawn_wisp(
     mut spawn_requests: EventReader<SpawnWispEvent>,
 ) {
     for spawn in spawn_requests.read() {
-        commands.spawn((
+        let mut ec = commands.spawn((
             StateScoped(LevelLoadState::Loaded),
             Mesh3d(res.mesh.clone()),
             MeshMaterial3d(res.material.clone()),
             spawn.location,
             Name::new("wisp"),
-            CombatantBundle::<PlayerTeam> {
+            CombatantBundle {
                 combatant: Combatant::new(10., 0.),
+                team: TeamId::PLAYER,
                 ..default()
             },
             PhysicsBundle {
@@ -84,11 +101,29 @@ fn spawn_wisp(
                 gravity: GravityMult::new(0.5),
                 ..default()
             },
+            CollisionLayers::PLAYER,
             Wisp,
             Idler::default(),
             SmoothLookTo::new(0.5),
             bevy::pbr::CubemapVisibleEntities::default(),
             bevy::render::primitives::CubemapFrusta::default(),
         ));
+        if let Some(anchor) = spawn.citizen_of {
+            let id = ec.id();
+            ec.insert((
+                Name::new("citizen wisp"),
+                GravityMult::new(0.),
+                ControllableBundle {
+                    mode: MovementMode::Flying,
+                    move_speed: MoveSpeed::new(0.1, 0.1, 0.1),
+                    ..default()
+                },
+                citizen::Citizen { anchor },
+                citizen::CitizenJob::default(),
+                //hauling buffer
+                Inventory::new(id, 4),
+                citizen::citizen_thinker(),
+            ));
+        }
     }
 }
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::integrity::CheckSupportEvent;
+
 use util::direction::DirectionFlags;
 
 use actors::block_actors::{FallingBlock, SpawnFallingBlockEvent};
@@ -32,6 +34,7 @@ pub fn process_fall(
     level: Res<Level>,
     id_query: Query<&BlockId>,
     mut update_writer: EventWriter<ChunkUpdatedEvent>,
+    mut check_writer: EventWriter<CheckSupportEvent>,
     mut commands: Commands,
 ) {
     for used in uses.read() {
@@ -43,6 +46,7 @@ pub fn process_fall(
                 &mut update_writer,
                 &mut commands,
             );
+            check_writer.send(CheckSupportEvent(used.block_position));
             fall_writer.send(SpawnFallingBlockEvent {
                 position: used.block_position.to_vec3(),
                 initial_velocity: Vec3::ZERO,
@@ -51,6 +55,7 @@ pub fn process_fall(
                     place_on_landing: true,
                     impact_direcitons: DirectionFlags::all(),
                 },
+                attached: Vec::new(),
             });
         }
     }
@@ -0,0 +1,113 @@
+use bevy::{prelude::*, utils::hashbrown::HashSet};
+
+use actors::block_actors::{FallingBlock, SpawnFallingBlockEvent};
+use interfaces::{components::Id, scheduling::LevelSystemSet};
+use util::direction::{Direction, DirectionFlags};
+use world::{
+    block::{BlockCoord, BlockId, BlockType},
+    events::{BlockBrokenEvent, ChunkUpdatedEvent, ExplosionEvent},
+    level::Level,
+};
+
+//blocks that lose their connection to the ground fall
+pub struct IntegrityPlugin;
+
+impl Plugin for IntegrityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CheckSupportEvent>().add_systems(
+            FixedUpdate,
+            collapse_unsupported.in_set(LevelSystemSet::PostTick),
+        );
+    }
+}
+
+//bigger groups are treated as connected to the ground, so breaking terrain doesn't flood fill the world
+const MAX_LOOSE_BLOCKS: usize = 256;
+//loose groups up to this size fall as one body, bigger ones fall apart into single blocks
+const MAX_RIGID_BLOCKS: usize = 32;
+
+//send when a block is removed without a BlockBrokenEvent or ExplosionEvent, so its neighbors are checked
+#[derive(Event)]
+pub struct CheckSupportEvent(pub BlockCoord);
+
+fn collapse_unsupported(
+    mut broken_reader: EventReader<BlockBrokenEvent>,
+    mut explosion_reader: EventReader<ExplosionEvent>,
+    mut check_reader: EventReader<CheckSupportEvent>,
+    level: Res<Level>,
+    id_query: Query<&BlockId>,
+    mut fall_writer: EventWriter<SpawnFallingBlockEvent>,
+    mut update_writer: EventWriter<ChunkUpdatedEvent>,
+    mut commands: Commands,
+    mut seeds: Local<Vec<BlockCoord>>,
+) {
+    seeds.clear();
+    for removed in broken_reader
+        .read()
+        .map(|event| event.coord)
+        .chain(check_reader.read().map(|CheckSupportEvent(coord)| *coord))
+    {
+        seeds.extend(Direction::iter().map(|dir| removed.offset(dir)));
+    }
+    for ExplosionEvent { radius, origin } in explosion_reader.read() {
+        //everything just outside the crater
+        let size = radius.ceil() as i32;
+        for x in -size - 1..size + 2 {
+            for y in -size - 1..size + 2 {
+                for z in -size - 1..size + 2 {
+                    if x * x + y * y + z * z > size * size {
+                        seeds.push(*origin + BlockCoord::new(x, y, z));
+                    }
+                }
+            }
+        }
+    }
+    if seeds.is_empty() {
+        return;
+    }
+    seeds.sort_unstable_by_key(|coord| (coord.x, coord.y, coord.z));
+    seeds.dedup();
+
+    //dynamic blocks keep their state on their entity, so they stay put and hold up what's attached to them
+    let is_anchor = |entity: Entity| matches!(id_query.get(entity), Ok(BlockId(Id::Dynamic(_))));
+    let mut supported = HashSet::new();
+    for seed in seeds.iter() {
+        let Some(group) =
+            level.find_unsupported_group(*seed, MAX_LOOSE_BLOCKS, &mut supported, is_anchor)
+        else {
+            continue;
+        };
+        level.batch_set_block_entities(
+            group.iter().map(|(coord, _)| (*coord, BlockType::Empty)),
+            &id_query,
+            &mut update_writer,
+            &mut commands,
+        );
+        let falling_block = |block: Entity| FallingBlock {
+            block,
+            place_on_landing: true,
+            impact_direcitons: DirectionFlags::all(),
+        };
+        if group.len() <= MAX_RIGID_BLOCKS {
+            let (origin, block) = group[0];
+            fall_writer.send(SpawnFallingBlockEvent {
+                position: origin.to_vec3(),
+                initial_velocity: Vec3::ZERO,
+                falling_block: falling_block(block),
+                attached: group[1..]
+                    .iter()
+                    .map(|(coord, block)| (*coord - origin, *block))
+                    .collect(),
+            });
+        } else {
+            for (coord, block) in group {
+                fall_writer.send(SpawnFallingBlockEvent {
+                    position: coord.to_vec3(),
+                    initial_velocity: Vec3::ZERO,
+                    falling_block: falling_block(block),
+                    attached: Vec::new(),
+                });
+            }
+        }
+    }
+}
@@ -8,11 +8,12 @@ use world::{events::BlockDamageSetEvent, level::Level};
 pub struct BlocksPlugin;
 
 pub mod fall;
+pub mod integrity;
 pub mod tnt;
 
 impl Plugin for BlocksPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((tnt::TNTPlugin, fall::FallPlugin))
+        app.add_plugins((tnt::TNTPlugin, fall::FallPlugin, integrity::IntegrityPlugin))
             .add_systems(Update, heal_block_damages.in_set(LevelSystemSet::Main));
     }
 }
@@ -1,5 +1,7 @@
 use bevy::prelude::*;
 
+use crate::integrity::CheckSupportEvent;
+
 use actors::block_actors::{FallingBlock, LandedFallingBlockEvent, SpawnFallingBlockEvent};
 use interfaces::scheduling::LevelSystemSet;
 use util::direction::DirectionFlags;
@@ -34,6 +36,7 @@ pub fn process_tnt(
     level: Res<Level>,
     id_query: Query<&BlockId>,
     mut update_writer: EventWriter<ChunkUpdatedEvent>,
+    mut check_writer: EventWriter<CheckSupportEvent>,
     mut commands: Commands,
 ) {
     for used in uses.read() {
@@ -45,6 +48,7 @@ pub fn process_tnt(
                 &mut update_writer,
                 &mut commands,
             );
+            check_writer.send(CheckSupportEvent(used.block_position));
             explosions.send(SpawnFallingBlockEvent {
                 position: used.block_position.to_vec3(),
                 initial_velocity: Vec3::ZERO,
@@ -53,6 +57,7 @@ pub fn process_tnt(
                     place_on_landing: false,
                     impact_direcitons: DirectionFlags::all(),
                 },
+                attached: Vec::new(),
             });
         }
     }
@@ -0,0 +1,78 @@
+use ahash::HashSet;
+use bevy::prelude::*;
+use engine::items::inventory::Inventory;
+use interfaces::scheduling::LevelSystemSet;
+
+use crate::{CachedEntityRecipe, Recipe};
+
+pub struct RecipeDiscoveryPlugin;
+
+impl Plugin for RecipeDiscoveryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RecipeDiscoveredEvent>().add_systems(
+            FixedUpdate,
+            discover_recipes.in_set(LevelSystemSet::PostTick),
+        );
+    }
+}
+
+//recipes the entity has unlocked. stored by value instead of entity so it can be saved across runs
+//entities without this can craft (and see) any recipe
+#[derive(Component, Default, Debug, Clone)]
+pub struct KnownRecipes {
+    recipes: HashSet<Recipe>,
+}
+
+impl KnownRecipes {
+    pub fn new(recipes: impl IntoIterator<Item = Recipe>) -> Self {
+        Self {
+            recipes: recipes.into_iter().collect(),
+        }
+    }
+    pub fn contains(&self, recipe: &Recipe) -> bool {
+        self.recipes.contains(recipe)
+    }
+    //returns true if the recipe wasn't known before
+    pub fn insert(&mut self, recipe: Recipe) -> bool {
+        self.recipes.insert(recipe)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &Recipe> {
+        self.recipes.iter()
+    }
+    pub fn len(&self) -> usize {
+        self.recipes.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty()
+    }
+}
+
+#[derive(Event)]
+pub struct RecipeDiscoveredEvent {
+    pub discoverer: Entity,
+    pub recipe: Entity,
+}
+
+//a recipe is discovered the first time the entity holds enough of any one of its ingredients
+fn discover_recipes(
+    mut query: Query<
+        (Entity, &Inventory, &mut KnownRecipes),
+        Or<(Changed<Inventory>, Added<KnownRecipes>)>,
+    >,
+    recipe_query: Query<(Entity, &Recipe, &CachedEntityRecipe)>,
+    mut writer: EventWriter<RecipeDiscoveredEvent>,
+) {
+    for (discoverer, inventory, mut known) in query.iter_mut() {
+        for (recipe_entity, recipe, cached) in recipe_query.iter() {
+            if known.contains(recipe) || !cached.has_any_input(inventory) {
+                continue;
+            }
+            known.insert(recipe.clone());
+            info!("discovered recipe {:?}", recipe);
+            writer.send(RecipeDiscoveredEvent {
+                discoverer,
+                recipe: recipe_entity,
+            });
+        }
+    }
+}
@@ -1,19 +1,83 @@
+use std::{collections::VecDeque, time::Duration};
+
+use ahash::HashMap;
 use bevy::prelude::*;
-use engine::items::{inventory::Inventory, ItemName, ItemResources, ItemStack, MaxStackSize};
+use engine::items::{
+    inventory::Inventory, ItemName, ItemResources, ItemStack, MaxStackSize, SpawnDroppedItemEvent,
+};
 use interfaces::scheduling::LevelSystemSet;
 use serde::{Deserialize, Serialize};
 
+pub mod discovery;
+pub mod repair;
+pub mod stations;
+
+use discovery::KnownRecipes;
+use stations::NearbyCraftingStations;
+
 pub struct RecipePlugin;
 
 impl Plugin for RecipePlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<Recipe>()
-            .add_event::<CraftEvent>()
-            .add_systems(FixedUpdate, craft.in_set(LevelSystemSet::Tick))
-            .add_systems(
-                FixedPreUpdate,
-                cache_recipe_entities.run_if(resource_exists::<ItemResources>),
-            );
+        app.add_plugins((
+            stations::CraftingStationPlugin,
+            discovery::RecipeDiscoveryPlugin,
+            repair::RepairPlugin,
+        ))
+        .register_type::<Recipe>()
+        .register_type::<RecipeIngredient>()
+        .register_type::<ItemTagDefinition>()
+        .add_event::<CraftEvent>()
+        .add_event::<CraftFinishedEvent>()
+        .add_systems(
+            FixedUpdate,
+            (craft, update_crafting_queues)
+                .chain()
+                .in_set(LevelSystemSet::Tick),
+        )
+        .add_systems(
+            FixedPreUpdate,
+            cache_recipe_entities.run_if(resource_exists::<ItemResources>),
+        );
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum RecipeIngredient {
+    Item(ItemName),
+    //any item listed in the ItemTagDefinition with this tag
+    Tag(String),
+}
+
+impl Default for RecipeIngredient {
+    fn default() -> Self {
+        Self::Tag(String::new())
+    }
+}
+
+impl RecipeIngredient {
+    //returns None if the item or tag was not found
+    pub fn lookup(
+        &self,
+        items: &ItemResources,
+        tags: &HashMap<String, Vec<Entity>>,
+    ) -> Option<Vec<Entity>> {
+        match self {
+            RecipeIngredient::Item(item) => {
+                let Some(item_entity) = items.registry.get_basic(item) else {
+                    warn!("recipe has unknown item name {:?}", item);
+                    return None;
+                };
+                Some(vec![item_entity])
+            }
+            RecipeIngredient::Tag(tag) => match tags.get(tag) {
+                Some(tagged) if !tagged.is_empty() => Some(tagged.clone()),
+                _ => {
+                    warn!("recipe has unknown or empty item tag {:?}", tag);
+                    None
+                }
+            },
+        }
     }
 }
 
@@ -22,47 +86,113 @@ impl Plugin for RecipePlugin {
 )]
 #[reflect(Component, FromWorld)]
 pub struct Recipe {
-    pub inputs: Vec<(ItemName, u32)>,
-    pub output: (ItemName, u32),
+    pub inputs: Vec<(RecipeIngredient, u32)>,
+    pub outputs: Vec<(ItemName, u32)>,
+    //recipes with a station can only be crafted near a CraftingStation of that category
+    pub station: Option<String>,
+    //zero crafts instantly
+    pub craft_time: Duration,
+}
+
+//groups items so recipes can ask for "any log" instead of a specific item
+#[derive(Default, Clone, Debug, PartialEq, Eq, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct ItemTagDefinition {
+    pub tag: String,
+    pub items: Vec<ItemName>,
 }
 
 #[derive(Event)]
 pub struct CraftEvent {
     pub crafter: Entity,
-    // optimize - probably fine to own this, but maybe can improve using entity as id and mapping in multiplayer?
-    pub recipe: CachedEntityRecipe,
+    //entity with the Recipe and CachedEntityRecipe
+    pub recipe: Entity,
+}
+
+//sent when the outputs of a craft are given to the crafter
+#[derive(Event)]
+pub struct CraftFinishedEvent {
+    pub crafter: Entity,
+    pub recipe: Entity,
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedIngredient {
+    //any of these items can be used
+    pub items: Vec<Entity>,
+    pub count: u32,
+}
+
+impl CachedIngredient {
+    pub fn number_in(&self, inventory: &Inventory) -> u32 {
+        self.items
+            .iter()
+            .map(|item| inventory.number_of_type(*item))
+            .sum()
+    }
+    //the item to show in UI for this ingredient
+    pub fn display_stack(&self) -> ItemStack {
+        ItemStack::new(self.items[0], self.count)
+    }
+    //removes from whichever matching items we have until count are taken
+    pub(crate) fn remove_from(&self, inventory: &mut Inventory) {
+        let mut to_remove = self.count;
+        for item in self.items.iter() {
+            if to_remove == 0 {
+                break;
+            }
+            to_remove -= inventory.remove_items(ItemStack::new(*item, to_remove));
+        }
+    }
 }
 
 #[derive(Component, Debug, Clone)]
 pub struct CachedEntityRecipe {
-    pub inputs: Vec<ItemStack>,
-    pub output: ItemStack,
+    pub inputs: Vec<CachedIngredient>,
+    pub outputs: Vec<ItemStack>,
+    pub station: Option<String>,
+    pub craft_time: Duration,
 }
 
 impl CachedEntityRecipe {
-    //returns None if an item was not found
-    pub fn from_recipe(recipe: &Recipe, items: &Res<ItemResources>) -> Option<Self> {
-        let Some(output_entity) = items.registry.get_basic(&recipe.output.0) else {
-            warn!("recipe has unknown output item name {:?}", recipe.output.0);
-            return None;
-        };
-        let inputs = recipe
-            .inputs
+    //returns None if an item or tag was not found
+    pub fn from_recipe(
+        recipe: &Recipe,
+        items: &ItemResources,
+        tags: &HashMap<String, Vec<Entity>>,
+    ) -> Option<Self> {
+        let outputs = recipe
+            .outputs
             .iter()
             .filter_map(|(item, count)| {
                 let Some(item_entity) = items.registry.get_basic(item) else {
-                    warn!("recipe has unknown item name {:?}", item);
+                    warn!("recipe has unknown output item name {:?}", item);
                     return None;
                 };
                 Some(ItemStack::new(item_entity, *count))
             })
             .collect::<Vec<_>>();
-        if inputs.len() != recipe.inputs.len() {
+        let inputs = recipe
+            .inputs
+            .iter()
+            .filter_map(|(ingredient, count)| {
+                Some(CachedIngredient {
+                    items: ingredient.lookup(items, tags)?,
+                    count: *count,
+                })
+            })
+            .collect::<Vec<_>>();
+        if inputs.len() != recipe.inputs.len()
+            || outputs.len() != recipe.outputs.len()
+            || outputs.is_empty()
+        {
             None
         } else {
             Some(Self {
                 inputs,
-                output: ItemStack::new(output_entity, recipe.output.1),
+                outputs,
+                station: recipe.station.clone(),
+                craft_time: recipe.craft_time,
             })
         }
     }
@@ -70,59 +200,185 @@ impl CachedEntityRecipe {
     pub fn has_any_input(&self, inventory: &Inventory) -> bool {
         self.inputs
             .iter()
-            .any(|stack| inventory.number_of_type(stack.id) >= stack.size)
+            .any(|ingredient| ingredient.number_in(inventory) >= ingredient.count)
     }
 
     pub fn has_inputs(&self, inventory: &Inventory) -> bool {
         self.inputs
             .iter()
-            .all(|stack| inventory.number_of_type(stack.id) >= stack.size)
+            .all(|ingredient| ingredient.number_in(inventory) >= ingredient.count)
+    }
+
+    //recipes without a station can be crafted anywhere
+    pub fn has_station(&self, nearby: Option<&NearbyCraftingStations>) -> bool {
+        match &self.station {
+            None => true,
+            Some(station) => nearby.is_some_and(|n| n.contains(station)),
+        }
     }
 
-    // accounts for having recipe inputs and having the output slot available
+    // accounts for having recipe inputs and having the output slots available
     pub fn can_craft(&self, inventory: &Inventory, pickup_query: &Query<&MaxStackSize>) -> bool {
-        inventory.can_pickup_item(self.output, pickup_query) && self.has_inputs(inventory)
+        self.outputs
+            .iter()
+            .all(|output| inventory.can_pickup_item(*output, pickup_query))
+            && self.has_inputs(inventory)
     }
 }
 
+pub struct ActiveCraft {
+    pub recipe: Entity,
+    pub remaining: Duration,
+}
+
+//timed crafts waiting to finish, front of the queue is the one in progress
+#[derive(Component, Default)]
+pub struct CraftingQueue(pub VecDeque<ActiveCraft>);
+
 fn craft(
     mut reader: EventReader<CraftEvent>,
-    mut inv_query: Query<&mut Inventory>,
+    mut crafter_query: Query<(
+        &mut Inventory,
+        Option<&NearbyCraftingStations>,
+        Option<&KnownRecipes>,
+        Option<&mut CraftingQueue>,
+    )>,
+    recipe_query: Query<(&Recipe, &CachedEntityRecipe)>,
     pickup_query: Query<&MaxStackSize>,
+    mut finished_writer: EventWriter<CraftFinishedEvent>,
+    mut commands: Commands,
 ) {
     for CraftEvent { crafter, recipe } in reader.read() {
         info!("recv craft event");
-        let Ok(mut inventory) = inv_query.get_mut(*crafter) else {
+        let Ok((mut inventory, nearby, known, queue)) = crafter_query.get_mut(*crafter) else {
             warn!("trying to craft from something without an inventory");
             continue;
         };
-        // make sure we have space for the output before removing items
-        if recipe.can_craft(&inventory, &pickup_query) {
-            inventory.pickup_item(recipe.output, &pickup_query);
-
-            // remove inputs from inventory
-            for input in recipe.inputs.iter() {
-                inventory.remove_items(*input);
+        let Ok((raw_recipe, cached)) = recipe_query.get(*recipe) else {
+            warn!("trying to craft an invalid recipe");
+            continue;
+        };
+        if known.is_some_and(|known| !known.contains(raw_recipe)) {
+            info!("crafter doesn't know that recipe");
+            continue;
+        }
+        if !cached.has_station(nearby) {
+            info!("not near a {:?}", cached.station);
+            continue;
+        }
+        if cached.craft_time.is_zero() {
+            // make sure we have space for the outputs before removing items
+            if !cached.can_craft(&inventory, &pickup_query) {
+                info!("couldn't craft item");
+                continue;
             }
-
+            for input in cached.inputs.iter() {
+                input.remove_from(&mut inventory);
+            }
+            for output in cached.outputs.iter() {
+                inventory.pickup_item(*output, &pickup_query);
+            }
+            finished_writer.send(CraftFinishedEvent {
+                crafter: *crafter,
+                recipe: *recipe,
+            });
             info!("crafted item");
         } else {
-            info!("couldn't craft item");
+            if !cached.has_inputs(&inventory) {
+                info!("couldn't craft item");
+                continue;
+            }
+            //inputs are taken up front so they can't be used for something else while we wait
+            for input in cached.inputs.iter() {
+                input.remove_from(&mut inventory);
+            }
+            let active = ActiveCraft {
+                recipe: *recipe,
+                remaining: cached.craft_time,
+            };
+            match queue {
+                Some(mut queue) => queue.0.push_back(active),
+                None => {
+                    commands
+                        .entity(*crafter)
+                        .insert(CraftingQueue(VecDeque::from([active])));
+                }
+            }
+            info!("started crafting item");
+        }
+    }
+}
+
+fn update_crafting_queues(
+    mut crafter_query: Query<(Entity, &mut CraftingQueue, &mut Inventory, &GlobalTransform)>,
+    recipe_query: Query<&CachedEntityRecipe>,
+    pickup_query: Query<&MaxStackSize>,
+    mut finished_writer: EventWriter<CraftFinishedEvent>,
+    mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
+    time: Res<Time>,
+) {
+    for (crafter, mut queue, mut inventory, gtf) in crafter_query.iter_mut() {
+        let Some(active) = queue.0.front_mut() else {
+            continue;
+        };
+        active.remaining = active.remaining.saturating_sub(time.delta());
+        if !active.remaining.is_zero() {
+            continue;
+        }
+        let recipe = active.recipe;
+        queue.0.pop_front();
+        let Ok(cached) = recipe_query.get(recipe) else {
+            continue;
+        };
+        for output in cached.outputs.iter() {
+            //no room, so drop what's left at the crafter's feet
+            if let Some(leftover) = inventory.pickup_item(*output, &pickup_query) {
+                drop_writer.send(SpawnDroppedItemEvent {
+                    postion: gtf.translation(),
+                    velocity: Vec3::ZERO,
+                    stack: leftover,
+                });
+            }
+        }
+        finished_writer.send(CraftFinishedEvent { crafter, recipe });
+        info!("finished crafting item");
+    }
+}
+
+//maps each tag to the items it contains
+pub(crate) fn build_tag_map(
+    tag_query: &Query<&ItemTagDefinition>,
+    items: &ItemResources,
+) -> HashMap<String, Vec<Entity>> {
+    let mut tags: HashMap<String, Vec<Entity>> = HashMap::default();
+    for definition in tag_query.iter() {
+        let tagged = tags.entry(definition.tag.clone()).or_default();
+        for item in definition.items.iter() {
+            match items.registry.get_basic(item) {
+                Some(item_entity) => tagged.push(item_entity),
+                None => warn!("item tag {:?} has unknown item {:?}", definition.tag, item),
+            }
         }
     }
+    tags
 }
 
 fn cache_recipe_entities(
     query: Query<(Entity, &Recipe), Without<CachedEntityRecipe>>,
+    tag_query: Query<&ItemTagDefinition>,
     mut commands: Commands,
     items: Res<ItemResources>,
 ) {
+    if query.is_empty() {
+        return;
+    }
+    let tags = build_tag_map(&tag_query, &items);
     for (entity, recipe) in query.iter() {
         let Some(mut ec) = commands.get_entity(entity) else {
             warn!("adding cached recipe to invalid entity somehow");
             continue;
         };
-        match CachedEntityRecipe::from_recipe(recipe, &items) {
+        match CachedEntityRecipe::from_recipe(recipe, &items, &tags) {
             Some(cached) => {
                 ec.insert(cached);
                 info!("cached recipe {:?}", recipe);
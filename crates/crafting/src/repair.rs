@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+use engine::items::{inventory::Inventory, metadata::ItemMetadata, ItemResources, ItemStack};
+use interfaces::scheduling::LevelSystemSet;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    build_tag_map, stations::NearbyCraftingStations, CachedIngredient, ItemTagDefinition,
+    RecipeIngredient,
+};
+
+pub struct RepairPlugin;
+
+impl Plugin for RepairPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<RepairRecipe>()
+            .add_event::<RepairEvent>()
+            .add_systems(FixedUpdate, repair.in_set(LevelSystemSet::Tick))
+            .add_systems(
+                FixedPreUpdate,
+                cache_repair_recipes.run_if(resource_exists::<ItemResources>),
+            );
+    }
+}
+
+//restores durability to a damaged item in exchange for some material
+#[derive(Default, Clone, Debug, PartialEq, Eq, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct RepairRecipe {
+    pub item: RecipeIngredient,
+    pub material: (RecipeIngredient, u32),
+    //durability restored per repair
+    pub amount: u32,
+    pub station: Option<String>,
+}
+
+#[derive(Event)]
+pub struct RepairEvent {
+    pub crafter: Entity,
+    //entity with the RepairRecipe and CachedRepairRecipe
+    pub recipe: Entity,
+    //slot in the crafter's inventory holding the item to repair
+    pub inventory_slot: usize,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct CachedRepairRecipe {
+    pub items: Vec<Entity>,
+    pub material: CachedIngredient,
+    pub amount: u32,
+    pub station: Option<String>,
+}
+
+impl CachedRepairRecipe {
+    pub fn has_station(&self, nearby: Option<&NearbyCraftingStations>) -> bool {
+        match &self.station {
+            None => true,
+            Some(station) => nearby.is_some_and(|n| n.contains(station)),
+        }
+    }
+
+    //true if this recipe repairs the stack and it has durability to restore
+    pub fn repairs(&self, stack: ItemStack, meta: Option<&ItemMetadata>) -> bool {
+        self.items.contains(&stack.id)
+            && meta
+                .and_then(|meta| meta.durability)
+                .is_some_and(|durability| durability.is_damaged())
+    }
+
+    pub fn has_material(&self, inventory: &Inventory) -> bool {
+        self.material.number_in(inventory) >= self.material.count
+    }
+}
+
+fn repair(
+    mut reader: EventReader<RepairEvent>,
+    mut crafter_query: Query<(&mut Inventory, Option<&NearbyCraftingStations>)>,
+    recipe_query: Query<&CachedRepairRecipe>,
+    mut meta_query: Query<&mut ItemMetadata>,
+) {
+    for RepairEvent {
+        crafter,
+        recipe,
+        inventory_slot,
+    } in reader.read()
+    {
+        let Ok((mut inventory, nearby)) = crafter_query.get_mut(*crafter) else {
+            warn!("trying to repair from something without an inventory");
+            continue;
+        };
+        let Ok(cached) = recipe_query.get(*recipe) else {
+            warn!("trying to use an invalid repair recipe");
+            continue;
+        };
+        if !cached.has_station(nearby) {
+            info!("not near a {:?}", cached.station);
+            continue;
+        }
+        let Some(stack) = inventory.get(*inventory_slot) else {
+            continue;
+        };
+        let Some(mut meta) = stack.meta.and_then(|meta| meta_query.get_mut(meta).ok()) else {
+            info!("item has no durability to repair");
+            continue;
+        };
+        if !cached.repairs(stack, Some(&*meta)) || !cached.has_material(&inventory) {
+            info!("couldn't repair item");
+            continue;
+        }
+        cached.material.remove_from(&mut inventory);
+        if let Some(durability) = meta.durability.as_mut() {
+            durability.repair(cached.amount);
+        }
+        info!("repaired item");
+    }
+}
+
+fn cache_repair_recipes(
+    query: Query<(Entity, &RepairRecipe), Without<CachedRepairRecipe>>,
+    tag_query: Query<&ItemTagDefinition>,
+    mut commands: Commands,
+    items: Res<ItemResources>,
+) {
+    if query.is_empty() {
+        return;
+    }
+    let tags = build_tag_map(&tag_query, &items);
+    for (entity, recipe) in query.iter() {
+        let Some(mut ec) = commands.get_entity(entity) else {
+            warn!("adding cached repair recipe to invalid entity somehow");
+            continue;
+        };
+        let (material, count) = &recipe.material;
+        match (
+            recipe.item.lookup(&items, &tags),
+            material.lookup(&items, &tags),
+        ) {
+            (Some(repaired), Some(material_items)) => {
+                ec.insert(CachedRepairRecipe {
+                    items: repaired,
+                    material: CachedIngredient {
+                        items: material_items,
+                        count: *count,
+                    },
+                    amount: recipe.amount,
+                    station: recipe.station.clone(),
+                });
+                info!("cached repair recipe {:?}", recipe);
+            }
+            _ => warn!("failed to lookup items for repair recipe {:?}", recipe),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use ahash::HashSet;
+use bevy::prelude::*;
+use engine::actors::Player;
+use interfaces::scheduling::LevelSystemSet;
+use serde::{Deserialize, Serialize};
+use util::LocalRepeatingTimer;
+use world::{block::BlockCoord, level::Level};
+
+pub struct CraftingStationPlugin;
+
+impl Plugin for CraftingStationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CraftingStation>().add_systems(
+            FixedUpdate,
+            (add_nearby_stations, update_nearby_stations)
+                .chain()
+                .in_set(LevelSystemSet::PreTick),
+        );
+    }
+}
+
+//put on usable blocks. unlocks recipes with a matching station category for crafters in range
+#[derive(Default, Clone, Debug, PartialEq, Eq, Component, Reflect, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct CraftingStation {
+    pub category: String,
+}
+
+//in blocks, on each axis
+pub const STATION_RANGE: i32 = 4;
+const STATION_CHECK_INTERVAL_MS: u64 = 250;
+
+//station categories in range of the crafter
+#[derive(Component, Default, Debug)]
+pub struct NearbyCraftingStations {
+    categories: HashSet<String>,
+}
+
+impl NearbyCraftingStations {
+    pub fn contains(&self, category: &str) -> bool {
+        self.categories.contains(category)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.categories.iter()
+    }
+}
+
+fn add_nearby_stations(
+    query: Query<Entity, (With<Player>, Without<NearbyCraftingStations>)>,
+    mut commands: Commands,
+) {
+    for entity in query.iter() {
+        commands
+            .entity(entity)
+            .insert(NearbyCraftingStations::default());
+    }
+}
+
+fn update_nearby_stations(
+    mut timer: Local<LocalRepeatingTimer<{ STATION_CHECK_INTERVAL_MS }>>,
+    time: Res<Time>,
+    mut query: Query<(&GlobalTransform, &mut NearbyCraftingStations)>,
+    station_query: Query<&CraftingStation>,
+    level: Res<Level>,
+) {
+    timer.tick(time.delta());
+    if !timer.just_finished() {
+        return;
+    }
+    let mut found = HashSet::default();
+    for (gtf, mut nearby) in query.iter_mut() {
+        found.clear();
+        let center = BlockCoord::from(gtf.translation());
+        for x in -STATION_RANGE..=STATION_RANGE {
+            for y in -STATION_RANGE..=STATION_RANGE {
+                for z in -STATION_RANGE..=STATION_RANGE {
+                    if let Some(station) = level
+                        .get_block_entity(center + BlockCoord::new(x, y, z))
+                        .and_then(|block| station_query.get(block).ok())
+                    {
+                        found.insert(station.category.clone());
+                    }
+                }
+            }
+        }
+        //only touch the component when something changed so UI can rely on change detection
+        if found != nearby.categories {
+            nearby.categories.clone_from(&found);
+        }
+    }
+}
@@ -16,7 +16,12 @@ pub struct DashPlugin;
 
 impl Plugin for DashPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, do_dash.in_set(LevelSystemSet::Main));
+        app.add_systems(
+            FixedUpdate,
+            do_dash
+                .after(crate::controllers::player_controller::dash_player)
+                .in_set(LevelSystemSet::PreTick),
+        );
     }
 }
 
@@ -54,7 +54,7 @@ impl Stamina {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
 pub struct StaminaCost {
     cost: f32,
 }
@@ -1,11 +1,14 @@
 use core::f32;
 
 use bevy::prelude::*;
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
-use crate::items::{loot::CachedLootTable, SpawnDroppedItemEvent};
+use crate::items::{
+    inventory::Inventory,
+    loot::{CachedLootTable, LootContext, LootContextParam, LootRng},
+    SpawnDroppedItemEvent,
+};
 
-use physics::movement::{Mass, Velocity};
 use world::atmosphere::DayStartedEvent;
 
 use super::*;
@@ -17,12 +20,7 @@ impl Plugin for DamagePlugin {
         app.add_systems(
             //todo - move to posttick
             PostUpdate,
-            (
-                kill_on_sunrise,
-                process_attacks,
-                (update_health, apply_knockback),
-            )
-                .chain(),
+            (kill_on_sunrise, process_attacks, update_health).chain(),
         )
         .add_observer(do_death);
         app.add_event::<TriggerDamageEvent>();
@@ -64,6 +62,7 @@ fn process_attacks(
             },
             knockback_impulse: attack.knockback,
             hit_location: gtf.translation(),
+            melee: attack.melee,
         }));
     }
 }
@@ -116,20 +115,6 @@ fn update_health(
     }
 }
 
-fn apply_knockback(
-    mut reader: EventReader<AttackEvent>,
-    mut query: Query<(&mut Velocity, Option<&Mass>)>,
-) {
-    for AttackEvent {
-        target, knockback, ..
-    } in reader.read()
-    {
-        if let Ok((mut v, opt_mass)) = query.get_mut(*target) {
-            opt_mass.unwrap_or_default().add_impulse(*knockback, &mut v);
-        }
-    }
-}
-
 pub fn do_death(
     death_trigger: Trigger<DeathEvent>,
     death_type: Query<(
@@ -146,12 +131,23 @@ pub fn do_death(
         Option<&CachedLootTable<Entity>>,
     )>,
     parent_query: Query<&Combatant>,
+    inventory_query: Query<&Inventory>,
+    loot_params: LootContextParam,
+    mut loot_rng: ResMut<LootRng>,
     mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
     mut commands: Commands,
 ) {
-    let mut rng = thread_rng();
+    let rng = &mut loot_rng.0;
     let event = death_trigger.event();
     let dying_entity = event.final_blow.target;
+    let held_item = inventory_query
+        .get(event.final_blow.attacker)
+        .ok()
+        .and_then(|inventory| inventory.selected_item_entity());
+    let loot_context = |position: Vec3| LootContext {
+        held_item,
+        ..loot_params.at(position)
+    };
     //todo - this is really inefficient. relations!!
     //  or just maintain a list of children for each combatant (bleh)
     for (child_entity, tf, combatant, death, drops) in child_query.iter() {
@@ -161,7 +157,8 @@ pub fn do_death(
                 tf.translation,
                 death,
                 drops,
-                &mut rng,
+                &loot_context(tf.translation),
+                rng,
                 &mut drop_writer,
                 &mut commands,
             );
@@ -174,7 +171,8 @@ pub fn do_death(
             tf.translation,
             death,
             drops,
-            &mut rng,
+            &loot_context(tf.translation),
+            rng,
             &mut drop_writer,
             &mut commands,
         );
@@ -186,12 +184,13 @@ fn entity_die(
     position: Vec3,
     death: &DeathInfo,
     drops: Option<&CachedLootTable<Entity>>,
+    loot_context: &LootContext,
     rng: &mut impl Rng,
     drop_writer: &mut EventWriter<SpawnDroppedItemEvent>,
     commands: &mut Commands,
 ) {
     if let Some(loot) = drops {
-        loot.drop_items(position, drop_writer, rng);
+        loot.drop_items(position, loot_context, drop_writer, rng);
     }
     match death.death_type {
         DeathType::Default => commands.entity(entity).despawn_recursive(),
@@ -223,6 +222,7 @@ fn kill_on_sunrise(
                 dtype: DamageType::HPRemoval,
             },
             knockback: Vec3::ZERO,
+            melee: false,
         });
     }
 }
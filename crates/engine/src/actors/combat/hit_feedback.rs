@@ -0,0 +1,255 @@
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+
+use interfaces::scheduling::LevelSystemSet;
+use physics::movement::{Mass, Velocity};
+
+use crate::{actors::LocalPlayer, camera::MainCamera, effects::camera::CameraShake};
+
+use super::{AttackEvent, DamageTakenEvent};
+
+//everything that makes a hit feel like a hit: hit-stop, knockback and camera shake
+pub struct HitFeedbackPlugin;
+
+impl Plugin for HitFeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                start_hit_feedback,
+                start_knockback,
+                slow_hit_stopped,
+                apply_knockback_curves,
+            )
+                .chain()
+                .in_set(LevelSystemSet::PreTick),
+        )
+        .add_systems(
+            FixedUpdate,
+            resume_hit_stopped.in_set(LevelSystemSet::PostTick),
+        )
+        .insert_resource(HitFeedbackSettings::default());
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct HitFeedbackSettings {
+    //ticks the target and melee attacker are slowed down for after a hit
+    pub hit_stop_ticks: u32,
+    //how fast hit-stopped entities move, 0 freezes them
+    pub hit_stop_time_scale: f32,
+    //camera trauma the local player gets per point of damage taken
+    pub trauma_per_damage: f32,
+    pub max_trauma_per_hit: f32,
+}
+
+impl Default for HitFeedbackSettings {
+    fn default() -> Self {
+        Self {
+            hit_stop_ticks: 4,
+            hit_stop_time_scale: 0.1,
+            trauma_per_damage: 0.15,
+            max_trauma_per_hit: 0.6,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum KnockbackFalloff {
+    //all at once
+    Impulse,
+    //the same push every tick
+    Constant,
+    //strongest at the start, fading out linearly
+    #[default]
+    Linear,
+    //strongest at the start, fading out quickly
+    Quadratic,
+}
+
+impl KnockbackFalloff {
+    //fraction of the knockback delivered after progress (0-1) through the curve
+    pub fn delivered(self, progress: f32) -> f32 {
+        let t = progress.clamp(0.0, 1.0);
+        match self {
+            KnockbackFalloff::Impulse => {
+                if t > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            KnockbackFalloff::Constant => t,
+            KnockbackFalloff::Linear => 1.0 - (1.0 - t).powi(2),
+            KnockbackFalloff::Quadratic => 1.0 - (1.0 - t).powi(3),
+        }
+    }
+}
+
+//how an entity is pushed when it's attacked. the knockback impulse is spread over ticks following falloff
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct KnockbackCurve {
+    pub falloff: KnockbackFalloff,
+    pub ticks: u32,
+    pub multiplier: f32,
+}
+
+impl Default for KnockbackCurve {
+    fn default() -> Self {
+        Self {
+            falloff: KnockbackFalloff::default(),
+            ticks: 6,
+            multiplier: 1.0,
+        }
+    }
+}
+
+impl KnockbackCurve {
+    pub fn impulse() -> Self {
+        Self {
+            falloff: KnockbackFalloff::Impulse,
+            ticks: 1,
+            multiplier: 1.0,
+        }
+    }
+
+    //share of the knockback applied on the given tick, the shares add up to 1
+    pub fn tick_fraction(self, tick: u32) -> f32 {
+        let ticks = self.ticks.max(1) as f32;
+        self.falloff.delivered((tick + 1) as f32 / ticks)
+            - self.falloff.delivered(tick as f32 / ticks)
+    }
+}
+
+//knockbacks still being applied: (total impulse, curve, ticks applied so far)
+#[derive(Component, Default)]
+pub struct ActiveKnockback(Vec<(Vec3, KnockbackCurve, u32)>);
+
+//entity moves at time_scale speed until the hit-stop wears off
+#[derive(Component, Clone, Copy, Debug)]
+pub struct HitStop {
+    pub ticks_left: u32,
+    pub time_scale: f32,
+    //velocity the entity would have without the hit-stop
+    velocity: Vec3,
+}
+
+//hit-stop and camera shake only happen when damage is actually taken
+fn start_hit_feedback(
+    mut reader: EventReader<DamageTakenEvent>,
+    mut stop_query: Query<(&Velocity, Option<&mut HitStop>)>,
+    local_player_query: Query<(), With<LocalPlayer>>,
+    mut camera_query: Query<&mut CameraShake>,
+    camera: Res<MainCamera>,
+    settings: Res<HitFeedbackSettings>,
+    mut new_stops: Local<EntityHashMap<HitStop>>,
+    mut commands: Commands,
+) {
+    new_stops.clear();
+    for event in reader.read() {
+        if event.damage.amount <= 0.0 {
+            continue;
+        }
+        //a shooter doesn't feel their projectile landing somewhere else
+        let attacker = event.melee.then_some(event.attacker);
+        for entity in std::iter::once(event.target).chain(attacker) {
+            match stop_query.get_mut(entity) {
+                Ok((_, Some(mut stop))) => {
+                    stop.ticks_left = stop.ticks_left.max(settings.hit_stop_ticks);
+                }
+                Ok((v, None)) => {
+                    new_stops.entry(entity).or_insert(HitStop {
+                        ticks_left: settings.hit_stop_ticks,
+                        time_scale: settings.hit_stop_time_scale,
+                        velocity: v.0,
+                    });
+                }
+                Err(_) => {}
+            }
+        }
+        if local_player_query.contains(event.target) {
+            if let Ok(mut shake) = camera_query.get_mut(camera.0) {
+                shake.add_trauma(
+                    (event.damage.amount * settings.trauma_per_damage)
+                        .min(settings.max_trauma_per_hit),
+                );
+            }
+        }
+    }
+    for (entity, stop) in new_stops.drain() {
+        if let Some(mut ec) = commands.get_entity(entity) {
+            ec.try_insert(stop);
+        }
+    }
+}
+
+//every attack pushes its target, even if the damage is blocked or the target is invulnerable
+fn start_knockback(
+    mut reader: EventReader<AttackEvent>,
+    mut knockback_query: Query<(Option<&KnockbackCurve>, Option<&mut ActiveKnockback>)>,
+    mut new_knockbacks: Local<EntityHashMap<ActiveKnockback>>,
+    mut commands: Commands,
+) {
+    new_knockbacks.clear();
+    for event in reader.read() {
+        if event.knockback == Vec3::ZERO {
+            continue;
+        }
+        if let Ok((curve, active)) = knockback_query.get_mut(event.target) {
+            let knockback = (event.knockback, curve.copied().unwrap_or_default(), 0);
+            match active {
+                Some(mut active) => active.0.push(knockback),
+                None => new_knockbacks
+                    .entry(event.target)
+                    .or_default()
+                    .0
+                    .push(knockback),
+            }
+        }
+    }
+    for (entity, knockback) in new_knockbacks.drain() {
+        if let Some(mut ec) = commands.get_entity(entity) {
+            ec.try_insert(knockback);
+        }
+    }
+}
+
+fn slow_hit_stopped(mut query: Query<(&mut Velocity, &HitStop)>) {
+    for (mut v, stop) in query.iter_mut() {
+        v.0 = stop.velocity * stop.time_scale;
+    }
+}
+
+//whatever changed the velocity during the tick only has time_scale of its effect
+fn resume_hit_stopped(
+    mut query: Query<(Entity, &mut Velocity, &mut HitStop)>,
+    mut commands: Commands,
+) {
+    for (entity, mut v, mut stop) in query.iter_mut() {
+        let change = v.0 - stop.velocity * stop.time_scale;
+        stop.velocity += change * stop.time_scale;
+        stop.ticks_left = stop.ticks_left.saturating_sub(1);
+        if stop.ticks_left == 0 {
+            v.0 = stop.velocity;
+            commands.entity(entity).remove::<HitStop>();
+        }
+    }
+}
+
+//knockback waits for hit-stop to end, so the hit lands before the target is sent flying
+fn apply_knockback_curves(
+    mut query: Query<(&mut Velocity, &mut ActiveKnockback, Option<&Mass>), Without<HitStop>>,
+) {
+    for (mut v, mut active, opt_mass) in query.iter_mut() {
+        let mass = opt_mass.copied().unwrap_or_default();
+        for (impulse, curve, tick) in active.0.iter_mut() {
+            mass.add_impulse(
+                *impulse * curve.multiplier * curve.tick_fraction(*tick),
+                &mut v,
+            );
+            *tick += 1;
+        }
+        active
+            .0
+            .retain(|(_, curve, tick)| *tick < curve.ticks.max(1));
+    }
+}
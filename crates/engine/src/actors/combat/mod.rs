@@ -5,8 +5,8 @@ use team::*;
 
 pub mod damage;
 pub mod death_effects;
+pub mod hit_feedback;
 pub mod projectile;
-#[macro_use]
 pub mod team;
 
 use interfaces::scheduling::*;
@@ -21,7 +21,9 @@ impl Plugin for CombatPlugin {
         app.add_plugins((
             projectile::ProjectilePlugin,
             death_effects::DeathEffectsPlugin,
+            hit_feedback::HitFeedbackPlugin,
             damage::DamagePlugin,
+            team::TeamPlugin,
         ))
         .add_event::<AttackEvent>()
         .add_event::<DeathEvent>()
@@ -30,8 +32,7 @@ impl Plugin for CombatPlugin {
         .add_systems(PreUpdate, purge_despawned_targets)
         .add_systems(
             FixedUpdate,
-            (update_aggro_on_player, all_teams_system!(do_contact_damage))
-                .in_set(LevelSystemSet::Tick),
+            (update_aggro_on_player, do_contact_damage).in_set(LevelSystemSet::Tick),
         )
         .add_systems(PostUpdate, update_combat_relationships)
         .insert_resource(CombatantRelationships::default())
@@ -43,11 +44,11 @@ impl Plugin for CombatPlugin {
 pub struct LevelEntity(Entity);
 
 #[derive(Bundle, Clone)]
-pub struct CombatantBundle<T: team::Team> {
+pub struct CombatantBundle {
     pub combatant: Combatant,
     pub death_info: DeathInfo,
     pub invulnerability: Invulnerability,
-    pub team: T,
+    pub team: TeamId,
 }
 
 #[derive(Component, Clone)]
@@ -65,13 +66,13 @@ impl ContactDamage {
     }
 }
 
-impl<T: team::Team> Default for CombatantBundle<T> {
+impl Default for CombatantBundle {
     fn default() -> Self {
         Self {
             combatant: Combatant::new(10.0, 0.0),
             death_info: DeathInfo::default(),
             invulnerability: Invulnerability::default(),
-            team: T::default(),
+            team: TeamId::FREE_FOR_ALL,
         }
     }
 }
@@ -384,6 +385,8 @@ pub struct AttackEvent {
     pub target: Entity,
     pub damage: Damage,
     pub knockback: Vec3,
+    //the attacker landed the hit itself, rather than through a projectile or explosion
+    pub melee: bool,
 }
 
 //entities may be despawned depending on event ordering and death behavior
@@ -395,6 +398,7 @@ pub struct DamageTakenEvent {
     pub damage: Damage,
     pub knockback_impulse: Vec3,
     pub hit_location: Vec3,
+    pub melee: bool,
 }
 
 #[derive(Clone, Copy, Event)]
@@ -553,22 +557,24 @@ fn create_level_entity(mut commands: Commands) {
     commands.insert_resource(LevelEntity(entity));
 }
 
-fn do_contact_damage<T: Team>(
-    attacker_query: Query<(Entity, &ContactDamage, &GlobalTransform, &Aabb), With<T>>,
-    target_query: Query<(Entity, &GlobalTransform, &Aabb), T::Targets>,
+fn do_contact_damage(
+    attacker_query: Query<(Entity, &ContactDamage, &GlobalTransform, &Aabb, &TeamId)>,
+    target_query: Query<(Entity, &GlobalTransform, &Aabb, &TeamId)>,
+    relationships: Res<TeamRelationships>,
     mut attack_writer: EventWriter<AttackEvent>,
 ) {
     const AABB_SCALE: Vec3 = Vec3::splat(1.1);
-    for (entity, cd, gtf, aabb) in attacker_query.iter() {
-        for (target_entity, target_gtf, _) in
+    for (entity, cd, gtf, aabb, team) in attacker_query.iter() {
+        for (target_entity, target_gtf, _, _) in
             target_query
                 .iter()
-                .filter(move |(_, target_gtf, target_aabb)| {
-                    target_aabb.intersects_aabb(
-                        target_gtf.translation(),
-                        aabb.scale(AABB_SCALE),
-                        gtf.translation(),
-                    )
+                .filter(|(_, target_gtf, target_aabb, target_team)| {
+                    relationships.is_hostile(*team, **target_team)
+                        && target_aabb.intersects_aabb(
+                            target_gtf.translation(),
+                            aabb.scale(AABB_SCALE),
+                            gtf.translation(),
+                        )
                 })
         {
             //they intersect
@@ -577,6 +583,7 @@ fn do_contact_damage<T: Team>(
                 target: target_entity,
                 damage: cd.damage,
                 knockback: (target_gtf.translation() - gtf.translation()) * cd.knockback,
+                melee: true,
             });
         }
     }
@@ -1,13 +1,18 @@
 use std::time::Duration;
 
-use crate::all_teams_system;
 use bevy::prelude::*;
 use interfaces::scheduling::*;
 use physics::{
+    ccd::ContinuousCollision,
     collision::{Aabb, CollidingBlocks},
-    movement::Velocity,
+    entity_collision::CollisionLayers,
+    movement::{GravityMult, Restitution, Velocity},
+    spatial::SpatialIndex,
+};
+use world::{
+    block::BlockCoord,
+    events::{DealBlockDamageEvent, ExplosionEvent},
 };
-use world::events::DealBlockDamageEvent;
 
 use super::*;
 
@@ -18,12 +23,17 @@ impl Plugin for ProjectilePlugin {
         app.add_systems(
             FixedUpdate,
             (
-                all_teams_system!(test_projectile_hit),
+                init_projectile_behaviors,
+                (acquire_homing_targets, home_projectiles).chain(),
+                test_projectile_hit,
                 update_projectile_lifetime,
             )
                 .in_set(LevelSystemSet::PreTick)
                 .chain(),
-        );
+        )
+        .register_type::<ProjectileBehaviors>()
+        .register_type::<ProjectileHoming>()
+        .register_type::<ProjectileExplosion>();
     }
 }
 
@@ -55,10 +65,66 @@ pub struct Projectile {
 #[derive(Component)]
 pub struct ProjectileSpawnedInEntity(pub Entity);
 
+//declarative flight and hit behaviors, usually loaded from item data
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+pub struct ProjectileBehaviors {
+    //overrides the projectile's GravityMult for ballistic arcs. None keeps whatever the spawner set
+    pub gravity: Option<f32>,
+    //number of extra entities the projectile can pass through before its hit behavior kicks in
+    pub pierce: u32,
+    pub homing: Option<ProjectileHoming>,
+    //number of times the projectile can bounce off of terrain before its hit behavior kicks in
+    pub bounces: u32,
+    //only used if bounces > 0
+    pub restitution: f32,
+    //detonates when the projectile is despawned by a hit or by running out of lifetime
+    pub explosion: Option<ProjectileExplosion>,
+}
+
+impl ProjectileBehaviors {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+pub struct ProjectileHoming {
+    //radians per second
+    pub turn_rate: f32,
+    //how far away the projectile will look for a target if it doesn't have one
+    pub range: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+pub struct ProjectileExplosion {
+    //terrain is destroyed within this radius (see ExplosionEvent)
+    pub radius: f32,
+    //dealt to every hostile entity within the radius
+    pub damage: Damage,
+    pub knockback: f32,
+}
+
+//how much of the projectile's pierce and bounce budget has been used
+#[derive(Component, Default)]
+pub struct ProjectileHitState {
+    pub pierced: u32,
+    pub bounced: u32,
+    //entities that were pierced, so we don't hit them again on the next tick
+    pub hit_entities: Vec<Entity>,
+}
+
 #[derive(Bundle)]
 pub struct ProjectileBundle {
     pub projectile: Projectile,
     pub inside_entity: ProjectileSpawnedInEntity,
+    pub behaviors: ProjectileBehaviors,
+    pub hit_state: ProjectileHitState,
+    //fast projectiles would otherwise skip over entities between ticks
+    pub sweep: ContinuousCollision,
+    pub layers: CollisionLayers,
 }
 
 impl ProjectileBundle {
@@ -67,18 +133,194 @@ impl ProjectileBundle {
         Self {
             inside_entity: ProjectileSpawnedInEntity(projectile.owner),
             projectile,
+            behaviors: default(),
+            hit_state: default(),
+            sweep: default(),
+            layers: CollisionLayers::PROJECTILE,
+        }
+    }
+    pub fn with_behaviors(mut self, behaviors: ProjectileBehaviors) -> Self {
+        self.behaviors = behaviors;
+        self
+    }
+}
+
+fn init_projectile_behaviors(
+    query: Query<(Entity, &ProjectileBehaviors, &Projectile), Added<ProjectileBehaviors>>,
+    owner_query: Query<&AggroTargets, Without<Projectile>>,
+    mut commands: Commands,
+) {
+    for (entity, behaviors, projectile) in query.iter() {
+        let mut ec = commands.entity(entity);
+        if let Some(gravity) = behaviors.gravity {
+            ec.insert(GravityMult::new(gravity));
+        }
+        if behaviors.bounces > 0 {
+            ec.insert(Restitution(behaviors.restitution));
+        }
+        if behaviors.homing.is_some() {
+            //start off chasing whatever the owner is chasing
+            let targets = owner_query
+                .get(projectile.owner)
+                .ok()
+                .and_then(|aggro| aggro.current_target())
+                .map(|target| vec![(target, 0)])
+                .unwrap_or_default();
+            ec.insert(AggroTargets::new(targets));
         }
     }
 }
 
+fn acquire_homing_targets(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &ProjectileBehaviors,
+        &Projectile,
+        &TeamId,
+        &mut AggroTargets,
+    )>,
+    target_query: Query<
+        (Entity, &GlobalTransform, &TeamId),
+        (With<Combatant>, Without<Projectile>),
+    >,
+    index: Res<SpatialIndex>,
+    relationships: Res<TeamRelationships>,
+) {
+    for (entity, tf, behaviors, projectile, team, mut aggro) in query.iter_mut() {
+        let Some(homing) = behaviors.homing else {
+            continue;
+        };
+        if aggro.current_target().is_some() {
+            continue;
+        }
+        let pos = tf.translation();
+        let closest = index
+            .query_radius(pos, homing.range)
+            .filter_map(|target| target_query.get(target).ok())
+            .filter(|(target, _, target_team)| {
+                *target != entity
+                    && *target != projectile.owner
+                    && relationships.is_hostile(*team, **target_team)
+            })
+            .map(|(target, target_tf, _)| (target, target_tf.translation().distance_squared(pos)))
+            .filter(|(_, sqr_dist)| *sqr_dist <= homing.range * homing.range)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((target, _)) = closest {
+            aggro.add_target(target, 0);
+        }
+    }
+}
+
+fn home_projectiles(
+    mut query: Query<(
+        &GlobalTransform,
+        &ProjectileBehaviors,
+        &AggroTargets,
+        &mut Velocity,
+    )>,
+    target_query: Query<&GlobalTransform>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_secs();
+    for (tf, behaviors, aggro, mut v) in query.iter_mut() {
+        let Some(homing) = behaviors.homing else {
+            continue;
+        };
+        let Some(target_tf) = aggro
+            .current_target()
+            .and_then(|t| target_query.get(t).ok())
+        else {
+            continue;
+        };
+        let speed = v.0.length();
+        let (Some(dir), Some(desired)) = (
+            v.0.try_normalize(),
+            (target_tf.translation() - tf.translation()).try_normalize(),
+        ) else {
+            continue;
+        };
+        let angle = dir.angle_between(desired);
+        let max_turn = homing.turn_rate * dt;
+        let new_dir = if angle <= max_turn {
+            desired
+        } else {
+            Quat::IDENTITY.slerp(Quat::from_rotation_arc(dir, desired), max_turn / angle) * dir
+        };
+        v.0 = new_dir * speed;
+    }
+}
+
+//sends the terrain explosion and damages every hostile entity in range
+fn detonate(
+    explosion: &ProjectileExplosion,
+    origin: Vec3,
+    projectile: &Projectile,
+    team: TeamId,
+    relationships: &TeamRelationships,
+    object_query: &Query<(Entity, &GlobalTransform, &Aabb, &TeamId)>,
+    index: &SpatialIndex,
+    explosion_writer: &mut EventWriter<ExplosionEvent>,
+    attack_writer: &mut EventWriter<AttackEvent>,
+) {
+    explosion_writer.send(ExplosionEvent {
+        radius: explosion.radius,
+        origin: BlockCoord::from(origin),
+    });
+    for (target, target_tf, _, target_team) in index
+        .query_radius(origin, explosion.radius)
+        .filter_map(|entity| object_query.get(entity).ok())
+    {
+        if !relationships.is_hostile(team, *target_team) {
+            continue;
+        }
+        let delta = target_tf.translation() - origin;
+        if delta.length_squared() > explosion.radius * explosion.radius {
+            continue;
+        }
+        attack_writer.send(AttackEvent {
+            attacker: projectile.owner,
+            target,
+            damage: explosion.damage,
+            knockback: delta.normalize_or_zero() * explosion.knockback,
+            melee: false,
+        });
+    }
+}
+
 fn update_projectile_lifetime(
-    query: Query<(Entity, &Projectile)>,
+    query: Query<(
+        Entity,
+        &Projectile,
+        &GlobalTransform,
+        Option<&ProjectileBehaviors>,
+        Option<&TeamId>,
+    )>,
     mut commands: Commands,
     time: Res<Time>,
+    object_query: Query<(Entity, &GlobalTransform, &Aabb, &TeamId)>,
+    index: Res<SpatialIndex>,
+    relationships: Res<TeamRelationships>,
+    mut explosion_writer: EventWriter<ExplosionEvent>,
+    mut attack_writer: EventWriter<AttackEvent>,
 ) {
     let curr_time = time.elapsed();
-    for (entity, proj) in query.iter() {
+    for (entity, proj, tf, behaviors, team) in query.iter() {
         if proj.despawn_time < curr_time {
+            if let Some(explosion) = behaviors.and_then(|b| b.explosion.as_ref()) {
+                detonate(
+                    explosion,
+                    tf.translation(),
+                    proj,
+                    //projectiles without a team hurt everyone
+                    team.copied().unwrap_or(TeamId::FREE_FOR_ALL),
+                    &relationships,
+                    &object_query,
+                    &index,
+                    &mut explosion_writer,
+                    &mut attack_writer,
+                );
+            }
             if let Some(action) = &proj.on_hit {
                 action(
                     ProjectileHit {
@@ -94,28 +336,78 @@ fn update_projectile_lifetime(
 }
 
 //todo - add collision events
-fn test_projectile_hit<T: Team>(
-    query: Query<
-        (
-            Entity,
-            &GlobalTransform,
-            &Projectile,
-            Option<&Velocity>,
-            Option<&ProjectileSpawnedInEntity>,
-            &CollidingBlocks,
-            &Aabb,
-        ),
-        With<T>,
-    >,
+fn test_projectile_hit(
+    mut query: Query<(
+        Entity,
+        &GlobalTransform,
+        &Projectile,
+        Option<&Velocity>,
+        Option<&ProjectileSpawnedInEntity>,
+        &CollidingBlocks,
+        &Aabb,
+        &TeamId,
+        Option<&ProjectileBehaviors>,
+        Option<&mut ProjectileHitState>,
+        Option<&ContinuousCollision>,
+    )>,
     mut attack_writer: EventWriter<AttackEvent>,
     mut commands: Commands,
-    object_query: Query<(Entity, &GlobalTransform, &Aabb), T::Targets>,
+    object_query: Query<(Entity, &GlobalTransform, &Aabb, &TeamId)>,
+    index: Res<SpatialIndex>,
+    relationships: Res<TeamRelationships>,
     mut damage_writer: EventWriter<DealBlockDamageEvent>,
+    mut explosion_writer: EventWriter<ExplosionEvent>,
+    mut exclude: Local<Vec<Entity>>,
 ) {
-    for (proj_entity, tf, proj, v, opt_in_entity, colliding_blocks, aabb) in query.iter() {
-        let opt_hit_entity = test_box::<T>(tf.translation(), *aabb, &object_query, &[proj_entity]);
-        if opt_hit_entity.is_some() || !colliding_blocks.is_empty() {
-            let hit_blocks = colliding_blocks.iter().map(|&(coord, _, _)| coord);
+    for (
+        proj_entity,
+        tf,
+        proj,
+        v,
+        opt_in_entity,
+        colliding_blocks,
+        aabb,
+        team,
+        behaviors,
+        mut hit_state,
+        sweep,
+    ) in query.iter_mut()
+    {
+        let behaviors = behaviors.copied().unwrap_or_default();
+        exclude.clear();
+        exclude.push(proj_entity);
+        if let Some(state) = hit_state.as_ref() {
+            exclude.extend(state.hit_entities.iter().copied());
+        }
+        let mut hit_terrain = !colliding_blocks.is_empty();
+        let opt_hit_entity = match sweep {
+            //the first contact along last tick's path, so entities behind a wall aren't hit
+            Some(sweep) if sweep.delta != Vec3::ZERO => sweep
+                .sweep_entities(*aabb, &index, |entity| {
+                    let (_, gtf, col, target_team) = object_query.get(entity).ok()?;
+                    (!exclude.contains(&entity) && relationships.is_hostile(*team, *target_team))
+                        .then(|| (gtf.translation(), *col))
+                })
+                .map(|hit| {
+                    //the entity was reached before the terrain
+                    hit_terrain &= hit.toi >= sweep.terrain_toi();
+                    hit.entity
+                }),
+            _ => test_box(
+                tf.translation(),
+                *aabb,
+                *team,
+                &relationships,
+                &object_query,
+                &index,
+                &exclude,
+            ),
+        };
+        if opt_hit_entity.is_some() || hit_terrain {
+            let hit_blocks = colliding_blocks
+                .iter()
+                .filter(|_| hit_terrain)
+                .map(|&(coord, _, _)| coord);
             if let Some(&ProjectileSpawnedInEntity(ignore)) = opt_in_entity {
                 //don't want to hit the entity we spawn in
                 if opt_hit_entity.map(|t| ignore == t).unwrap_or(false) {
@@ -128,6 +420,7 @@ fn test_projectile_hit<T: Team>(
                     target: hit,
                     damage: proj.damage,
                     knockback: v.map_or(Vec3::ZERO, |v| v.0 * proj.knockback_mult),
+                    melee: false,
                 });
             }
 
@@ -140,6 +433,23 @@ fn test_projectile_hit<T: Team>(
                     &mut commands,
                 );
             }
+            //piercing and bouncing use up their budget before the normal hit behavior happens
+            let absorbed = match (opt_hit_entity, hit_state.as_deref_mut()) {
+                (Some(hit), Some(state)) if state.pierced < behaviors.pierce => {
+                    state.pierced += 1;
+                    state.hit_entities.push(hit);
+                    true
+                }
+                (None, Some(state)) if state.bounced < behaviors.bounces => {
+                    state.bounced += 1;
+                    true
+                }
+                _ => false,
+            };
+            if absorbed {
+                continue;
+            }
+            //only the hit that stops the projectile damages terrain, bounces don't dig through walls
             for block_position in hit_blocks {
                 damage_writer.send(DealBlockDamageEvent {
                     block_position,
@@ -149,7 +459,22 @@ fn test_projectile_hit<T: Team>(
             }
 
             match proj.hit_behavior {
-                ProjecileHitBehavior::Despawn => commands.entity(proj_entity).despawn_recursive(),
+                ProjecileHitBehavior::Despawn => {
+                    if let Some(explosion) = behaviors.explosion.as_ref() {
+                        detonate(
+                            explosion,
+                            tf.translation(),
+                            proj,
+                            *team,
+                            &relationships,
+                            &object_query,
+                            &index,
+                            &mut explosion_writer,
+                            &mut attack_writer,
+                        );
+                    }
+                    commands.entity(proj_entity).despawn_recursive()
+                }
                 ProjecileHitBehavior::None => (),
             }
         } else if opt_hit_entity.is_none() {
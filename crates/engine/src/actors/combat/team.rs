@@ -1,144 +1,251 @@
-use bevy::{ecs::query::QueryFilter, prelude::*};
+use ahash::HashMap;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use physics::collision::{Aabb, BlockPhysics};
+use physics::{
+    collision::{Aabb, BlockPhysics},
+    spatial::SpatialIndex,
+};
 use world::{block::BlockCoord, level::Level};
 
 use super::Combatant;
 
-#[derive(Component, Clone, Copy, Default)]
-pub struct PlayerTeam;
+pub struct TeamPlugin;
 
-#[derive(Component, Clone, Copy, Default)]
-pub struct EnemyTeam;
+impl Plugin for TeamPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TeamRelationships::default())
+            .add_event::<SetTeamRelationshipEvent>()
+            .add_systems(PreUpdate, set_team_relationships)
+            .register_type::<TeamId>();
+    }
+}
 
-#[derive(Component, Clone, Copy, Default)]
-pub struct FreeForAllTeam;
+//teams are data, not types. an entity's team can be swapped at runtime (for example, charming an enemy)
+//  by inserting a new TeamId, and how teams feel about each other lives in TeamRelationships
+#[derive(
+    Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect, Serialize, Deserialize,
+)]
+#[reflect(Component)]
+pub struct TeamId(pub u32);
 
-//when adding a new team, add to macro below
-pub trait Team: Component + Clone + Copy + Default + Send + Sync {
-    type Targets: QueryFilter;
-    type Allies: QueryFilter;
+impl TeamId {
+    pub const PLAYER: TeamId = TeamId(0);
+    pub const ENEMY: TeamId = TeamId(1);
+    //hostile to everyone, including itself
+    pub const FREE_FOR_ALL: TeamId = TeamId(2);
+    //first id that isn't reserved by a builtin team
+    pub const FIRST_CUSTOM: TeamId = TeamId(3);
 }
 
-impl Team for PlayerTeam {
-    type Targets = Or<(With<EnemyTeam>, With<FreeForAllTeam>)>;
-    type Allies = With<PlayerTeam>;
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect, Serialize, Deserialize)]
+pub enum TeamRelationship {
+    #[default]
+    Hostile,
+    Neutral,
+    Allied,
 }
-impl Team for EnemyTeam {
-    type Targets = Or<(With<PlayerTeam>, With<FreeForAllTeam>)>;
-    type Allies = With<EnemyTeam>;
-}
-impl Team for FreeForAllTeam {
-    type Targets = Or<(With<PlayerTeam>, With<EnemyTeam>, With<FreeForAllTeam>)>;
-    type Allies = ();
+
+//relationships are symmetric, so (a,b) and (b,a) share an entry
+//if there is no entry, a team is allied with itself and hostile to everyone else
+#[derive(Resource)]
+pub struct TeamRelationships {
+    table: HashMap<(TeamId, TeamId), TeamRelationship>,
+    next_id: u32,
 }
 
-//for use when building app, for example:
-//  app.add_systems(FixedUpdate, all_teams_system!(do_contact_damage))
-//will expand the macro to a tuple with `do_contact_damage::<Team>` for all teams
-//note: have to import all teams in destination module
-#[macro_export]
-macro_rules! all_teams_system {
-    (
-        $name:ident
-    ) => {
-        (
-            $name::<PlayerTeam>,
-            $name::<EnemyTeam>,
-            $name::<FreeForAllTeam>,
-        )
-    };
+impl Default for TeamRelationships {
+    fn default() -> Self {
+        let mut relationships = Self {
+            table: HashMap::default(),
+            next_id: TeamId::FIRST_CUSTOM.0,
+        };
+        relationships.set(
+            TeamId::FREE_FOR_ALL,
+            TeamId::FREE_FOR_ALL,
+            TeamRelationship::Hostile,
+        );
+        relationships
+    }
 }
 
-//for use when building app, for example:
-//  all_teams_function(app, add_event, Event)
-//will expand the macro to a list of statements `app.add_event::<Event<Team>>();` for all teams
-//note: have to import all teams in destination module
-#[macro_export]
-macro_rules! all_teams_function {
-    (
-        $app:ident,
-        $function:ident,
-        $event:ident
-        $(,$args:expr)?
-    ) => {
-        $app.$function::<$event<PlayerTeam>>($($args)?);
-        $app.$function::<$event<EnemyTeam>>($($args)?);
-        $app.$function::<$event<FreeForAllTeam>>($($args)?);
-    };
+impl TeamRelationships {
+    fn key(a: TeamId, b: TeamId) -> (TeamId, TeamId) {
+        if a.0 <= b.0 {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    //creates a new team that uses the default relationships until told otherwise
+    pub fn add_team(&mut self) -> TeamId {
+        let id = TeamId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    pub fn get(&self, a: TeamId, b: TeamId) -> TeamRelationship {
+        match self.table.get(&Self::key(a, b)) {
+            Some(relationship) => *relationship,
+            None if a == b => TeamRelationship::Allied,
+            None => TeamRelationship::Hostile,
+        }
+    }
+
+    pub fn set(&mut self, a: TeamId, b: TeamId, relationship: TeamRelationship) {
+        self.table.insert(Self::key(a, b), relationship);
+    }
+
+    //goes back to the default relationship between a and b
+    pub fn reset(&mut self, a: TeamId, b: TeamId) {
+        self.table.remove(&Self::key(a, b));
+    }
+
+    pub fn is_hostile(&self, a: TeamId, b: TeamId) -> bool {
+        self.get(a, b) == TeamRelationship::Hostile
+    }
+
+    pub fn is_allied(&self, a: TeamId, b: TeamId) -> bool {
+        self.get(a, b) == TeamRelationship::Allied
+    }
 }
 
-pub trait AddTeam {
-    type TargetResultType;
-    type AllyResultType;
+//use this to change relationships from places that can't get the resource mutably (e.g. over the network)
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SetTeamRelationshipEvent {
+    pub a: TeamId,
+    pub b: TeamId,
+    //None resets to default
+    pub relationship: Option<TeamRelationship>,
+}
 
-    fn append_target<T>(self, t: T) -> Self::TargetResultType;
-    fn append_ally<T>(self, t: T) -> Self::AllyResultType;
+fn set_team_relationships(
+    mut reader: EventReader<SetTeamRelationshipEvent>,
+    mut relationships: ResMut<TeamRelationships>,
+) {
+    for SetTeamRelationshipEvent { a, b, relationship } in reader.read() {
+        match relationship {
+            Some(relationship) => relationships.set(*a, *b, *relationship),
+            None => relationships.reset(*a, *b),
+        }
+    }
 }
 
-pub fn get_targets_in_range<'a, T: Team>(
-    query: &'a Query<'a, 'a, (Entity, &'a Combatant, &'a GlobalTransform), T::Targets>,
+pub fn get_targets_in_range<'a>(
+    query: &'a Query<'a, 'a, (Entity, &'a Combatant, &'a GlobalTransform, &'a TeamId)>,
+    index: &'a SpatialIndex,
+    relationships: &'a TeamRelationships,
+    team: TeamId,
     origin: Vec3,
     range: f32,
 ) -> impl Iterator<Item = (Entity, &'a Combatant, &'a GlobalTransform)> {
     let sqr_dist = range * range;
-    query
-        .iter()
-        .filter(move |(_, _, gtf)| gtf.translation().distance_squared(origin) <= sqr_dist)
+    index
+        .query_radius(origin, range)
+        .filter_map(move |entity| query.get(entity).ok())
+        .filter(move |(_, _, gtf, target_team)| {
+            relationships.is_hostile(team, **target_team)
+                && gtf.translation().distance_squared(origin) <= sqr_dist
+        })
+        .map(|(entity, combatant, gtf, _)| (entity, combatant, gtf))
 }
 
-pub fn get_colliding_targets<'a, 'b: 'a, T: Team>(
-    query: &'b Query<'a, 'a, (Entity, &'a Combatant, &'a GlobalTransform, &'a Aabb), T::Targets>,
+pub fn get_colliding_targets<'a, 'b: 'a>(
+    query: &'b Query<
+        'a,
+        'a,
+        (
+            Entity,
+            &'a Combatant,
+            &'a GlobalTransform,
+            &'a Aabb,
+            &'a TeamId,
+        ),
+    >,
+    index: &'b SpatialIndex,
+    relationships: &'b TeamRelationships,
+    team: TeamId,
     origin: Vec3,
     aabb: Aabb,
     my_aabb_scale: f32,
 ) -> impl Iterator<Item = (Entity, &'b Combatant, &'b GlobalTransform, &'b Aabb)> {
-    query.iter().filter(move |(_, _, gtf, target_aabb)| {
-        target_aabb.intersects_aabb(
-            gtf.translation(),
-            aabb.scale(Vec3::ONE * my_aabb_scale),
-            origin,
-        )
-    })
+    let aabb = aabb.scale(Vec3::ONE * my_aabb_scale);
+    index
+        .query_aabb(aabb.world_min(origin), aabb.world_max(origin))
+        .filter_map(move |entity| query.get(entity).ok())
+        .filter(move |(_, _, gtf, target_aabb, target_team)| {
+            relationships.is_hostile(team, **target_team)
+                && target_aabb.intersects_aabb(gtf.translation(), aabb, origin)
+        })
+        .map(|(entity, combatant, gtf, aabb, _)| (entity, combatant, gtf, aabb))
 }
 
-pub fn get_allies_in_range<'a, T: Team>(
-    query: &'a Query<'a, 'a, (Entity, &'a Combatant, &'a GlobalTransform), T::Allies>,
+pub fn get_allies_in_range<'a>(
+    query: &'a Query<'a, 'a, (Entity, &'a Combatant, &'a GlobalTransform, &'a TeamId)>,
+    index: &'a SpatialIndex,
+    relationships: &'a TeamRelationships,
+    team: TeamId,
     origin: Vec3,
     range: f32,
 ) -> impl Iterator<Item = (Entity, &'a Combatant, &'a GlobalTransform)> {
     let sqr_dist = range * range;
-    query
-        .iter()
-        .filter(move |(_, _, gtf)| gtf.translation().distance_squared(origin) <= sqr_dist)
+    index
+        .query_radius(origin, range)
+        .filter_map(move |entity| query.get(entity).ok())
+        .filter(move |(_, _, gtf, target_team)| {
+            relationships.is_allied(team, **target_team)
+                && gtf.translation().distance_squared(origin) <= sqr_dist
+        })
+        .map(|(entity, combatant, gtf, _)| (entity, combatant, gtf))
 }
 
-pub fn get_colliding_allies<'a, T: Team>(
-    query: &'a Query<'a, 'a, (Entity, &'a Combatant, &'a GlobalTransform, &'a Aabb), T::Allies>,
+pub fn get_colliding_allies<'a>(
+    query: &'a Query<
+        'a,
+        'a,
+        (
+            Entity,
+            &'a Combatant,
+            &'a GlobalTransform,
+            &'a Aabb,
+            &'a TeamId,
+        ),
+    >,
+    index: &'a SpatialIndex,
+    relationships: &'a TeamRelationships,
+    team: TeamId,
     origin: Vec3,
     aabb: Aabb,
     my_aabb_scale: f32,
 ) -> impl Iterator<Item = (Entity, &'a Combatant, &'a GlobalTransform, &'a Aabb)> {
-    query.iter().filter(move |(_, _, gtf, target_aabb)| {
-        target_aabb.intersects_aabb(
-            gtf.translation(),
-            aabb.scale(Vec3::ONE * my_aabb_scale),
-            origin,
-        )
-    })
+    let aabb = aabb.scale(Vec3::ONE * my_aabb_scale);
+    index
+        .query_aabb(aabb.world_min(origin), aabb.world_max(origin))
+        .filter_map(move |entity| query.get(entity).ok())
+        .filter(move |(_, _, gtf, target_aabb, target_team)| {
+            relationships.is_allied(team, **target_team)
+                && target_aabb.intersects_aabb(gtf.translation(), aabb, origin)
+        })
+        .map(|(entity, combatant, gtf, aabb, _)| (entity, combatant, gtf, aabb))
 }
 
-//todo improve this
-pub fn test_point<T: Team>(
+pub fn test_point(
     point: Vec3,
+    team: TeamId,
+    relationships: &TeamRelationships,
     level: &Level,
     physics_query: &Query<&BlockPhysics>,
-    object_query: &Query<(Entity, &GlobalTransform, &Aabb), T::Targets>,
+    object_query: &Query<(Entity, &GlobalTransform, &Aabb, &TeamId)>,
+    index: &SpatialIndex,
     exclude: &[Entity],
 ) -> Option<Entity> {
     //test entity
-    for (entity, tf, col) in object_query.iter() {
-        if exclude.contains(&entity) {
+    for (entity, tf, col, target_team) in index
+        .query_aabb(point, point)
+        .filter_map(|entity| object_query.get(entity).ok())
+    {
+        if exclude.contains(&entity) || !relationships.is_hostile(team, *target_team) {
             continue;
         }
         if col.intersects_point(tf.translation(), point) {
@@ -150,12 +257,8 @@ pub fn test_point<T: Team>(
     let test_block_coord = BlockCoord::from(point);
     if let Some(block_entity) = level.get_block_entity(test_block_coord) {
         if !exclude.contains(&block_entity) {
-            if let Some(collider) = physics_query
-                .get(block_entity)
-                .ok()
-                .and_then(Aabb::from_block)
-            {
-                if collider.intersects_point(test_block_coord.to_vec3(), point) {
+            if let Ok(physics) = physics_query.get(block_entity) {
+                if physics.intersects_point(test_block_coord.to_vec3(), point) {
                     //our point intersects the block
                     return Some(block_entity);
                 }
@@ -165,16 +268,21 @@ pub fn test_point<T: Team>(
     None
 }
 
-//todo improve this
-pub fn test_box<T: crate::actors::team::Team>(
+pub fn test_box(
     point: Vec3,
     aabb: Aabb,
-    object_query: &Query<(Entity, &GlobalTransform, &Aabb), T::Targets>,
+    team: TeamId,
+    relationships: &TeamRelationships,
+    object_query: &Query<(Entity, &GlobalTransform, &Aabb, &TeamId)>,
+    index: &SpatialIndex,
     exclude: &[Entity],
 ) -> Option<Entity> {
     //test entity
-    for (entity, tf, col) in object_query.iter() {
-        if exclude.contains(&entity) {
+    for (entity, tf, col, target_team) in index
+        .query_aabb(aabb.world_min(point), aabb.world_max(point))
+        .filter_map(|entity| object_query.get(entity).ok())
+    {
+        if exclude.contains(&entity) || !relationships.is_hostile(team, *target_team) {
             continue;
         }
         if aabb.intersects_aabb(point, *col, tf.translation()) {
@@ -9,6 +9,8 @@ use world::{level::Level, settings::GraphicsSettings, FixedUpdateBlockGizmos};
 
 use physics::{
     collision::{Aabb, BlockPhysics},
+    entity_collision::CollisionLayers,
+    force_field::{self, Airflow},
     movement::{GravityMult, Mass, Velocity},
     PhysicsBundle,
 };
@@ -20,7 +22,7 @@ use interfaces::{
 };
 
 use crate::{
-    actors::team::PlayerTeam,
+    actors::team::TeamId,
     items::{
         inventory::Inventory,
         item_attributes::{ItemSwingSpeed, ItemUseSpeed},
@@ -207,6 +209,7 @@ impl Plugin for GhostPlugin {
                 FixedUpdate,
                 (float_boost, update_floater)
                     .chain()
+                    .after(force_field::apply_force_fields)
                     .in_set(PhysicsLevelSet::Main),
             )
             .add_event::<SpawnGhostEvent>();
@@ -293,8 +296,9 @@ fn spawn_ghost(
                 Mesh3d(res.center_mesh.clone()),
                 spawn.location,
                 Name::new("ghost"),
-                CombatantBundle::<PlayerTeam> {
+                CombatantBundle {
                     combatant: Combatant::new(10.0, 0.),
+                    team: TeamId::PLAYER,
                     ..default()
                 },
                 PhysicsBundle {
@@ -302,7 +306,9 @@ fn spawn_ghost(
                     mass: Mass(0.5),
                     ..default()
                 },
+                CollisionLayers::PLAYER,
                 Float::default(),
+                Airflow::default(),
                 Ghost,
                 Idler::default(),
                 SmoothLookTo::new(0.5),
@@ -712,13 +718,19 @@ fn get_float_delta_velocity(desired_height_change: f32, interpolate_speed: f32)
 }
 
 fn update_floater(
-    mut query: Query<(&mut Velocity, &mut Float, &GlobalTransform, &Aabb)>,
+    mut query: Query<(
+        &mut Velocity,
+        &mut Float,
+        &GlobalTransform,
+        &Aabb,
+        Option<&Airflow>,
+    )>,
     physics_query: Query<&BlockPhysics>,
     level: Res<Level>,
     mut block_gizmos: ResMut<FixedUpdateBlockGizmos>,
 ) {
     const CHECK_MULT: f32 = 2.0;
-    for (mut v, float, gtf, aabb) in query.iter_mut() {
+    for (mut v, float, gtf, aabb, opt_airflow) in query.iter_mut() {
         let translation = gtf.translation();
         //the ground has check area slightly larger than the actual hitbox to climb walls
         let ground_area = aabb.scale(float.ground_aabb_scale).move_min(Vec3::new(
@@ -807,6 +819,10 @@ fn update_floater(
             //don't want to get pulled down to the ground or pushed up to the ceiling
             continue;
         }
+        if v.0.y > 0.0 && ceiling_y.is_none() && opt_airflow.is_some_and(|air| air.y > 0.0) {
+            //riding an updraft, let it carry us up
+            continue;
+        }
         if v.0.y * delta_v.signum() >= delta_v.abs() {
             //we are already moving in the right direction faster than the floater would push
             //slow down a bit to reduce bobbing
@@ -828,10 +844,13 @@ fn float_boost(
         &mut FloatBoost,
         &mut GravityMult,
         Option<&mut Stamina>,
+        Option<&Airflow>,
     )>,
 ) {
-    for (mut float, mut boost, mut grav, stamina_opt) in query.iter_mut() {
-        if boost.enabled {
+    for (mut float, mut boost, mut grav, stamina_opt, opt_airflow) in query.iter_mut() {
+        //gliding on an updraft is free
+        let in_updraft = opt_airflow.is_some_and(|air| air.y > 0.0);
+        if boost.enabled && !in_updraft {
             if let Some(mut stamina) = stamina_opt {
                 stamina.change(-boost.stamina_per_tick);
                 if stamina.current <= 0.0 {
@@ -7,11 +7,12 @@ use lightyear::{prelude::client::Predicted, shared::replication::components::Con
 use player_controller::RotateWithMouse;
 
 use crate::{
-    actors::{ghost::FloatBoost, team::PlayerTeam, Invulnerability, MoveSpeed},
+    actors::{ghost::FloatBoost, team::TeamId, Invulnerability, MoveSpeed},
     camera::MainCamera,
     controllers::*,
     items::{
-        inventory::Inventory,
+        equipment::Equipment,
+        inventory::{Inventory, InventoryLayout},
         item_attributes::{ItemSwingSpeed, ItemUseSpeed},
         *,
     },
@@ -34,6 +35,13 @@ use world::{
     chunk_loading::ChunkLoader, level::Level, settings::Settings, spawn_point::SpawnPoint,
 };
 
+//one hotbar row and two pages of backpack
+pub const PLAYER_INVENTORY_LAYOUT: InventoryLayout = InventoryLayout {
+    hotbar_slots: 10,
+    page_slots: 30,
+    pages: 2,
+};
+
 #[derive(Component)]
 pub struct Player {
     pub hit_damage: Damage,
@@ -227,7 +235,7 @@ pub(crate) fn spawn_local_player(
         &held_item_resouces,
         &mut commands,
     );
-    let mut inventory = Inventory::new(player_id, 40);
+    let mut inventory = Inventory::with_layout(player_id, PLAYER_INVENTORY_LAYOUT);
 
     inventory.pickup_item(
         ItemStack::new(
@@ -309,6 +317,27 @@ pub(crate) fn spawn_local_player(
         ),
         &item_query,
     );
+    for (launcher, count) in [("seeker_coin", 100), ("bomb_ball_launcher", 20)] {
+        inventory.pickup_item(
+            ItemStack::new(
+                resources
+                    .registry
+                    .get_basic(&ItemName::core(launcher))
+                    .unwrap(),
+                count,
+            ),
+            &item_query,
+        );
+    }
+    for gear in ["ghost_hood", "ruby_chestplate", "feather_charm"] {
+        inventory.pickup_item(
+            ItemStack::new(
+                resources.registry.get_basic(&ItemName::core(gear)).unwrap(),
+                1,
+            ),
+            &item_query,
+        );
+    }
 
     commands.entity(player_id).insert(inventory);
     //makes sure that player is actually spawned before this occurs, since events fire at a different time than commands
@@ -336,6 +365,8 @@ fn populate_player_entity(
                     .add_offset(Vec3::new(0.0, -0.3, 0.0)),
                 ..default()
             },
+            entity_collision::CollisionLayers::PLAYER,
+            collision::StepUp::default(),
             ItemUseSpeed {
                 windup: Duration::ZERO,
                 backswing: Duration::from_millis(100),
@@ -351,13 +382,16 @@ fn populate_player_entity(
             },
         ),
         Dash::new(0.5, Duration::from_secs_f32(0.5)),
-        CombatantBundle::<PlayerTeam> {
+        //dashes and grapple pulls are fast enough to skip over enemies
+        ccd::ContinuousCollision::default(),
+        force_field::Airflow::default(),
+        CombatantBundle {
             combatant: Combatant::new(10.0, 0.0),
             death_info: DeathInfo {
                 death_type: crate::actors::DeathType::LocalPlayer,
             },
             invulnerability: Invulnerability::new(Duration::from_secs(1)),
-            ..default()
+            team: TeamId::PLAYER,
         },
         RotateWithMouse {
             pitch_bound: PI * 0.49,
@@ -369,6 +403,7 @@ fn populate_player_entity(
         },
         FloatBoost::default().with_extra_height(3.0),
         DroppedItemPickerUpper { radius: 3. },
+        Equipment::player(),
     ));
     //right hand
     let right_hand = spawn_ghost_hand(
@@ -400,6 +435,22 @@ fn populate_player_entity(
     );
 
     commands.entity(right_hand).add_child(item_visualizer);
+    //worn gear, in the same order as Equipment::player()
+    let equipment_offsets = [
+        Vec3::new(0.0, 0.9, 0.0),
+        Vec3::new(0.0, 0.0, -0.5),
+        Vec3::new(-0.6, 0.3, 0.0),
+        Vec3::new(0.6, 0.3, 0.0),
+    ];
+    for (slot, offset) in equipment_offsets.into_iter().enumerate() {
+        let equipped_visualizer = held_item_resources.create_equipped_item_visualizer(
+            commands,
+            entity,
+            slot,
+            Transform::from_translation(offset).with_scale(Vec3::splat(0.6)),
+        );
+        commands.entity(entity).add_child(equipped_visualizer);
+    }
 }
 
 fn handle_disconnect(mut commands: Commands, mut removed: RemovedComponents<RemoteClient>) {
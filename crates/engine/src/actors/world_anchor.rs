@@ -3,14 +3,14 @@ use core::f32;
 use crate::util::SendEventCommand;
 use bevy::prelude::*;
 use interfaces::scheduling::*;
-use physics::{collision::Aabb, movement::Mass, PhysicsBundle};
+use physics::{collision::Aabb, entity_collision::CollisionLayers, movement::Mass, PhysicsBundle};
 use world::{
     atmosphere::Calendar, chunk_loading::ChunkLoader, level::Level, settings::Settings,
     spawn_point::SpawnPoint,
 };
 
 use super::{
-    team::PlayerTeam, ActorName, ActorResources, Combatant, CombatantBundle, DeathEvent, DeathInfo,
+    team::TeamId, ActorName, ActorResources, Combatant, CombatantBundle, DeathEvent, DeathInfo,
 };
 
 #[derive(Resource)]
@@ -25,6 +25,16 @@ pub struct WorldAnchor;
 #[derive(Component)]
 pub struct ActiveWorldAnchor;
 
+//max number of citizens that can be bound to this anchor
+#[derive(Component, Clone, Copy)]
+pub struct CitizenCap(pub u32);
+
+impl Default for CitizenCap {
+    fn default() -> Self {
+        Self(4)
+    }
+}
+
 #[derive(Event)]
 pub struct SpawnWorldAnchorEvent {
     pub location: Transform,
@@ -85,11 +95,12 @@ pub fn spawn_world_anchor(
                 SceneRoot(res.scene.clone_weak()),
                 spawn.location.with_scale(Vec3::new(2.0, 2.0, 2.0)),
                 Name::new("world anchor"),
-                CombatantBundle::<PlayerTeam> {
+                CombatantBundle {
                     combatant: Combatant::new(10., 0.),
                     death_info: DeathInfo {
                         death_type: super::DeathType::Immortal,
                     },
+                    team: TeamId::PLAYER,
                     ..default()
                 },
                 PhysicsBundle {
@@ -98,8 +109,11 @@ pub fn spawn_world_anchor(
                     mass: Mass(f32::INFINITY),
                     ..default()
                 },
+                //immovable, so players and enemies can't walk through it
+                CollisionLayers::PLAYER,
                 WorldAnchor,
                 ActiveWorldAnchor,
+                CitizenCap::default(),
                 ChunkLoader {
                     mesh: false,
                     ..settings.init_loader.clone()
@@ -97,7 +97,7 @@ fn on_enter_menu(mut commands: Commands, main_camera: Res<MainCamera>) {
     ec.remove::<(Skybox, DistanceFog)>();
 }
 
-fn follow_player(
+pub(crate) fn follow_player(
     player_query: Query<&GlobalTransform, With<LocalPlayer>>,
     mut update_query: Query<&mut Transform, Without<LocalPlayer>>,
     camera: Res<MainCamera>,
@@ -15,9 +15,21 @@ pub enum Action {
     Punch,
     Use,
     DropItem,
+    MarkArea,
+    CycleBuildMode,
+    ToggleBuildMirror,
+    UndoBuild,
     Look,
     Scroll,
     ToggleInventory,
+    //number keys, indexes into the hotbar
+    SelectHotbarSlot(u8),
+    //modifiers for clicking on inventory slots
+    QuickMoveStack,
+    SplitStack,
+    SortInventory,
+    CompactInventory,
+    QuickStack,
     ToggleUIHidden,
     ToggleDebugUIHidden,
     ToggleGizmoOverlap,
@@ -36,8 +48,22 @@ impl Actionlike for Action {
     }
 }
 
+//in the order they're laid out on the keyboard, so 0 selects the last slot
+const HOTBAR_KEYS: [KeyCode; 10] = [
+    KeyCode::Digit1,
+    KeyCode::Digit2,
+    KeyCode::Digit3,
+    KeyCode::Digit4,
+    KeyCode::Digit5,
+    KeyCode::Digit6,
+    KeyCode::Digit7,
+    KeyCode::Digit8,
+    KeyCode::Digit9,
+    KeyCode::Digit0,
+];
+
 pub fn get_input_map() -> InputMap<Action> {
-    InputMap::default()
+    let mut map = InputMap::default()
         .with(Action::MoveForward, KeyCode::KeyW)
         .with(Action::MoveLeft, KeyCode::KeyA)
         .with(Action::MoveBack, KeyCode::KeyS)
@@ -50,12 +76,26 @@ pub fn get_input_map() -> InputMap<Action> {
         .with(Action::Punch, MouseButton::Left)
         .with(Action::Use, MouseButton::Right)
         .with(Action::DropItem, KeyCode::KeyG)
+        .with(Action::MarkArea, KeyCode::KeyM)
+        .with(Action::CycleBuildMode, KeyCode::KeyB)
+        .with(Action::ToggleBuildMirror, KeyCode::KeyN)
+        .with(Action::UndoBuild, KeyCode::KeyZ)
         .with_dual_axis(Action::Look, MouseMove::default())
         .with_axis(Action::Scroll, MouseScrollAxis::Y)
         .with(Action::ToggleInventory, KeyCode::Escape)
+        //shift and control would also dash and descend while clicking
+        .with(Action::QuickMoveStack, KeyCode::AltLeft)
+        .with(Action::SplitStack, KeyCode::KeyX)
+        .with(Action::SortInventory, KeyCode::KeyR)
+        .with(Action::CompactInventory, KeyCode::KeyC)
+        .with(Action::QuickStack, KeyCode::KeyQ)
         .with(Action::ToggleUIHidden, KeyCode::F1)
         .with(Action::ToggleDebugUIHidden, KeyCode::F3)
         .with(Action::ToggleGizmoOverlap, KeyCode::F4)
         .with(Action::ToggleDebugUIDetail, KeyCode::F5)
-        .with(Action::ToggleFullscreen, KeyCode::F11)
+        .with(Action::ToggleFullscreen, KeyCode::F11);
+    for (slot, key) in HOTBAR_KEYS.into_iter().enumerate() {
+        map.insert(Action::SelectHotbarSlot(slot as u8), key);
+    }
+    map
 }
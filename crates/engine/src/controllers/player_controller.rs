@@ -1,6 +1,14 @@
 use crate::{
     actors::*,
-    items::{inventory::Inventory, SpawnDroppedItemEvent},
+    items::{
+        building::{
+            BuildMirror, BuildingState, MirrorAxis, SetBuildMirrorEvent, SetBuildModeEvent,
+            UndoBuildEvent,
+        },
+        inventory::Inventory,
+        metadata::ItemMetadata,
+        SpawnDroppedItemEvent,
+    },
 };
 use abilities::{
     dash::{CurrentlyDashing, Dash},
@@ -33,17 +41,25 @@ impl Plugin for PlayerControllerPlugin {
             Update,
             (
                 rotate_mouse,
-                boost_float_player,
-                move_player,
-                dash_player,
                 follow_local_player,
                 player_punch,
                 player_use,
-                toggle_player_flight,
                 player_drop_item,
+                player_building_controls,
             )
                 .in_set(LevelSystemSet::Main),
         )
+        //movement runs on the fixed tick, the same step the server checks reported positions against
+        .add_systems(
+            FixedUpdate,
+            (
+                toggle_player_flight,
+                boost_float_player,
+                move_player,
+                dash_player,
+            )
+                .in_set(LevelSystemSet::PreTick),
+        )
         .add_systems(Update, update_window_focused)
         .insert_resource(CursorLocked(false));
     }
@@ -295,6 +311,7 @@ pub fn player_punch(
                                     target: hit.entity,
                                     damage: player.hit_damage,
                                     knockback: *tf.forward(),
+                                    melee: true,
                                 });
                             }
                         }
@@ -365,11 +382,13 @@ pub fn player_drop_item(
         With<ControlledPlayer>,
     >,
     mut drop: EventWriter<SpawnDroppedItemEvent>,
+    meta_query: Query<&ItemMetadata>,
+    mut commands: Commands,
 ) {
     for (mut inv, tf, v, action) in query.iter_mut() {
         if action.just_pressed(&Action::DropItem) {
             let slot = inv.selected_slot();
-            if let Some(stack) = inv.drop_items(slot, 1) {
+            if let Some(stack) = inv.drop_items(slot, 1, &meta_query, &mut commands) {
                 drop.send(SpawnDroppedItemEvent {
                     postion: tf.translation,
                     velocity: v.0 + tf.forward().as_vec3() * 0.2,
@@ -379,3 +398,49 @@ pub fn player_drop_item(
         }
     }
 }
+
+pub fn player_building_controls(
+    query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &ActionState<Action>,
+            Option<&BuildingState>,
+        ),
+        With<ControlledPlayer>,
+    >,
+    mut mode_writer: EventWriter<SetBuildModeEvent>,
+    mut mirror_writer: EventWriter<SetBuildMirrorEvent>,
+    mut undo_writer: EventWriter<UndoBuildEvent>,
+) {
+    for (entity, tf, action, state) in query.iter() {
+        if action.just_pressed(&Action::CycleBuildMode) {
+            mode_writer.send(SetBuildModeEvent {
+                user: entity,
+                mode: state.map(|s| s.mode).unwrap_or_default().next(),
+            });
+        }
+        if action.just_pressed(&Action::ToggleBuildMirror) {
+            //mirrors left to right across the block we're standing in
+            let forward = tf.forward();
+            let mirror = match state.and_then(|s| s.mirror) {
+                Some(_) => None,
+                None => Some(BuildMirror {
+                    origin: tf.translation().into(),
+                    axis: if forward.z.abs() >= forward.x.abs() {
+                        MirrorAxis::X
+                    } else {
+                        MirrorAxis::Z
+                    },
+                }),
+            };
+            mirror_writer.send(SetBuildMirrorEvent {
+                user: entity,
+                mirror,
+            });
+        }
+        if action.just_pressed(&Action::UndoBuild) {
+            undo_writer.send(UndoBuildEvent { user: entity });
+        }
+    }
+}
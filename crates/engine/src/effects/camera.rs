@@ -4,19 +4,26 @@ use crate::{
     actors::MoveSpeed,
     util::{inverse_lerp, lerp, lerp_delta_time, DEG_TO_RAD},
 };
+use interfaces::scheduling::GameState;
 use physics::movement::Velocity;
 
 pub struct CameraEffectsPlugin;
 
 impl Plugin for CameraEffectsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, do_camera_fov_effect);
+        app.add_systems(Update, do_camera_fov_effect).add_systems(
+            Update,
+            do_camera_shake
+                .after(crate::camera::follow_player)
+                .run_if(in_state(GameState::Game)),
+        );
     }
 }
 
 #[derive(Bundle, Default)]
 pub struct CameraEffectsBundle {
     fov_effect: CameraFOVEffect,
+    shake: CameraShake,
 }
 
 #[derive(Component)]
@@ -69,3 +76,67 @@ fn do_camera_fov_effect(
         projection.fov += fov_delta;
     }
 }
+
+//trauma based shake, applied on top of wherever the camera was put this frame
+#[derive(Component)]
+pub struct CameraShake {
+    //0-1, the shake is trauma squared so small hits barely shake
+    pub trauma: f32,
+    pub decay_per_sec: f32,
+    pub max_angle_rad: f32,
+    pub max_offset: f32,
+    pub frequency: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay_per_sec: 1.5,
+            max_angle_rad: 4.0 * DEG_TO_RAD,
+            max_offset: 0.15,
+            frequency: 15.0,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn shake(&self) -> f32 {
+        self.trauma * self.trauma
+    }
+}
+
+//smooth value in -1..1 that's different for each seed
+fn shake_noise(t: f32, seed: f32) -> f32 {
+    ((t + seed * 7.1).sin() + (t * 2.3 + seed * 3.7).sin() * 0.5) / 1.5
+}
+
+fn do_camera_shake(mut query: Query<(&mut Transform, &mut CameraShake)>, time: Res<Time>) {
+    for (mut tf, mut effect) in query.iter_mut() {
+        let shake = effect.shake();
+        if shake > 0.0 {
+            let t = time.elapsed_secs() * effect.frequency;
+            let angle = effect.max_angle_rad * shake;
+            let rotation = Quat::from_euler(
+                EulerRot::YXZ,
+                angle * shake_noise(t, 0.0),
+                angle * shake_noise(t, 1.0),
+                angle * shake_noise(t, 2.0),
+            );
+            let offset = effect.max_offset
+                * shake
+                * Vec3::new(
+                    shake_noise(t, 3.0),
+                    shake_noise(t, 4.0),
+                    shake_noise(t, 5.0),
+                );
+            tf.rotation *= rotation;
+            tf.translation += tf.rotation * offset;
+        }
+        effect.trauma = (effect.trauma - effect.decay_per_sec * time.delta_secs()).max(0.0);
+    }
+}
@@ -1,16 +1,19 @@
 use bevy::prelude::*;
 
-use physics::{
-    collision::{Aabb, BlockPhysics},
-    query::{self, Raycast, RaycastHit},
-};
+use interfaces::components::Id;
+use physics::collision::{Aabb, BlockPhysics};
 use world::{
-    block::{BlockId, BlockType},
+    block::{BlockId, BlockResources, BlockType},
+    block_buffer::{BlockBuffer, BlockChange},
     events::ChunkUpdatedEvent,
     level::Level,
 };
 
-use super::{HitResult, UseEndEvent, UseItemEvent};
+use super::{
+    building::{placement_target, BuildHistory, BuildOperation, BuildingState},
+    inventory::Inventory,
+    HitResult, UseEndEvent, UseItemEvent,
+};
 
 #[derive(Component)]
 pub struct BlockItem(pub Entity);
@@ -24,6 +27,12 @@ pub fn use_block_entity_item(
     block_physics_query: Query<&BlockPhysics>,
     object_query: Query<(Entity, &GlobalTransform, &Aabb)>,
     id_query: Query<&BlockId>,
+    resources: Res<BlockResources>,
+    mut user_query: Query<(
+        &mut Inventory,
+        Option<&mut BuildingState>,
+        Option<&mut BuildHistory>,
+    )>,
     mut commands: Commands,
 ) {
     for UseItemEvent {
@@ -34,35 +43,103 @@ pub fn use_block_entity_item(
     } in reader.read()
     {
         if let Ok(block_item) = block_query.get(stack.id) {
-            if let Some(RaycastHit::Block(coord, hit)) = query::raycast(
-                Raycast::new(tf.translation, tf.forward(), 10.0),
-                &level,
-                &block_physics_query,
-                &object_query,
-                &[*user],
-            ) {
-                let normal = crate::util::max_component_norm(hit.hit_pos - coord.center()).into();
-                level.set_block_entity(
-                    coord + normal,
-                    BlockType::Filled(block_item.0),
-                    &id_query,
-                    &mut update_writer,
-                    &mut commands,
-                );
+            let Some((target, hit_pos)) =
+                placement_target(*tf, *user, &level, &block_physics_query, &object_query)
+            else {
                 hit_writer.send(UseEndEvent {
                     user: *user,
                     inventory_slot: *inventory_slot,
                     stack: *stack,
-                    result: HitResult::Hit(hit.hit_pos),
+                    result: HitResult::Miss,
                 });
-            } else {
+                continue;
+            };
+            let (mut inventory, mut state, history) = match user_query.get_mut(*user) {
+                Ok((inventory, state, history)) => (Some(inventory), state, history),
+                Err(_) => (None, None, None),
+            };
+            let coords = match state.as_deref_mut() {
+                Some(state) if state.needs_anchor() => {
+                    //the first use of a fill only marks where it starts, so nothing is consumed
+                    state.anchor = Some(target);
+                    hit_writer.send(UseEndEvent {
+                        user: *user,
+                        inventory_slot: *inventory_slot,
+                        stack: *stack,
+                        result: HitResult::Miss,
+                    });
+                    continue;
+                }
+                Some(state) => {
+                    let coords = state.placement(target);
+                    state.anchor = None;
+                    coords
+                }
+                None => vec![target],
+            };
+            //can't place more blocks than are in the stack
+            let available = match (inventory.as_ref(), inventory_slot) {
+                (Some(inventory), Some(slot)) => inventory
+                    .get(*slot)
+                    .filter(|slot_stack| slot_stack.id == stack.id)
+                    .map_or(0, |slot_stack| slot_stack.size as usize),
+                _ => coords.len(),
+            };
+            let mut buffer = BlockBuffer::default();
+            let mut placed = Vec::new();
+            for coord in coords
+                .into_iter()
+                .filter(|coord| level.get_block(*coord) == Some(BlockType::Empty))
+                .take(available)
+            {
+                let block = match id_query.get(block_item.0) {
+                    //dynamic blocks need a new entity for each placement
+                    Ok(id @ BlockId(Id::Dynamic(_))) => {
+                        match resources
+                            .registry
+                            .generate_entity(*id, coord, &mut commands)
+                        {
+                            Some(entity) => BlockType::Filled(entity),
+                            None => continue,
+                        }
+                    }
+                    _ => BlockType::Filled(block_item.0),
+                };
+                buffer.set(coord, BlockChange::Set(block));
+                placed.push((coord, block));
+            }
+            if placed.is_empty() {
                 hit_writer.send(UseEndEvent {
                     user: *user,
                     inventory_slot: *inventory_slot,
                     stack: *stack,
                     result: HitResult::Miss,
                 });
+                continue;
+            }
+            level.add_buffer(buffer, &mut commands, &mut update_writer);
+            //the hit consumes one item, take the rest here
+            if let (Some(inventory), Some(slot)) = (inventory.as_mut(), inventory_slot) {
+                inventory.consume_items(*slot, placed.len() as u32 - 1);
+            }
+            let operation = BuildOperation {
+                item: stack.id,
+                placed,
+            };
+            match history {
+                Some(mut history) => history.push(operation),
+                None => {
+                    let mut history = BuildHistory::default();
+                    history.push(operation);
+                    commands.entity(*user).insert(history);
+                }
             }
+            hit_writer.send(UseEndEvent {
+                user: *user,
+                inventory_slot: *inventory_slot,
+                stack: *stack,
+                result: HitResult::Hit(hit_pos),
+            });
         }
     }
 }
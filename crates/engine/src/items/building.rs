@@ -0,0 +1,368 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use interfaces::scheduling::{ItemSystemSet, LevelSystemSet};
+use physics::{
+    collision::{Aabb, BlockPhysics},
+    query::{self, Raycast, RaycastHit},
+};
+use world::{
+    block::{BlockCoord, BlockId, BlockType},
+    events::ChunkUpdatedEvent,
+    level::Level,
+};
+
+use crate::actors::LocalPlayer;
+
+use super::{
+    block_item::BlockItem,
+    inventory::{Inventory, ItemTargetPosition, ItemUsageOffset},
+    ItemStack, MaxStackSize, SpawnDroppedItemEvent,
+};
+
+pub struct BuildingPlugin;
+
+impl Plugin for BuildingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SetBuildModeEvent>()
+            .add_event::<SetBuildMirrorEvent>()
+            .add_event::<UndoBuildEvent>()
+            .add_systems(
+                Update,
+                (set_build_mode, set_build_mirror, undo_builds)
+                    .chain()
+                    .in_set(ItemSystemSet::Usage),
+            )
+            .add_systems(Update, update_build_preview.in_set(LevelSystemSet::Main));
+    }
+}
+
+//biggest fill a single placement can do
+pub const MAX_BUILD_BLOCKS: usize = 512;
+//number of operations that can be undone
+const MAX_BUILD_HISTORY: usize = 16;
+//same reach as placing single blocks
+pub const BUILD_REACH: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BuildMode {
+    #[default]
+    Single,
+    //the fill modes take two uses: the first sets the anchor, the second places everything between it and the target
+    Line,
+    Wall,
+    Floor,
+}
+
+impl BuildMode {
+    pub fn next(self) -> Self {
+        match self {
+            BuildMode::Single => BuildMode::Line,
+            BuildMode::Line => BuildMode::Wall,
+            BuildMode::Wall => BuildMode::Floor,
+            BuildMode::Floor => BuildMode::Single,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MirrorAxis {
+    //reflects across the plane with constant x
+    X,
+    Z,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuildMirror {
+    pub origin: BlockCoord,
+    pub axis: MirrorAxis,
+}
+
+impl BuildMirror {
+    pub fn reflect(self, coord: BlockCoord) -> BlockCoord {
+        match self.axis {
+            MirrorAxis::X => BlockCoord::new(2 * self.origin.x - coord.x, coord.y, coord.z),
+            MirrorAxis::Z => BlockCoord::new(coord.x, coord.y, 2 * self.origin.z - coord.z),
+        }
+    }
+}
+
+//put on entities that place blocks with more than single placements
+#[derive(Component, Default, Debug)]
+pub struct BuildingState {
+    pub mode: BuildMode,
+    pub anchor: Option<BlockCoord>,
+    pub mirror: Option<BuildMirror>,
+}
+
+impl BuildingState {
+    //every position the placement would fill, including mirrored ones. may include filled positions
+    pub fn placement(&self, target: BlockCoord) -> Vec<BlockCoord> {
+        let mut coords = shape_coords(self.mode, self.anchor.unwrap_or(target), target);
+        if let Some(mirror) = self.mirror {
+            let mirrored: Vec<BlockCoord> =
+                coords.iter().map(|coord| mirror.reflect(*coord)).collect();
+            for coord in mirrored {
+                if !coords.contains(&coord) {
+                    coords.push(coord);
+                }
+            }
+        }
+        coords.truncate(MAX_BUILD_BLOCKS);
+        coords
+    }
+    //true if this use only sets the anchor
+    pub fn needs_anchor(&self) -> bool {
+        self.mode != BuildMode::Single && self.anchor.is_none()
+    }
+}
+
+//blocks placed by each placement, most recent last
+#[derive(Component, Default, Debug)]
+pub struct BuildHistory(VecDeque<BuildOperation>);
+
+#[derive(Debug)]
+pub struct BuildOperation {
+    pub item: Entity,
+    pub placed: Vec<(BlockCoord, BlockType)>,
+}
+
+impl BuildHistory {
+    pub fn push(&mut self, operation: BuildOperation) {
+        if self.0.len() >= MAX_BUILD_HISTORY {
+            self.0.pop_front();
+        }
+        self.0.push_back(operation);
+    }
+}
+
+#[derive(Event)]
+pub struct SetBuildModeEvent {
+    pub user: Entity,
+    pub mode: BuildMode,
+}
+
+#[derive(Event)]
+pub struct SetBuildMirrorEvent {
+    pub user: Entity,
+    pub mirror: Option<BuildMirror>,
+}
+
+//removes the blocks from the user's last placement and gives back the items
+#[derive(Event)]
+pub struct UndoBuildEvent {
+    pub user: Entity,
+}
+
+fn line_coords(a: BlockCoord, b: BlockCoord) -> Vec<BlockCoord> {
+    let diff = b - a;
+    let steps = diff.x.abs().max(diff.y.abs()).max(diff.z.abs());
+    if steps == 0 {
+        return vec![a];
+    }
+    let step = Vec3::new(diff.x as f32, diff.y as f32, diff.z as f32) / steps as f32;
+    (0..=steps)
+        .map(|i| {
+            let offset = (step * i as f32).round();
+            a + BlockCoord::new(offset.x as i32, offset.y as i32, offset.z as i32)
+        })
+        .collect()
+}
+
+pub fn shape_coords(mode: BuildMode, a: BlockCoord, b: BlockCoord) -> Vec<BlockCoord> {
+    match mode {
+        BuildMode::Single => vec![b],
+        BuildMode::Line => line_coords(a, b),
+        BuildMode::Wall => {
+            //a horizontal line at the anchor's height, extruded up or down to the target's
+            let base = line_coords(a, BlockCoord::new(b.x, a.y, b.z));
+            (a.y.min(b.y)..=a.y.max(b.y))
+                .flat_map(|y| {
+                    base.iter()
+                        .map(move |coord| BlockCoord::new(coord.x, y, coord.z))
+                })
+                .collect()
+        }
+        BuildMode::Floor => (a.x.min(b.x)..=a.x.max(b.x))
+            .flat_map(|x| (a.z.min(b.z)..=a.z.max(b.z)).map(move |z| BlockCoord::new(x, a.y, z)))
+            .collect(),
+    }
+}
+
+//block that a block item used from here would be placed at
+pub fn placement_target(
+    tf: Transform,
+    user: Entity,
+    level: &Level,
+    block_physics_query: &Query<&BlockPhysics>,
+    object_query: &Query<(Entity, &GlobalTransform, &Aabb)>,
+) -> Option<(BlockCoord, Vec3)> {
+    match query::raycast(
+        Raycast::new(tf.translation, tf.forward(), BUILD_REACH),
+        level,
+        block_physics_query,
+        object_query,
+        &[user],
+    ) {
+        Some(RaycastHit::Block(coord, hit)) => {
+            let normal = crate::util::max_component_norm(hit.hit_pos - coord.center()).into();
+            Some((coord + normal, hit.hit_pos))
+        }
+        _ => None,
+    }
+}
+
+fn set_build_mode(
+    mut reader: EventReader<SetBuildModeEvent>,
+    mut query: Query<&mut BuildingState>,
+    mut commands: Commands,
+) {
+    for SetBuildModeEvent { user, mode } in reader.read() {
+        match query.get_mut(*user) {
+            Ok(mut state) => {
+                state.mode = *mode;
+                state.anchor = None;
+            }
+            Err(_) => {
+                if let Some(mut ec) = commands.get_entity(*user) {
+                    ec.insert(BuildingState {
+                        mode: *mode,
+                        ..default()
+                    });
+                }
+            }
+        }
+        info!("build mode set to {:?}", mode);
+    }
+}
+
+fn set_build_mirror(
+    mut reader: EventReader<SetBuildMirrorEvent>,
+    mut query: Query<&mut BuildingState>,
+    mut commands: Commands,
+) {
+    for SetBuildMirrorEvent { user, mirror } in reader.read() {
+        match query.get_mut(*user) {
+            Ok(mut state) => state.mirror = *mirror,
+            Err(_) => {
+                if let Some(mut ec) = commands.get_entity(*user) {
+                    ec.insert(BuildingState {
+                        mirror: *mirror,
+                        ..default()
+                    });
+                }
+            }
+        }
+        info!("build mirror set to {:?}", mirror);
+    }
+}
+
+fn undo_builds(
+    mut reader: EventReader<UndoBuildEvent>,
+    mut user_query: Query<(&mut BuildHistory, &mut Inventory, &GlobalTransform)>,
+    level: Res<Level>,
+    id_query: Query<&BlockId>,
+    data_query: Query<&MaxStackSize>,
+    mut update_writer: EventWriter<ChunkUpdatedEvent>,
+    mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
+    mut commands: Commands,
+) {
+    for UndoBuildEvent { user } in reader.read() {
+        let Ok((mut history, mut inventory, gtf)) = user_query.get_mut(*user) else {
+            continue;
+        };
+        let Some(BuildOperation { item, placed }) = history.0.pop_back() else {
+            info!("nothing to undo");
+            continue;
+        };
+        //only take back blocks that haven't been broken or replaced since, so items can't be duplicated
+        let to_remove: Vec<(BlockCoord, BlockType)> = placed
+            .into_iter()
+            .filter(|(coord, block)| level.get_block(*coord) == Some(*block))
+            .map(|(coord, _)| (coord, BlockType::Empty))
+            .collect();
+        let refund = to_remove.len() as u32;
+        level.batch_set_block_entities(
+            to_remove.into_iter(),
+            &id_query,
+            &mut update_writer,
+            &mut commands,
+        );
+        if refund > 0
+            && let Some(stack) = inventory.pickup_item(ItemStack::new(item, refund), &data_query)
+        {
+            drop_writer.send(SpawnDroppedItemEvent {
+                postion: gtf.translation(),
+                velocity: Vec3::ZERO,
+                stack,
+            });
+        }
+        info!("undid placement of {} blocks", refund);
+    }
+}
+
+//where the local player's held block item would be placed, drawn by the ui
+#[derive(Component, Default, Debug, PartialEq)]
+pub struct BuildPreview {
+    //the block type being placed, None when not holding a block item or not aiming at anything
+    pub block: Option<Entity>,
+    //(position, true if the position is free)
+    pub cells: Vec<(BlockCoord, bool)>,
+    pub anchor: Option<BlockCoord>,
+}
+
+fn update_build_preview(
+    mut player_query: Query<
+        (
+            Entity,
+            &Inventory,
+            Option<&BuildingState>,
+            Option<&mut BuildPreview>,
+        ),
+        With<LocalPlayer>,
+    >,
+    use_pos_query: Query<(&GlobalTransform, Option<&ItemUsageOffset>)>,
+    block_item_query: Query<&BlockItem>,
+    level: Res<Level>,
+    block_physics_query: Query<&BlockPhysics>,
+    object_query: Query<(Entity, &GlobalTransform, &Aabb)>,
+    mut commands: Commands,
+) {
+    let Ok((player, inventory, state, opt_preview)) = player_query.get_single_mut() else {
+        return;
+    };
+    let mut preview = BuildPreview::default();
+    if let Some(block_item) = inventory
+        .selected_item_entity()
+        .and_then(|item| block_item_query.get(item).ok())
+        && let Some(tf) = ItemTargetPosition::Entity(player).get_use_pos(&use_pos_query)
+        && let Some((target, _)) =
+            placement_target(tf, player, &level, &block_physics_query, &object_query)
+    {
+        let default_state = BuildingState::default();
+        let state = state.unwrap_or(&default_state);
+        let placement = if state.needs_anchor() {
+            vec![target]
+        } else {
+            state.placement(target)
+        };
+        preview.block = Some(block_item.0);
+        preview.cells = placement
+            .into_iter()
+            .map(|coord| (coord, level.get_block(coord) == Some(BlockType::Empty)))
+            .collect();
+        preview.anchor = state.anchor;
+    }
+    match opt_preview {
+        //only touch it when it changes so the ui can skip unchanged previews
+        Some(mut old) => {
+            old.set_if_neq(preview);
+        }
+        None => {
+            if let Some(mut ec) = commands.get_entity(player) {
+                ec.try_insert(preview);
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use interfaces::scheduling::LevelSystemSet;
+use rand::{rngs::StdRng, thread_rng, SeedableRng};
+use util::noise::ToSeed;
+use world::{
+    block::{BlockCoord, BlockTemplate, RemovedBlock},
+    events::BlockUsedEvent,
+    level::Level,
+};
+
+use super::{
+    inventory::Inventory,
+    loot::{ItemLootTable, LootContextParam},
+    ItemResources, ItemStack, MaxStackSize, SpawnDroppedItemEvent,
+};
+
+pub struct ContainerPlugin;
+
+impl Plugin for ContainerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ContainerBlock>()
+            .register_type::<ContainerLoot>()
+            .add_systems(
+                Update,
+                add_container_inventories.in_set(LevelSystemSet::LoadingAndMain),
+            )
+            .add_systems(
+                Update,
+                (
+                    open_containers,
+                    fill_loot_containers,
+                    close_distant_containers,
+                )
+                    .chain()
+                    .in_set(LevelSystemSet::Main),
+            )
+            .add_observer(drop_container_contents);
+    }
+}
+
+//usable block that stores items. must also be a DynamicBlock so each placed container gets its own inventory
+#[derive(Component, Clone, Copy, Debug, Reflect, Default)]
+#[reflect(Component, FromWorld)]
+pub struct ContainerBlock {
+    pub slots: usize,
+}
+
+//rolled into the container the first time it's opened, for containers placed by worldgen
+//removed once rolled or if the container already has saved contents
+#[derive(Component, Clone, Debug, Reflect, Default)]
+#[reflect(Component, FromWorld)]
+pub struct ContainerLoot(pub ItemLootTable);
+
+//put on an entity that is looking into a container
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenContainer(pub Entity);
+
+//in blocks, from the block's center
+pub const CONTAINER_RANGE: f32 = 8.0;
+
+//the template loaded from file doesn't get an inventory, only placed blocks do
+fn add_container_inventories(
+    query: Query<(Entity, &ContainerBlock), (With<BlockTemplate>, Without<Inventory>)>,
+    mut commands: Commands,
+) {
+    for (entity, container) in query.iter() {
+        commands
+            .entity(entity)
+            .insert(Inventory::new(entity, container.slots));
+    }
+}
+
+fn open_containers(
+    mut reader: EventReader<BlockUsedEvent>,
+    container_query: Query<(), (With<ContainerBlock>, With<Inventory>)>,
+    mut commands: Commands,
+) {
+    for BlockUsedEvent {
+        user, block_used, ..
+    } in reader.read()
+    {
+        if container_query.contains(*block_used) {
+            if let Some(mut ec) = commands.get_entity(*user) {
+                ec.insert(OpenContainer(*block_used));
+            }
+        }
+    }
+}
+
+fn fill_loot_containers(
+    opener_query: Query<&OpenContainer, Changed<OpenContainer>>,
+    mut container_query: Query<(&mut Inventory, &BlockCoord, &ContainerLoot), With<BlockTemplate>>,
+    pickup_query: Query<&MaxStackSize>,
+    items: Res<ItemResources>,
+    loot_params: LootContextParam,
+    level: Res<Level>,
+    mut commands: Commands,
+) {
+    for OpenContainer(container) in opener_query.iter() {
+        let Ok((mut inventory, coord, ContainerLoot(table))) = container_query.get_mut(*container)
+        else {
+            continue;
+        };
+        //seeded by position and rolled without the time, so a world always generates the same loot
+        let mut rng = StdRng::seed_from_u64(level.seed ^ coord.to_seed());
+        let loot = table
+            .cache(&items.registry)
+            .get_loot(&loot_params.placed_at(coord.center()), &mut rng);
+        for (item, size) in loot {
+            if size > 0 {
+                inventory.pickup_item(ItemStack::new(item, size), &pickup_query);
+            }
+        }
+        //marks the inventory changed even if nothing dropped, so we don't roll again after reloading
+        inventory.set_changed();
+        commands.entity(*container).remove::<ContainerLoot>();
+    }
+}
+
+fn close_distant_containers(
+    query: Query<(Entity, &OpenContainer, &GlobalTransform)>,
+    container_query: Query<&BlockCoord, (With<ContainerBlock>, Without<RemovedBlock>)>,
+    mut commands: Commands,
+) {
+    for (entity, OpenContainer(container), gtf) in query.iter() {
+        let in_range = container_query.get(*container).is_ok_and(|coord| {
+            coord.center().distance_squared(gtf.translation()) <= CONTAINER_RANGE * CONTAINER_RANGE
+        });
+        if !in_range {
+            commands.entity(entity).remove::<OpenContainer>();
+        }
+    }
+}
+
+//runs when the block entity is despawned, so contents drop no matter how the container was broken
+//containers despawned by chunk unloading or leaving the level aren't broken, so they keep their (saved) contents
+fn drop_container_contents(
+    trigger: Trigger<OnRemove, ContainerBlock>,
+    query: Query<(&Inventory, &BlockCoord, Has<RemovedBlock>)>,
+    mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
+    mut commands: Commands,
+) {
+    let Ok((inventory, coord, broken)) = query.get(trigger.entity()) else {
+        return;
+    };
+    if !broken {
+        //the metadata was saved with the inventory and is spawned again when it loads
+        for meta in inventory
+            .iter()
+            .flatten()
+            .filter_map(|(stack, _)| stack.meta)
+        {
+            if let Some(ec) = commands.get_entity(meta) {
+                ec.despawn_recursive();
+            }
+        }
+        return;
+    }
+    let mut rng = thread_rng();
+    for (stack, _) in inventory.iter().flatten() {
+        let random_v = util::sample_sphere_surface(&mut rng) * 0.05;
+        let random_strength = util::random_proportion(&mut rng) + 0.5;
+        drop_writer.send(SpawnDroppedItemEvent {
+            postion: coord.center(),
+            velocity: random_strength * (random_v + Vec3::Y * 0.1),
+            stack: *stack,
+        });
+    }
+}
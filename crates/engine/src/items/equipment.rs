@@ -0,0 +1,310 @@
+use std::ops::{Add, Sub};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use interfaces::scheduling::ItemSystemSet;
+
+use crate::actors::{
+    abilities::{dash::Dash, stamina::Stamina},
+    ghost::FloatBoost,
+    Combatant, MoveSpeed,
+};
+
+use super::{
+    inventory::Inventory, metadata::ItemMetadata, ItemStack, MaxStackSize, SpawnDroppedItemEvent,
+};
+
+pub struct EquipmentPlugin;
+
+impl Plugin for EquipmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EquipItemEvent>()
+            .add_event::<UnequipItemEvent>()
+            .add_systems(
+                Update,
+                (equip_items, unequip_items, apply_equipment_stats)
+                    .chain()
+                    .in_set(ItemSystemSet::DropPickup),
+            )
+            .register_type::<EquipmentSlot>()
+            .register_type::<Equippable>()
+            .register_type::<EquipmentStats>();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Reflect, Serialize, Deserialize)]
+pub enum EquipmentSlot {
+    #[default]
+    Head,
+    Body,
+    Trinket,
+}
+
+impl EquipmentSlot {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            EquipmentSlot::Head => "Head",
+            EquipmentSlot::Body => "Body",
+            EquipmentSlot::Trinket => "Trinket",
+        }
+    }
+}
+
+//put on items that can be worn in an equipment slot
+#[derive(Clone, Hash, Eq, PartialEq, Component, Reflect, Default, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct Equippable {
+    pub slot: EquipmentSlot,
+}
+
+//stat bonuses while the item is equipped. these are added on top of the wearer's own stats
+#[derive(Clone, Copy, Debug, PartialEq, Component, Reflect, Default, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct EquipmentStats {
+    pub defense: f32,
+    pub max_stamina: f32,
+    //added to MoveSpeed::accel_add
+    pub move_accel: f32,
+    pub max_move_speed: f32,
+    pub dash_speed: f32,
+    pub float_height: f32,
+}
+
+impl Add for EquipmentStats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            defense: self.defense + rhs.defense,
+            max_stamina: self.max_stamina + rhs.max_stamina,
+            move_accel: self.move_accel + rhs.move_accel,
+            max_move_speed: self.max_move_speed + rhs.max_move_speed,
+            dash_speed: self.dash_speed + rhs.dash_speed,
+            float_height: self.float_height + rhs.float_height,
+        }
+    }
+}
+
+impl Sub for EquipmentStats {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            defense: self.defense - rhs.defense,
+            max_stamina: self.max_stamina - rhs.max_stamina,
+            move_accel: self.move_accel - rhs.move_accel,
+            max_move_speed: self.max_move_speed - rhs.max_move_speed,
+            dash_speed: self.dash_speed - rhs.dash_speed,
+            float_height: self.float_height - rhs.float_height,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Equipment {
+    slots: Vec<(EquipmentSlot, Option<ItemStack>)>,
+    //bonuses currently applied to the wearer, so we can take them back off when gear changes
+    applied: EquipmentStats,
+}
+
+impl Equipment {
+    pub fn new(layout: &[EquipmentSlot]) -> Self {
+        Self {
+            slots: layout.iter().map(|slot| (*slot, None)).collect(),
+            applied: EquipmentStats::default(),
+        }
+    }
+    pub fn player() -> Self {
+        Self::new(&[
+            EquipmentSlot::Head,
+            EquipmentSlot::Body,
+            EquipmentSlot::Trinket,
+            EquipmentSlot::Trinket,
+        ])
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (EquipmentSlot, Option<ItemStack>)> + '_ {
+        self.slots.iter().copied()
+    }
+    pub fn get(&self, slot: usize) -> Option<ItemStack> {
+        self.slots.get(slot).and_then(|(_, stack)| *stack)
+    }
+    pub fn slot_type(&self, slot: usize) -> Option<EquipmentSlot> {
+        self.slots.get(slot).map(|(slot_type, _)| *slot_type)
+    }
+    //prefers empty slots, then falls back to replacing the first slot of the right type
+    pub fn find_slot(&self, slot_type: EquipmentSlot) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|(t, stack)| *t == slot_type && stack.is_none())
+            .or_else(|| self.slots.iter().position(|(t, _)| *t == slot_type))
+    }
+    //returns what was previously in the slot
+    pub fn set(&mut self, slot: usize, stack: Option<ItemStack>) -> Option<ItemStack> {
+        std::mem::replace(&mut self.slots[slot].1, stack)
+    }
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+}
+
+//moves a single item from the entity's inventory to its equipment
+#[derive(Event, Clone, Copy)]
+pub struct EquipItemEvent {
+    pub entity: Entity,
+    pub inventory_slot: usize,
+    //None picks a slot based on the item's Equippable slot type
+    pub equipment_slot: Option<usize>,
+}
+
+//moves an equipped item back to the entity's inventory
+#[derive(Event, Clone, Copy)]
+pub struct UnequipItemEvent {
+    pub entity: Entity,
+    pub equipment_slot: usize,
+    //None puts it wherever it fits
+    pub inventory_slot: Option<usize>,
+}
+
+fn equip_items(
+    mut reader: EventReader<EquipItemEvent>,
+    mut query: Query<(&mut Equipment, &mut Inventory, &GlobalTransform)>,
+    equippable_query: Query<&Equippable>,
+    data_query: Query<&MaxStackSize>,
+    meta_query: Query<&ItemMetadata>,
+    mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
+    mut commands: Commands,
+) {
+    for EquipItemEvent {
+        entity,
+        inventory_slot,
+        equipment_slot,
+    } in reader.read().copied()
+    {
+        let Ok((mut equipment, mut inventory, gtf)) = query.get_mut(entity) else {
+            continue;
+        };
+        let Some(stack) = inventory.get(inventory_slot) else {
+            continue;
+        };
+        let Ok(equippable) = equippable_query.get(stack.id) else {
+            continue;
+        };
+        let Some(equipment_slot) = equipment_slot.or_else(|| equipment.find_slot(equippable.slot))
+        else {
+            continue;
+        };
+        if equipment.slot_type(equipment_slot) != Some(equippable.slot) {
+            continue;
+        }
+        let Some(equipping) = inventory.drop_items(inventory_slot, 1, &meta_query, &mut commands)
+        else {
+            continue;
+        };
+        if let Some(previous) = equipment.set(equipment_slot, Some(equipping)) {
+            //swap the old gear back into the inventory, or drop it if there's no room
+            if let Some(leftover) = inventory.pickup_item(previous, &data_query) {
+                drop_writer.send(SpawnDroppedItemEvent {
+                    postion: gtf.translation(),
+                    velocity: Vec3::ZERO,
+                    stack: leftover,
+                });
+            }
+        }
+    }
+}
+
+fn unequip_items(
+    mut reader: EventReader<UnequipItemEvent>,
+    mut query: Query<(&mut Equipment, &mut Inventory)>,
+    data_query: Query<&MaxStackSize>,
+) {
+    for UnequipItemEvent {
+        entity,
+        equipment_slot,
+        inventory_slot,
+    } in reader.read().copied()
+    {
+        let Ok((mut equipment, mut inventory)) = query.get_mut(entity) else {
+            continue;
+        };
+        let Some(stack) = equipment.get(equipment_slot) else {
+            continue;
+        };
+        match inventory_slot {
+            Some(slot) if slot < inventory.len() && inventory.get(slot).is_none() => {
+                inventory.set_slot_no_events(slot, stack);
+            }
+            _ => {
+                if !inventory.can_pickup_item(stack, &data_query) {
+                    //no room, leave it equipped
+                    continue;
+                }
+                inventory.pickup_item(stack, &data_query);
+            }
+        }
+        equipment.set(equipment_slot, None);
+    }
+}
+
+fn apply_equipment_stats(
+    mut query: Query<
+        (
+            &mut Equipment,
+            Option<&mut Combatant>,
+            Option<&mut Stamina>,
+            Option<&mut MoveSpeed>,
+            Option<&mut Dash>,
+            Option<&mut FloatBoost>,
+        ),
+        Changed<Equipment>,
+    >,
+    stats_query: Query<&EquipmentStats>,
+    meta_query: Query<&ItemMetadata>,
+) {
+    for (mut equipment, combatant, stamina, move_speed, dash, float_boost) in query.iter_mut() {
+        let total = equipment
+            .iter()
+            .filter_map(|(_, stack)| stack)
+            .map(|stack| {
+                let base = stats_query.get(stack.id).copied().unwrap_or_default();
+                //modifiers on this particular piece of gear
+                let modifiers = stack
+                    .meta
+                    .and_then(|meta| meta_query.get(meta).ok())
+                    .map(ItemMetadata::stats)
+                    .unwrap_or_default();
+                base + modifiers
+            })
+            .fold(EquipmentStats::default(), |acc, stats| acc + stats);
+        //every stat takes the difference from what was applied last time, so other changes to them are kept
+        let delta = total - equipment.applied;
+        if delta == EquipmentStats::default() {
+            continue;
+        }
+        if let Some(mut combatant) = combatant {
+            let (Combatant::Root { defense, .. } | Combatant::Child { defense, .. }) =
+                combatant.as_mut();
+            defense.current += delta.defense;
+        }
+        if let Some(mut stamina) = stamina {
+            stamina.max += delta.max_stamina;
+            stamina.change(0.0);
+        }
+        if let Some(mut move_speed) = move_speed {
+            move_speed.accel_add += delta.move_accel;
+            move_speed.max_speed += delta.max_move_speed;
+        }
+        if let Some(mut dash) = dash {
+            dash.current_speed += delta.dash_speed;
+        }
+        if let Some(mut float_boost) = float_boost {
+            float_boost.extra_height += delta.float_height;
+        }
+        //don't retrigger Changed<Equipment> for our own bookkeeping
+        equipment.bypass_change_detection().applied = total;
+    }
+}
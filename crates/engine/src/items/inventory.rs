@@ -87,21 +87,64 @@ impl ItemAction {
     }
 }
 
+//how an inventory's slots are split up. the hotbar comes first and is what gets selected from,
+//followed by backpack pages that are only shown with the full inventory
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InventoryLayout {
+    pub hotbar_slots: usize,
+    pub page_slots: usize,
+    pub pages: usize,
+}
+
+impl InventoryLayout {
+    //everything is in the hotbar, for things that don't have a backpack
+    pub fn hotbar_only(slots: usize) -> Self {
+        Self {
+            hotbar_slots: slots,
+            page_slots: 0,
+            pages: 0,
+        }
+    }
+    pub fn slots(&self) -> usize {
+        self.hotbar_slots + self.page_slots * self.pages
+    }
+    pub fn page_range(&self, page: usize) -> std::ops::Range<usize> {
+        let start = self.hotbar_slots + page * self.page_slots;
+        start..start + self.page_slots
+    }
+    //None for hotbar slots
+    pub fn page_of(&self, slot: usize) -> Option<usize> {
+        if slot < self.hotbar_slots || self.page_slots == 0 {
+            None
+        } else {
+            Some((slot - self.hotbar_slots) / self.page_slots)
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct Inventory {
     items: Vec<Option<(ItemStack, ItemAction)>>,
     owner: Entity,
     selected_slot: usize,
+    layout: InventoryLayout,
 }
 
 impl Inventory {
     pub fn new(owner: Entity, slots: usize) -> Self {
+        Self::with_layout(owner, InventoryLayout::hotbar_only(slots))
+    }
+    pub fn with_layout(owner: Entity, layout: InventoryLayout) -> Self {
         Self {
-            items: vec![None; slots],
+            items: vec![None; layout.slots()],
             owner,
             selected_slot: 0,
+            layout,
         }
     }
+    pub fn layout(&self) -> InventoryLayout {
+        self.layout
+    }
     pub fn iter(&self) -> std::slice::Iter<'_, Option<(ItemStack, ItemAction)>> {
         self.items.iter()
     }
@@ -117,10 +160,12 @@ impl Inventory {
     pub fn selected_item(&self) -> Option<ItemStack> {
         self.get(self.selected_slot())
     }
-    //if slot_num is negative or over the number of slots in the inventory, loop back around
+    //only hotbar slots can be selected. if slot_num is negative or past the end of the hotbar, loop back around
     pub fn select_slot(&mut self, slot_num: i32) {
-        //loop back around
-        let new_slot = slot_num.rem_euclid(self.items.len() as i32) as usize;
+        if self.layout.hotbar_slots == 0 {
+            return;
+        }
+        let new_slot = slot_num.rem_euclid(self.layout.hotbar_slots as i32) as usize;
         if new_slot != self.selected_slot {
             if let Some((_, action)) = &mut self.items[new_slot] {
                 action.cancel_action();
@@ -154,6 +199,62 @@ impl Inventory {
             })
             .sum()
     }
+    //total value of every currency item held
+    pub fn currency(&self, currency_query: &Query<&shop::Currency>) -> u32 {
+        self.items
+            .iter()
+            .flatten()
+            .filter_map(|(stack, _)| {
+                currency_query
+                    .get(stack.id)
+                    .ok()
+                    .map(|currency| currency.0 * stack.size)
+            })
+            .sum()
+    }
+    //removes currency worth at least amount, most valuable items first, and returns the change owed
+    //nothing is removed if the inventory holds less than amount
+    pub fn spend_currency(
+        &mut self,
+        amount: u32,
+        currency_query: &Query<&shop::Currency>,
+    ) -> Option<u32> {
+        if self.currency(currency_query) < amount {
+            return None;
+        }
+        let mut slots: Vec<(usize, u32, u32)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, item)| {
+                let (stack, _) = item.as_ref()?;
+                let currency = currency_query.get(stack.id).ok()?;
+                (currency.0 > 0).then_some((slot, currency.0, stack.size))
+            })
+            .collect();
+        slots.sort_by_key(|(slot, value, _)| (std::cmp::Reverse(*value), *slot));
+        let mut remaining = amount;
+        let mut removals = vec![0; slots.len()];
+        for (i, (_, value, size)) in slots.iter().enumerate() {
+            removals[i] = (*size).min(remaining / value);
+            remaining -= removals[i] * value;
+        }
+        let mut change = 0;
+        if remaining > 0 {
+            //every slot left over is worth more than what's still owed, so one more of the cheapest covers it
+            let i = (0..slots.len())
+                .filter(|i| removals[*i] < slots[*i].2)
+                .min_by_key(|i| slots[*i].1)?;
+            removals[i] += 1;
+            change = slots[i].1 - remaining;
+        }
+        for ((slot, _, _), take) in slots.into_iter().zip(removals) {
+            if take > 0 {
+                descrease_slot_size(&mut self.items[slot], take);
+            }
+        }
+        Some(change)
+    }
     //returns what's left (if any) of the item stack after picking up
     pub fn pickup_item(
         &mut self,
@@ -166,12 +267,16 @@ impl Inventory {
             match stacks {
                 Some(stack) => {
                     //pick up part of the stack
-                    if stack.0.id != item.id {
+                    if !stack.0.can_stack_with(&item) {
                         continue;
                     }
-                    let picking_up = item
-                        .size
-                        .min(data_query.get(item.id).unwrap().0.saturating_sub(item.size));
+                    let picking_up = item.size.min(
+                        data_query
+                            .get(item.id)
+                            .unwrap()
+                            .0
+                            .saturating_sub(stack.0.size),
+                    );
                     if picking_up > 0 {
                         item.size -= picking_up;
                         stack.0.size += picking_up;
@@ -198,12 +303,16 @@ impl Inventory {
             match stacks {
                 Some(stack) => {
                     //pick up part of the stack
-                    if stack.0.id != item.id {
+                    if !stack.0.can_stack_with(&item) {
                         continue;
                     }
-                    let picking_up = item
-                        .size
-                        .min(data_query.get(item.id).unwrap().0.saturating_sub(item.size));
+                    let picking_up = item.size.min(
+                        data_query
+                            .get(item.id)
+                            .unwrap()
+                            .0
+                            .saturating_sub(stack.0.size),
+                    );
                     if picking_up > 0 {
                         item.size -= picking_up;
                     }
@@ -243,16 +352,37 @@ impl Inventory {
     pub fn set_slot_no_events(&mut self, slot: usize, item: ItemStack) {
         self.items[slot] = Some((item, default()));
     }
+    //changes the metadata without interrupting any action the item is doing
+    pub fn set_slot_meta(&mut self, slot: usize, meta: Option<Entity>) {
+        if let Some((stack, _)) = &mut self.items[slot] {
+            stack.meta = meta;
+        }
+    }
     pub fn swap_slots(&mut self, slot_a: usize, slot_b: usize) {
         self.items.swap(slot_a, slot_b);
     }
-    // returns the number of items moved
+    // returns the number of items moved. part of a stack moved into an empty slot gets its own copy of the metadata
     pub fn move_items(
         &mut self,
         from_slot: usize,
         to_slot: usize,
         max_count: u32,
         data_query: &Query<&MaxStackSize>,
+        meta_query: &Query<&metadata::ItemMetadata>,
+        commands: &mut Commands,
+    ) -> u32 {
+        let moved = self.move_items_keep_meta(from_slot, to_slot, max_count, data_query);
+        let kept = self.get(from_slot);
+        copy_split_meta(kept, &mut self.items[to_slot], meta_query, commands);
+        moved
+    }
+    //merging into an occupied slot never splits a stack, so this leaves the metadata alone
+    fn move_items_keep_meta(
+        &mut self,
+        from_slot: usize,
+        to_slot: usize,
+        max_count: u32,
+        data_query: &Query<&MaxStackSize>,
     ) -> u32 {
         if max_count == 0 || from_slot == to_slot {
             // no items to move
@@ -265,7 +395,7 @@ impl Inventory {
         };
         match self.items[to_slot].clone() {
             Some((to_stack, to_action)) => {
-                if !matches!(to_action, ItemAction::None) || from_stack.id != to_stack.id {
+                if !matches!(to_action, ItemAction::None) || !from_stack.can_stack_with(&to_stack) {
                     return 0; //block move if there's an active action or the items aren't the same
                 }
                 let Ok(max_stack_size) = data_query.get(to_stack.id) else {
@@ -285,14 +415,153 @@ impl Inventory {
                     self.swap_slots(from_slot, to_slot);
                 } else {
                     //we are only moving part of the stack
-                    self.items[to_slot] =
-                        Some((ItemStack::new(from_stack.id, moving), ItemAction::None));
+                    self.items[to_slot] = Some((from_stack.with_size(moving), ItemAction::None));
                     descrease_slot_size(&mut self.items[from_slot], moving);
                 }
                 moving
             }
         }
     }
+    // like move_items, but into a slot of another inventory. returns the number of items moved
+    pub fn move_items_to(
+        &mut self,
+        from_slot: usize,
+        target: &mut Inventory,
+        to_slot: usize,
+        max_count: u32,
+        data_query: &Query<&MaxStackSize>,
+        meta_query: &Query<&metadata::ItemMetadata>,
+        commands: &mut Commands,
+    ) -> u32 {
+        let moved = self.move_items_to_keep_meta(from_slot, target, to_slot, max_count, data_query);
+        let kept = self.get(from_slot);
+        copy_split_meta(kept, &mut target.items[to_slot], meta_query, commands);
+        moved
+    }
+    fn move_items_to_keep_meta(
+        &mut self,
+        from_slot: usize,
+        target: &mut Inventory,
+        to_slot: usize,
+        max_count: u32,
+        data_query: &Query<&MaxStackSize>,
+    ) -> u32 {
+        if max_count == 0 {
+            return 0;
+        }
+        let Some((from_stack, ItemAction::None)) = self.items[from_slot].clone() else {
+            return 0;
+        };
+        match target.items[to_slot].clone() {
+            Some((to_stack, to_action)) => {
+                if !matches!(to_action, ItemAction::None) || !from_stack.can_stack_with(&to_stack) {
+                    return 0;
+                }
+                let Ok(max_stack_size) = data_query.get(to_stack.id) else {
+                    return 0;
+                };
+                let moving = max_count
+                    .min(from_stack.size)
+                    .min(max_stack_size.0.saturating_sub(to_stack.size));
+                descrease_slot_size(&mut self.items[from_slot], moving);
+                target.items[to_slot].as_mut().unwrap().0.size += moving;
+                moving
+            }
+            None => {
+                let moving = max_count.min(from_stack.size);
+                target.items[to_slot] = Some((from_stack.with_size(moving), ItemAction::None));
+                descrease_slot_size(&mut self.items[from_slot], moving);
+                moving
+            }
+        }
+    }
+    pub fn swap_slot_with(&mut self, slot: usize, target: &mut Inventory, target_slot: usize) {
+        std::mem::swap(&mut self.items[slot], &mut target.items[target_slot]);
+    }
+    fn is_idle(&self, first_slot: usize) -> bool {
+        self.items[first_slot.min(self.items.len())..]
+            .iter()
+            .flatten()
+            .all(|(_, action)| matches!(action, ItemAction::None))
+    }
+    //merges partial stacks of the same item, starting from first_slot. earlier stacks are filled first
+    pub fn compact(&mut self, first_slot: usize, data_query: &Query<&MaxStackSize>) {
+        if !self.is_idle(first_slot) {
+            return;
+        }
+        for to_slot in first_slot..self.items.len() {
+            for from_slot in to_slot + 1..self.items.len() {
+                if self.items[to_slot].is_some() {
+                    self.move_items_keep_meta(from_slot, to_slot, u32::MAX, data_query);
+                }
+            }
+        }
+    }
+    //compacts, then orders the stacks from first_slot onwards by key with empty slots at the end
+    //does nothing if any of those items are being used, since the action would follow the wrong stack
+    pub fn sort_slots<K: Ord>(
+        &mut self,
+        first_slot: usize,
+        data_query: &Query<&MaxStackSize>,
+        mut key: impl FnMut(&ItemStack) -> K,
+    ) {
+        if !self.is_idle(first_slot) {
+            return;
+        }
+        self.compact(first_slot, data_query);
+        let first_slot = first_slot.min(self.items.len());
+        self.items[first_slot..].sort_by_cached_key(|slot| match slot {
+            Some((stack, _)) => (false, Some(key(stack))),
+            None => (true, None),
+        });
+    }
+    //moves as much of the slot as fits into target, topping up matching stacks before using empty slots
+    //returns the number of items moved
+    pub fn transfer_slot_to(
+        &mut self,
+        slot: usize,
+        target: &mut Inventory,
+        data_query: &Query<&MaxStackSize>,
+    ) -> u32 {
+        let Some((stack, ItemAction::None)) = self.items[slot].clone() else {
+            return 0;
+        };
+        let mut moved = 0;
+        let matching = (0..target.len()).filter(|i| {
+            target.items[*i]
+                .as_ref()
+                .is_some_and(|(to_stack, _)| to_stack.can_stack_with(&stack))
+        });
+        let empty = (0..target.len()).filter(|i| target.items[*i].is_none());
+        for to_slot in matching.chain(empty).collect::<Vec<_>>() {
+            if moved == stack.size {
+                break;
+            }
+            //whatever is left goes into an empty slot whole, so the stack is never split
+            moved += self.move_items_to_keep_meta(slot, target, to_slot, u32::MAX, data_query);
+        }
+        moved
+    }
+    //moves half of the stack (rounded down) into the first empty slot. returns the new slot
+    //the new stack gets its own copy of the metadata
+    pub fn split_slot(
+        &mut self,
+        slot: usize,
+        data_query: &Query<&MaxStackSize>,
+        meta_query: &Query<&metadata::ItemMetadata>,
+        commands: &mut Commands,
+    ) -> Option<usize> {
+        let stack = self.get(slot)?;
+        let half = stack.size / 2;
+        if half == 0 {
+            return None;
+        }
+        let empty_slot = self.items.iter().position(|item| item.is_none())?;
+        if self.move_items(slot, empty_slot, half, data_query, meta_query, commands) == 0 {
+            return None;
+        }
+        Some(empty_slot)
+    }
     //returns the dropped items
     pub fn drop_slot(&mut self, slot: usize) -> Option<ItemStack> {
         let item = self.items[slot].clone();
@@ -300,17 +569,27 @@ impl Inventory {
         let dropped = item.map(|(stack, _)| stack);
         dropped
     }
-    //returns the dropped items
-    pub fn drop_items(&mut self, slot: usize, max_drops: u32) -> Option<ItemStack> {
+    //returns the dropped items, which get their own copy of the metadata if some are left in the slot
+    pub fn drop_items(
+        &mut self,
+        slot: usize,
+        max_drops: u32,
+        meta_query: &Query<&metadata::ItemMetadata>,
+        commands: &mut Commands,
+    ) -> Option<ItemStack> {
         let (stack, _) = self.items[slot].clone()?;
-        descrease_slot_size(&mut self.items[slot], max_drops);
-        let dropped_size = self.items[slot]
-            .as_ref()
-            .map(|(new_stack, _)| stack.size - new_stack.size)
-            .unwrap_or(stack.size);
-        let dropped_stack = ItemStack::new(stack.id, dropped_size);
-
-        Some(dropped_stack)
+        let dropped_size = descrease_slot_size(&mut self.items[slot], max_drops);
+        if dropped_size == 0 {
+            return None;
+        }
+        let mut dropped = Some((stack.with_size(dropped_size), ItemAction::None));
+        copy_split_meta(self.get(slot), &mut dropped, meta_query, commands);
+        dropped.map(|(stack, _)| stack)
+    }
+    //removes items that were used up, returns the number removed
+    //the metadata stays with what's left, see ItemMetadata::despawn_if_used_up for when nothing is
+    pub fn consume_items(&mut self, slot: usize, max_count: u32) -> u32 {
+        descrease_slot_size(&mut self.items[slot], max_count)
     }
     pub fn use_item(&mut self, slot: usize, target: ItemTargetPosition) {
         if let Some((_, action)) = &mut self.items[slot] {
@@ -337,6 +616,21 @@ impl Inventory {
     }
 }
 
+//a stack split in two would share its metadata, so the part that was split off gets a copy
+fn copy_split_meta(
+    kept: Option<ItemStack>,
+    split: &mut Option<(ItemStack, ItemAction)>,
+    meta_query: &Query<&metadata::ItemMetadata>,
+    commands: &mut Commands,
+) {
+    let (Some(kept), Some((split, _))) = (kept, split.as_mut()) else {
+        return;
+    };
+    if kept.meta.is_some() && kept.meta == split.meta {
+        split.meta = metadata::ItemMetadata::spawn_copy(split.meta, meta_query, commands);
+    }
+}
+
 fn descrease_slot_size(stack_opt: &mut Option<(ItemStack, ItemAction)>, max_amount: u32) -> u32 {
     let mut removed = 0;
     *stack_opt = stack_opt.as_ref().and_then(|(stack, act)| {
@@ -345,7 +639,7 @@ fn descrease_slot_size(stack_opt: &mut Option<(ItemStack, ItemAction)>, max_amou
         if new_size == 0 {
             None
         } else {
-            Some((ItemStack::new(stack.id, new_size), act.clone()))
+            Some((stack.with_size(new_size), act.clone()))
         }
     });
     removed
@@ -363,6 +657,14 @@ pub fn tick_item_timers(
     mut swing_writer: EventWriter<SwingItemEvent>,
 ) {
     for mut inventory in query.iter_mut() {
+        //don't mutably borrow idle inventories, so change detection only fires on real changes
+        if inventory
+            .iter()
+            .flatten()
+            .all(|(_, action)| matches!(action, ItemAction::None))
+        {
+            continue;
+        }
         let owner = inventory.owner;
         //speeds to use if the equipped item doesn't have a speed
         let base_use_speed = use_speed_query.get(inventory.owner).ok();
@@ -0,0 +1,302 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use interfaces::scheduling::ItemSystemSet;
+use world::block::{BlockCoord, RemovedBlock};
+
+use super::{
+    block_item::BlockItem,
+    container::{ContainerBlock, OpenContainer, CONTAINER_RANGE},
+    equipment::Equippable,
+    inventory::Inventory,
+    metadata::ItemMetadata,
+    ItemName, MaxStackSize,
+};
+
+//higher-level inventory operations. everything goes through events that name the acting entity,
+//so a server can replay client requests and each one is checked for access before it runs
+pub struct InventoryActionsPlugin;
+
+impl Plugin for InventoryActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ItemCategory>()
+            .add_event::<SortInventoryEvent>()
+            .add_event::<CompactInventoryEvent>()
+            .add_event::<TransferStackEvent>()
+            .add_event::<SplitStackEvent>()
+            .add_event::<QuickStackEvent>()
+            .add_systems(
+                Update,
+                (
+                    sort_inventories,
+                    compact_inventories,
+                    transfer_stacks,
+                    split_stacks,
+                    quick_stack,
+                )
+                    .chain()
+                    .in_set(ItemSystemSet::DropPickup),
+            );
+    }
+}
+
+//used for sorting. items without one are categorized by what they are, or put in Misc
+#[derive(
+    Component,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Reflect,
+    Serialize,
+    Deserialize,
+)]
+#[reflect(Component, FromWorld)]
+pub enum ItemCategory {
+    Tool,
+    Weapon,
+    Equipment,
+    Consumable,
+    Block,
+    Material,
+    #[default]
+    Misc,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    //by category, then name
+    Category,
+    Name,
+}
+
+#[derive(Event)]
+pub struct SortInventoryEvent {
+    pub user: Entity,
+    pub inventory: Entity,
+    pub order: SortOrder,
+    //slots before this are left alone, so the hotbar doesn't get shuffled
+    pub first_slot: usize,
+}
+
+//merges partial stacks
+#[derive(Event)]
+pub struct CompactInventoryEvent {
+    pub user: Entity,
+    pub inventory: Entity,
+    pub first_slot: usize,
+}
+
+//moves as much of a stack as fits into another inventory (alt-click)
+#[derive(Event)]
+pub struct TransferStackEvent {
+    pub user: Entity,
+    pub from: Entity,
+    pub slot: usize,
+    pub to: Entity,
+}
+
+//moves half of a stack into an empty slot of the same inventory
+#[derive(Event)]
+pub struct SplitStackEvent {
+    pub user: Entity,
+    pub inventory: Entity,
+    pub slot: usize,
+}
+
+//tops up stacks in every container in range with matching items from the user's inventory
+#[derive(Event)]
+pub struct QuickStackEvent {
+    pub user: Entity,
+    //slots before this are never taken from
+    pub first_slot: usize,
+}
+
+type CategoryQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static ItemCategory>,
+        Has<BlockItem>,
+        Has<Equippable>,
+        Option<&'static Name>,
+        Option<&'static ItemName>,
+    ),
+>;
+
+//the user can touch their own inventory and the container they have open
+fn can_access(user: Entity, inventory: Entity, open_query: &Query<&OpenContainer>) -> bool {
+    user == inventory
+        || open_query
+            .get(user)
+            .is_ok_and(|OpenContainer(container)| *container == inventory)
+}
+
+fn item_category(item: Entity, category_query: &CategoryQuery) -> ItemCategory {
+    match category_query.get(item) {
+        Ok((Some(category), ..)) => *category,
+        Ok((None, true, ..)) => ItemCategory::Block,
+        Ok((None, _, true, ..)) => ItemCategory::Equipment,
+        _ => ItemCategory::Misc,
+    }
+}
+
+fn item_display_name(item: Entity, category_query: &CategoryQuery) -> String {
+    match category_query.get(item) {
+        Ok((_, _, _, Some(name), _)) => name.to_string(),
+        Ok((_, _, _, None, Some(name))) => name.name.clone(),
+        _ => String::new(),
+    }
+}
+
+fn sort_inventories(
+    mut reader: EventReader<SortInventoryEvent>,
+    mut inventory_query: Query<&mut Inventory>,
+    open_query: Query<&OpenContainer>,
+    category_query: CategoryQuery,
+    data_query: Query<&MaxStackSize>,
+) {
+    for SortInventoryEvent {
+        user,
+        inventory,
+        order,
+        first_slot,
+    } in reader.read()
+    {
+        if !can_access(*user, *inventory, &open_query) {
+            warn!("{:?} tried to sort an inventory it can't access", user);
+            continue;
+        }
+        let Ok(mut inventory) = inventory_query.get_mut(*inventory) else {
+            continue;
+        };
+        //bigger stacks first so partial stacks end up at the back
+        inventory.sort_slots(*first_slot, &data_query, |stack| {
+            let name = item_display_name(stack.id, &category_query);
+            let category = match order {
+                SortOrder::Category => item_category(stack.id, &category_query),
+                SortOrder::Name => ItemCategory::default(),
+            };
+            (category, name, stack.id, std::cmp::Reverse(stack.size))
+        });
+    }
+}
+
+fn compact_inventories(
+    mut reader: EventReader<CompactInventoryEvent>,
+    mut inventory_query: Query<&mut Inventory>,
+    open_query: Query<&OpenContainer>,
+    data_query: Query<&MaxStackSize>,
+) {
+    for CompactInventoryEvent {
+        user,
+        inventory,
+        first_slot,
+    } in reader.read()
+    {
+        if !can_access(*user, *inventory, &open_query) {
+            warn!("{:?} tried to compact an inventory it can't access", user);
+            continue;
+        }
+        if let Ok(mut inventory) = inventory_query.get_mut(*inventory) {
+            inventory.compact(*first_slot, &data_query);
+        }
+    }
+}
+
+fn transfer_stacks(
+    mut reader: EventReader<TransferStackEvent>,
+    mut inventory_query: Query<&mut Inventory>,
+    open_query: Query<&OpenContainer>,
+    data_query: Query<&MaxStackSize>,
+) {
+    for TransferStackEvent {
+        user,
+        from,
+        slot,
+        to,
+    } in reader.read()
+    {
+        if !can_access(*user, *from, &open_query) || !can_access(*user, *to, &open_query) {
+            warn!(
+                "{:?} tried to transfer with an inventory it can't access",
+                user
+            );
+            continue;
+        }
+        let Ok([mut from_inventory, mut to_inventory]) = inventory_query.get_many_mut([*from, *to])
+        else {
+            continue;
+        };
+        if *slot < from_inventory.len() {
+            from_inventory.transfer_slot_to(*slot, &mut to_inventory, &data_query);
+        }
+    }
+}
+
+fn split_stacks(
+    mut reader: EventReader<SplitStackEvent>,
+    mut inventory_query: Query<&mut Inventory>,
+    open_query: Query<&OpenContainer>,
+    data_query: Query<&MaxStackSize>,
+    meta_query: Query<&ItemMetadata>,
+    mut commands: Commands,
+) {
+    for SplitStackEvent {
+        user,
+        inventory,
+        slot,
+    } in reader.read()
+    {
+        if !can_access(*user, *inventory, &open_query) {
+            warn!("{:?} tried to split a stack it can't access", user);
+            continue;
+        }
+        if let Ok(mut inventory) = inventory_query.get_mut(*inventory)
+            && *slot < inventory.len()
+        {
+            inventory.split_slot(*slot, &data_query, &meta_query, &mut commands);
+        }
+    }
+}
+
+fn quick_stack(
+    mut reader: EventReader<QuickStackEvent>,
+    mut inventory_query: Query<&mut Inventory>,
+    user_query: Query<&GlobalTransform>,
+    container_query: Query<(Entity, &BlockCoord), (With<ContainerBlock>, Without<RemovedBlock>)>,
+    data_query: Query<&MaxStackSize>,
+) {
+    for QuickStackEvent { user, first_slot } in reader.read() {
+        let Ok(gtf) = user_query.get(*user) else {
+            continue;
+        };
+        let position = gtf.translation();
+        let containers: Vec<Entity> = container_query
+            .iter()
+            .filter(|(_, coord)| {
+                coord.center().distance_squared(position) <= CONTAINER_RANGE * CONTAINER_RANGE
+            })
+            .map(|(entity, _)| entity)
+            .collect();
+        for container in containers {
+            let Ok([mut user_inventory, mut container_inventory]) =
+                inventory_query.get_many_mut([*user, container])
+            else {
+                continue;
+            };
+            for slot in *first_slot..user_inventory.len() {
+                if let Some(stack) = user_inventory.get(slot)
+                    && container_inventory.has_item(stack.id)
+                {
+                    user_inventory.transfer_slot_to(slot, &mut container_inventory, &data_query);
+                }
+            }
+        }
+    }
+}
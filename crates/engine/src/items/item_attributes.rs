@@ -3,7 +3,7 @@ use std::time::Duration;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use super::{inventory::Inventory, UseEndEvent};
+use super::{inventory::Inventory, metadata::ItemMetadata, UseEndEvent};
 use interfaces::scheduling::ItemSystemSet;
 
 pub struct ItemAttributesPlugin;
@@ -47,6 +47,7 @@ fn consume_items(
     on_hit_query: Query<&ConsumeItemOnHit>,
     on_sucess_query: Query<&ConsumeItemOnSucess>,
     mut inventory_query: Query<&mut Inventory>,
+    mut commands: Commands,
 ) {
     for UseEndEvent {
         user,
@@ -62,8 +63,11 @@ fn consume_items(
             || (result.is_hit() && on_hit_query.contains(stack.id));
         if consume {
             if let Some(slot_num) = inventory_slot {
-                if let Ok(mut inv) = inventory_query.get_mut(*user) {
-                    inv.drop_items(*slot_num, 1);
+                if let Ok(mut inv) = inventory_query.get_mut(*user)
+                    && let Some(used) = inv.get(*slot_num)
+                    && inv.consume_items(*slot_num, 1) > 0
+                {
+                    ItemMetadata::despawn_if_used_up(used, &inv, *slot_num, &mut commands);
                 }
             }
         }
@@ -1,11 +1,12 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 
-use rand::prelude::*;
+use rand::{prelude::*, rngs::StdRng};
 use rand_distr::Uniform;
 use serde::{Deserialize, Serialize};
 use util::random_proportion;
 
-use interfaces::scheduling::LevelSystemSet;
+use interfaces::scheduling::{LevelLoadState, LevelSystemSet};
+use world::{atmosphere::Calendar, level::Level, worldgen::BiomeSampler};
 
 use super::{ItemName, ItemRegistry, ItemResources, ItemStack, SpawnDroppedItemEvent};
 
@@ -13,16 +14,75 @@ pub struct LootPlugin;
 
 impl Plugin for LootPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, cache_item_loot_table.in_set(LevelSystemSet::Main));
+        app.init_resource::<LootRng>()
+            .add_systems(Update, cache_item_loot_table.in_set(LevelSystemSet::Main))
+            .add_systems(OnEnter(LevelLoadState::Loading), seed_loot_rng);
         app.register_type::<ItemLootTable>();
     }
 }
 
+//all loot rolls go through this, so seeding it makes drops reproducible
+//seeded from the level when it loads, before that it uses a fixed seed
+#[derive(Resource)]
+pub struct LootRng(pub StdRng);
+
+impl LootRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for LootRng {
+    fn default() -> Self {
+        Self::seeded(0)
+    }
+}
+
+//what the conditions in a loot table are checked against
+#[derive(Clone, Debug, Default)]
+pub struct LootContext {
+    //the calendar day, which is also the number of the current or most recent night
+    pub night: u64,
+    pub in_night: bool,
+    pub biome: Option<&'static str>,
+    //item held by whatever killed the entity or broke the block
+    pub held_item: Option<Entity>,
+    //level of the breaking tool for the kind of tool the block needs
+    pub tool_tier: Option<u32>,
+}
+
+#[derive(SystemParam)]
+pub struct LootContextParam<'w> {
+    calendar: Option<Res<'w, Calendar>>,
+    biomes: BiomeSampler<'w>,
+}
+
+impl LootContextParam<'_> {
+    pub fn at(&self, position: Vec3) -> LootContext {
+        LootContext {
+            night: self.calendar.as_ref().map_or(0, |c| c.time.day),
+            in_night: self.calendar.as_ref().is_some_and(|c| c.in_night()),
+            ..self.placed_at(position)
+        }
+    }
+    //leaves out the time, for loot that has to roll the same whenever it's generated
+    pub fn placed_at(&self, position: Vec3) -> LootContext {
+        LootContext {
+            biome: self.biomes.biome_name_at(position),
+            ..default()
+        }
+    }
+}
+
 #[derive(Default, Clone, Debug, PartialEq, Component, Reflect, Serialize, Deserialize)]
 #[reflect(Component, FromWorld)]
 pub struct ItemLootTable {
-    //everything is dropped if a drop occurs
+    //each drop is rolled independently, everything is dropped if a drop occurs
     pub drops: Vec<ItemLootTableDrop>,
+    //one choice is picked from each group per roll
+    #[reflect(default)]
+    #[serde(default)]
+    pub groups: Vec<LootChoiceGroup>,
 }
 
 impl ItemLootTable {
@@ -36,19 +96,124 @@ impl ItemLootTable {
                         item,
                         drop_chance: drop.drop_chance,
                         drop_count_range: drop.drop_count_range,
+                        conditions: cache_conditions(&drop.conditions, items),
                     })
                 })
                 .collect(),
+            groups: self
+                .groups
+                .iter()
+                .map(|group| CachedLootGroup {
+                    rolls: group.rolls,
+                    choices: group
+                        .choices
+                        .iter()
+                        .flat_map(|choice| {
+                            let entry = match &choice.entry {
+                                LootEntry::Nothing => CachedLootEntry::Nothing,
+                                LootEntry::Item { item, count_range } => CachedLootEntry::Item {
+                                    item: items.get_basic(item)?,
+                                    count_range: *count_range,
+                                },
+                                LootEntry::Table(table) => {
+                                    CachedLootEntry::Table(table.cache(items))
+                                }
+                            };
+                            Some((choice.weight, entry))
+                        })
+                        .collect(),
+                    conditions: cache_conditions(&group.conditions, items),
+                })
+                .collect(),
         }
     }
 }
 
-#[derive(Default, Clone, Debug, PartialEq, Component, Reflect, Serialize, Deserialize)]
+#[derive(Default, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
 pub struct ItemLootTableDrop {
     pub item: ItemName,
     pub drop_chance: f32,
     //inclusive
     pub drop_count_range: (u32, u32),
+    //all must pass for the drop to be rolled
+    #[reflect(default)]
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct LootChoiceGroup {
+    pub rolls: u32,
+    pub choices: Vec<LootChoice>,
+    //all must pass for the group to be rolled
+    #[reflect(default)]
+    #[serde(default)]
+    pub conditions: Vec<LootCondition>,
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct LootChoice {
+    //chance of being picked is weight / total weight of the group
+    pub weight: u32,
+    pub entry: LootEntry,
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum LootEntry {
+    #[default]
+    Nothing,
+    Item {
+        item: ItemName,
+        //inclusive
+        count_range: (u32, u32),
+    },
+    //rolls the whole table in place of this entry
+    Table(ItemLootTable),
+}
+
+#[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum LootCondition {
+    //inclusive
+    MinNight(u64),
+    //inclusive
+    MaxNight(u64),
+    Night,
+    Day,
+    Biome(String),
+    HeldItem(ItemName),
+    MinToolTier(u32),
+    //passes if any of these pass
+    AnyOf(Vec<LootCondition>),
+    //passes if none of these pass
+    NoneOf(Vec<LootCondition>),
+}
+
+fn cache_conditions(
+    conditions: &[LootCondition],
+    items: &ItemRegistry,
+) -> Vec<CachedLootCondition> {
+    conditions
+        .iter()
+        .map(|condition| match condition {
+            LootCondition::MinNight(night) => CachedLootCondition::MinNight(*night),
+            LootCondition::MaxNight(night) => CachedLootCondition::MaxNight(*night),
+            LootCondition::Night => CachedLootCondition::Night,
+            LootCondition::Day => CachedLootCondition::Day,
+            LootCondition::Biome(biome) => CachedLootCondition::Biome(biome.clone()),
+            LootCondition::HeldItem(item) => {
+                let entity = items.get_basic(item);
+                if entity.is_none() {
+                    warn!("loot condition has unknown item name {:?}", item);
+                }
+                CachedLootCondition::HeldItem(entity)
+            }
+            LootCondition::MinToolTier(tier) => CachedLootCondition::MinToolTier(*tier),
+            LootCondition::AnyOf(any) => CachedLootCondition::AnyOf(cache_conditions(any, items)),
+            LootCondition::NoneOf(none) => {
+                CachedLootCondition::NoneOf(cache_conditions(none, items))
+            }
+        })
+        .collect()
 }
 
 pub trait LootTableDroppable: Send + Sync + Clone {}
@@ -57,6 +222,7 @@ impl<T: Send + Sync + Clone> LootTableDroppable for T {}
 #[derive(Component)]
 pub struct CachedLootTable<T: LootTableDroppable> {
     pub drops: Vec<CachedLootTableDrop<T>>,
+    pub groups: Vec<CachedLootGroup<T>>,
 }
 
 pub struct CachedLootTableDrop<T> {
@@ -64,27 +230,108 @@ pub struct CachedLootTableDrop<T> {
     pub drop_chance: f32,
     //inclusive
     pub drop_count_range: (u32, u32),
+    pub conditions: Vec<CachedLootCondition>,
+}
+
+pub struct CachedLootGroup<T: LootTableDroppable> {
+    pub rolls: u32,
+    pub choices: Vec<(u32, CachedLootEntry<T>)>,
+    pub conditions: Vec<CachedLootCondition>,
+}
+
+pub enum CachedLootEntry<T: LootTableDroppable> {
+    Nothing,
+    Item { item: T, count_range: (u32, u32) },
+    Table(CachedLootTable<T>),
+}
+
+pub enum CachedLootCondition {
+    MinNight(u64),
+    MaxNight(u64),
+    Night,
+    Day,
+    Biome(String),
+    //None if the item wasn't found, which never passes
+    HeldItem(Option<Entity>),
+    MinToolTier(u32),
+    AnyOf(Vec<CachedLootCondition>),
+    NoneOf(Vec<CachedLootCondition>),
+}
+
+impl CachedLootCondition {
+    pub fn passes(&self, context: &LootContext) -> bool {
+        match self {
+            CachedLootCondition::MinNight(night) => context.night >= *night,
+            CachedLootCondition::MaxNight(night) => context.night <= *night,
+            CachedLootCondition::Night => context.in_night,
+            CachedLootCondition::Day => !context.in_night,
+            CachedLootCondition::Biome(biome) => context.biome == Some(biome.as_str()),
+            CachedLootCondition::HeldItem(item) => item.is_some() && context.held_item == *item,
+            CachedLootCondition::MinToolTier(tier) => context
+                .tool_tier
+                .is_some_and(|tool_tier| tool_tier >= *tier),
+            CachedLootCondition::AnyOf(any) => any.iter().any(|c| c.passes(context)),
+            CachedLootCondition::NoneOf(none) => !none.iter().any(|c| c.passes(context)),
+        }
+    }
+    pub fn all_pass(conditions: &[CachedLootCondition], context: &LootContext) -> bool {
+        conditions.iter().all(|c| c.passes(context))
+    }
+}
+
+fn roll_count(range: (u32, u32), rng: &mut impl Rng) -> u32 {
+    Uniform::new_inclusive(range.0, range.1.max(range.0)).sample(rng)
 }
 
 impl<T: LootTableDroppable> CachedLootTable<T> {
-    pub fn get_loot(&self) -> impl Iterator<Item = (T, u32)> + use<'_, T> {
-        let mut rng = thread_rng();
-        self.drops.iter().flat_map(move |drop| {
-            let prop = random_proportion(&mut rng);
-            info!(
-                "this is the drop chance {:?} this is the roll {:?}",
-                drop.drop_chance, prop
-            );
-            if prop > drop.drop_chance {
-                None
-            } else {
-                Some((
-                    drop.item.clone(),
-                    Uniform::new_inclusive(drop.drop_count_range.0, drop.drop_count_range.1)
-                        .sample(&mut rng),
-                ))
+    //the same context and rng state always give the same loot
+    pub fn get_loot(&self, context: &LootContext, rng: &mut impl Rng) -> Vec<(T, u32)> {
+        let mut loot = Vec::new();
+        self.roll_into(context, rng, &mut loot);
+        loot
+    }
+
+    fn roll_into(&self, context: &LootContext, rng: &mut impl Rng, loot: &mut Vec<(T, u32)>) {
+        for drop in self.drops.iter() {
+            if !CachedLootCondition::all_pass(&drop.conditions, context) {
+                continue;
             }
-        })
+            //always roll so the rng advances the same amount regardless of the outcome
+            let prop = random_proportion(rng);
+            let count = roll_count(drop.drop_count_range, rng);
+            if prop <= drop.drop_chance {
+                loot.push((drop.item.clone(), count));
+            }
+        }
+        for group in self.groups.iter() {
+            if !CachedLootCondition::all_pass(&group.conditions, context) {
+                continue;
+            }
+            let total_weight: u32 = group.choices.iter().map(|(weight, _)| *weight).sum();
+            if total_weight == 0 {
+                continue;
+            }
+            for _ in 0..group.rolls {
+                let mut pick = rng.gen_range(0..total_weight);
+                let Some((_, entry)) = group.choices.iter().find(|(weight, _)| {
+                    if pick < *weight {
+                        true
+                    } else {
+                        pick -= weight;
+                        false
+                    }
+                }) else {
+                    continue;
+                };
+                match entry {
+                    CachedLootEntry::Nothing => {}
+                    CachedLootEntry::Item { item, count_range } => {
+                        loot.push((item.clone(), roll_count(*count_range, rng)))
+                    }
+                    CachedLootEntry::Table(table) => table.roll_into(context, rng, loot),
+                }
+            }
+        }
     }
 }
 
@@ -92,10 +339,14 @@ impl CachedLootTable<Entity> {
     pub fn drop_items(
         &self,
         position: Vec3,
+        context: &LootContext,
         drop_writer: &mut EventWriter<SpawnDroppedItemEvent>,
         rng: &mut impl Rng,
     ) {
-        for (item, size) in self.get_loot() {
+        for (item, size) in self.get_loot(context, rng) {
+            if size == 0 {
+                continue;
+            }
             let random_v = util::sample_sphere_surface(rng) * 0.05;
             let random_strength = util::random_proportion(rng) + 0.5;
             drop_writer.send(SpawnDroppedItemEvent {
@@ -107,6 +358,10 @@ impl CachedLootTable<Entity> {
     }
 }
 
+fn seed_loot_rng(level: Res<Level>, mut commands: Commands) {
+    commands.insert_resource(LootRng::seeded(level.seed ^ 0x1007_5EED_1007_5EED));
+}
+
 fn cache_item_loot_table(
     mut commands: Commands,
     query: Query<(Entity, &ItemLootTable), Without<CachedLootTable<Entity>>>,
@@ -0,0 +1,244 @@
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use interfaces::scheduling::{ItemSystemSet, LevelLoadState};
+use world::level::Level;
+
+use super::{equipment::EquipmentStats, inventory::Inventory, ItemStack};
+
+pub struct ItemMetadataPlugin;
+
+impl Plugin for ItemMetadataPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageItemEvent>()
+            .add_event::<ItemBrokenEvent>()
+            .init_resource::<DurabilityRng>()
+            .add_systems(OnEnter(LevelLoadState::Loading), seed_durability_rng)
+            .add_systems(Update, damage_items.in_set(ItemSystemSet::DropPickup))
+            .register_type::<MaxDurability>()
+            .register_type::<ItemMetadata>();
+    }
+}
+
+//put on item types that wear out. stacks get their own durability the first time they are damaged
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Component, Reflect, Default, Serialize, Deserialize,
+)]
+#[reflect(Component, FromWorld)]
+pub struct MaxDurability(pub u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Default, Serialize, Deserialize)]
+pub struct Durability {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Durability {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+    pub fn is_damaged(&self) -> bool {
+        self.current < self.max
+    }
+    pub fn repair(&mut self, amount: u32) {
+        self.current = self.current.saturating_add(amount).min(self.max);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum ItemModifier {
+    //multiplies melee damage
+    Damage(f32),
+    //chance (0-1) that durability damage is ignored
+    Unbreaking(f32),
+    //added on top of the item's EquipmentStats while worn
+    Stats(EquipmentStats),
+}
+
+//data for a single stack, stored on its own entity that ItemStack::meta points to
+//the entity lives as long as the stack: splitting gives the new stack a copy, and using up the stack despawns it
+#[derive(Clone, Debug, PartialEq, Component, Reflect, Default, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct ItemMetadata {
+    pub durability: Option<Durability>,
+    pub custom_name: Option<String>,
+    pub modifiers: Vec<ItemModifier>,
+}
+
+impl ItemMetadata {
+    pub fn spawn(self, commands: &mut Commands) -> Entity {
+        commands
+            .spawn((
+                self,
+                Name::new("item metadata"),
+                StateScoped(LevelLoadState::Loaded),
+            ))
+            .id()
+    }
+    //spawns a copy of the metadata for a stack that was split off of the one using meta
+    pub fn spawn_copy(
+        meta: Option<Entity>,
+        meta_query: &Query<&ItemMetadata>,
+        commands: &mut Commands,
+    ) -> Option<Entity> {
+        meta.and_then(|meta| meta_query.get(meta).ok())
+            .map(|data| data.clone().spawn(commands))
+    }
+    //call after items are used up rather than moved somewhere else
+    //despawns the metadata if that emptied the slot the stack was in
+    pub fn despawn_if_used_up(
+        stack: ItemStack,
+        inventory: &Inventory,
+        slot: usize,
+        commands: &mut Commands,
+    ) {
+        let Some(meta) = stack.meta else {
+            return;
+        };
+        if inventory
+            .get(slot)
+            .is_none_or(|remaining| remaining.meta != Some(meta))
+        {
+            if let Some(ec) = commands.get_entity(meta) {
+                ec.despawn_recursive();
+            }
+        }
+    }
+    pub fn damage_mult(&self) -> f32 {
+        self.modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                ItemModifier::Damage(mult) => *mult,
+                _ => 1.0,
+            })
+            .product()
+    }
+    pub fn stats(&self) -> EquipmentStats {
+        self.modifiers
+            .iter()
+            .fold(EquipmentStats::default(), |acc, modifier| match modifier {
+                ItemModifier::Stats(stats) => acc + *stats,
+                _ => acc,
+            })
+    }
+    //multiple unbreaking modifiers each get a chance to save the item
+    pub fn unbreaking_chance(&self) -> f32 {
+        1.0 - self
+            .modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                ItemModifier::Unbreaking(chance) => 1.0 - chance.clamp(0.0, 1.0),
+                _ => 1.0,
+            })
+            .product::<f32>()
+    }
+}
+
+//wears down the item in the slot, if it has MaxDurability
+#[derive(Event)]
+pub struct DamageItemEvent {
+    pub user: Entity,
+    pub inventory_slot: usize,
+    pub amount: u32,
+}
+
+//sent after an item runs out of durability and is removed from the inventory
+#[derive(Event)]
+pub struct ItemBrokenEvent {
+    pub user: Entity,
+    pub stack: ItemStack,
+}
+
+//rolls whether unbreaking saves an item, seeded from the level like LootRng
+#[derive(Resource)]
+pub struct DurabilityRng(pub StdRng);
+
+impl DurabilityRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for DurabilityRng {
+    fn default() -> Self {
+        Self::seeded(0)
+    }
+}
+
+fn seed_durability_rng(level: Res<Level>, mut commands: Commands) {
+    commands.insert_resource(DurabilityRng::seeded(level.seed ^ 0xD00A_B1E5_D00A_B1E5));
+}
+
+fn damage_items(
+    mut reader: EventReader<DamageItemEvent>,
+    mut rng: ResMut<DurabilityRng>,
+    mut inventory_query: Query<&mut Inventory>,
+    durability_query: Query<&MaxDurability>,
+    mut meta_query: Query<&mut ItemMetadata>,
+    mut broken_writer: EventWriter<ItemBrokenEvent>,
+    mut commands: Commands,
+) {
+    //metadata spawned here isn't in meta_query until commands are applied,
+    //so later hits on the same stack this frame damage the copy kept here
+    let mut spawned = EntityHashMap::<ItemMetadata>::default();
+    for DamageItemEvent {
+        user,
+        inventory_slot,
+        amount,
+    } in reader.read()
+    {
+        let Ok(mut inventory) = inventory_query.get_mut(*user) else {
+            continue;
+        };
+        let Some(stack) = inventory.get(*inventory_slot) else {
+            continue;
+        };
+        let Ok(MaxDurability(max)) = durability_query.get(stack.id) else {
+            continue;
+        };
+        let mut queried = stack.meta.and_then(|meta| meta_query.get_mut(meta).ok());
+        let meta = match stack.meta.and_then(|meta| spawned.get_mut(&meta)) {
+            Some(meta) => Some(meta),
+            None => queried.as_deref_mut(),
+        };
+        let remaining = match meta {
+            Some(meta) => {
+                if rng.0.gen::<f32>() < meta.unbreaking_chance() {
+                    continue;
+                }
+                let durability = meta.durability.get_or_insert(Durability::new(*max));
+                durability.current = durability.current.saturating_sub(*amount);
+                durability.current
+            }
+            None => {
+                let remaining = max.saturating_sub(*amount);
+                if remaining > 0 {
+                    let data = ItemMetadata {
+                        durability: Some(Durability {
+                            current: remaining,
+                            max: *max,
+                        }),
+                        ..default()
+                    };
+                    let meta = data.clone().spawn(&mut commands);
+                    spawned.insert(meta, data);
+                    inventory.set_slot_meta(*inventory_slot, Some(meta));
+                }
+                remaining
+            }
+        };
+        if remaining == 0 {
+            inventory.drop_slot(*inventory_slot);
+            if let Some(meta) = stack.meta {
+                spawned.remove(&meta);
+                commands.entity(meta).despawn();
+            }
+            info!("item broke!");
+            broken_writer.send(ItemBrokenEvent { user: *user, stack });
+        }
+    }
+    for (meta, data) in spawned {
+        commands.entity(meta).insert(data);
+    }
+}
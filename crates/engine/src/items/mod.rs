@@ -9,9 +9,15 @@ use interfaces::scheduling::ItemSystemSet;
 use self::item_attributes::ItemAttributesPlugin;
 
 pub mod block_item;
+pub mod building;
+pub mod container;
+pub mod equipment;
 pub mod inventory;
+pub mod inventory_actions;
 pub mod item_attributes;
 pub mod loot;
+pub mod metadata;
+pub mod shop;
 
 pub struct ItemsPlugin;
 
@@ -24,7 +30,16 @@ impl Plugin for ItemsPlugin {
             .add_event::<SwingItemEvent>()
             .add_event::<SwingEndEvent>()
             .add_event::<SpawnDroppedItemEvent>()
-            .add_plugins((ItemAttributesPlugin, loot::LootPlugin))
+            .add_plugins((
+                ItemAttributesPlugin,
+                loot::LootPlugin,
+                equipment::EquipmentPlugin,
+                container::ContainerPlugin,
+                metadata::ItemMetadataPlugin,
+                inventory_actions::InventoryActionsPlugin,
+                building::BuildingPlugin,
+                shop::ShopPlugin,
+            ))
             .add_systems(
                 Update,
                 (block_item::use_block_entity_item,).in_set(ItemSystemSet::UsageProcessing),
@@ -43,10 +58,27 @@ impl Plugin for ItemsPlugin {
 pub struct ItemStack {
     pub id: Entity,
     pub size: u32,
+    //entity with the ItemMetadata for this stack, if it has any
+    pub meta: Option<Entity>,
 }
 impl ItemStack {
     pub fn new(id: Entity, size: u32) -> ItemStack {
-        Self { id, size }
+        Self {
+            id,
+            size,
+            meta: None,
+        }
+    }
+    pub fn with_meta(self, meta: Option<Entity>) -> ItemStack {
+        Self { meta, ..self }
+    }
+    //keeps the item and metadata
+    pub fn with_size(self, size: u32) -> ItemStack {
+        Self { size, ..self }
+    }
+    //stacks with different metadata never merge
+    pub fn can_stack_with(&self, other: &ItemStack) -> bool {
+        self.id == other.id && self.meta == other.meta
     }
 }
 
@@ -0,0 +1,284 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use interfaces::scheduling::{ItemSystemSet, LevelSystemSet};
+use util::noise::ToSeed;
+use world::{
+    atmosphere::Calendar,
+    block::{BlockCoord, BlockTemplate, RemovedBlock},
+    events::BlockUsedEvent,
+    level::Level,
+};
+
+use super::{
+    inventory::Inventory, ItemName, ItemResources, ItemStack, MaxStackSize, SpawnDroppedItemEvent,
+};
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Currency>()
+            .register_type::<ShopBlock>()
+            .add_event::<TradeEvent>()
+            .add_systems(
+                Update,
+                (open_shops, restock_shops, close_distant_shops)
+                    .chain()
+                    .in_set(LevelSystemSet::Main),
+            )
+            .add_systems(Update, trade.in_set(ItemSystemSet::DropPickup));
+    }
+}
+
+//items with this are money. the value is how much one item is worth
+#[derive(
+    Component, Clone, Copy, Debug, PartialEq, Eq, Reflect, Default, Serialize, Deserialize,
+)]
+#[reflect(Component, FromWorld)]
+pub struct Currency(pub u32);
+
+//usable block that trades with players. must also be a DynamicBlock so each shop keeps its own stock
+#[derive(Component, Clone, Debug, Reflect, Default, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct ShopBlock {
+    //item the shop pays with, prices are in units of its currency value
+    pub currency: ItemName,
+    //how many offers are picked from the list each day
+    pub offers_per_day: usize,
+    //added to the multiplier on sell prices and buy counts each night, so later nights trade better
+    pub night_scaling: f32,
+    pub offers: Vec<ShopOffer>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Default, Serialize, Deserialize)]
+pub enum ShopOfferKind {
+    //the player pays for the items
+    #[default]
+    Buy,
+    //the player hands over the items and gets paid
+    Sell,
+}
+
+#[derive(Clone, Debug, PartialEq, Reflect, Default, Serialize, Deserialize)]
+pub struct ShopOffer {
+    pub item: ItemName,
+    pub count: u32,
+    pub price: u32,
+    pub kind: ShopOfferKind,
+    //chance of being picked relative to the other offers
+    pub weight: u32,
+    //times the offer can be taken before the next restock
+    pub uses: u32,
+}
+
+//the offers a placed shop has today. saved with the block, so reloading doesn't restock it
+#[derive(Component, Clone, Debug)]
+pub struct ShopStock {
+    pub day: u64,
+    pub offers: Vec<ActiveOffer>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActiveOffer {
+    pub item: Entity,
+    pub count: u32,
+    pub price: u32,
+    pub kind: ShopOfferKind,
+    pub uses_left: u32,
+}
+
+//put on an entity that is trading with a shop
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpenShop(pub Entity);
+
+//in blocks, from the block's center
+pub const SHOP_RANGE: f32 = 8.0;
+
+//takes the offer once. checked against the user's open shop so a server can replay client requests
+#[derive(Event)]
+pub struct TradeEvent {
+    pub user: Entity,
+    pub shop: Entity,
+    pub offer: usize,
+}
+
+impl ShopBlock {
+    fn roll_offers(&self, items: &ItemResources, night: u64, rng: &mut StdRng) -> Vec<ActiveOffer> {
+        let mult = 1.0 + night as f32 * self.night_scaling;
+        let Ok(picked) = self
+            .offers
+            .choose_multiple_weighted(rng, self.offers_per_day, |offer| offer.weight as f32)
+        else {
+            warn!("shop has invalid offer weights");
+            return Vec::new();
+        };
+        picked
+            .filter_map(|offer| {
+                let item = items.registry.get_basic(&offer.item)?;
+                let (count, price) = match offer.kind {
+                    ShopOfferKind::Buy => ((offer.count as f32 * mult).round() as u32, offer.price),
+                    ShopOfferKind::Sell => {
+                        (offer.count, (offer.price as f32 * mult).round() as u32)
+                    }
+                };
+                Some(ActiveOffer {
+                    item,
+                    count,
+                    price,
+                    kind: offer.kind,
+                    uses_left: offer.uses,
+                })
+            })
+            .collect()
+    }
+}
+
+fn open_shops(
+    mut reader: EventReader<BlockUsedEvent>,
+    shop_query: Query<(), (With<ShopBlock>, With<BlockTemplate>)>,
+    mut commands: Commands,
+) {
+    for BlockUsedEvent {
+        user, block_used, ..
+    } in reader.read()
+    {
+        if shop_query.contains(*block_used) {
+            if let Some(mut ec) = commands.get_entity(*user) {
+                ec.insert(OpenShop(*block_used));
+            }
+        }
+    }
+}
+
+//offers change at the start of each day
+//a reloaded shop may get rolled here before its saved stock is loaded, the saved stock replaces it
+fn restock_shops(
+    shop_query: Query<(Entity, &ShopBlock, &BlockCoord, Option<&ShopStock>), With<BlockTemplate>>,
+    calendar: Option<Res<Calendar>>,
+    items: Res<ItemResources>,
+    level: Res<Level>,
+    mut commands: Commands,
+) {
+    let day = calendar.map_or(0, |c| c.time.day);
+    for (entity, shop, coord, stock) in shop_query.iter() {
+        if stock.is_some_and(|stock| stock.day == day) {
+            continue;
+        }
+        //seeded so every player sees the same offers
+        let mut rng = StdRng::seed_from_u64(level.seed ^ coord.to_seed() ^ day);
+        commands.entity(entity).insert(ShopStock {
+            day,
+            offers: shop.roll_offers(&items, day, &mut rng),
+        });
+    }
+}
+
+fn close_distant_shops(
+    query: Query<(Entity, &OpenShop, &GlobalTransform)>,
+    shop_query: Query<&BlockCoord, (With<ShopBlock>, Without<RemovedBlock>)>,
+    mut commands: Commands,
+) {
+    for (entity, OpenShop(shop), gtf) in query.iter() {
+        let in_range = shop_query.get(*shop).is_ok_and(|coord| {
+            coord.center().distance_squared(gtf.translation()) <= SHOP_RANGE * SHOP_RANGE
+        });
+        if !in_range {
+            commands.entity(entity).remove::<OpenShop>();
+        }
+    }
+}
+
+//splits an amount into currency items, most valuable first
+//anything smaller than the cheapest currency can't be paid out
+pub fn currency_stacks(
+    mut amount: u32,
+    currency_query: &Query<(Entity, &Currency)>,
+) -> Vec<ItemStack> {
+    let mut currencies: Vec<(Entity, u32)> = currency_query
+        .iter()
+        .filter(|(_, currency)| currency.0 > 0)
+        .map(|(item, currency)| (item, currency.0))
+        .collect();
+    currencies.sort_by_key(|(item, value)| (std::cmp::Reverse(*value), *item));
+    currencies
+        .into_iter()
+        .filter_map(|(item, value)| {
+            let count = amount / value;
+            amount -= count * value;
+            (count > 0).then(|| ItemStack::new(item, count))
+        })
+        .collect()
+}
+
+fn trade(
+    mut reader: EventReader<TradeEvent>,
+    mut user_query: Query<(&OpenShop, &mut Inventory, &GlobalTransform)>,
+    mut shop_query: Query<(&ShopBlock, &mut ShopStock)>,
+    currency_query: Query<&Currency>,
+    currency_items: Query<(Entity, &Currency)>,
+    data_query: Query<&MaxStackSize>,
+    items: Res<ItemResources>,
+    mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
+) {
+    for TradeEvent { user, shop, offer } in reader.read() {
+        let Ok((OpenShop(open), mut inventory, gtf)) = user_query.get_mut(*user) else {
+            continue;
+        };
+        if open != shop {
+            warn!("{:?} tried to trade with a shop it doesn't have open", user);
+            continue;
+        }
+        let Ok((shop_block, mut stock)) = shop_query.get_mut(*shop) else {
+            continue;
+        };
+        let Some(active) = stock.offers.get(*offer).copied() else {
+            continue;
+        };
+        if active.uses_left == 0 {
+            info!("offer is sold out");
+            continue;
+        }
+        let received = match active.kind {
+            ShopOfferKind::Buy => {
+                let Some(change) = inventory.spend_currency(active.price, &currency_query) else {
+                    info!("can't afford offer");
+                    continue;
+                };
+                let mut received = vec![ItemStack::new(active.item, active.count)];
+                received.extend(currency_stacks(change, &currency_items));
+                received
+            }
+            ShopOfferKind::Sell => {
+                let Some(currency) = items.registry.get_basic(&shop_block.currency) else {
+                    warn!("shop currency {:?} doesn't exist", shop_block.currency);
+                    continue;
+                };
+                let value = currency_query.get(currency).map_or(1, |c| c.0.max(1));
+                if inventory.number_of_type(active.item) < active.count {
+                    info!("not enough items to sell");
+                    continue;
+                }
+                inventory.remove_items(ItemStack::new(active.item, active.count));
+                //paid in the shop's currency, and whatever that can't divide evenly in smaller ones
+                let mut received = vec![ItemStack::new(currency, active.price / value)];
+                received.extend(currency_stacks(active.price % value, &currency_items));
+                received
+            }
+        };
+        stock.offers[*offer].uses_left -= 1;
+        //whatever doesn't fit is dropped at the user's feet
+        for stack in received {
+            if stack.size > 0
+                && let Some(stack) = inventory.pickup_item(stack, &data_query)
+            {
+                drop_writer.send(SpawnDroppedItemEvent {
+                    postion: gtf.translation(),
+                    velocity: Vec3::ZERO,
+                    stack,
+                });
+            }
+        }
+    }
+}
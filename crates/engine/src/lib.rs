@@ -17,6 +17,8 @@ pub mod controllers;
 pub mod effects;
 pub mod items;
 pub mod state;
+#[cfg(test)]
+mod test;
 
 use ::util;
 use bevy::prelude::*;
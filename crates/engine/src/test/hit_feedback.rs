@@ -0,0 +1,104 @@
+#[allow(unused_imports)]
+mod hit_feedback {
+    use bevy::prelude::*;
+    use interfaces::testing::{start_level, test_app};
+    use physics::movement::Velocity;
+
+    use crate::{
+        actors::{
+            hit_feedback::{HitFeedbackPlugin, HitStop, KnockbackCurve, KnockbackFalloff},
+            AttackEvent, Damage, DamageTakenEvent,
+        },
+        camera::MainCamera,
+    };
+
+    const FALLOFFS: [KnockbackFalloff; 4] = [
+        KnockbackFalloff::Impulse,
+        KnockbackFalloff::Constant,
+        KnockbackFalloff::Linear,
+        KnockbackFalloff::Quadratic,
+    ];
+
+    #[test]
+    fn test_tick_fractions_sum_to_one() {
+        for falloff in FALLOFFS {
+            for ticks in [0, 1, 2, 6, 13] {
+                let curve = KnockbackCurve {
+                    falloff,
+                    ticks,
+                    multiplier: 1.0,
+                };
+                let total: f32 = (0..ticks.max(1))
+                    .map(|tick| curve.tick_fraction(tick))
+                    .sum();
+                assert!(
+                    (total - 1.0).abs() < 1e-5,
+                    "{:?} over {} ticks delivers {}",
+                    falloff,
+                    ticks,
+                    total
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tick_fractions_fade_out() {
+        for falloff in [KnockbackFalloff::Linear, KnockbackFalloff::Quadratic] {
+            let curve = KnockbackCurve {
+                falloff,
+                ticks: 6,
+                multiplier: 1.0,
+            };
+            for tick in 1..6 {
+                assert!(curve.tick_fraction(tick) < curve.tick_fraction(tick - 1));
+            }
+        }
+        //nothing is left over after the curve ends
+        assert_eq!(KnockbackCurve::default().tick_fraction(100), 0.0);
+    }
+
+    fn feedback_app() -> App {
+        let mut app = test_app();
+        app.add_plugins(HitFeedbackPlugin)
+            .add_event::<AttackEvent>()
+            .add_event::<DamageTakenEvent>()
+            .insert_resource(MainCamera(Entity::PLACEHOLDER));
+        start_level(&mut app);
+        app
+    }
+
+    //(attacker, target) after a hit, ticked once
+    fn hit(melee: bool) -> (App, Entity, Entity) {
+        let mut app = feedback_app();
+        let attacker = app.world_mut().spawn(Velocity(Vec3::X)).id();
+        let target = app.world_mut().spawn(Velocity(Vec3::ZERO)).id();
+        app.world_mut().send_event(DamageTakenEvent {
+            attacker,
+            target,
+            damage: Damage::new(1.0),
+            knockback_impulse: Vec3::ZERO,
+            hit_location: Vec3::ZERO,
+            melee,
+        });
+        app.world_mut().run_schedule(FixedUpdate);
+        (app, attacker, target)
+    }
+
+    #[test]
+    fn test_melee_hit_stops_both() {
+        let (app, attacker, target) = hit(true);
+        assert!(app.world().get::<HitStop>(target).is_some());
+        assert!(app.world().get::<HitStop>(attacker).is_some());
+    }
+
+    #[test]
+    fn test_ranged_hit_only_stops_target() {
+        let (mut app, attacker, target) = hit(false);
+        assert!(app.world().get::<HitStop>(target).is_some());
+        assert!(app.world().get::<HitStop>(attacker).is_none());
+        //and the shooter keeps moving at full speed
+        app.world_mut().run_schedule(FixedUpdate);
+        assert_eq!(app.world().get::<Velocity>(attacker).unwrap().0, Vec3::X);
+    }
+}
@@ -0,0 +1,162 @@
+#[allow(unused_imports)]
+mod inventory_actions {
+    use bevy::prelude::*;
+    use interfaces::testing::{start_level, test_app};
+
+    use crate::{
+        controllers::{get_input_map, Action},
+        items::{
+            container::OpenContainer,
+            inventory::Inventory,
+            inventory_actions::{InventoryActionsPlugin, SplitStackEvent, TransferStackEvent},
+            metadata::ItemMetadata,
+            ItemStack, MaxStackSize,
+        },
+    };
+
+    fn inventory_app() -> App {
+        let mut app = test_app();
+        app.add_plugins(InventoryActionsPlugin);
+        start_level(&mut app);
+        app
+    }
+
+    fn spawn_inventory(app: &mut App, slots: &[Option<ItemStack>]) -> Entity {
+        let entity = app.world_mut().spawn_empty().id();
+        let mut inventory = Inventory::new(entity, slots.len());
+        for (slot, stack) in slots.iter().enumerate() {
+            if let Some(stack) = stack {
+                inventory.set_slot_no_events(slot, *stack);
+            }
+        }
+        app.world_mut().entity_mut(entity).insert(inventory);
+        entity
+    }
+
+    fn sizes(app: &App, inventory: Entity) -> Vec<u32> {
+        app.world()
+            .get::<Inventory>(inventory)
+            .unwrap()
+            .iter()
+            .map(|slot| slot.as_ref().map_or(0, |(stack, _)| stack.size))
+            .collect()
+    }
+
+    #[test]
+    fn test_inventory_modifiers_dont_move_the_player() {
+        let map = get_input_map();
+        let movement = [
+            Action::MoveForward,
+            Action::MoveBack,
+            Action::MoveLeft,
+            Action::MoveRight,
+            Action::MoveUp,
+            Action::MoveDown,
+            Action::Float,
+            Action::Dash,
+        ];
+        for modifier in [Action::QuickMoveStack, Action::SplitStack] {
+            for binding in map.get_buttonlike(&modifier).unwrap() {
+                for action in movement {
+                    assert!(
+                        !map.get_buttonlike(&action)
+                            .is_some_and(|bindings| bindings.contains(binding)),
+                        "{:?} shares {:?} with {:?}",
+                        modifier,
+                        binding,
+                        action
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_quick_move_tops_up_the_open_container() {
+        let mut app = inventory_app();
+        let item = app.world_mut().spawn(MaxStackSize(64)).id();
+        let player = spawn_inventory(&mut app, &[Some(ItemStack::new(item, 10)), None]);
+        let container = spawn_inventory(&mut app, &[Some(ItemStack::new(item, 60)), None]);
+        app.world_mut()
+            .entity_mut(player)
+            .insert(OpenContainer(container));
+        app.world_mut().send_event(TransferStackEvent {
+            user: player,
+            from: player,
+            slot: 0,
+            to: container,
+        });
+        app.world_mut().run_schedule(Update);
+        assert_eq!(sizes(&app, player), vec![0, 0]);
+        assert_eq!(sizes(&app, container), vec![64, 6]);
+    }
+
+    #[test]
+    fn test_quick_move_needs_the_container_open() {
+        let mut app = inventory_app();
+        let item = app.world_mut().spawn(MaxStackSize(64)).id();
+        let player = spawn_inventory(&mut app, &[Some(ItemStack::new(item, 10))]);
+        let container = spawn_inventory(&mut app, &[None]);
+        app.world_mut().send_event(TransferStackEvent {
+            user: player,
+            from: player,
+            slot: 0,
+            to: container,
+        });
+        app.world_mut().run_schedule(Update);
+        assert_eq!(sizes(&app, player), vec![10]);
+        assert_eq!(sizes(&app, container), vec![0]);
+    }
+
+    #[test]
+    fn test_split_stack() {
+        let mut app = inventory_app();
+        let item = app.world_mut().spawn(MaxStackSize(64)).id();
+        let meta = app
+            .world_mut()
+            .spawn(ItemMetadata {
+                custom_name: Some("lucky".to_string()),
+                ..default()
+            })
+            .id();
+        let player = spawn_inventory(
+            &mut app,
+            &[
+                None,
+                Some(ItemStack::new(item, 7).with_meta(Some(meta))),
+                None,
+            ],
+        );
+        app.world_mut().send_event(SplitStackEvent {
+            user: player,
+            inventory: player,
+            slot: 1,
+        });
+        app.world_mut().run_schedule(Update);
+        //half (rounded down) goes to the first empty slot
+        assert_eq!(sizes(&app, player), vec![3, 4, 0]);
+        //and gets its own copy of the metadata
+        let inventory = app.world().get::<Inventory>(player).unwrap();
+        let split_meta = inventory.get(0).unwrap().meta.unwrap();
+        assert_ne!(split_meta, meta);
+        assert_eq!(inventory.get(1).unwrap().meta, Some(meta));
+        assert_eq!(
+            app.world().get::<ItemMetadata>(split_meta),
+            app.world().get::<ItemMetadata>(meta)
+        );
+    }
+
+    #[test]
+    fn test_split_single_item() {
+        let mut app = inventory_app();
+        let item = app.world_mut().spawn(MaxStackSize(64)).id();
+        let player = spawn_inventory(&mut app, &[Some(ItemStack::new(item, 1)), None]);
+        app.world_mut().send_event(SplitStackEvent {
+            user: player,
+            inventory: player,
+            slot: 0,
+        });
+        app.world_mut().run_schedule(Update);
+        assert_eq!(sizes(&app, player), vec![1, 0]);
+    }
+}
@@ -0,0 +1,125 @@
+#[allow(unused_imports)]
+mod loot {
+    use bevy::prelude::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::items::loot::{
+        CachedLootCondition, CachedLootEntry, CachedLootGroup, CachedLootTable,
+        CachedLootTableDrop, LootContext, LootRng,
+    };
+
+    fn table() -> CachedLootTable<&'static str> {
+        CachedLootTable {
+            drops: vec![
+                CachedLootTableDrop {
+                    item: "coin",
+                    drop_chance: 0.5,
+                    drop_count_range: (1, 10),
+                    conditions: Vec::new(),
+                },
+                CachedLootTableDrop {
+                    item: "moon_shard",
+                    drop_chance: 0.75,
+                    drop_count_range: (1, 3),
+                    conditions: vec![CachedLootCondition::Night, CachedLootCondition::MinNight(3)],
+                },
+            ],
+            groups: vec![CachedLootGroup {
+                rolls: 4,
+                choices: vec![
+                    (2, CachedLootEntry::Nothing),
+                    (
+                        3,
+                        CachedLootEntry::Item {
+                            item: "gem",
+                            count_range: (1, 2),
+                        },
+                    ),
+                    (
+                        1,
+                        CachedLootEntry::Table(CachedLootTable {
+                            drops: vec![CachedLootTableDrop {
+                                item: "ore",
+                                drop_chance: 1.0,
+                                drop_count_range: (2, 5),
+                                conditions: vec![CachedLootCondition::MinToolTier(2)],
+                            }],
+                            groups: Vec::new(),
+                        }),
+                    ),
+                ],
+                conditions: vec![CachedLootCondition::NoneOf(vec![
+                    CachedLootCondition::Biome("void".to_string()),
+                ])],
+            }],
+        }
+    }
+
+    fn contexts() -> Vec<LootContext> {
+        vec![
+            LootContext::default(),
+            LootContext {
+                night: 5,
+                in_night: true,
+                tool_tier: Some(3),
+                ..default()
+            },
+            LootContext {
+                night: 1,
+                in_night: true,
+                biome: Some("void"),
+                tool_tier: Some(1),
+                ..default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_seeded_loot_is_deterministic() {
+        let table = table();
+        for seed in [0, 1, 42, u64::MAX] {
+            let mut rng_a = LootRng::seeded(seed);
+            let mut rng_b = LootRng::seeded(seed);
+            //rolling many times in a row checks that conditions don't desync the rng streams
+            for _ in 0..32 {
+                for context in contexts() {
+                    assert_eq!(
+                        table.get_loot(&context, &mut rng_a.0),
+                        table.get_loot(&context, &mut rng_b.0)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_loot_conditions() {
+        let table = table();
+        let mut rng = StdRng::seed_from_u64(3);
+        let [day, night, void] = contexts().try_into().unwrap();
+        for _ in 0..64 {
+            let day_loot = table.get_loot(&day, &mut rng);
+            assert!(day_loot.iter().all(|(item, _)| *item != "moon_shard"));
+            //no tool, so the nested table never drops ore
+            assert!(day_loot.iter().all(|(item, _)| *item != "ore"));
+            //the group is skipped entirely in the void
+            let void_loot = table.get_loot(&void, &mut rng);
+            assert!(void_loot.iter().all(|(item, _)| *item == "coin"));
+        }
+        let night_loot = (0..64)
+            .flat_map(|_| table.get_loot(&night, &mut rng))
+            .collect::<Vec<_>>();
+        assert!(night_loot.iter().any(|(item, _)| *item == "moon_shard"));
+        assert!(night_loot.iter().any(|(item, _)| *item == "ore"));
+        for (item, count) in night_loot {
+            let range = match item {
+                "coin" => 1..=10,
+                "moon_shard" => 1..=3,
+                "gem" => 1..=2,
+                "ore" => 2..=5,
+                _ => panic!("unexpected item {}", item),
+            };
+            assert!(range.contains(&count));
+        }
+    }
+}
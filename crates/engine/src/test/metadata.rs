@@ -0,0 +1,72 @@
+#[allow(unused_imports)]
+mod metadata {
+    use bevy::prelude::*;
+    use interfaces::testing::{start_level, test_app};
+
+    use crate::items::{
+        inventory::Inventory,
+        metadata::{DamageItemEvent, Durability, ItemMetadata, ItemMetadataPlugin, MaxDurability},
+        ItemStack,
+    };
+
+    fn metadata_app() -> App {
+        let mut app = test_app();
+        app.add_plugins(ItemMetadataPlugin);
+        start_level(&mut app);
+        app
+    }
+
+    //a user holding one undamaged item with the given durability
+    fn spawn_user(app: &mut App, max_durability: u32) -> Entity {
+        let item = app.world_mut().spawn(MaxDurability(max_durability)).id();
+        let user = app.world_mut().spawn_empty().id();
+        let mut inventory = Inventory::new(user, 1);
+        inventory.set_slot_no_events(0, ItemStack::new(item, 1));
+        app.world_mut().entity_mut(user).insert(inventory);
+        user
+    }
+
+    fn hit(user: Entity, amount: u32) -> DamageItemEvent {
+        DamageItemEvent {
+            user,
+            inventory_slot: 0,
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_hits_in_the_same_frame_add_up() {
+        let mut app = metadata_app();
+        let user = spawn_user(&mut app, 10);
+        app.world_mut()
+            .send_event_batch([hit(user, 3), hit(user, 4)]);
+        app.world_mut().run_schedule(Update);
+        let stack = app.world().get::<Inventory>(user).unwrap().get(0).unwrap();
+        let meta = app
+            .world()
+            .get::<ItemMetadata>(stack.meta.unwrap())
+            .unwrap();
+        assert_eq!(
+            meta.durability,
+            Some(Durability {
+                current: 3,
+                max: 10
+            })
+        );
+        //both hits went to the same metadata entity
+        let mut meta_query = app.world_mut().query::<&ItemMetadata>();
+        assert_eq!(meta_query.iter(app.world()).count(), 1);
+    }
+
+    #[test]
+    fn test_hits_in_the_same_frame_can_break() {
+        let mut app = metadata_app();
+        let user = spawn_user(&mut app, 10);
+        app.world_mut()
+            .send_event_batch([hit(user, 6), hit(user, 6)]);
+        app.world_mut().run_schedule(Update);
+        assert!(app.world().get::<Inventory>(user).unwrap().get(0).is_none());
+        let mut meta_query = app.world_mut().query::<&ItemMetadata>();
+        assert_eq!(meta_query.iter(app.world()).count(), 0);
+    }
+}
@@ -0,0 +1,6 @@
+mod hit_feedback;
+mod inventory_actions;
+mod loot;
+mod metadata;
+mod projectile;
+mod shop;
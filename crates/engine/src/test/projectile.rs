@@ -0,0 +1,98 @@
+#[allow(unused_imports)]
+mod projectile {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+    use interfaces::testing::{start_level, test_app};
+    use physics::{
+        collision::{Aabb, BlockPhysics, CollidingBlocks},
+        movement::Velocity,
+        spatial::SpatialIndex,
+    };
+    use world::{
+        block::BlockCoord,
+        events::{DealBlockDamageEvent, ExplosionEvent},
+    };
+
+    use crate::actors::{
+        projectile::{
+            ProjecileHitBehavior, Projectile, ProjectileBehaviors, ProjectileHitState,
+            ProjectilePlugin,
+        },
+        team::{TeamId, TeamRelationships},
+        AttackEvent, Damage,
+    };
+
+    const WALL: BlockCoord = BlockCoord::new(1, 0, 0);
+
+    fn projectile_app() -> App {
+        let mut app = test_app();
+        app.add_plugins(ProjectilePlugin)
+            .add_event::<AttackEvent>()
+            .add_event::<DealBlockDamageEvent>()
+            .add_event::<ExplosionEvent>()
+            .init_resource::<SpatialIndex>()
+            .init_resource::<TeamRelationships>();
+        start_level(&mut app);
+        app
+    }
+
+    //a projectile pressed up against a wall, as if it just hit it
+    fn spawn_against_wall(app: &mut App, bounces: u32) -> Entity {
+        app.world_mut()
+            .spawn((
+                Projectile {
+                    owner: Entity::PLACEHOLDER,
+                    damage: Damage::new(1.0),
+                    terrain_damage: 0.5,
+                    knockback_mult: 0.0,
+                    despawn_time: Duration::MAX,
+                    hit_behavior: ProjecileHitBehavior::Despawn,
+                    on_hit: None,
+                },
+                ProjectileBehaviors {
+                    bounces,
+                    restitution: 1.0,
+                    ..default()
+                },
+                ProjectileHitState::default(),
+                CollidingBlocks {
+                    pos_x: vec![(WALL, Entity::PLACEHOLDER, BlockPhysics::Solid)],
+                    ..default()
+                },
+                Aabb::centered(Vec3::splat(0.25)),
+                Velocity(Vec3::X),
+                TeamId::FREE_FOR_ALL,
+                GlobalTransform::default(),
+            ))
+            .id()
+    }
+
+    fn tick_block_damage(app: &mut App) -> Vec<(BlockCoord, f32)> {
+        app.world_mut().run_schedule(FixedUpdate);
+        app.world_mut()
+            .resource_mut::<Events<DealBlockDamageEvent>>()
+            .drain()
+            .map(|event| (event.block_position, event.damage))
+            .collect()
+    }
+
+    #[test]
+    fn test_stopping_hit_damages_terrain() {
+        let mut app = projectile_app();
+        let projectile = spawn_against_wall(&mut app, 0);
+        assert_eq!(tick_block_damage(&mut app), vec![(WALL, 0.5)]);
+        assert!(app.world().get_entity(projectile).is_err());
+    }
+
+    #[test]
+    fn test_bounces_dont_damage_terrain() {
+        let mut app = projectile_app();
+        let projectile = spawn_against_wall(&mut app, 2);
+        assert!(tick_block_damage(&mut app).is_empty());
+        assert!(tick_block_damage(&mut app).is_empty());
+        //out of bounces, so this hit stops it
+        assert_eq!(tick_block_damage(&mut app), vec![(WALL, 0.5)]);
+        assert!(app.world().get_entity(projectile).is_err());
+    }
+}
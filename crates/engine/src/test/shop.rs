@@ -0,0 +1,104 @@
+#[allow(unused_imports)]
+mod shop {
+    use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+    use crate::items::{
+        inventory::Inventory,
+        shop::{currency_stacks, Currency},
+        ItemStack,
+    };
+
+    //returns the change owed and the slot sizes after spending
+    fn spend(
+        world: &mut World,
+        slots: &[Option<ItemStack>],
+        amount: u32,
+    ) -> (Option<u32>, Vec<u32>) {
+        let mut inventory = Inventory::new(Entity::PLACEHOLDER, slots.len());
+        for (slot, stack) in slots.iter().enumerate() {
+            if let Some(stack) = stack {
+                inventory.set_slot_no_events(slot, *stack);
+            }
+        }
+        world
+            .run_system_once(move |currency_query: Query<&Currency>| {
+                let change = inventory.spend_currency(amount, &currency_query);
+                let sizes = inventory
+                    .iter()
+                    .map(|slot| slot.as_ref().map_or(0, |(stack, _)| stack.size))
+                    .collect();
+                (change, sizes)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_spend_exact() {
+        let mut world = World::new();
+        let coin = world.spawn(Currency(1)).id();
+        let gem = world.spawn(Currency(10)).id();
+        let (change, sizes) = spend(
+            &mut world,
+            &[Some(ItemStack::new(coin, 5)), Some(ItemStack::new(gem, 3))],
+            23,
+        );
+        assert_eq!(change, Some(0));
+        assert_eq!(sizes, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_spend_makes_change() {
+        let mut world = World::new();
+        let coin = world.spawn(Currency(1)).id();
+        let gem = world.spawn(Currency(10)).id();
+        let (change, sizes) = spend(
+            &mut world,
+            &[Some(ItemStack::new(coin, 2)), Some(ItemStack::new(gem, 2))],
+            15,
+        );
+        //one gem and both coins only make 12, so a second gem is spent
+        assert_eq!(change, Some(5));
+        assert_eq!(sizes, vec![2, 0]);
+    }
+
+    #[test]
+    fn test_spend_is_atomic() {
+        let mut world = World::new();
+        let coin = world.spawn(Currency(1)).id();
+        let gem = world.spawn(Currency(10)).id();
+        let rock = world.spawn_empty().id();
+        let (change, sizes) = spend(
+            &mut world,
+            &[
+                Some(ItemStack::new(coin, 4)),
+                Some(ItemStack::new(gem, 1)),
+                Some(ItemStack::new(rock, 10)),
+            ],
+            15,
+        );
+        assert_eq!(change, None);
+        assert_eq!(sizes, vec![4, 1, 10]);
+    }
+
+    #[test]
+    fn test_currency_stacks() {
+        let mut world = World::new();
+        let coin = world.spawn(Currency(1)).id();
+        let gem = world.spawn(Currency(10)).id();
+        let stacks = world
+            .run_system_once(|currency_query: Query<(Entity, &Currency)>| {
+                currency_stacks(27, &currency_query)
+            })
+            .unwrap();
+        assert_eq!(
+            stacks,
+            vec![ItemStack::new(gem, 2), ItemStack::new(coin, 7)]
+        );
+        let none = world
+            .run_system_once(|currency_query: Query<(Entity, &Currency)>| {
+                currency_stacks(0, &currency_query)
+            })
+            .unwrap();
+        assert!(none.is_empty());
+    }
+}
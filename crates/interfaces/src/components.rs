@@ -78,3 +78,9 @@ pub struct RemoteClient(pub ClientId);
 pub struct VisualizeHeldItem {
     pub inventory: Entity,
 }
+
+#[derive(Component)]
+pub struct VisualizeEquippedItem {
+    pub equipment: Entity,
+    pub slot: usize,
+}
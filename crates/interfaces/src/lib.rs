@@ -2,6 +2,7 @@ pub mod components;
 pub mod resources;
 pub mod scheduling;
 pub mod serialization;
+pub mod testing;
 
 use bevy::prelude::*;
 
@@ -24,4 +24,22 @@ impl HeldItemResources {
             ))
             .id()
     }
+
+    pub fn create_equipped_item_visualizer(
+        &self,
+        commands: &mut Commands,
+        equipment: Entity,
+        slot: usize,
+        tf: Transform,
+    ) -> Entity {
+        #[allow(state_scoped_entities)]
+        commands
+            .spawn((
+                MeshMaterial3d(self.color_material.clone()),
+                Mesh3d::default(),
+                tf,
+                crate::components::VisualizeEquippedItem { equipment, slot },
+            ))
+            .id()
+    }
 }
@@ -0,0 +1,26 @@
+//fixture for the crates' tests
+use bevy::{prelude::*, state::app::StatesPlugin};
+
+use crate::{
+    scheduling::{GameState, LevelLoadState},
+    InterfacesPlugin,
+};
+
+//headless app with the game's schedules and states, add the plugins under test to it
+pub fn test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin, InterfacesPlugin));
+    app
+}
+
+//enters the game with a loaded level, so systems that need one start running
+pub fn start_level(app: &mut App) {
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Game);
+    app.world_mut().run_schedule(StateTransition);
+    app.world_mut()
+        .resource_mut::<NextState<LevelLoadState>>()
+        .set(LevelLoadState::Loaded);
+    app.world_mut().run_schedule(StateTransition);
+}
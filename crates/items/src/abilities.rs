@@ -0,0 +1,621 @@
+use std::{sync::Arc, time::Duration};
+
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+use actors::spawning::{DefaultSpawnArgs, ProjectileSpawnArgs, SpawnProjectileEvent};
+use engine::{
+    actors::{
+        abilities::stamina::{Stamina, StaminaCost},
+        team::{TeamId, TeamRelationships},
+        Combatant, CombatantBundle, Damage,
+    },
+    items::{metadata::DamageItemEvent, HitResult, UseEndEvent, UseItemEvent},
+};
+use interfaces::scheduling::ItemSystemSet;
+use physics::{
+    collision::{Aabb, BlockPhysics},
+    movement::{Mass, Velocity},
+    query::{self, Raycast, RaycastHit},
+};
+use util::direction::Direction;
+use world::{
+    block::BlockCoord,
+    events::{BlockHitEvent, DealBlockDamageEvent},
+    level::Level,
+};
+
+use crate::tools::{
+    abilities::{AxeAbilityTarget, ShovelAbilityTarget},
+    calc_block_damage, Tool, ToolResistance,
+};
+
+pub struct ItemAbilityPlugin;
+
+impl Plugin for ItemAbilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ItemAbility>().add_systems(
+            Update,
+            (
+                use_item_abilities,
+                block_hit_abilities.after(crate::tools::on_swing),
+            )
+                .in_set(ItemSystemSet::UsageProcessing),
+        );
+    }
+}
+
+//how far an ability can reach to find its target
+const ABILITY_REACH: f32 = 10.0;
+
+//an active ability triggered by using the item or by swinging it at blocks
+#[derive(Component, Reflect, Clone, Debug, Default)]
+#[reflect(Component, FromWorld)]
+pub struct ItemAbility {
+    #[reflect(default)]
+    pub trigger: AbilityTrigger,
+    pub stamina_cost: StaminaCost,
+    pub cooldown: Duration,
+    pub shape: AbilityShape,
+    pub effects: Vec<AbilityEffect>,
+}
+
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq)]
+pub enum AbilityShape {
+    //only the block or entity being looked at
+    #[default]
+    Target,
+    //around the targeted point, or the user if nothing is targeted
+    Sphere {
+        radius: f32,
+    },
+    //in front of the user. angle is in degrees from the look direction
+    Cone {
+        range: f32,
+        half_angle: f32,
+    },
+    //square tunnel into the targeted block along the look direction
+    Tunnel {
+        radius: i32,
+        length: i32,
+    },
+    //blocks connected to the targeted block that the effect can target, like a tree
+    Connected {
+        max_blocks: usize,
+        search_radius: i32,
+    },
+}
+
+//which blocks a block damage effect hits
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AbilityBlockTarget {
+    #[default]
+    Any,
+    Axe,
+    Shovel,
+}
+
+#[derive(Reflect, Clone, Debug)]
+pub enum AbilityEffect {
+    //multiplies the item's normal tool damage
+    BlockDamage {
+        mult: f32,
+        targets: AbilityBlockTarget,
+    },
+    //pushes hostile entities in the area away from the user
+    Knockback {
+        strength: f32,
+    },
+    //fired from the user along the look direction, spread evenly across spread degrees
+    SpawnProjectile {
+        name: String,
+        damage: Damage,
+        speed: f32,
+        count: u32,
+        spread: f32,
+    },
+    //heals the user
+    Heal {
+        amount: f32,
+    },
+}
+
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AbilityTrigger {
+    //using the item. the windup from ItemUseSpeed is the charge-up
+    #[default]
+    Use,
+    //every block the item is swung at, like felling a tree with an axe. the hit block is the target
+    BlockHit,
+}
+
+//when each ability item can next be used, by item
+#[derive(Component, Default)]
+pub struct AbilityCooldowns(HashMap<Entity, Duration>);
+
+fn block_targeted(
+    target: AbilityBlockTarget,
+    block: Entity,
+    axe_query: &Query<(), With<AxeAbilityTarget>>,
+    shovel_query: &Query<(), With<ShovelAbilityTarget>>,
+) -> bool {
+    match target {
+        AbilityBlockTarget::Any => true,
+        AbilityBlockTarget::Axe => axe_query.contains(block),
+        AbilityBlockTarget::Shovel => shovel_query.contains(block),
+    }
+}
+
+//searches up first, expands in a square, then searches down
+fn connected_blocks(
+    level: &Level,
+    start: BlockCoord,
+    max_blocks: usize,
+    search_radius: i32,
+    is_target: &impl Fn(Entity) -> bool,
+) -> Vec<BlockCoord> {
+    let mut found = vec![start];
+    let mut seen = HashSet::new();
+    seen.insert(start);
+    let mut i = 0;
+    while i < found.len() && found.len() < max_blocks {
+        let pos = found[i];
+        i += 1;
+        for y in (1..=search_radius).chain(-search_radius..0) {
+            for square_radius in 0..=search_radius {
+                for x in -square_radius..=square_radius {
+                    for z in -square_radius..=square_radius {
+                        let next = pos + BlockCoord::new(x, y, z);
+                        if found.len() < max_blocks
+                            && level.get_block_entity(next).is_some_and(is_target)
+                            && seen.insert(next)
+                        {
+                            found.push(next);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    found
+}
+
+impl AbilityShape {
+    fn blocks(
+        self,
+        level: &Level,
+        tf: Transform,
+        target: Option<BlockCoord>,
+        is_target: &impl Fn(Entity) -> bool,
+    ) -> Vec<BlockCoord> {
+        match self {
+            AbilityShape::Target => target.into_iter().collect(),
+            AbilityShape::Sphere { radius } => {
+                let center = target.map_or(tf.translation, |coord| coord.center());
+                let r = radius.ceil() as i32;
+                let center_coord = BlockCoord::from(center);
+                let mut blocks = Vec::new();
+                for x in -r..=r {
+                    for y in -r..=r {
+                        for z in -r..=r {
+                            let coord = center_coord + BlockCoord::new(x, y, z);
+                            if coord.center().distance_squared(center) <= radius * radius {
+                                blocks.push(coord);
+                            }
+                        }
+                    }
+                }
+                blocks
+            }
+            AbilityShape::Cone { range, half_angle } => {
+                let r = range.ceil() as i32;
+                let origin = BlockCoord::from(tf.translation);
+                let min_cos = half_angle.to_radians().cos();
+                let mut blocks = Vec::new();
+                for x in -r..=r {
+                    for y in -r..=r {
+                        for z in -r..=r {
+                            let coord = origin + BlockCoord::new(x, y, z);
+                            let delta = coord.center() - tf.translation;
+                            if delta.length_squared() <= range * range
+                                && delta.normalize_or_zero().dot(*tf.forward()) >= min_cos
+                            {
+                                blocks.push(coord);
+                            }
+                        }
+                    }
+                }
+                blocks
+            }
+            AbilityShape::Tunnel { radius, length } => {
+                let Some(target) = target else {
+                    return Vec::new();
+                };
+                let direction = Direction::from(tf.forward());
+                let axis = BlockCoord::from(direction);
+                let mut blocks = Vec::new();
+                for len in 0..length {
+                    direction.for_each_in_plane(radius, |offset| {
+                        blocks.push(axis * len + offset.into() + target);
+                    });
+                }
+                blocks
+            }
+            AbilityShape::Connected {
+                max_blocks,
+                search_radius,
+            } => match target {
+                Some(target) if level.get_block_entity(target).is_some_and(is_target) => {
+                    connected_blocks(level, target, max_blocks, search_radius, is_target)
+                }
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    fn contains_point(self, tf: Transform, target: Option<Vec3>, point: Vec3) -> bool {
+        match self {
+            AbilityShape::Target => false,
+            AbilityShape::Sphere { radius } => {
+                target.unwrap_or(tf.translation).distance_squared(point) <= radius * radius
+            }
+            AbilityShape::Cone { range, half_angle } => {
+                let delta = point - tf.translation;
+                delta.length_squared() <= range * range
+                    && delta.normalize_or_zero().dot(*tf.forward()) >= half_angle.to_radians().cos()
+            }
+            //these only make sense for blocks
+            AbilityShape::Tunnel { .. } | AbilityShape::Connected { .. } => false,
+        }
+    }
+}
+
+//the parts of the world abilities can affect
+#[derive(SystemParam)]
+struct AbilityEffectParam<'w, 's> {
+    target_query: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static GlobalTransform,
+            &'static TeamId,
+            &'static mut Velocity,
+            Option<&'static Mass>,
+        ),
+    >,
+    level: Res<'w, Level>,
+    resistance_query: Query<'w, 's, &'static ToolResistance>,
+    axe_query: Query<'w, 's, (), With<AxeAbilityTarget>>,
+    shovel_query: Query<'w, 's, (), With<ShovelAbilityTarget>>,
+    relationships: Res<'w, TeamRelationships>,
+    block_damage_writer: EventWriter<'w, DealBlockDamageEvent>,
+    projectile_writer: EventWriter<'w, SpawnProjectileEvent>,
+}
+
+//everything about a single activation of an ability
+struct AbilityActivation<'a> {
+    ability: &'a ItemAbility,
+    item: Entity,
+    user: Entity,
+    tf: Transform,
+    team: TeamId,
+    tool: Tool,
+    target_block: Option<BlockCoord>,
+    target_entity: Option<Entity>,
+    hit_pos: Option<Vec3>,
+    //already damaged by whatever triggered the ability
+    skip_block: Option<BlockCoord>,
+}
+
+impl AbilityEffectParam<'_, '_> {
+    fn apply(&mut self, activation: AbilityActivation, mut combatant: Option<&mut Combatant>) {
+        let AbilityActivation {
+            ability,
+            item,
+            user,
+            tf,
+            team,
+            tool,
+            target_block,
+            target_entity,
+            hit_pos,
+            skip_block,
+        } = activation;
+        for effect in ability.effects.iter() {
+            match effect {
+                AbilityEffect::BlockDamage { mult, targets } => {
+                    let is_target = |block: Entity| {
+                        block_targeted(*targets, block, &self.axe_query, &self.shovel_query)
+                    };
+                    for coord in ability
+                        .shape
+                        .blocks(&self.level, tf, target_block, &is_target)
+                    {
+                        if Some(coord) == skip_block {
+                            continue;
+                        }
+                        let Some(block) = self.level.get_block_entity(coord) else {
+                            continue;
+                        };
+                        if !is_target(block) {
+                            continue;
+                        }
+                        let damage = calc_block_damage(
+                            self.resistance_query
+                                .get(block)
+                                .copied()
+                                .unwrap_or_default(),
+                            tool,
+                        ) * mult;
+                        self.block_damage_writer.send(DealBlockDamageEvent {
+                            block_position: coord,
+                            damage,
+                            damager: Some(item),
+                        });
+                    }
+                }
+                AbilityEffect::Knockback { strength } => {
+                    for (entity, target_gtf, target_team, mut v, mass) in
+                        self.target_query.iter_mut()
+                    {
+                        let in_area = Some(entity) == target_entity
+                            || ability
+                                .shape
+                                .contains_point(tf, hit_pos, target_gtf.translation());
+                        if entity == user
+                            || !in_area
+                            || !self.relationships.is_hostile(team, *target_team)
+                        {
+                            continue;
+                        }
+                        let direction =
+                            (target_gtf.translation() - tf.translation).normalize_or_zero();
+                        mass.unwrap_or_default()
+                            .add_impulse(direction * *strength, &mut v);
+                    }
+                }
+                AbilityEffect::SpawnProjectile {
+                    name,
+                    damage,
+                    speed,
+                    count,
+                    spread,
+                } => {
+                    for i in 0..*count {
+                        //evenly spaced around the look direction
+                        let t = if *count > 1 {
+                            i as f32 / (*count - 1) as f32 - 0.5
+                        } else {
+                            0.0
+                        };
+                        let direction = Quat::from_axis_angle(*tf.up(), (t * spread).to_radians())
+                            * *tf.forward();
+                        self.projectile_writer.send(SpawnProjectileEvent {
+                            name: Arc::new(name.clone()),
+                            default: DefaultSpawnArgs {
+                                transform: Transform::from_translation(tf.translation),
+                            },
+                            projectile: ProjectileSpawnArgs {
+                                velocity: Velocity(direction * *speed),
+                                combat: CombatantBundle {
+                                    combatant: Combatant::new(100.0, 0.0),
+                                    team,
+                                    ..default()
+                                },
+                                owner: user,
+                                damage: *damage,
+                                lifetime_mult: 1.0,
+                                knockback_mult: 1.0,
+                                terrain_damage_mult: 1.0,
+                                behaviors: default(),
+                            },
+                        });
+                    }
+                }
+                AbilityEffect::Heal { amount } => {
+                    if let Some(Combatant::Root { health, .. }) = combatant.as_deref_mut() {
+                        health.current = (health.current + amount).min(health.max);
+                    }
+                }
+            }
+        }
+    }
+}
+
+//pays the stamina cost and starts the cooldown, or returns false if the ability can't be used yet
+fn try_activate(
+    ability: &ItemAbility,
+    item: Entity,
+    user: Entity,
+    stamina: Option<&mut Stamina>,
+    cooldowns: Option<&mut AbilityCooldowns>,
+    now: Duration,
+    commands: &mut Commands,
+) -> bool {
+    let ready = cooldowns
+        .as_ref()
+        .and_then(|cooldowns| cooldowns.0.get(&item))
+        .is_none_or(|ready_at| *ready_at <= now);
+    let paid = ready
+        && match stamina {
+            Some(stamina) => ability.stamina_cost.apply(stamina),
+            None => ability.stamina_cost == StaminaCost::default(),
+        };
+    if !paid {
+        return false;
+    }
+    match cooldowns {
+        Some(cooldowns) => {
+            cooldowns.0.insert(item, now + ability.cooldown);
+        }
+        None => {
+            let mut cooldowns = AbilityCooldowns::default();
+            cooldowns.0.insert(item, now + ability.cooldown);
+            commands.entity(user).insert(cooldowns);
+        }
+    }
+    true
+}
+
+fn use_item_abilities(
+    mut reader: EventReader<UseItemEvent>,
+    mut hit_writer: EventWriter<UseEndEvent>,
+    ability_query: Query<(&ItemAbility, Option<&Tool>)>,
+    mut user_query: Query<(
+        Option<&mut Stamina>,
+        Option<&mut AbilityCooldowns>,
+        Option<&mut Combatant>,
+        Option<&TeamId>,
+    )>,
+    block_physics_query: Query<&BlockPhysics>,
+    object_query: Query<(Entity, &GlobalTransform, &Aabb)>,
+    time: Res<Time>,
+    mut effects: AbilityEffectParam,
+    mut durability_writer: EventWriter<DamageItemEvent>,
+    mut commands: Commands,
+) {
+    for UseItemEvent {
+        user,
+        inventory_slot,
+        stack,
+        tf,
+    } in reader.read()
+    {
+        let Ok((ability, tool)) = ability_query.get(stack.id) else {
+            continue;
+        };
+        if ability.trigger != AbilityTrigger::Use {
+            continue;
+        }
+        let Ok((mut stamina, mut cooldowns, combatant, team)) = user_query.get_mut(*user) else {
+            continue;
+        };
+        if !try_activate(
+            ability,
+            stack.id,
+            *user,
+            stamina.as_deref_mut(),
+            cooldowns.as_deref_mut(),
+            time.elapsed(),
+            &mut commands,
+        ) {
+            info!("ability not ready or not enough stamina");
+            hit_writer.send(UseEndEvent {
+                user: *user,
+                inventory_slot: *inventory_slot,
+                stack: *stack,
+                result: HitResult::Fail,
+            });
+            continue;
+        }
+
+        let (target_block, target_entity, hit_pos) = match query::raycast(
+            Raycast::new(tf.translation, tf.forward(), ABILITY_REACH),
+            &effects.level,
+            &block_physics_query,
+            &object_query,
+            &[*user],
+        ) {
+            Some(RaycastHit::Block(coord, hit)) => (Some(coord), None, Some(hit.hit_pos)),
+            Some(RaycastHit::Object(hit)) => (None, Some(hit.entity), Some(hit.hit_pos)),
+            None => (None, None, None),
+        };
+        effects.apply(
+            AbilityActivation {
+                ability,
+                item: stack.id,
+                user: *user,
+                tf: *tf,
+                team: team.copied().unwrap_or(TeamId::PLAYER),
+                tool: tool.copied().unwrap_or_default(),
+                target_block,
+                target_entity,
+                hit_pos,
+                skip_block: None,
+            },
+            combatant.map(|c| c.into_inner()),
+        );
+        if let Some(slot) = inventory_slot {
+            durability_writer.send(DamageItemEvent {
+                user: *user,
+                inventory_slot: *slot,
+                amount: 1,
+            });
+        }
+        hit_writer.send(UseEndEvent {
+            user: *user,
+            inventory_slot: *inventory_slot,
+            stack: *stack,
+            result: hit_pos.map_or(HitResult::Miss, HitResult::Hit),
+        });
+    }
+}
+
+//the swing already damaged the hit block and used durability, the ability spreads the hit to the rest of its shape
+fn block_hit_abilities(
+    mut reader: EventReader<BlockHitEvent>,
+    ability_query: Query<(&ItemAbility, Option<&Tool>)>,
+    mut user_query: Query<(
+        &GlobalTransform,
+        Option<&mut Stamina>,
+        Option<&mut AbilityCooldowns>,
+        Option<&mut Combatant>,
+        Option<&TeamId>,
+    )>,
+    time: Res<Time>,
+    mut effects: AbilityEffectParam,
+    mut commands: Commands,
+) {
+    for BlockHitEvent {
+        item,
+        user,
+        hit_forward,
+        block_position,
+    } in reader.read()
+    {
+        let (Some(item), Some(user)) = (*item, *user) else {
+            continue;
+        };
+        let Ok((ability, tool)) = ability_query.get(item) else {
+            continue;
+        };
+        if ability.trigger != AbilityTrigger::BlockHit {
+            continue;
+        }
+        let Ok((gtf, mut stamina, mut cooldowns, combatant, team)) = user_query.get_mut(user)
+        else {
+            continue;
+        };
+        if !try_activate(
+            ability,
+            item,
+            user,
+            stamina.as_deref_mut(),
+            cooldowns.as_deref_mut(),
+            time.elapsed(),
+            &mut commands,
+        ) {
+            continue;
+        }
+        effects.apply(
+            AbilityActivation {
+                ability,
+                item,
+                user,
+                tf: Transform::from_translation(gtf.translation())
+                    .looking_to(*hit_forward, Vec3::Y),
+                team: team.copied().unwrap_or(TeamId::PLAYER),
+                tool: tool.copied().unwrap_or_default(),
+                target_block: Some(*block_position),
+                target_entity: None,
+                hit_pos: Some(block_position.center()),
+                skip_block: Some(*block_position),
+            },
+            combatant.map(|c| c.into_inner()),
+        );
+    }
+}
@@ -1,17 +1,23 @@
 use bevy::prelude::*;
 use engine::items::{
-    block_item::BlockItem, item_attributes::ConsumeItemOnHit, loot::CachedLootTable, CreatorItem,
-    ItemBundle, ItemName, ItemResources, ItemStack, MaxStackSize, SpawnDroppedItemEvent,
+    block_item::BlockItem,
+    inventory::Inventory,
+    item_attributes::ConsumeItemOnHit,
+    loot::{CachedLootTable, LootContext, LootContextParam, LootRng},
+    CreatorItem, ItemBundle, ItemName, ItemResources, ItemStack, MaxStackSize,
+    SpawnDroppedItemEvent,
 };
 
 use interfaces::scheduling::LevelSystemSet;
-use rand::thread_rng;
 use world::{
-    block::{BlockId, BlockName, SingleBlockMesh},
+    block::{BlockId, BlockName, BlockTemplate, SingleBlockMesh},
     events::BlockBrokenEvent,
 };
 
-use crate::item_mesher::{ItemMesh, ItemMeshMaterial};
+use crate::{
+    item_mesher::{ItemMesh, ItemMeshMaterial},
+    tools::{Tool, ToolResistance},
+};
 pub struct BlockItemsPlugin;
 
 impl Plugin for BlockItemsPlugin {
@@ -28,7 +34,8 @@ fn create_block_item(
     mut items: ResMut<ItemResources>,
     block_query: Query<
         (Entity, &BlockName, Option<&SingleBlockMesh>),
-        (Added<BlockId>, Without<CreatorItem>),
+        //placed dynamic blocks share their template's item
+        (Added<BlockId>, Without<CreatorItem>, Without<BlockTemplate>),
     >,
     mut commands: Commands,
 ) {
@@ -57,34 +64,71 @@ fn create_block_item(
     }
 }
 
+//drops are only given if the breaker's tool is strong enough for the block's ToolResistance
 fn do_block_drops(
-    block_query: Query<(&CreatorItem, Option<&CachedLootTable<Entity>>)>,
+    block_query: Query<(
+        &CreatorItem,
+        Option<&CachedLootTable<Entity>>,
+        Option<&ToolResistance>,
+    )>,
+    template_query: Query<&BlockTemplate>,
+    tool_query: Query<&Tool>,
+    inventory_query: Query<&Inventory>,
     mut reader: EventReader<BlockBrokenEvent>,
     mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
+    loot_params: LootContextParam,
+    mut loot_rng: ResMut<LootRng>,
 ) {
-    let mut rng = thread_rng();
+    let rng = &mut loot_rng.0;
     for BlockBrokenEvent {
         block,
         coord,
-        broken_by: _,
+        broken_by,
     } in reader.read()
     {
-        match block_query.get(*block) {
-            Ok((_, Some(loot_table))) => {
+        let drop_source = template_query
+            .get(*block)
+            .map_or(*block, |BlockTemplate(template)| *template);
+        let Ok((CreatorItem(creator_item), loot_table, resistance)) = block_query.get(drop_source)
+        else {
+            warn!("no block drop for {:?}", block);
+            continue;
+        };
+        //blocks are broken by the item used or by an entity with tool power, same as when dealing damage
+        let tool = broken_by
+            .and_then(|entity| tool_query.get(entity).ok())
+            .copied()
+            .unwrap_or_default();
+        let Some(tool_tier) = resistance.copied().unwrap_or_default().harvest_tier(tool) else {
+            debug!("tool too weak to harvest {:?}", block);
+            continue;
+        };
+        match loot_table {
+            Some(loot_table) => {
                 info!("dropping from loot table");
-                loot_table.drop_items(coord.center(), &mut drop_writer, &mut rng);
+                let context = LootContext {
+                    held_item: broken_by.map(|entity| {
+                        inventory_query
+                            .get(entity)
+                            .ok()
+                            .and_then(|inventory| inventory.selected_item_entity())
+                            .unwrap_or(entity)
+                    }),
+                    tool_tier: Some(tool_tier),
+                    ..loot_params.at(coord.center())
+                };
+                loot_table.drop_items(coord.center(), &context, &mut drop_writer, rng);
             }
-            Ok((CreatorItem(creator_item), None)) => {
+            None => {
                 info!("dropping from creator item");
-                let random_v = util::sample_sphere_surface(&mut rng) * 0.05;
-                let random_strength = util::random_proportion(&mut rng) + 0.5;
+                let random_v = util::sample_sphere_surface(rng) * 0.05;
+                let random_strength = util::random_proportion(rng) + 0.5;
                 drop_writer.send(SpawnDroppedItemEvent {
                     postion: coord.center(),
                     velocity: random_strength * (random_v + Vec3::Y * 0.1),
                     stack: ItemStack::new(*creator_item, 1),
                 });
             }
-            Err(_) => warn!("no block drop for {:?}", block),
         }
     }
 }
@@ -37,6 +37,7 @@ fn use_suicide_pill(
                     damage: engine::actors::Damage::new(0.0),
                     knockback_impulse: Vec3::ZERO,
                     hit_location: Vec3::ZERO,
+                    melee: false,
                 },
                 damage_taken: 0.0,
             });
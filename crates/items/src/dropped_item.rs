@@ -11,7 +11,9 @@ use interfaces::{
 };
 use physics::{
     collision::{Aabb, Friction},
+    entity_collision::CollisionLayers,
     movement::Velocity,
+    spatial::SpatialIndex,
     FrictionBundle, PhysicsBundle,
 };
 
@@ -62,6 +64,7 @@ fn spawn_dropped_item(
                 },
                 ..default()
             },
+            CollisionLayers::ITEM,
             InactiveDroppedItem {
                 becomes: DroppedItem { stack: spawn.stack },
                 at: time.elapsed() + inactive_duration,
@@ -110,11 +113,16 @@ fn pickup_dropped_item(
         Without<DroppedItemPickerUpper>,
     >,
     stack_query: Query<&MaxStackSize>,
+    index: Res<SpatialIndex>,
     mut commands: Commands,
 ) {
-    // todo - optimize spatial query
     for (mut inv, picker_upper, picker_upper_tf) in picker_upper_query.iter_mut() {
-        for (dropped_entity, mut dropped_item, dropped_tf) in dropped_item_query.iter_mut() {
+        for candidate in index.query_radius(picker_upper_tf.translation, picker_upper.radius) {
+            let Ok((dropped_entity, mut dropped_item, dropped_tf)) =
+                dropped_item_query.get_mut(candidate)
+            else {
+                continue;
+            };
             if picker_upper_tf
                 .translation
                 .distance_squared(dropped_tf.translation)
@@ -1,6 +1,9 @@
 use bevy::{pbr::ExtendedMaterial, prelude::*};
 
-use interfaces::{components::VisualizeHeldItem, resources::HeldItemResources};
+use interfaces::{
+    components::{VisualizeEquippedItem, VisualizeHeldItem},
+    resources::HeldItemResources,
+};
 use materials::{ColorArrayExtension, TextureArrayExtension};
 use util::palette::Palette;
 
@@ -11,7 +14,7 @@ use world::{
     util::BlockPalette,
 };
 
-use engine::items::{inventory::Inventory, ItemIcon, ItemName};
+use engine::items::{equipment::Equipment, inventory::Inventory, ItemIcon, ItemName};
 
 pub struct ItemMesherPlugin;
 
@@ -25,7 +28,8 @@ impl Plugin for ItemMesherPlugin {
             )
             .add_systems(
                 Update,
-                visualize_held_item.run_if(resource_exists::<HeldItemResources>),
+                (visualize_held_item, visualize_equipped_items)
+                    .run_if(resource_exists::<HeldItemResources>),
             )
             .add_systems(
                 Update,
@@ -73,22 +77,19 @@ fn setup_held_item(
     }
 }
 
+type ColorItemMaterial = MeshMaterial3d<ExtendedMaterial<StandardMaterial, ColorArrayExtension>>;
+type TextureItemMaterial =
+    MeshMaterial3d<ExtendedMaterial<StandardMaterial, TextureArrayExtension>>;
+
 fn visualize_held_item(
     mut commands: Commands,
-    //ugly but idc
     mut color_held_query: Query<
         (Entity, &VisualizeHeldItem, &mut Mesh3d),
-        (
-            With<MeshMaterial3d<ExtendedMaterial<StandardMaterial, ColorArrayExtension>>>,
-            Without<MeshMaterial3d<ExtendedMaterial<StandardMaterial, TextureArrayExtension>>>,
-        ),
+        (With<ColorItemMaterial>, Without<TextureItemMaterial>),
     >,
     mut texture_held_query: Query<
         (Entity, &VisualizeHeldItem, &mut Mesh3d),
-        (
-            With<MeshMaterial3d<ExtendedMaterial<StandardMaterial, TextureArrayExtension>>>,
-            Without<MeshMaterial3d<ExtendedMaterial<StandardMaterial, ColorArrayExtension>>>,
-        ),
+        (With<TextureItemMaterial>, Without<ColorItemMaterial>),
     >,
     inv_query: Query<&Inventory>,
     item_query: Query<&ItemMesh>,
@@ -98,20 +99,15 @@ fn visualize_held_item(
     //color materials
     for (entity, held, mut mesh) in color_held_query.iter_mut() {
         if let Ok(inv) = inv_query.get(held.inventory) {
-            if let Some(item) = inv.selected_item_entity() {
-                if let Ok(item_mesh) = item_query.get(item) {
-                    mesh.0 = item_mesh.mesh.clone();
-                    if item_mesh.material == ItemMeshMaterial::TextureArray {
-                        //we have color material attached, need to switch to texture
-                        commands.entity(entity).remove::<MeshMaterial3d<ExtendedMaterial<StandardMaterial, ColorArrayExtension>>>()
-                            .insert(MeshMaterial3d(res.texture_material.clone()));
-                    }
-                } else {
-                    *mesh = Default::default();
-                }
-            } else {
-                *mesh = Default::default();
-            }
+            set_visualized_item(
+                entity,
+                inv.selected_item_entity(),
+                ItemMeshMaterial::ColorArray,
+                &mut mesh,
+                &item_query,
+                &res,
+                &mut commands,
+            );
         } else {
             //owner despawned
             commands.entity(entity).despawn_recursive();
@@ -121,24 +117,64 @@ fn visualize_held_item(
     //texture materials
     for (entity, held, mut mesh) in texture_held_query.iter_mut() {
         if let Ok(inv) = inv_query.get(held.inventory) {
-            if let Some(item) = inv.selected_item_entity() {
-                if let Ok(item_mesh) = item_query.get(item) {
-                    mesh.0 = item_mesh.mesh.clone();
-                    if item_mesh.material == ItemMeshMaterial::ColorArray {
-                        //we have texture material attached, need to switch to color
-                        commands
-                            .entity(entity)
-                            .remove::<MeshMaterial3d<
-                                ExtendedMaterial<StandardMaterial, TextureArrayExtension>,
-                            >>()
-                            .insert(MeshMaterial3d(res.color_material.clone()));
-                    }
-                } else {
-                    *mesh = Default::default();
-                }
-            } else {
-                *mesh = Default::default();
-            }
+            set_visualized_item(
+                entity,
+                inv.selected_item_entity(),
+                ItemMeshMaterial::TextureArray,
+                &mut mesh,
+                &item_query,
+                &res,
+                &mut commands,
+            );
+        } else {
+            //owner despawned
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn visualize_equipped_items(
+    mut commands: Commands,
+    mut color_equipped_query: Query<
+        (Entity, &VisualizeEquippedItem, &mut Mesh3d),
+        (With<ColorItemMaterial>, Without<TextureItemMaterial>),
+    >,
+    mut texture_equipped_query: Query<
+        (Entity, &VisualizeEquippedItem, &mut Mesh3d),
+        (With<TextureItemMaterial>, Without<ColorItemMaterial>),
+    >,
+    equipment_query: Query<&Equipment>,
+    item_query: Query<&ItemMesh>,
+    res: Res<HeldItemResources>,
+) {
+    for (entity, equipped, mut mesh) in color_equipped_query.iter_mut() {
+        if let Ok(equipment) = equipment_query.get(equipped.equipment) {
+            set_visualized_item(
+                entity,
+                equipment.get(equipped.slot).map(|stack| stack.id),
+                ItemMeshMaterial::ColorArray,
+                &mut mesh,
+                &item_query,
+                &res,
+                &mut commands,
+            );
+        } else {
+            //owner despawned
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    for (entity, equipped, mut mesh) in texture_equipped_query.iter_mut() {
+        if let Ok(equipment) = equipment_query.get(equipped.equipment) {
+            set_visualized_item(
+                entity,
+                equipment.get(equipped.slot).map(|stack| stack.id),
+                ItemMeshMaterial::TextureArray,
+                &mut mesh,
+                &item_query,
+                &res,
+                &mut commands,
+            );
         } else {
             //owner despawned
             commands.entity(entity).despawn_recursive();
@@ -146,6 +182,39 @@ fn visualize_held_item(
     }
 }
 
+//shows the item's mesh on the visualizer entity, swapping materials if the item needs the other one
+fn set_visualized_item(
+    entity: Entity,
+    item: Option<Entity>,
+    current_material: ItemMeshMaterial,
+    mesh: &mut Mesh3d,
+    item_query: &Query<&ItemMesh>,
+    res: &HeldItemResources,
+    commands: &mut Commands,
+) {
+    let Some(item_mesh) = item.and_then(|item| item_query.get(item).ok()) else {
+        *mesh = Default::default();
+        return;
+    };
+    mesh.0 = item_mesh.mesh.clone();
+    if item_mesh.material != current_material {
+        match item_mesh.material {
+            ItemMeshMaterial::TextureArray => {
+                commands
+                    .entity(entity)
+                    .remove::<ColorItemMaterial>()
+                    .insert(MeshMaterial3d(res.texture_material.clone()));
+            }
+            ItemMeshMaterial::ColorArray => {
+                commands
+                    .entity(entity)
+                    .remove::<TextureItemMaterial>()
+                    .insert(MeshMaterial3d(res.color_material.clone()));
+            }
+        }
+    }
+}
+
 pub fn generate_item_meshes(
     mut events: ResMut<Events<GenerateItemMeshEvent>>,
     item_query: Query<(&ItemIcon, Option<&ItemName>)>,
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+pub mod abilities;
 mod actor_items;
 mod assault_items;
 pub mod block_items;
@@ -7,6 +8,8 @@ mod debug;
 mod dropped_item;
 mod grapple_item;
 pub mod item_mesher;
+#[cfg(test)]
+mod test;
 mod time_items;
 mod tools;
 mod weapons;
@@ -16,6 +19,7 @@ pub struct ItemsPlugin;
 impl Plugin for ItemsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
+            abilities::ItemAbilityPlugin,
             weapons::WeaponItemPlugin,
             time_items::TimeItemsPlugin,
             grapple_item::GrappleItemPlugin,
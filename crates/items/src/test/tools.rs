@@ -0,0 +1,67 @@
+#[allow(unused_imports)]
+mod tools {
+    use crate::tools::{calc_block_damage, Tool, ToolResistance, CITIZEN_TOOL};
+
+    const RUBY_ORE: ToolResistance = ToolResistance::Pickaxe(2);
+
+    fn pickaxe(level: u32) -> Tool {
+        Tool {
+            pickaxe: level,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_harvest_tier() {
+        assert_eq!(RUBY_ORE.harvest_tier(pickaxe(0)), None);
+        assert_eq!(RUBY_ORE.harvest_tier(pickaxe(1)), None);
+        assert_eq!(RUBY_ORE.harvest_tier(pickaxe(2)), Some(2));
+        assert_eq!(RUBY_ORE.harvest_tier(pickaxe(3)), Some(3));
+        //the wrong kind of tool doesn't count, no matter how strong
+        let axe = Tool {
+            axe: 5,
+            ..Default::default()
+        };
+        assert_eq!(RUBY_ORE.harvest_tier(axe), None);
+        assert_eq!(
+            ToolResistance::Instant.harvest_tier(Tool::default()),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_harvest_tier_matches_block_damage() {
+        let resistances = [
+            ToolResistance::Instant,
+            ToolResistance::Axe(1),
+            RUBY_ORE,
+            ToolResistance::Shovel(0),
+        ];
+        for resistance in resistances {
+            for level in 0..4 {
+                for tool in [
+                    pickaxe(level),
+                    Tool {
+                        axe: level,
+                        pickaxe: level,
+                        shovel: level,
+                    },
+                ] {
+                    //a tool that can break a block always gets its drops
+                    assert_eq!(
+                        resistance.harvest_tier(tool).is_some(),
+                        calc_block_damage(resistance, tool) > 0.0,
+                        "{:?} with {:?}",
+                        resistance,
+                        tool
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_citizens_harvest_ore() {
+        assert_eq!(RUBY_ORE.harvest_tier(CITIZEN_TOOL), Some(2));
+    }
+}
@@ -10,7 +10,8 @@ use world::{
     level::Level,
 };
 
-use engine::items::{HitResult, SwingEndEvent, SwingItemEvent};
+use actors::wisp::citizen::Citizen;
+use engine::items::{metadata::DamageItemEvent, HitResult, SwingEndEvent, SwingItemEvent};
 use interfaces::scheduling::ItemSystemSet;
 
 pub mod abilities;
@@ -26,10 +27,18 @@ impl Plugin for ToolsPlugin {
             .add_systems(
                 Update,
                 (on_swing, deal_block_damage).in_set(ItemSystemSet::UsageProcessing),
-            );
+            )
+            .add_observer(give_citizens_tools);
     }
 }
 
+//citizens mine without items. blocks treat them as tier 2 tools, so ore they are told to mine still drops
+pub const CITIZEN_TOOL: Tool = Tool {
+    axe: 2,
+    pickaxe: 2,
+    shovel: 2,
+};
+
 //denotes required power if attached to a block
 //is used in `Tool` to give power of said tool
 #[derive(Copy, Clone, Hash, Eq, Debug, PartialEq, Component, Reflect, Serialize, Deserialize)]
@@ -47,6 +56,20 @@ impl Default for ToolResistance {
     }
 }
 
+impl ToolResistance {
+    //the tool's level for the kind of tool this needs, or None if the tool is too weak to harvest it
+    //instant blocks can be harvested by anything, and give tier 0
+    pub fn harvest_tier(self, tool: Tool) -> Option<u32> {
+        let (required, level) = match self {
+            ToolResistance::Instant => return Some(0),
+            ToolResistance::Axe(required) => (required, tool.axe),
+            ToolResistance::Pickaxe(required) => (required, tool.pickaxe),
+            ToolResistance::Shovel(required) => (required, tool.shovel),
+        };
+        (level >= required).then_some(level)
+    }
+}
+
 #[derive(
     Copy, Clone, Hash, Eq, Debug, PartialEq, Component, Reflect, Default, Serialize, Deserialize,
 )]
@@ -71,6 +94,7 @@ pub fn on_swing(
     block_physics_query: Query<&BlockPhysics>,
     object_query: Query<(Entity, &GlobalTransform, &Aabb)>,
     item_query: Query<(), Without<DontHitBlocks>>,
+    mut durability_writer: EventWriter<DamageItemEvent>,
 ) {
     for SwingItemEvent {
         user,
@@ -95,6 +119,13 @@ pub fn on_swing(
                 block_position,
                 hit_forward: tf.forward(),
             });
+            if let Some(slot) = inventory_slot {
+                durability_writer.send(DamageItemEvent {
+                    user: *user,
+                    inventory_slot: *slot,
+                    amount: 1,
+                });
+            }
             swing_hit_writer.send(SwingEndEvent {
                 user: *user,
                 inventory_slot: *inventory_slot,
@@ -145,6 +176,10 @@ fn deal_block_damage(
     }
 }
 
+fn give_citizens_tools(trigger: Trigger<OnAdd, Citizen>, mut commands: Commands) {
+    commands.entity(trigger.entity()).insert(CITIZEN_TOOL);
+}
+
 pub fn calc_block_damage(resistance: ToolResistance, tool: Tool) -> f32 {
     const MAX_HITS_TO_BREAK: u32 = 5;
     match resistance {
@@ -2,7 +2,9 @@ use std::sync::Arc;
 
 use bevy::prelude::*;
 
-use engine::actors::{team::PlayerTeam, AttackEvent, Combatant, CombatantBundle, Damage};
+use engine::actors::{
+    projectile::ProjectileBehaviors, team::TeamId, AttackEvent, Combatant, CombatantBundle, Damage,
+};
 use interfaces::scheduling::ItemSystemSet;
 use physics::{
     collision::{Aabb, BlockPhysics},
@@ -13,7 +15,10 @@ use world::level::Level;
 
 use actors::spawning::{ProjectileSpawnArgs, SpawnProjectileEvent};
 
-use engine::items::{HitResult, SwingEndEvent, SwingItemEvent, UseEndEvent, UseItemEvent};
+use engine::items::{
+    metadata::{DamageItemEvent, ItemMetadata},
+    HitResult, SwingEndEvent, SwingItemEvent, UseEndEvent, UseItemEvent,
+};
 
 pub struct WeaponItemPlugin;
 
@@ -44,6 +49,8 @@ pub struct ProjectileLauncherItem {
     pub lifetime_mult: f32,
     pub knockback_mult: f32,
     pub terrain_damage_mult: f32,
+    //gravity, piercing, homing, bouncing and explosions
+    pub behaviors: ProjectileBehaviors,
 }
 
 impl Default for ProjectileLauncherItem {
@@ -55,6 +62,7 @@ impl Default for ProjectileLauncherItem {
             lifetime_mult: 1.,
             knockback_mult: 1.,
             terrain_damage_mult: 1.,
+            behaviors: Default::default(),
         }
     }
 }
@@ -67,6 +75,8 @@ pub fn attack_melee(
     physics_query: Query<&BlockPhysics>,
     weapon_query: Query<&MeleeWeaponItem>,
     object_query: Query<(Entity, &GlobalTransform, &Aabb)>,
+    meta_query: Query<&ItemMetadata>,
+    mut durability_writer: EventWriter<DamageItemEvent>,
 ) {
     for SwingItemEvent {
         user,
@@ -83,12 +93,24 @@ pub fn attack_melee(
                 &object_query,
                 &[*user],
             ) {
+                let mut damage = weapon.damage;
+                if let Some(meta) = stack.meta.and_then(|meta| meta_query.get(meta).ok()) {
+                    damage.amount *= meta.damage_mult();
+                }
                 attack_writer.send(AttackEvent {
                     attacker: *user,
                     target: hit.entity,
-                    damage: weapon.damage,
+                    damage,
                     knockback: tf.forward() * weapon.knockback,
+                    melee: true,
                 });
+                if let Some(slot) = inventory_slot {
+                    durability_writer.send(DamageItemEvent {
+                        user: *user,
+                        inventory_slot: *slot,
+                        amount: 1,
+                    });
+                }
                 swing_hit_writer.send(SwingEndEvent {
                     user: *user,
                     inventory_slot: *inventory_slot,
@@ -112,8 +134,10 @@ pub fn attack_melee(
 pub fn launch_coin(
     mut attack_item_reader: EventReader<UseItemEvent>,
     mut hit_writer: EventWriter<UseEndEvent>,
-    mut writer: EventWriter<SpawnProjectileEvent<PlayerTeam>>,
+    mut writer: EventWriter<SpawnProjectileEvent>,
     weapon_query: Query<&ProjectileLauncherItem>,
+    team_query: Query<&TeamId>,
+    mut durability_writer: EventWriter<DamageItemEvent>,
 ) {
     for UseItemEvent {
         user,
@@ -128,10 +152,12 @@ pub fn launch_coin(
                 default: actors::spawning::DefaultSpawnArgs {
                     transform: Transform::from_translation(tf.translation),
                 },
-                projectile: ProjectileSpawnArgs::<PlayerTeam> {
+                projectile: ProjectileSpawnArgs {
                     velocity: Velocity(tf.forward() * weapon.speed),
                     combat: CombatantBundle {
                         combatant: Combatant::new(100.0, 0.0),
+                        //projectiles fight for whoever launched them
+                        team: team_query.get(*user).copied().unwrap_or(TeamId::PLAYER),
                         ..default()
                     },
                     owner: *user,
@@ -139,8 +165,16 @@ pub fn launch_coin(
                     lifetime_mult: weapon.lifetime_mult,
                     knockback_mult: weapon.knockback_mult,
                     terrain_damage_mult: weapon.terrain_damage_mult,
+                    behaviors: weapon.behaviors,
                 },
             });
+            if let Some(slot) = inventory_slot {
+                durability_writer.send(DamageItemEvent {
+                    user: *user,
+                    inventory_slot: *slot,
+                    amount: 1,
+                });
+            }
             hit_writer.send(UseEndEvent {
                 user: *user,
                 inventory_slot: *inventory_slot,
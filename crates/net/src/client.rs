@@ -31,9 +31,13 @@ pub(crate) struct ClientPlugin {
 
 impl Plugin for ClientPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Game), connect)
-            .add_systems(OnEnter(NetworkingState::Connected), on_connected)
-            .add_systems(Update, on_server_ready);
+        app.add_plugins((
+            crate::reconciliation::ClientReconciliationPlugin,
+            crate::inventory_actions::ClientInventoryActionsPlugin,
+        ))
+        .add_systems(OnEnter(GameState::Game), connect)
+        .add_systems(OnEnter(NetworkingState::Connected), on_connected)
+        .add_systems(Update, on_server_ready);
 
         // .add_systems(
         //     Update,
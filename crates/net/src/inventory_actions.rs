@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use interfaces::scheduling::{ClientState, ItemSystemSet, NetworkType, ServerState};
+use lightyear::prelude::client::ClientConnectionManager;
+use lightyear::prelude::server::MessageEvent as ServerMessageEvent;
+
+use engine::{
+    actors::LocalPlayer,
+    items::{
+        container::{ContainerBlock, OpenContainer, CONTAINER_RANGE},
+        inventory::Inventory,
+        inventory_actions::{
+            CompactInventoryEvent, QuickStackEvent, SortInventoryEvent, SplitStackEvent,
+            TransferStackEvent,
+        },
+    },
+};
+use world::{block::BlockCoord, level::Level};
+
+use crate::{
+    protocol::{InventoryActionMessage, InventoryTarget, OrderedReliable},
+    server::NetworkPlayerMap,
+};
+
+//the server replays inventory actions from remote players as if they did them locally
+pub struct ServerInventoryActionsPlugin;
+
+impl Plugin for ServerInventoryActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            receive_inventory_actions
+                .before(ItemSystemSet::DropPickup)
+                .run_if(in_state(ServerState::Active)),
+        );
+    }
+}
+
+//the client does its inventory actions locally and forwards them to the server
+pub struct ClientInventoryActionsPlugin;
+
+impl Plugin for ClientInventoryActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            send_inventory_actions
+                .after(ItemSystemSet::DropPickup)
+                .run_if(in_state(NetworkType::Client).and(in_state(ClientState::Ready))),
+        );
+    }
+}
+
+//entities aren't the same on both sides, so containers are sent by position
+fn to_target(
+    user: Entity,
+    inventory: Entity,
+    coord_query: &Query<&BlockCoord>,
+) -> Option<InventoryTarget> {
+    if inventory == user {
+        Some(InventoryTarget::Own)
+    } else {
+        coord_query
+            .get(inventory)
+            .ok()
+            .map(|coord| InventoryTarget::Container(*coord))
+    }
+}
+
+fn send_inventory_actions(
+    mut sort_reader: EventReader<SortInventoryEvent>,
+    mut compact_reader: EventReader<CompactInventoryEvent>,
+    mut transfer_reader: EventReader<TransferStackEvent>,
+    mut split_reader: EventReader<SplitStackEvent>,
+    mut quick_stack_reader: EventReader<QuickStackEvent>,
+    local_query: Query<(), With<LocalPlayer>>,
+    coord_query: Query<&BlockCoord>,
+    mut conn: ResMut<ClientConnectionManager>,
+) {
+    let is_local = |user: Entity| local_query.contains(user);
+    let target = |user: Entity, inventory: Entity| to_target(user, inventory, &coord_query);
+    let mut messages = Vec::new();
+    messages.extend(
+        sort_reader
+            .read()
+            .filter(|e| is_local(e.user))
+            .filter_map(|e| {
+                Some(InventoryActionMessage::Sort {
+                    inventory: target(e.user, e.inventory)?,
+                    order: e.order,
+                    first_slot: e.first_slot,
+                })
+            }),
+    );
+    messages.extend(
+        compact_reader
+            .read()
+            .filter(|e| is_local(e.user))
+            .filter_map(|e| {
+                Some(InventoryActionMessage::Compact {
+                    inventory: target(e.user, e.inventory)?,
+                    first_slot: e.first_slot,
+                })
+            }),
+    );
+    messages.extend(
+        transfer_reader
+            .read()
+            .filter(|e| is_local(e.user))
+            .filter_map(|e| {
+                Some(InventoryActionMessage::TransferStack {
+                    from: target(e.user, e.from)?,
+                    slot: e.slot,
+                    to: target(e.user, e.to)?,
+                })
+            }),
+    );
+    messages.extend(
+        split_reader
+            .read()
+            .filter(|e| is_local(e.user))
+            .filter_map(|e| {
+                Some(InventoryActionMessage::SplitStack {
+                    inventory: target(e.user, e.inventory)?,
+                    slot: e.slot,
+                })
+            }),
+    );
+    messages.extend(
+        quick_stack_reader
+            .read()
+            .filter(|e| is_local(e.user))
+            .map(|e| InventoryActionMessage::QuickStack {
+                first_slot: e.first_slot,
+            }),
+    );
+    for mut message in messages {
+        if let Err(e) = conn.send_message::<OrderedReliable, InventoryActionMessage>(&mut message) {
+            error!("Error sending inventory action: {:?}", e);
+        }
+    }
+}
+
+//the user is always the sender's player, so a client can't act for someone else
+//the events go through the same handlers as local ones, which check can_access before doing anything.
+//containers are opened on the client, so the server opens them for the sender if they're in reach
+fn receive_inventory_actions(
+    mut reader: EventReader<ServerMessageEvent<InventoryActionMessage>>,
+    players: Res<NetworkPlayerMap>,
+    level: Res<Level>,
+    player_query: Query<&GlobalTransform>,
+    container_query: Query<(), (With<ContainerBlock>, With<Inventory>)>,
+    mut commands: Commands,
+    mut sort_writer: EventWriter<SortInventoryEvent>,
+    mut compact_writer: EventWriter<CompactInventoryEvent>,
+    mut transfer_writer: EventWriter<TransferStackEvent>,
+    mut split_writer: EventWriter<SplitStackEvent>,
+    mut quick_stack_writer: EventWriter<QuickStackEvent>,
+) {
+    for ServerMessageEvent {
+        message,
+        context: client_id,
+    } in reader.read()
+    {
+        let Some(&user) = players.client_id_to_entity_id.get(client_id) else {
+            warn!("inventory action from unknown client {:?}", client_id);
+            continue;
+        };
+        let mut resolve = |target: InventoryTarget| match target {
+            InventoryTarget::Own => Some(user),
+            InventoryTarget::Container(coord) => {
+                let container = level.get_block_entity(coord)?;
+                let position = player_query.get(user).ok()?.translation();
+                let in_reach = container_query.contains(container)
+                    && coord.center().distance_squared(position)
+                        <= CONTAINER_RANGE * CONTAINER_RANGE;
+                if !in_reach {
+                    warn!(
+                        "client {:?} used container at {:?} out of reach",
+                        client_id, coord
+                    );
+                    return None;
+                }
+                commands.entity(user).insert(OpenContainer(container));
+                Some(container)
+            }
+        };
+        match *message {
+            InventoryActionMessage::Sort {
+                inventory,
+                order,
+                first_slot,
+            } => {
+                if let Some(inventory) = resolve(inventory) {
+                    sort_writer.send(SortInventoryEvent {
+                        user,
+                        inventory,
+                        order,
+                        first_slot,
+                    });
+                }
+            }
+            InventoryActionMessage::Compact {
+                inventory,
+                first_slot,
+            } => {
+                if let Some(inventory) = resolve(inventory) {
+                    compact_writer.send(CompactInventoryEvent {
+                        user,
+                        inventory,
+                        first_slot,
+                    });
+                }
+            }
+            InventoryActionMessage::TransferStack { from, slot, to } => {
+                if let (Some(from), Some(to)) = (resolve(from), resolve(to)) {
+                    transfer_writer.send(TransferStackEvent {
+                        user,
+                        from,
+                        slot,
+                        to,
+                    });
+                }
+            }
+            InventoryActionMessage::SplitStack { inventory, slot } => {
+                if let Some(inventory) = resolve(inventory) {
+                    split_writer.send(SplitStackEvent {
+                        user,
+                        inventory,
+                        slot,
+                    });
+                }
+            }
+            InventoryActionMessage::QuickStack { first_slot } => {
+                quick_stack_writer.send(QuickStackEvent { user, first_slot });
+            }
+        }
+    }
+}
@@ -1,3 +1,6 @@
+//bevy system signatures often violate this
+#![allow(clippy::too_many_arguments)]
+
 use bevy::{prelude::*, utils::HashMap};
 
 use interfaces::scheduling::{ClientState, NetworkType, ServerState};
@@ -6,12 +9,17 @@ use serde::{Deserialize, Serialize};
 
 use engine::{actors::LocalPlayer, items::ItemNameIdMap};
 use physics::movement::Velocity;
+use reconciliation::MovementValidation;
 use world::{block::BlockNameIdMap, chunk::ChunkSaveFormat};
 
 pub mod client;
 pub mod config;
+pub mod inventory_actions;
 mod protocol;
+pub mod reconciliation;
 pub mod server;
+#[cfg(test)]
+mod test;
 
 pub struct NetPlugin;
 
@@ -58,10 +66,6 @@ pub enum ClientMessage {
     ChatMessage {
         message: String,
     },
-    UpdatePosition {
-        transform: Transform,
-        velocity: Vec3,
-    },
     UseItem {
         tf: GlobalTransform,
         slot: usize,
@@ -125,9 +129,10 @@ pub struct UpdateEntityVelocity {
     pub velocity: Vec3,
 }
 
+//players validated by the server only move through their own simulation, see reconciliation.rs
 fn process_transform_updates(
     mut reader: EventReader<UpdateEntityTransform>,
-    mut query: Query<&mut Transform>,
+    mut query: Query<&mut Transform, Without<MovementValidation>>,
     local_player_query: Query<&LocalPlayer>,
 ) {
     const LOCAL_PLAYER_UPDATE_SQR_DIST: f32 = 1.0; //only update our local position if there's a desync with the server to avoid
@@ -149,7 +154,7 @@ fn process_transform_updates(
 
 fn process_velocity_updates(
     mut reader: EventReader<UpdateEntityVelocity>,
-    mut query: Query<&mut Velocity>,
+    mut query: Query<&mut Velocity, Without<MovementValidation>>,
     local_player_query: Query<&LocalPlayer>,
 ) {
     const LOCAL_PLAYER_UPDATE_SQR_DIST: f32 = 100.0; //only update our local position if there's a desync with the server to avoid
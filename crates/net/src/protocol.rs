@@ -4,7 +4,9 @@ use interfaces::components::RemoteClient;
 use lightyear::{prelude::*, utils::bevy::TransformLinearInterpolation};
 use serde::{Deserialize, Serialize};
 
+use engine::items::inventory_actions::SortOrder;
 use physics::movement::{Acceleration, Velocity};
+use world::{atmosphere::Weather, block::BlockCoord};
 
 pub(crate) struct ProtocolPlugin;
 
@@ -12,6 +14,12 @@ impl Plugin for ProtocolPlugin {
     fn build(&self, app: &mut App) {
         app.register_message::<ClientInfoMessage>(ChannelDirection::ClientToServer);
         app.register_message::<PlayerListMessage>(ChannelDirection::ServerToClient);
+        app.register_message::<PositionUpdateMessage>(ChannelDirection::ClientToServer);
+        app.register_message::<PositionCorrectionMessage>(ChannelDirection::ServerToClient);
+        app.register_message::<InventoryActionMessage>(ChannelDirection::ClientToServer);
+
+        // resources
+        app.register_resource::<Weather>(ChannelDirection::ServerToClient);
 
         // components
         app.register_component::<RemoteClient>(ChannelDirection::ServerToClient)
@@ -69,3 +77,54 @@ pub(crate) struct ClientInfoMessage {
 pub(crate) struct PlayerListMessage {
     pub name: Vec<String>,
 }
+
+// client sends its predicted movement every few ticks, the server takes it if the player could have moved there
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PositionUpdateMessage {
+    pub tick: Tick,
+    pub translation: Vec3,
+    //clients control where they look, the server's copy has to face the same way for attacks and replication
+    pub rotation: Quat,
+}
+
+// server sends when it rejects a position update, the client rolls back to it and replays its inputs since
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PositionCorrectionMessage {
+    //server tick the state is from
+    pub tick: Tick,
+    pub translation: Vec3,
+    pub velocity: Vec3,
+}
+
+// an inventory a client wants to act on. entities differ between client and server, so containers go by position
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub(crate) enum InventoryTarget {
+    Own,
+    Container(BlockCoord),
+}
+
+// client sends when its player sorts, moves or splits stacks, the server replays it for that player
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub(crate) enum InventoryActionMessage {
+    Sort {
+        inventory: InventoryTarget,
+        order: SortOrder,
+        first_slot: usize,
+    },
+    Compact {
+        inventory: InventoryTarget,
+        first_slot: usize,
+    },
+    TransferStack {
+        from: InventoryTarget,
+        slot: usize,
+        to: InventoryTarget,
+    },
+    SplitStack {
+        inventory: InventoryTarget,
+        slot: usize,
+    },
+    QuickStack {
+        first_slot: usize,
+    },
+}
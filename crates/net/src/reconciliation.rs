@@ -0,0 +1,427 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use interfaces::{
+    components::RemoteClient,
+    scheduling::{ClientState, LevelSystemSet, NetworkType, ServerState},
+};
+use leafwing_input_manager::prelude::ActionState;
+use lightyear::prelude::client::{ClientConnectionManager, Confirmed, Predicted, PredictionSet};
+use lightyear::prelude::server::{ConnectionManager, MessageEvent as ServerMessageEvent};
+use lightyear::prelude::*;
+use lightyear::shared::events::components::MessageEvent;
+
+use engine::{
+    actors::{
+        abilities::dash::{CurrentlyDashing, Dash},
+        LocalPlayer, MoveSpeed,
+    },
+    controllers::{player_controller::RotateWithMouse, Action},
+};
+use physics::{
+    collision::{Aabb, BlockPhysics},
+    constraint::{Constraint, ConstraintKind},
+    grapple::Grappled,
+    movement::Velocity,
+};
+use world::level::Level;
+
+use crate::{
+    protocol::{
+        OrderedReliable, PositionCorrectionMessage, PositionUpdateMessage, UnorderedUnreliable,
+    },
+    server::NetworkPlayerMap,
+};
+
+//how often the client reports where it thinks it is
+const UPDATE_INTERVAL_TICKS: u16 = 4;
+//updates further apart than this are checked as if they were this far apart
+pub const MAX_CHECKED_TICKS: u16 = 64;
+//slack for float error and the server not knowing exactly when inputs landed
+pub const SPEED_SLACK: f32 = 1.25;
+pub const POSITION_TOLERANCE: f32 = 0.5;
+//ticks either side of an update that a dash still counts for, inputs and updates don't land on the same tick
+pub const DASH_GRACE_TICKS: u16 = 8;
+
+//remote players' inputs reach the server through lightyear's input plugin and move the server's copy of them.
+//the positions clients predict are checked against that simulation, and a correction is sent for the ones that couldn't have happened
+pub struct ServerReconciliationPlugin;
+
+impl Plugin for ServerReconciliationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                add_movement_validation,
+                record_simulated_movement,
+                validate_position_updates,
+            )
+                .chain()
+                .in_set(LevelSystemSet::PostTick)
+                .run_if(in_state(ServerState::Active)),
+        );
+    }
+}
+
+//the client reports its movement, and rolls back to the server's state when a report is rejected
+pub struct ClientReconciliationPlugin;
+
+impl Plugin for ClientReconciliationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            apply_position_corrections
+                .after(PredictionSet::SpawnHistory)
+                .before(PredictionSet::CheckRollback)
+                .run_if(in_state(NetworkType::Client).and(in_state(ClientState::Ready))),
+        )
+        .add_systems(
+            FixedUpdate,
+            send_position_updates
+                .in_set(LevelSystemSet::PostTick)
+                .run_if(in_state(NetworkType::Client).and(in_state(ClientState::Ready))),
+        );
+    }
+}
+
+//server side, last accepted state of a remote player and where the server's simulation had them recently
+#[derive(Component, Debug)]
+pub struct MovementValidation {
+    last_tick: Option<Tick>,
+    last_translation: Vec3,
+    //oldest first, at most MAX_CHECKED_TICKS long
+    simulated: VecDeque<(Tick, Vec3)>,
+    last_dash: Option<Tick>,
+    //number of updates that were rejected, for kicking cheaters later
+    pub rejected: u32,
+}
+
+impl MovementValidation {
+    pub fn new(translation: Vec3) -> Self {
+        Self {
+            last_tick: None,
+            last_translation: translation,
+            simulated: VecDeque::with_capacity(MAX_CHECKED_TICKS as usize),
+            last_dash: None,
+            rejected: 0,
+        }
+    }
+
+    //stores the server's position for the player at the end of a tick
+    pub fn record(&mut self, tick: Tick, translation: Vec3, dashing: bool) {
+        if self.simulated.len() >= MAX_CHECKED_TICKS as usize {
+            self.simulated.pop_front();
+        }
+        self.simulated.push_back((tick, translation));
+        if dashing {
+            self.last_dash = Some(tick);
+        }
+    }
+
+    pub fn simulated_at(&self, tick: Tick) -> Option<Vec3> {
+        self.simulated
+            .iter()
+            .find(|(recorded, _)| *recorded == tick)
+            .map(|(_, translation)| *translation)
+    }
+
+    //true if the player was dashing in the ticks an update covers
+    pub fn dashed_during(&self, tick: Tick, ticks_covered: u16) -> bool {
+        self.last_dash.is_some_and(|dash| {
+            let since = tick - dash;
+            since >= -(DASH_GRACE_TICKS as i16)
+                && since <= (ticks_covered.min(MAX_CHECKED_TICKS) + DASH_GRACE_TICKS) as i16
+        })
+    }
+
+    //moves the recorded simulation after tick along with the player
+    fn shift_after(&mut self, tick: Tick, offset: Vec3) {
+        for (recorded, translation) in self.simulated.iter_mut() {
+            if *recorded - tick > 0 {
+                *translation += offset;
+            }
+        }
+    }
+}
+
+//fastest a player could move themselves in one tick without dashing, before slack
+pub fn max_tick_speed(move_speed: &MoveSpeed, grapple_speed: Option<f32>) -> f32 {
+    move_speed.max_speed.max(grapple_speed.unwrap_or_default())
+}
+
+//true if the claimed position is reachable from the last accepted one and close to where the server has the player
+pub fn is_plausible_move(
+    claim: Vec3,
+    last_translation: Vec3,
+    server_translation: Vec3,
+    max_speed: f32,
+    ticks_since_last: u16,
+    ticks_from_server: u16,
+) -> bool {
+    let speed = max_speed * SPEED_SLACK;
+    let reach = speed * ticks_since_last.min(MAX_CHECKED_TICKS) as f32 + POSITION_TOLERANCE;
+    let desync = speed * ticks_from_server.min(MAX_CHECKED_TICKS) as f32 + POSITION_TOLERANCE;
+    claim.distance(last_translation) <= reach && claim.distance(server_translation) <= desync
+}
+
+//true if a player's collider at the position would be inside solid terrain
+pub fn inside_terrain(
+    collider: Aabb,
+    translation: Vec3,
+    level: &Level,
+    physics_query: &Query<&BlockPhysics>,
+) -> bool {
+    level
+        .get_blocks_in_volume(collider.to_volume(translation))
+        .iter()
+        .filter_map(|(coord, block)| {
+            block
+                .and_then(|b| b.entity())
+                .and_then(|e| physics_query.get(e).ok())
+                .map(|physics| (coord, physics))
+        })
+        .any(|(coord, physics)| collider.intersects_block(translation, physics, coord.into()))
+}
+
+fn add_movement_validation(
+    query: Query<(Entity, &Transform), (With<RemoteClient>, Without<MovementValidation>)>,
+    mut commands: Commands,
+) {
+    for (entity, tf) in query.iter() {
+        if let Some(mut ec) = commands.get_entity(entity) {
+            ec.try_insert(MovementValidation::new(tf.translation));
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum PositionCheck {
+    //older than an update that was already checked
+    Stale,
+    Accepted,
+    //the server's state, for the client to snap back to
+    Rejected(PositionCorrectionMessage),
+}
+
+//compares an update to the server's simulation, and nudges the server's player to it when it's plausible
+//dash_speed only counts if the server saw the player dash around the update's ticks, and blocked positions are never accepted
+pub(crate) fn check_position_update(
+    update: &PositionUpdateMessage,
+    server_tick: Tick,
+    max_speed: f32,
+    dash_speed: Option<f32>,
+    tf: &mut Transform,
+    v: &Velocity,
+    validation: &mut MovementValidation,
+    blocked: impl Fn(Vec3) -> bool,
+) -> PositionCheck {
+    let ticks_since_last = match validation.last_tick {
+        //unreliable channel, so old updates can arrive after newer ones
+        Some(last_tick) if update.tick - last_tick <= 0 => return PositionCheck::Stale,
+        Some(last_tick) => (update.tick - last_tick) as u16,
+        None => MAX_CHECKED_TICKS,
+    };
+    validation.last_tick = Some(update.tick);
+    let max_speed = dash_speed
+        .filter(|_| validation.dashed_during(update.tick, ticks_since_last))
+        .map_or(max_speed, |dash_speed| max_speed.max(dash_speed));
+    //updates from ticks the server hasn't simulated yet are compared to where it has the player now
+    let (simulated, ticks_from_server) = match validation.simulated_at(update.tick) {
+        Some(simulated) => (simulated, 0),
+        None => (tf.translation, (update.tick - server_tick).unsigned_abs()),
+    };
+    if !blocked(update.translation)
+        && is_plausible_move(
+            update.translation,
+            validation.last_translation,
+            simulated,
+            max_speed,
+            ticks_since_last,
+            ticks_from_server,
+        )
+    {
+        //move the server's player by the difference so float error doesn't build up between the simulations.
+        //velocity stays the server's own, so clients can't raise their speed limit
+        let offset = update.translation - simulated;
+        if !blocked(tf.translation + offset) {
+            tf.translation += offset;
+            validation.shift_after(update.tick, offset);
+        }
+        validation.last_translation = update.translation;
+        tf.rotation = update.rotation;
+        return PositionCheck::Accepted;
+    }
+    validation.rejected += 1;
+    validation.last_translation = tf.translation;
+    //updates stamped before now were sent from the rejected path, so drop them instead of correcting each one
+    validation.last_tick = Some(server_tick);
+    PositionCheck::Rejected(PositionCorrectionMessage {
+        tick: server_tick,
+        translation: tf.translation,
+        velocity: v.0,
+    })
+}
+
+fn record_simulated_movement(
+    mut query: Query<(
+        &Transform,
+        &mut MovementValidation,
+        Option<&ActionState<Action>>,
+        Option<&CurrentlyDashing>,
+    )>,
+    tick_manager: Res<TickManager>,
+) {
+    let tick = tick_manager.tick();
+    for (tf, mut validation, opt_action, opt_dashing) in query.iter_mut() {
+        //the replicated input covers dashes that start before the server's copy can afford them
+        let dashing =
+            opt_dashing.is_some() || opt_action.is_some_and(|action| action.pressed(&Action::Dash));
+        validation.record(tick, tf.translation, dashing);
+    }
+}
+
+fn validate_position_updates(
+    mut reader: EventReader<ServerMessageEvent<PositionUpdateMessage>>,
+    mut query: Query<(
+        &mut Transform,
+        &Velocity,
+        &Aabb,
+        &mut MovementValidation,
+        &MoveSpeed,
+        Option<&Dash>,
+        Option<&Grappled>,
+        Option<&mut RotateWithMouse>,
+    )>,
+    constraint_query: Query<&Constraint>,
+    physics_query: Query<&BlockPhysics>,
+    level: Res<Level>,
+    players: Res<NetworkPlayerMap>,
+    tick_manager: Res<TickManager>,
+    mut conn: ResMut<ConnectionManager>,
+) {
+    let server_tick = tick_manager.tick();
+    for ServerMessageEvent {
+        message: update,
+        context: client_id,
+    } in reader.read()
+    {
+        let Some(entity) = players.client_id_to_entity_id.get(client_id) else {
+            warn!("position update from unknown client {:?}", client_id);
+            continue;
+        };
+        let Ok((
+            mut tf,
+            v,
+            collider,
+            mut validation,
+            move_speed,
+            opt_dash,
+            opt_grappled,
+            opt_rotation,
+        )) = query.get_mut(*entity)
+        else {
+            continue;
+        };
+        let grapple_speed = opt_grappled
+            .and_then(|grappled| constraint_query.get(grappled.constraint()).ok())
+            .and_then(|constraint| match constraint.kind {
+                ConstraintKind::Reel { max_speed, .. } => Some(max_speed),
+                _ => None,
+            });
+        //falling, knockback and explosions show up in the server's velocity, which clients never write
+        let max_speed = max_tick_speed(move_speed, grapple_speed) + v.0.length();
+        let mut correction = match check_position_update(
+            update,
+            server_tick,
+            max_speed,
+            opt_dash.map(|dash| dash.current_speed),
+            &mut tf,
+            v,
+            &mut validation,
+            |translation| inside_terrain(*collider, translation, &level, &physics_query),
+        ) {
+            PositionCheck::Stale => continue,
+            PositionCheck::Accepted => {
+                //rotate_mouse rebuilds the rotation from these every frame
+                if let Some(mut rotation) = opt_rotation {
+                    let (yaw, pitch, roll) = update.rotation.to_euler(EulerRot::YXZ);
+                    rotation.yaw = yaw;
+                    rotation.pitch = pitch;
+                    rotation.roll = roll;
+                }
+                continue;
+            }
+            PositionCheck::Rejected(correction) => correction,
+        };
+        warn!(
+            "rejected position update from {:?}: {:?} is blocked or too far from {:?} ({} rejected)",
+            client_id, update.translation, tf.translation, validation.rejected
+        );
+        if let Err(e) = conn
+            .send_message::<OrderedReliable, PositionCorrectionMessage>(*client_id, &mut correction)
+        {
+            error!("Error sending position correction: {:?}", e);
+        }
+    }
+}
+
+fn send_position_updates(
+    query: Query<&Transform, With<LocalPlayer>>,
+    tick_manager: Res<TickManager>,
+    mut conn: ResMut<ClientConnectionManager>,
+) {
+    let tick = tick_manager.tick();
+    if tick.0 % UPDATE_INTERVAL_TICKS != 0 {
+        return;
+    }
+    let Ok(tf) = query.get_single() else {
+        return;
+    };
+    if let Err(e) = conn.send_message::<UnorderedUnreliable, PositionUpdateMessage>(
+        &mut PositionUpdateMessage {
+            tick,
+            translation: tf.translation,
+            rotation: tf.rotation,
+        },
+    ) {
+        error!("Error sending position update: {:?}", e);
+    }
+}
+
+//sets the player's confirmed state to the server's at the correction's tick.
+//lightyear sees it doesn't match what was predicted for that tick, so it rolls the predicted player back to it
+//and replays the buffered inputs up to the current tick
+pub(crate) fn apply_position_correction(
+    confirmed: &mut Confirmed,
+    tf: &mut Transform,
+    v: &mut Velocity,
+    correction: &PositionCorrectionMessage,
+) {
+    confirmed.tick = correction.tick;
+    tf.translation = correction.translation;
+    v.0 = correction.velocity;
+}
+
+fn apply_position_corrections(
+    mut reader: EventReader<MessageEvent<PositionCorrectionMessage>>,
+    player_query: Query<&Predicted, With<LocalPlayer>>,
+    mut confirmed_query: Query<(&mut Confirmed, &mut Transform, &mut Velocity)>,
+) {
+    let Some(Ok((mut confirmed, mut tf, mut v))) = player_query
+        .get_single()
+        .ok()
+        .and_then(|predicted| predicted.confirmed_entity)
+        .map(|confirmed| confirmed_query.get_mut(confirmed))
+    else {
+        reader.clear();
+        return;
+    };
+    //ordered channel, so the last correction is the newest and the rollback replays from it
+    if let Some(MessageEvent {
+        message: correction,
+        ..
+    }) = reader.read().last()
+    {
+        apply_position_correction(&mut confirmed, &mut tf, &mut v, correction);
+    }
+}
@@ -20,6 +20,7 @@ use engine::{
 };
 use physics::movement::Velocity;
 use world::{
+    atmosphere::Weather,
     block::{BlockId, BlockRegistry, BlockResources},
     chunk::{ChunkCoord, ChunkType},
     events::ChunkUpdatedEvent,
@@ -38,8 +39,11 @@ pub(crate) struct ServerPlugin {
 
 impl Plugin for ServerPlugin {
     fn build(&self, app: &mut App) {
-        // todo - movement
         app.init_resource::<NetworkPlayerMap>()
+            .add_plugins((
+                crate::reconciliation::ServerReconciliationPlugin,
+                crate::inventory_actions::ServerInventoryActionsPlugin,
+            ))
             .add_systems(OnEnter(LevelLoadState::Loaded), start_server)
             .add_systems(
                 Update,
@@ -82,6 +86,9 @@ pub struct NetworkPlayerMap {
 fn start_server(mut commands: Commands, mut state: ResMut<NextState<ServerState>>) {
     info!("server is started!");
     commands.start_server();
+    //clients roll the same weather from the seed, this keeps them in sync if their calendars drift
+    //it only changes when a day or night starts, clients turn the wind themselves
+    commands.replicate_resource::<Weather, OrderedReliable>(NetworkTarget::All);
     state.set(ServerState::Active);
 }
 
@@ -138,10 +145,6 @@ fn handle_connections(
     }
 }
 
-fn movement() {
-    //todo - look at the leafwing integration
-}
-
 // fn handle_client_messages(
 //     mut server: ResMut<QuinnetServer>,
 //     mut users: ResMut<PlayerList>,
@@ -191,27 +194,6 @@ fn movement() {
 //                         ServerMessage::ChatMessage { client_id, message },
 //                     );
 //                 }
-//                 ClientMessage::UpdatePosition {
-//                     transform,
-//                     velocity,
-//                 } => {
-//                     if let Some(PlayerInfo {
-//                         username: _,
-//                         entity,
-//                     }) = users.infos.get(&client_id)
-//                     {
-//                         update_tf_writer.send(UpdateEntityTransform {
-//                             entity: *entity,
-//                             transform,
-//                         });
-//                         update_v_writer.send(UpdateEntityVelocity {
-//                             entity: *entity,
-//                             velocity,
-//                         });
-//                     } else {
-//                         warn!("Update position recieved for uninitialized client!");
-//                     }
-//                 }
 //                 ClientMessage::UseItem { tf, slot } => {
 //                     if let Some(PlayerInfo {
 //                         username: _,
@@ -0,0 +1,365 @@
+#[allow(unused_imports)]
+mod reconciliation {
+    use bevy::prelude::*;
+    use engine::actors::{abilities::dash::Dash, MoveSpeed};
+
+    use lightyear::prelude::{client::Confirmed, Tick};
+    use physics::movement::Velocity;
+
+    use crate::{
+        protocol::{PositionCorrectionMessage, PositionUpdateMessage},
+        reconciliation::{
+            apply_position_correction, check_position_update, is_plausible_move, max_tick_speed,
+            MovementValidation, PositionCheck, DASH_GRACE_TICKS, MAX_CHECKED_TICKS,
+            POSITION_TOLERANCE, SPEED_SLACK,
+        },
+    };
+
+    fn update(tick: u16, translation: Vec3) -> PositionUpdateMessage {
+        PositionUpdateMessage {
+            tick: Tick(tick),
+            translation,
+            rotation: Quat::IDENTITY,
+        }
+    }
+
+    fn open(_: Vec3) -> bool {
+        false
+    }
+
+    #[test]
+    fn test_max_tick_speed() {
+        let move_speed = MoveSpeed::default();
+        assert_eq!(max_tick_speed(&move_speed, None), move_speed.max_speed);
+        //reeling in a grapple is faster than walking
+        assert_eq!(max_tick_speed(&move_speed, Some(0.3)), 0.3);
+        //slower grapples don't cap the walking speed
+        assert_eq!(
+            max_tick_speed(&move_speed, Some(0.01)),
+            move_speed.max_speed
+        );
+    }
+
+    #[test]
+    fn test_plausible_move_reach() {
+        let reach = SPEED_SLACK * 4.0 + POSITION_TOLERANCE;
+        let near = Vec3::X * (reach - 0.01);
+        let far = Vec3::X * (reach + 0.01);
+        //server agrees with the claim, so only the distance from the last update matters
+        assert!(is_plausible_move(near, Vec3::ZERO, near, 1.0, 4, 0));
+        assert!(!is_plausible_move(far, Vec3::ZERO, far, 1.0, 4, 0));
+        //more ticks since the last update let the player go further
+        assert!(is_plausible_move(far, Vec3::ZERO, far, 1.0, 5, 0));
+        //standing still is always fine
+        assert!(is_plausible_move(
+            Vec3::ZERO,
+            Vec3::ZERO,
+            Vec3::ZERO,
+            0.0,
+            0,
+            0
+        ));
+    }
+
+    #[test]
+    fn test_plausible_move_desync() {
+        let claim = Vec3::X;
+        //reachable from the last update, but too far from where the server has the player
+        let server = claim + Vec3::Z * (POSITION_TOLERANCE + 0.01);
+        assert!(!is_plausible_move(claim, Vec3::ZERO, server, 1.0, 4, 0));
+        //the client's tick being ahead of the server's leaves room for the difference
+        assert!(is_plausible_move(claim, Vec3::ZERO, server, 1.0, 4, 1));
+    }
+
+    #[test]
+    fn test_plausible_move_caps_ticks() {
+        let reach = SPEED_SLACK * MAX_CHECKED_TICKS as f32 + POSITION_TOLERANCE;
+        let far = Vec3::X * (reach + 0.01);
+        //a long gap between updates doesn't allow teleporting across the world
+        assert!(!is_plausible_move(far, Vec3::ZERO, far, 1.0, u16::MAX, 0));
+        assert!(!is_plausible_move(
+            far,
+            Vec3::ZERO,
+            Vec3::ZERO,
+            1.0,
+            MAX_CHECKED_TICKS,
+            u16::MAX
+        ));
+        assert!(is_plausible_move(
+            Vec3::X * (reach - 0.01),
+            Vec3::ZERO,
+            Vec3::ZERO,
+            1.0,
+            u16::MAX,
+            u16::MAX
+        ));
+    }
+
+    #[test]
+    fn test_accepted_updates_keep_speed_limit() {
+        let max_speed = MoveSpeed::default().max_speed;
+        let step = max_speed * SPEED_SLACK * 4.0;
+        let mut tf = Transform::default();
+        let v = Velocity(Vec3::ZERO);
+        let mut validation = MovementValidation::new(Vec3::ZERO);
+        //first update is checked against the longest gap, so start from a known tick
+        assert_eq!(
+            check_position_update(
+                &update(4, Vec3::ZERO),
+                Tick(4),
+                max_speed,
+                None,
+                &mut tf,
+                &v,
+                &mut validation,
+                open
+            ),
+            PositionCheck::Accepted
+        );
+        //moving at the limit is fine every time, and accepting it doesn't raise the limit
+        let mut translation = Vec3::ZERO;
+        for i in 2..10 {
+            translation.x += step;
+            tf.translation = translation;
+            assert_eq!(
+                check_position_update(
+                    &update(i * 4, translation),
+                    Tick(i * 4),
+                    max_speed,
+                    None,
+                    &mut tf,
+                    &v,
+                    &mut validation,
+                    open
+                ),
+                PositionCheck::Accepted
+            );
+        }
+        let too_far = translation + Vec3::X * (step * 2.0 + POSITION_TOLERANCE);
+        tf.translation = too_far;
+        assert!(matches!(
+            check_position_update(
+                &update(40, too_far),
+                Tick(40),
+                max_speed,
+                None,
+                &mut tf,
+                &v,
+                &mut validation,
+                open
+            ),
+            PositionCheck::Rejected(_)
+        ));
+    }
+
+    #[test]
+    fn test_rejected_move_corrects_client() {
+        let server_translation = Vec3::new(1.0, 2.0, 3.0);
+        let server_velocity = Velocity(Vec3::new(0.0, -0.1, 0.0));
+        let mut server_tf = Transform::from_translation(server_translation);
+        let mut validation = MovementValidation::new(server_translation);
+        //client teleports away
+        let teleport = server_translation + Vec3::X * 100.0;
+        let mut client_tf = Transform::from_translation(teleport);
+        let mut client_v = Velocity(Vec3::X);
+        let PositionCheck::Rejected(correction) = check_position_update(
+            &update(10, teleport),
+            Tick(10),
+            MoveSpeed::default().max_speed,
+            None,
+            &mut server_tf,
+            &server_velocity,
+            &mut validation,
+            open,
+        ) else {
+            panic!("teleport was accepted");
+        };
+        //the server's player stays put
+        assert_eq!(server_tf.translation, server_translation);
+        assert_eq!(validation.rejected, 1);
+        assert_eq!(
+            correction,
+            PositionCorrectionMessage {
+                tick: Tick(10),
+                translation: server_translation,
+                velocity: server_velocity.0,
+            }
+        );
+        //and the client's confirmed state goes back to it, for lightyear to roll back to and replay from
+        let mut confirmed = Confirmed {
+            predicted: None,
+            interpolated: None,
+            tick: Tick(20),
+        };
+        apply_position_correction(&mut confirmed, &mut client_tf, &mut client_v, &correction);
+        assert_eq!(confirmed.tick, Tick(10));
+        assert_eq!(client_tf.translation, server_translation);
+        assert_eq!(client_v.0, server_velocity.0);
+        //updates sent from the teleported path before the correction arrived are dropped
+        assert_eq!(
+            check_position_update(
+                &update(8, teleport),
+                Tick(10),
+                MoveSpeed::default().max_speed,
+                None,
+                &mut server_tf,
+                &server_velocity,
+                &mut validation,
+                open
+            ),
+            PositionCheck::Stale
+        );
+        assert_eq!(validation.rejected, 1);
+    }
+
+    //the server's simulation moved along x from the origin at dash speed, with the player's last update at the origin
+    //returns where it ended up
+    fn dash_from_origin(
+        validation: &mut MovementValidation,
+        dash_speed: f32,
+        ticks: u16,
+        dash_seen: bool,
+    ) -> Vec3 {
+        let mut translation = Vec3::ZERO;
+        validation.record(Tick(0), translation, false);
+        assert_eq!(
+            check_position_update(
+                &update(0, translation),
+                Tick(0),
+                MoveSpeed::default().max_speed,
+                None,
+                &mut Transform::default(),
+                &Velocity(Vec3::ZERO),
+                validation,
+                open
+            ),
+            PositionCheck::Accepted
+        );
+        for tick in 1..=ticks {
+            translation.x += dash_speed;
+            validation.record(Tick(tick), translation, dash_seen);
+        }
+        translation
+    }
+
+    #[test]
+    fn test_legit_dash_is_accepted() {
+        let max_speed = MoveSpeed::default().max_speed;
+        let dash_speed = Dash::default().current_speed;
+        let mut validation = MovementValidation::new(Vec3::ZERO);
+        let end = dash_from_origin(&mut validation, dash_speed, 4, true);
+        //too far to walk
+        assert!(!is_plausible_move(end, Vec3::ZERO, end, max_speed, 4, 0));
+        let mut tf = Transform::from_translation(end);
+        assert_eq!(
+            check_position_update(
+                &update(4, end),
+                Tick(4),
+                max_speed,
+                Some(dash_speed),
+                &mut tf,
+                &Velocity(Vec3::ZERO),
+                &mut validation,
+                open
+            ),
+            PositionCheck::Accepted
+        );
+    }
+
+    #[test]
+    fn test_dash_needs_the_server_to_see_it() {
+        let max_speed = MoveSpeed::default().max_speed;
+        let dash_speed = Dash::default().current_speed;
+        //no dash input or dash state on the server, so the client can't claim one
+        let mut validation = MovementValidation::new(Vec3::ZERO);
+        let end = dash_from_origin(&mut validation, dash_speed, 4, false);
+        let mut tf = Transform::from_translation(end);
+        assert!(matches!(
+            check_position_update(
+                &update(4, end),
+                Tick(4),
+                max_speed,
+                Some(dash_speed),
+                &mut tf,
+                &Velocity(Vec3::ZERO),
+                &mut validation,
+                open
+            ),
+            PositionCheck::Rejected(_)
+        ));
+        //a dash long before the update doesn't count either
+        let mut validation = MovementValidation::new(Vec3::ZERO);
+        validation.record(Tick(0), Vec3::ZERO, true);
+        assert!(validation.dashed_during(Tick(4), 4));
+        assert!(!validation.dashed_during(Tick(4 + 4 + DASH_GRACE_TICKS + 1), 4));
+    }
+
+    #[test]
+    fn test_checked_against_simulation() {
+        let max_speed = MoveSpeed::default().max_speed;
+        let mut validation = MovementValidation::new(Vec3::ZERO);
+        for tick in 0..=8 {
+            validation.record(Tick(tick), Vec3::ZERO, false);
+        }
+        //reachable at walking speed, but the server's simulation stood still on that tick
+        let claim = Vec3::X * (POSITION_TOLERANCE + 0.01);
+        let mut tf = Transform::default();
+        assert!(matches!(
+            check_position_update(
+                &update(4, claim),
+                Tick(8),
+                max_speed,
+                None,
+                &mut tf,
+                &Velocity(Vec3::ZERO),
+                &mut validation,
+                open
+            ),
+            PositionCheck::Rejected(_)
+        ));
+        //within tolerance of the simulation, the server's player is moved by the difference
+        let claim = Vec3::X * (POSITION_TOLERANCE - 0.01);
+        let mut tf = Transform::from_translation(Vec3::Y);
+        let mut validation = MovementValidation::new(Vec3::ZERO);
+        for tick in 0..=8 {
+            validation.record(Tick(tick), Vec3::ZERO, false);
+        }
+        assert_eq!(
+            check_position_update(
+                &update(4, claim),
+                Tick(8),
+                max_speed,
+                None,
+                &mut tf,
+                &Velocity(Vec3::ZERO),
+                &mut validation,
+                open
+            ),
+            PositionCheck::Accepted
+        );
+        assert_eq!(tf.translation, Vec3::Y + claim);
+        //the ticks simulated since then moved along with it
+        assert_eq!(validation.simulated_at(Tick(8)), Some(claim));
+        assert_eq!(validation.simulated_at(Tick(4)), Some(Vec3::ZERO));
+    }
+
+    #[test]
+    fn test_inside_terrain_is_rejected() {
+        let max_speed = MoveSpeed::default().max_speed;
+        let mut validation = MovementValidation::new(Vec3::ZERO);
+        validation.record(Tick(4), Vec3::ZERO, false);
+        let mut tf = Transform::default();
+        //standing still, but in a wall
+        let result = check_position_update(
+            &update(4, Vec3::ZERO),
+            Tick(4),
+            max_speed,
+            None,
+            &mut tf,
+            &Velocity(Vec3::ZERO),
+            &mut validation,
+            |translation| translation.x < 0.5,
+        );
+        assert!(matches!(result, PositionCheck::Rejected(_)));
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use util::direction::Direction;
+use world::block::BlockCoord;
+
+use crate::{collision::Aabb, spatial::SpatialIndex};
+
+//continuous collision for fast bodies, whose movement in one tick can skip over thin things.
+//terrain is already swept by move_and_slide, which fills this in for bodies that have it
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ContinuousCollision {
+    //position at the start of the last tick's movement
+    pub start: Vec3,
+    //the movement that was attempted last tick, before terrain collision was resolved
+    pub delta: Vec3,
+    //first block hit along delta: (time of impact, block, normal)
+    pub terrain_hit: Option<(f32, BlockCoord, Direction)>,
+}
+
+impl ContinuousCollision {
+    //fraction of delta travelled before hitting terrain
+    pub fn terrain_toi(&self) -> f32 {
+        self.terrain_hit.map_or(1.0, |(toi, ..)| toi)
+    }
+
+    //first entity hit along the last tick's movement, if it comes before any terrain
+    pub fn sweep_entities(
+        &self,
+        aabb: Aabb,
+        index: &SpatialIndex,
+        candidate: impl FnMut(Entity) -> Option<(Vec3, Aabb)>,
+    ) -> Option<EntitySweepHit> {
+        sweep_entities(aabb, self.start, self.delta, index, candidate)
+            .filter(|hit| hit.toi <= self.terrain_toi())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EntitySweepHit {
+    pub entity: Entity,
+    //fraction of the movement travelled before touching the entity
+    pub toi: f32,
+    //position of the moving box at the time of impact
+    pub position: Vec3,
+    //face of the entity that was hit
+    pub normal: Direction,
+}
+
+//first entity the box touches when moving from start by delta.
+//candidate is called with each entity near the path and returns its position and collider if it can be hit
+pub fn sweep_entities(
+    aabb: Aabb,
+    start: Vec3,
+    delta: Vec3,
+    index: &SpatialIndex,
+    mut candidate: impl FnMut(Entity) -> Option<(Vec3, Aabb)>,
+) -> Option<EntitySweepHit> {
+    let end = start + delta;
+    index
+        .query_aabb(
+            aabb.world_min(start.min(end)),
+            aabb.world_max(start.max(end)),
+        )
+        .filter_map(|entity| {
+            let (pos, other) = candidate(entity)?;
+            //already touching counts as a hit at the start
+            if aabb.intersects_aabb(start, other, pos) {
+                let away = aabb.world_center(start) - other.world_center(pos);
+                return Some((entity, 0.0, Direction::from(away)));
+            }
+            let (toi, _, normal) = aabb.sweep_rect(start, delta, other, pos)?;
+            Some((entity, toi, normal))
+        })
+        //ties go to the lower entity so the result doesn't depend on the index's iteration order
+        .min_by(|(a, a_toi, _), (b, b_toi, _)| a_toi.total_cmp(b_toi).then(a.cmp(b)))
+        .map(|(entity, toi, normal)| EntitySweepHit {
+            entity,
+            toi,
+            position: start + delta * toi,
+            normal,
+        })
+}
@@ -8,9 +8,12 @@ use util::{
     *,
 };
 
-use world::{block::BlockCoord, block::BlockType, level::Level};
+use world::{
+    block::{BlockCoord, BlockMeshShape, BlockType},
+    level::Level,
+};
 
-use crate::DebugBlockHitboxes;
+use crate::{ccd::ContinuousCollision, DebugBlockHitboxes};
 
 use super::{movement::*, PhysicsLevelSet};
 
@@ -23,7 +26,8 @@ impl Plugin for CollisionPlugin {
             (move_and_slide, update_terrain_query_point).in_set(PhysicsLevelSet::Main),
         )
         .register_type::<Aabb>()
-        .register_type::<BlockPhysics>();
+        .register_type::<BlockPhysics>()
+        .register_type::<BlockOrientation>();
     }
 }
 
@@ -121,17 +125,71 @@ pub enum BlockPhysics {
     //standard block shape, solid block
     Solid,
     Aabb(Aabb),
+    //several boxes, for stairs and other shapes that aren't a single box. set in the block's asset
+    //the boxes are for the block facing the default way, and turned to the block's orientation
+    Compound(Vec<Aabb>, BlockOrientation),
+    //matches the block's mesh shape. replaced with the derived collider when blocks are loaded
+    FromMesh,
 }
 
 impl BlockPhysics {
     pub fn is_solid(&self) -> bool {
         match self {
-            BlockPhysics::Empty => false,
+            BlockPhysics::Empty | BlockPhysics::FromMesh => false,
             BlockPhysics::Solid | BlockPhysics::Aabb(_) => true,
+            BlockPhysics::Compound(parts, _) => !parts.is_empty(),
+        }
+    }
+
+    //boxes making up the collider, relative to the block's coordinate
+    pub fn parts(&self) -> impl Iterator<Item = Aabb> + '_ {
+        let single = match self {
+            BlockPhysics::Solid => Some(Aabb::new(Vec3::ONE, Vec3::ZERO)),
+            BlockPhysics::Aabb(aabb) => Some(*aabb),
+            _ => None,
+        };
+        let (compound, orientation) = match self {
+            BlockPhysics::Compound(parts, orientation) => (parts.as_slice(), *orientation),
+            _ => (&[][..], BlockOrientation::default()),
+        };
+        single
+            .into_iter()
+            .chain(compound.iter().map(move |part| orientation.rotate(*part)))
+    }
+
+    pub fn intersects_point(&self, block_pos: Vec3, point: Vec3) -> bool {
+        self.parts()
+            .any(|part| part.intersects_point(block_pos, point))
+    }
+
+    pub fn from_mesh_shape(shape: &BlockMeshShape) -> Self {
+        match shape {
+            BlockMeshShape::Empty | BlockMeshShape::Cross(_) => BlockPhysics::Empty,
+            BlockMeshShape::Uniform(_) | BlockMeshShape::MultiTexture(_) => BlockPhysics::Solid,
+            BlockMeshShape::BottomSlab(height, _) => {
+                BlockPhysics::Aabb(Aabb::new(Vec3::new(1.0, *height, 1.0), Vec3::ZERO))
+            }
         }
     }
 }
 
+//quarter turns around the y axis, counterclockwise seen from above
+#[derive(Clone, Copy, PartialEq, Eq, Default, Reflect, Debug, Serialize, Deserialize)]
+pub struct BlockOrientation(pub u8);
+
+impl BlockOrientation {
+    //turns a box inside the block about the block's center
+    pub fn rotate(self, aabb: Aabb) -> Aabb {
+        (0..self.0 % 4).fold(aabb, |aabb, _| {
+            //(x, z) -> (z, 1 - x)
+            Aabb::new(
+                Vec3::new(aabb.size.z, aabb.size.y, aabb.size.x),
+                Vec3::new(aabb.offset.z, aabb.offset.y, 1.0 - aabb.max().x),
+            )
+        })
+    }
+}
+
 //from offset to offset + size
 // most things work with GlobalTransform's translation, with the notable exception being terrain collision, which uses the local transform.
 // entities that are a child of another object should also have the IgnoreTerrainCollision component
@@ -213,15 +271,12 @@ impl Aabb {
     pub fn to_block_volume(self, pos: Vec3) -> Volume {
         Volume::new_inclusive(self.world_min(pos).my_into(), self.world_max(pos).my_into())
     }
+    //bounding box of the whole collider, for things that only need one box per block
     pub fn from_block(physics: &BlockPhysics) -> Option<Self> {
-        match physics {
-            BlockPhysics::Empty => None,
-            BlockPhysics::Solid => Some(Aabb {
-                size: Vec3::splat(1.0),
-                offset: Vec3::ZERO,
-            }),
-            BlockPhysics::Aabb(col) => Some(*col),
-        }
+        physics.parts().reduce(|a, b| {
+            let min = a.min().min(b.min());
+            Aabb::new(a.max().max(b.max()) - min, min)
+        })
     }
 
     pub fn intersects_point(self, my_pos: Vec3, point_pos: Vec3) -> bool {
@@ -245,11 +300,9 @@ impl Aabb {
         other: &BlockPhysics,
         other_pos: BlockCoord,
     ) -> bool {
-        if let Some(other_aabb) = Aabb::from_block(other) {
-            self.intersects_aabb(my_pos, other_aabb, other_pos.to_vec3())
-        } else {
-            false
-        }
+        other
+            .parts()
+            .any(|part| self.intersects_aabb(my_pos, part, other_pos.to_vec3()))
     }
 
     //I had a lot of issues getting swept collision working, expect a lot of comments
@@ -365,7 +418,95 @@ pub struct IgnoreTerrainCollision;
 #[derive(Component, Copy, Clone, Default)]
 pub struct TerrainQueryPoint;
 
-fn move_and_slide(
+//walks up ledges up to this height instead of being stopped by them, like half-slabs
+#[derive(Component, Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StepUp {
+    pub height: f32,
+}
+
+impl Default for StepUp {
+    fn default() -> Self {
+        Self { height: 0.55 }
+    }
+}
+
+//boxes that collide with terrain instead of the Aabb, for bodies that aren't box shaped like falling groups of blocks
+//the Aabb should still cover all of them, it's used to find nearby blocks and by everything else
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct CompoundCollider(pub Vec<Aabb>);
+
+//(time, block coord, block part collider, normal)
+type BlockHit = (f32, BlockCoord, Aabb, Direction);
+
+fn sweep_blocks(
+    parts: &[Aabb],
+    pos: Vec3,
+    v: Vec3,
+    colliders: &[(BlockCoord, Entity, &BlockPhysics)],
+    hits: &mut Vec<BlockHit>,
+) {
+    hits.clear();
+    for (coord, _, physics) in colliders {
+        for block_part in physics.parts() {
+            //only the first part to touch the block matters
+            if let Some((t, _, normal)) = earliest_sweep(parts, pos, v, block_part, coord.to_vec3())
+            {
+                hits.push((t, *coord, block_part, normal));
+            }
+        }
+    }
+}
+
+fn earliest_sweep(
+    parts: &[Aabb],
+    pos: Vec3,
+    v: Vec3,
+    other: Aabb,
+    other_pos: Vec3,
+) -> Option<(f32, Vec3, Direction)> {
+    parts
+        .iter()
+        .filter_map(|part| part.sweep_rect(pos, v, other, other_pos))
+        .min_by(|(t1, ..), (t2, ..)| t1.total_cmp(t2))
+}
+
+//how far up the entity has to move to walk over the ledge it's blocked by, if it can
+fn step_up_height(
+    col: Aabb,
+    pos: Vec3,
+    v: Vec3,
+    max_height: f32,
+    hits: &[BlockHit],
+    colliders: &[(BlockCoord, Entity, &BlockPhysics)],
+) -> Option<f32> {
+    const CLEARANCE: f32 = 0.001;
+    //only from the ground and not while jumping
+    let grounded = hits.iter().any(|(.., normal)| *normal == Direction::PosY);
+    if v.y > 0.0 || !grounded {
+        return None;
+    }
+    let feet = col.world_min(pos).y;
+    let rise = hits
+        .iter()
+        .filter(|(.., normal)| !matches!(normal, Direction::PosY | Direction::NegY))
+        .map(|(_, coord, part, _)| part.world_max(coord.to_vec3()).y - feet)
+        .reduce(f32::max)?;
+    if rise <= 0.0 || rise > max_height {
+        return None;
+    }
+    let raised = pos + Vec3::Y * (rise + CLEARANCE);
+    //needs room to stand both above where we are and where we're going
+    let blocked = [raised, raised + Vec3::new(v.x, 0.0, v.z)]
+        .into_iter()
+        .any(|test_pos| {
+            colliders
+                .iter()
+                .any(|(coord, _, physics)| col.intersects_block(test_pos, physics, *coord))
+        });
+    (!blocked).then_some(rise + CLEARANCE)
+}
+
+pub(crate) fn move_and_slide(
     mut objects: Query<
         (
             &mut Transform,
@@ -374,6 +515,9 @@ fn move_and_slide(
             &Aabb,
             &Restitution,
             Option<&mut CollidingBlocks>,
+            Option<&StepUp>,
+            Option<&mut ContinuousCollision>,
+            Option<&CompoundCollider>,
         ),
         Without<IgnoreTerrainCollision>,
     >,
@@ -384,28 +528,42 @@ fn move_and_slide(
 ) {
     block_gizmos.blocks.clear();
     block_gizmos.hit_blocks.clear();
-    let mut resolution_buffer: Vec<(f32, BlockCoord, Aabb)> = Vec::with_capacity(32);
-    for (mut tf, mut v, mut directions, col, restitution, mut opt_col_blocks) in objects.iter_mut()
+    let mut resolution_buffer: Vec<BlockHit> = Vec::with_capacity(32);
+    for (
+        mut tf,
+        mut v,
+        mut directions,
+        col,
+        restitution,
+        mut opt_col_blocks,
+        opt_step,
+        opt_ccd,
+        opt_compound,
+    ) in objects.iter_mut()
     {
+        let parts = opt_compound.map_or(std::slice::from_ref(col), |compound| &compound.0);
         if let Some(ref mut col_blocks) = opt_col_blocks {
             col_blocks.clear();
         }
-        resolution_buffer.clear();
-        directions.0 = DirectionFlags::default();
+        let step_height = opt_step.map_or(0.0, |step| step.height);
         let target_pos = tf.translation + v.0;
         let bounding_min = tf.translation.min(target_pos);
-        let bounding_max = tf.translation.max(target_pos);
+        //stepping up can move us above the target
+        let bounding_max = tf.translation.max(target_pos) + Vec3::Y * step_height;
         //all the blocks we can overlap with are in the bounding rectangle of current position and target position
         //add 1 in each direction to avoid issues with "precision"
         let overlaps = level.get_blocks_in_volume(Volume::new_inclusive(
             (BlockCoord::from(col.world_min(bounding_min)) - BlockCoord::new(1, 1, 1)).into(),
             (BlockCoord::from(col.world_max(bounding_max)) + BlockCoord::new(1, 1, 1)).into(),
         ));
-        let overlaps_iter = overlaps.iter().filter_map(|(coord, block)| {
-            block
-                .and_then(|b| b.entity())
-                .and_then(|e| block_physics.get(e).ok().map(|p| (coord, p, e)))
-        });
+        let colliders: Vec<(BlockCoord, Entity, &BlockPhysics)> = overlaps
+            .iter()
+            .filter_map(|(coord, block)| {
+                block
+                    .and_then(|b| b.entity())
+                    .and_then(|e| block_physics.get(e).ok().map(|p| (coord.into(), e, p)))
+            })
+            .collect();
         if *debug_state == DebugUIState::Shown {
             let gizmos_iter = overlaps.iter().map(|(coord, block)| {
                 (
@@ -420,35 +578,69 @@ fn move_and_slide(
         }
 
         //get all collision we need to resolve, sort in order of time, resolve all
-        for (c, p, e) in overlaps_iter {
-            if let Some(block_aabb) = Aabb::from_block(p) {
-                if let Some((t, _, normal)) =
-                    col.sweep_rect(tf.translation, v.0, block_aabb, c.as_vec3())
-                {
-                    //do on collision events here, we won't actually resolve all these collisions
-                    //  (resolving some will make the object not collide with others)
-                    if *debug_state == DebugUIState::Shown {
-                        block_gizmos.hit_blocks.insert(c.into());
-                    }
-                    if let Some(ref mut col_blocks) = opt_col_blocks {
-                        col_blocks.push(normal, (c.into(), e, p.clone()));
-                    }
-                    resolution_buffer.push((t, c.into(), block_aabb));
-                    directions.0.set(normal.opposite().into(), true);
+        sweep_blocks(
+            parts,
+            tf.translation,
+            v.0,
+            &colliders,
+            &mut resolution_buffer,
+        );
+        let step = (step_height > 0.0)
+            .then(|| {
+                step_up_height(
+                    *col,
+                    tf.translation,
+                    v.0,
+                    step_height,
+                    &resolution_buffer,
+                    &colliders,
+                )
+            })
+            .flatten();
+        if let Some(rise) = step {
+            //we stand on the ledge, then move as normal from there
+            tf.translation.y += rise;
+            v.y = v.y.max(0.0);
+            sweep_blocks(
+                parts,
+                tf.translation,
+                v.0,
+                &colliders,
+                &mut resolution_buffer,
+            );
+            directions.0.set(DirectionFlags::NegY, true);
+        }
+        for (_, coord, _, normal) in resolution_buffer.iter() {
+            //do on collision events here, we won't actually resolve all these collisions
+            //  (resolving some will make the object not collide with others)
+            if *debug_state == DebugUIState::Shown {
+                block_gizmos.hit_blocks.insert(*coord);
+            }
+            if let Some(ref mut col_blocks) = opt_col_blocks {
+                if let Some((_, e, p)) = colliders.iter().find(|(c, ..)| c == coord) {
+                    col_blocks.push(*normal, (*coord, *e, (*p).clone()));
                 }
             }
+            directions.0.set(normal.opposite().into(), true);
         }
 
         //we count NaN time as no collision, so this is ok (all other floats are comparable)
-        resolution_buffer.sort_unstable_by(|(t1, _, _), (t2, _, _)| {
+        resolution_buffer.sort_unstable_by(|(t1, ..), (t2, ..)| {
             debug_assert!(!t1.is_nan() && !t2.is_nan());
             t1.partial_cmp(t2).unwrap_or(std::cmp::Ordering::Equal)
         });
+        if let Some(mut ccd) = opt_ccd {
+            ccd.start = tf.translation;
+            ccd.delta = v.0;
+            ccd.terrain_hit = resolution_buffer
+                .first()
+                .map(|(t, coord, _, normal)| (*t, *coord, *normal));
+        }
 
         //resolve collisions
-        for (_, block_pos, block_aabb) in resolution_buffer.drain(..) {
+        for (_, block_pos, block_aabb, _) in resolution_buffer.drain(..) {
             if let Some((t, _, contact_normal)) =
-                col.sweep_rect(tf.translation, v.0, block_aabb, block_pos.to_vec3())
+                earliest_sweep(parts, tf.translation, v.0, block_aabb, block_pos.to_vec3())
             {
                 //prevent clipping into blocks due to floating point imprecision
                 let t_adj = if t < 0.0001 { 0.0 } else { t };
@@ -0,0 +1,438 @@
+use ahash::HashMap;
+use bevy::prelude::*;
+
+use interfaces::scheduling::PhysicsLevelSet;
+
+use crate::{
+    entity_collision::inverse_mass,
+    movement::{Acceleration, Mass, Velocity},
+};
+
+//constraints between pairs of entities or points in the world, each constraint is its own entity
+pub struct ConstraintPlugin;
+
+impl Plugin for ConstraintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            solve_constraints
+                .after(crate::entity_collision::push_apart_entities)
+                .before(crate::collision::move_and_slide)
+                .in_set(PhysicsLevelSet::Main),
+        )
+        .add_event::<ConstraintBrokenEvent>();
+    }
+}
+
+//length constraints are relaxed this many times a tick so connected constraints settle
+pub const SOLVER_ITERATIONS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConstraintAnchor {
+    Point(Vec3),
+    Entity {
+        entity: Entity,
+        offset: Vec3, //local space to entity
+    },
+}
+
+impl ConstraintAnchor {
+    pub fn entity(entity: Entity) -> Self {
+        Self::Entity {
+            entity,
+            offset: Vec3::ZERO,
+        }
+    }
+
+    pub fn get_entity(self) -> Option<Entity> {
+        match self {
+            ConstraintAnchor::Point(_) => None,
+            ConstraintAnchor::Entity { entity, .. } => Some(entity),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConstraintKind {
+    //keeps the anchors between min and max apart. min == max is a rigid distance, min 0 is a rope
+    Length { min: f32, max: f32 },
+    //changes velocity in proportion to how far the anchors are from rest_length
+    Spring { rest_length: f32, strength: f32 },
+    //accelerates a towards b until a is approaching faster than max_speed, ignores mass
+    Reel { strength: f32, max_speed: f32 },
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Constraint {
+    pub a: ConstraintAnchor,
+    pub b: ConstraintAnchor,
+    pub kind: ConstraintKind,
+    //fraction of a length error corrected each iteration
+    pub stiffness: f32,
+    //despawned once the anchors are further apart than this
+    pub break_length: Option<f32>,
+    //only a is moved, b is treated as immovable
+    pub one_way: bool,
+}
+
+impl Constraint {
+    pub fn new(a: ConstraintAnchor, b: ConstraintAnchor, kind: ConstraintKind) -> Self {
+        Self {
+            a,
+            b,
+            kind,
+            stiffness: 1.0,
+            break_length: None,
+            one_way: false,
+        }
+    }
+
+    pub fn distance(a: ConstraintAnchor, b: ConstraintAnchor, length: f32) -> Self {
+        Self::new(
+            a,
+            b,
+            ConstraintKind::Length {
+                min: length,
+                max: length,
+            },
+        )
+    }
+
+    pub fn rope(a: ConstraintAnchor, b: ConstraintAnchor, max_length: f32) -> Self {
+        Self::new(
+            a,
+            b,
+            ConstraintKind::Length {
+                min: 0.0,
+                max: max_length,
+            },
+        )
+    }
+
+    pub fn spring(
+        a: ConstraintAnchor,
+        b: ConstraintAnchor,
+        rest_length: f32,
+        strength: f32,
+    ) -> Self {
+        Self::new(
+            a,
+            b,
+            ConstraintKind::Spring {
+                rest_length,
+                strength,
+            },
+        )
+    }
+
+    pub fn reel(a: ConstraintAnchor, b: ConstraintAnchor, strength: f32, max_speed: f32) -> Self {
+        Self::new(
+            a,
+            b,
+            ConstraintKind::Reel {
+                strength,
+                max_speed,
+            },
+        )
+    }
+
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    pub fn with_break_length(mut self, break_length: f32) -> Self {
+        self.break_length = Some(break_length);
+        self
+    }
+
+    pub fn one_way(mut self) -> Self {
+        self.one_way = true;
+        self
+    }
+
+    //rigid links between consecutive anchors, each anchor is a of the link to the one before it,
+    //so one_way chains follow their first anchor.
+    //max_bend (radians) limits the angle between consecutive links, like a hinge
+    pub fn chain(
+        anchors: &[ConstraintAnchor],
+        link_length: f32,
+        max_bend: Option<f32>,
+    ) -> Vec<Self> {
+        let links = anchors
+            .windows(2)
+            .map(|pair| Self::distance(pair[1], pair[0], link_length));
+        let bends = max_bend.into_iter().flat_map(|max_bend| {
+            //two links bent by max_bend have their ends this far apart
+            let min = 2.0 * link_length * (max_bend.clamp(0.0, std::f32::consts::PI) / 2.0).cos();
+            anchors.windows(3).map(move |triple| {
+                Self::new(
+                    triple[2],
+                    triple[0],
+                    ConstraintKind::Length {
+                        min,
+                        max: f32::INFINITY,
+                    },
+                )
+            })
+        });
+        links.chain(bends).collect()
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ConstraintBrokenEvent {
+    pub constraint: Entity,
+    pub a: ConstraintAnchor,
+    pub b: ConstraintAnchor,
+}
+
+//velocity changes (to a, to b) that bring positions predicted after this tick's movement back within [min, max]
+pub fn length_correction(
+    a_pos: Vec3,
+    a_inv_mass: f32,
+    b_pos: Vec3,
+    b_inv_mass: f32,
+    min: f32,
+    max: f32,
+    stiffness: f32,
+) -> Option<(Vec3, Vec3)> {
+    let delta = b_pos - a_pos;
+    let length = delta.length();
+    let total_inv_mass = a_inv_mass + b_inv_mass;
+    if length <= f32::EPSILON || total_inv_mass == 0.0 {
+        return None;
+    }
+    let error = length - length.clamp(min, max);
+    if error == 0.0 {
+        return None;
+    }
+    //positive error pulls the anchors together, lighter anchors move further
+    let correction = delta / length * error * stiffness;
+    Some((
+        correction * a_inv_mass / total_inv_mass,
+        -correction * b_inv_mass / total_inv_mass,
+    ))
+}
+
+fn reel_force(pos: Vec3, anchor_pos: Vec3, strength: f32, current_v: Vec3, max_speed: f32) -> Vec3 {
+    let line = (anchor_pos - pos).normalize_or_zero();
+    let v_in_line = current_v.project_onto_normalized(line);
+    if !v_in_line.is_finite() || v_in_line.length_squared() > max_speed * max_speed {
+        Vec3::ZERO
+    } else {
+        line * strength
+    }
+}
+
+type BodyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Transform,
+        &'static mut Velocity,
+        Option<&'static mut Acceleration>,
+        Option<&'static Mass>,
+    ),
+>;
+
+//current world position, velocity and inverse mass. anchors without velocity can't be moved
+fn anchor_state(
+    anchor: ConstraintAnchor,
+    bodies: &BodyQuery,
+    fixed: &Query<&GlobalTransform, Without<Velocity>>,
+) -> Option<(Vec3, Vec3, f32)> {
+    match anchor {
+        ConstraintAnchor::Point(pos) => Some((pos, Vec3::ZERO, 0.0)),
+        ConstraintAnchor::Entity { entity, offset } => {
+            if let Ok((tf, v, _, mass)) = bodies.get(entity) {
+                Some((tf.transform_point(offset), v.0, inverse_mass(mass)))
+            } else {
+                fixed
+                    .get(entity)
+                    .ok()
+                    .map(|gtf| (gtf.transform_point(offset), Vec3::ZERO, 0.0))
+            }
+        }
+    }
+}
+
+fn add_velocity(anchor: ConstraintAnchor, dv: Vec3, bodies: &mut BodyQuery) {
+    if let Some(Ok((_, mut v, ..))) = anchor.get_entity().map(|entity| bodies.get_mut(entity)) {
+        v.0 += dv;
+    }
+}
+
+fn add_acceleration(anchor: ConstraintAnchor, da: Vec3, bodies: &mut BodyQuery) {
+    if let Some(Ok((_, _, Some(mut a), _))) =
+        anchor.get_entity().map(|entity| bodies.get_mut(entity))
+    {
+        a.0 += da;
+    }
+}
+
+//orders anchors by position, missing anchors first
+fn cmp_anchors(
+    a: ConstraintAnchor,
+    b: ConstraintAnchor,
+    bodies: &BodyQuery,
+    fixed: &Query<&GlobalTransform, Without<Velocity>>,
+) -> std::cmp::Ordering {
+    let a_pos = anchor_state(a, bodies, fixed).map(|(pos, ..)| pos);
+    let b_pos = anchor_state(b, bodies, fixed).map(|(pos, ..)| pos);
+    match (a_pos, b_pos) {
+        (Some(a_pos), Some(b_pos)) => a_pos
+            .x
+            .total_cmp(&b_pos.x)
+            .then(a_pos.y.total_cmp(&b_pos.y))
+            .then(a_pos.z.total_cmp(&b_pos.z)),
+        (a_pos, b_pos) => a_pos.is_some().cmp(&b_pos.is_some()),
+    }
+}
+
+//one way constraints are solved after the ones moving what they follow, so a chain follows its leader in one pass
+fn follow_depth(
+    body: Entity,
+    leaders: &HashMap<Entity, Vec<Entity>>,
+    depths: &mut HashMap<Entity, u32>,
+) -> u32 {
+    if let Some(depth) = depths.get(&body) {
+        return *depth;
+    }
+    //cycles stop here
+    depths.insert(body, 0);
+    let depth = leaders.get(&body).map_or(0, |body_leaders| {
+        body_leaders
+            .iter()
+            .map(|leader| follow_depth(*leader, leaders, depths) + 1)
+            .max()
+            .unwrap_or(0)
+    });
+    depths.insert(body, depth);
+    depth
+}
+
+//changes velocities before terrain collision, so constrained bodies are still stopped by walls.
+pub fn solve_constraints(
+    constraint_query: Query<(Entity, &Constraint)>,
+    mut bodies: BodyQuery,
+    fixed: Query<&GlobalTransform, Without<Velocity>>,
+    mut writer: EventWriter<ConstraintBrokenEvent>,
+    mut commands: Commands,
+    mut order: Local<Vec<(u32, Entity, Constraint)>>,
+    mut leaders: Local<HashMap<Entity, Vec<Entity>>>,
+    mut depths: Local<HashMap<Entity, u32>>,
+) {
+    order.clear();
+    order.extend(
+        constraint_query
+            .iter()
+            .map(|(entity, constraint)| (0, entity, *constraint)),
+    );
+    leaders.clear();
+    depths.clear();
+    for (_, _, constraint) in order.iter().filter(|(_, _, c)| c.one_way) {
+        if let (Some(a), Some(b)) = (constraint.a.get_entity(), constraint.b.get_entity()) {
+            leaders.entry(a).or_default().push(b);
+        }
+    }
+    for (depth, _, constraint) in order.iter_mut().filter(|(_, _, c)| c.one_way) {
+        if let Some(a) = constraint.a.get_entity() {
+            *depth = follow_depth(a, &leaders, &mut depths);
+        }
+    }
+    //followers after their leaders, then by anchor position for the same reason as push_apart_entities
+    order.sort_unstable_by(|(a_depth, a_entity, a), (b_depth, b_entity, b)| {
+        a_depth
+            .cmp(b_depth)
+            .then_with(|| cmp_anchors(a.a, b.a, &bodies, &fixed))
+            .then_with(|| cmp_anchors(a.b, b.b, &bodies, &fixed))
+            .then(a_entity.cmp(b_entity))
+    });
+
+    //forces and breaking use the positions at the start of the tick
+    order.retain(|(_, entity, constraint)| {
+        let (Some((a_pos, a_v, a_inv_mass)), Some((b_pos, _, b_inv_mass))) = (
+            anchor_state(constraint.a, &bodies, &fixed),
+            anchor_state(constraint.b, &bodies, &fixed),
+        ) else {
+            //anchor despawned
+            if let Some(mut ec) = commands.get_entity(*entity) {
+                ec.despawn();
+            }
+            return false;
+        };
+        if constraint
+            .break_length
+            .is_some_and(|limit| a_pos.distance(b_pos) > limit)
+        {
+            if let Some(mut ec) = commands.get_entity(*entity) {
+                ec.despawn();
+            }
+            writer.send(ConstraintBrokenEvent {
+                constraint: *entity,
+                a: constraint.a,
+                b: constraint.b,
+            });
+            return false;
+        }
+        let b_inv_mass = if constraint.one_way { 0.0 } else { b_inv_mass };
+        match constraint.kind {
+            ConstraintKind::Length { .. } => {}
+            ConstraintKind::Spring {
+                rest_length,
+                strength,
+            } => {
+                let delta = b_pos - a_pos;
+                let length = delta.length();
+                let total_inv_mass = a_inv_mass + b_inv_mass;
+                if length > f32::EPSILON && total_inv_mass > 0.0 {
+                    let dv = strength * (length - rest_length) * delta / length;
+                    add_velocity(constraint.a, dv * a_inv_mass / total_inv_mass, &mut bodies);
+                    add_velocity(constraint.b, -dv * b_inv_mass / total_inv_mass, &mut bodies);
+                }
+            }
+            ConstraintKind::Reel {
+                strength,
+                max_speed,
+            } => {
+                if a_inv_mass > 0.0 {
+                    let a = reel_force(a_pos, b_pos, strength, a_v, max_speed);
+                    add_acceleration(constraint.a, a, &mut bodies);
+                    //pulls back on b in proportion to their masses
+                    add_acceleration(constraint.b, -a * b_inv_mass / a_inv_mass, &mut bodies);
+                }
+            }
+        }
+        true
+    });
+
+    //length constraints use the positions predicted after this tick's movement
+    for _ in 0..SOLVER_ITERATIONS {
+        for (_, _, constraint) in order.iter() {
+            let ConstraintKind::Length { min, max } = constraint.kind else {
+                continue;
+            };
+            let (Some((a_pos, a_v, a_inv_mass)), Some((b_pos, b_v, b_inv_mass))) = (
+                anchor_state(constraint.a, &bodies, &fixed),
+                anchor_state(constraint.b, &bodies, &fixed),
+            ) else {
+                continue;
+            };
+            let b_inv_mass = if constraint.one_way { 0.0 } else { b_inv_mass };
+            let Some((a_dv, b_dv)) = length_correction(
+                a_pos + a_v,
+                a_inv_mass,
+                b_pos + b_v,
+                b_inv_mass,
+                min,
+                max,
+                constraint.stiffness,
+            ) else {
+                continue;
+            };
+            add_velocity(constraint.a, a_dv, &mut bodies);
+            add_velocity(constraint.b, b_dv, &mut bodies);
+        }
+    }
+}
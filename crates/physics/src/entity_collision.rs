@@ -0,0 +1,231 @@
+use bevy::prelude::*;
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+use interfaces::scheduling::PhysicsLevelSet;
+use util::direction::{Direction, DirectionFlags};
+
+use crate::{
+    ccd::ContinuousCollision,
+    collision::{Aabb, CollidingDirections, IgnoreTerrainCollision, TerrainQueryPoint},
+    movement::{Mass, Velocity},
+};
+
+//solid collisions between entities, so they push each other apart instead of overlapping
+pub struct EntityCollisionPlugin;
+
+impl Plugin for EntityCollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (clear_colliding_directions, push_apart_entities)
+                .chain()
+                .before(crate::collision::move_and_slide)
+                .in_set(PhysicsLevelSet::Main),
+        );
+    }
+}
+
+//fraction of an existing overlap that is resolved each tick. approaching velocity is always cancelled in full
+pub const PUSH_STRENGTH: f32 = 0.25;
+
+bitflags! {
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct CollisionLayer : u8 {
+        const Player = 0b0001;
+        const Enemy = 0b0010;
+        const Projectile = 0b0100;
+        const Item = 0b1000;
+    }
+}
+
+//entities with this are solid to other entities with it.
+//two entities collide only if each one's mask contains one of the other's layers
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CollisionLayers {
+    pub layers: CollisionLayer,
+    pub mask: CollisionLayer,
+}
+
+impl CollisionLayers {
+    pub const PLAYER: Self = Self::new(
+        CollisionLayer::Player,
+        CollisionLayer::Player.union(CollisionLayer::Enemy),
+    );
+    pub const ENEMY: Self = Self::new(
+        CollisionLayer::Enemy,
+        CollisionLayer::Player.union(CollisionLayer::Enemy),
+    );
+    pub const PROJECTILE: Self = Self::new(CollisionLayer::Projectile, CollisionLayer::empty());
+    pub const ITEM: Self = Self::new(CollisionLayer::Item, CollisionLayer::empty());
+
+    pub const fn new(layers: CollisionLayer, mask: CollisionLayer) -> Self {
+        Self { layers, mask }
+    }
+
+    pub fn collides_with(self, other: Self) -> bool {
+        self.mask.intersects(other.layers) && other.mask.intersects(self.layers)
+    }
+}
+
+//move_and_slide adds terrain contacts on top of entity contacts, so they're cleared before both
+fn clear_colliding_directions(
+    mut query: Query<
+        &mut CollidingDirections,
+        (
+            With<Aabb>,
+            Without<IgnoreTerrainCollision>,
+            Without<TerrainQueryPoint>,
+        ),
+    >,
+) {
+    for mut directions in query.iter_mut() {
+        directions.0 = DirectionFlags::empty();
+    }
+}
+
+//changes velocities so overlapping entities separate before terrain collision is resolved,
+//so entities pushed into a wall are stopped by it.
+//only reads the bodies' current state (not the spatial index, which is a tick behind) so it's safe to resimulate
+pub(crate) fn push_apart_entities(
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &mut CollidingDirections,
+            &Aabb,
+            &CollisionLayers,
+            Option<&Mass>,
+            Has<ContinuousCollision>,
+        ),
+        Without<IgnoreTerrainCollision>,
+    >,
+    mut bodies: Local<Vec<(Entity, Vec3, Vec3, CollisionLayers)>>,
+    mut pairs: Local<Vec<(usize, usize)>>,
+) {
+    //sweep and prune along x, bounds cover this tick's movement
+    bodies.clear();
+    bodies.extend(query.iter().map(|(entity, tf, v, _, aabb, layers, ..)| {
+        let target = tf.translation + v.0;
+        (
+            entity,
+            aabb.world_min(tf.translation.min(target)),
+            aabb.world_max(tf.translation.max(target)),
+            *layers,
+        )
+    }));
+    //sorted by the whole corner so the order doesn't depend on query iteration order or entity ids,
+    //which differ between a server and its clients. entity only breaks exact ties
+    bodies.sort_unstable_by(|(a, a_min, ..), (b, b_min, ..)| {
+        a_min
+            .x
+            .total_cmp(&b_min.x)
+            .then(a_min.y.total_cmp(&b_min.y))
+            .then(a_min.z.total_cmp(&b_min.z))
+            .then(a.cmp(b))
+    });
+    pairs.clear();
+    for (i, (_, a_min, a_max, a_layers)) in bodies.iter().enumerate() {
+        for (j, (_, b_min, b_max, b_layers)) in bodies.iter().enumerate().skip(i + 1) {
+            if b_min.x > a_max.x {
+                break;
+            }
+            if a_min.cmple(*b_max).all()
+                && b_min.cmple(*a_max).all()
+                && a_layers.collides_with(*b_layers)
+            {
+                pairs.push((i, j));
+            }
+        }
+    }
+    //resolved in sweep order, later pairs see velocities changed by earlier ones
+    for &(i, j) in pairs.iter() {
+        let (a, b) = (bodies[i].0, bodies[j].0);
+        let Ok([a_item, b_item]) = query.get_many_mut([a, b]) else {
+            continue;
+        };
+        let (_, a_tf, mut a_v, mut a_dirs, a_aabb, _, a_mass, a_ccd) = a_item;
+        let (_, b_tf, mut b_v, mut b_dirs, b_aabb, _, b_mass, b_ccd) = b_item;
+        let a_inv = inverse_mass(a_mass);
+        let b_inv = inverse_mass(b_mass);
+        if a_inv + b_inv == 0.0 {
+            continue;
+        }
+        let resolved = match overlap(
+            *a_aabb,
+            a_tf.translation + a_v.0,
+            *b_aabb,
+            b_tf.translation + b_v.0,
+        ) {
+            Some((axis, normal, depth)) => {
+                //how much of the overlap was already there before this tick's movement
+                let existing =
+                    overlap_on_axis(*a_aabb, a_tf.translation, *b_aabb, b_tf.translation, axis)
+                        .max(0.0);
+                Some((normal, depth - existing * (1.0 - PUSH_STRENGTH)))
+            }
+            //fast entities can pass through each other between ticks, so sweep them against each other
+            None if a_ccd || b_ccd => {
+                let relative_v = a_v.0 - b_v.0;
+                a_aabb
+                    .sweep_rect(a_tf.translation, relative_v, *b_aabb, b_tf.translation)
+                    .map(|(toi, _, face)| {
+                        let normal = face.to_vec3();
+                        //cancel the approach left after the time of impact
+                        (normal, -relative_v.dot(normal) * (1.0 - toi))
+                    })
+            }
+            None => None,
+        };
+        let Some((normal, correction)) = resolved else {
+            continue;
+        };
+        if correction <= 0.0 {
+            continue;
+        }
+        //normal points from b to a, lighter entities are pushed further
+        let push = normal * correction;
+        a_v.0 += push * a_inv / (a_inv + b_inv);
+        b_v.0 -= push * b_inv / (a_inv + b_inv);
+        let a_dir = Direction::from(-normal);
+        a_dirs.0 |= DirectionFlags::from(a_dir);
+        b_dirs.0 |= DirectionFlags::from(a_dir.opposite());
+    }
+}
+
+pub(crate) fn inverse_mass(mass: Option<&Mass>) -> f32 {
+    let mass = mass.copied().unwrap_or_default().0;
+    if mass.is_finite() && mass > 0.0 {
+        1.0 / mass
+    } else {
+        0.0
+    }
+}
+
+fn overlap_on_axis(a: Aabb, a_pos: Vec3, b: Aabb, b_pos: Vec3, axis: usize) -> f32 {
+    a.world_max(a_pos)[axis].min(b.world_max(b_pos)[axis])
+        - a.world_min(a_pos)[axis].max(b.world_min(b_pos)[axis])
+}
+
+//axis of least penetration, the unit normal from b to a along it, and the depth
+pub fn overlap(a: Aabb, a_pos: Vec3, b: Aabb, b_pos: Vec3) -> Option<(usize, Vec3, f32)> {
+    let mut best: Option<(usize, f32)> = None;
+    for axis in 0..3 {
+        let depth = overlap_on_axis(a, a_pos, b, b_pos, axis);
+        if depth <= 0.0 {
+            return None;
+        }
+        if best.is_none_or(|(_, best_depth)| depth < best_depth) {
+            best = Some((axis, depth));
+        }
+    }
+    let (axis, depth) = best?;
+    let mut normal = Vec3::ZERO;
+    normal[axis] = if a.world_center(a_pos)[axis] >= b.world_center(b_pos)[axis] {
+        1.0
+    } else {
+        -1.0
+    };
+    Some((axis, normal, depth))
+}
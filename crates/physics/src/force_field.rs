@@ -0,0 +1,284 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use interfaces::scheduling::PhysicsLevelSet;
+use lightyear::prelude::TickManager;
+use world::{
+    atmosphere::{Calendar, Weather},
+    block::{BlockCoord, RemovedBlock},
+};
+
+use crate::{
+    collision::{self, Aabb},
+    movement::{Acceleration, Mass, Velocity},
+};
+
+//wind, updrafts and repulsor blocks
+pub struct ForceFieldPlugin;
+
+impl Plugin for ForceFieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Wind>()
+            .add_systems(
+                FixedUpdate,
+                (update_wind, apply_force_fields)
+                    .chain()
+                    .before(collision::move_and_slide)
+                    .in_set(PhysicsLevelSet::Main),
+            )
+            .register_type::<ForceField>()
+            .register_type::<RepulsorBlock>();
+    }
+}
+
+//fraction of the difference between the air's speed and a body's speed made up each tick, for a body with 1 mass
+pub const AIR_COUPLING: f32 = 0.02;
+//furthest a repulsor block can push
+pub const MAX_REPULSOR_RANGE: f32 = 4.0;
+//how fast the wind catches up to the weather
+const WIND_CHANGE_PER_TICK: f32 = 0.002;
+
+//how strongly wind and force fields push this body, on top of Mass. 0 to ignore them
+#[derive(Component, Deref, DerefMut, PartialEq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ForceFieldResponse(pub f32);
+
+impl Default for ForceFieldResponse {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+//the velocity of the air around a body this tick, updated for bodies that have it
+#[derive(Component, Deref, DerefMut, PartialEq, Clone, Copy, Debug, Default)]
+pub struct Airflow(pub Vec3);
+
+//global wind, stronger the higher up you are
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Wind {
+    //air velocity at ground_altitude in blocks per tick
+    pub velocity: Vec3,
+    //gust strength relative to velocity
+    pub gustiness: f32,
+    //no wind below this altitude minus 1/altitude_gain, full wind here
+    pub ground_altitude: f32,
+    pub altitude_gain: f32,
+    pub max_altitude_mult: f32,
+    //the networked tick, so gusts line up for everyone
+    pub tick: u16,
+}
+
+impl Default for Wind {
+    fn default() -> Self {
+        Self {
+            velocity: Vec3::ZERO,
+            gustiness: 0.0,
+            ground_altitude: 0.0,
+            altitude_gain: 0.02,
+            max_altitude_mult: 3.0,
+            tick: 0,
+        }
+    }
+}
+
+impl Wind {
+    pub fn altitude_mult(&self, altitude: f32) -> f32 {
+        (1.0 + (altitude - self.ground_altitude) * self.altitude_gain)
+            .clamp(0.0, self.max_altitude_mult)
+    }
+
+    //gusts roll across the world instead of hitting everywhere at once
+    pub fn gust_mult(&self, position: Vec3) -> f32 {
+        //a whole number of gusts per tick wraparound, so the wind doesn't jump when the tick wraps
+        const GUST_FREQUENCY: f32 = std::f32::consts::TAU * 521.0 / 65536.0;
+        const GUST_WAVELENGTH: f32 = 0.1;
+        let along = position.xz().dot(self.velocity.xz().normalize_or_zero());
+        let phase = self.tick as f32 * GUST_FREQUENCY - along * GUST_WAVELENGTH;
+        1.0 + self.gustiness * phase.sin()
+    }
+
+    pub fn at(&self, position: Vec3) -> Vec3 {
+        self.velocity * self.altitude_mult(position.y) * self.gust_mult(position)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+pub enum ForceFieldKind {
+    //air moving at the given velocity, in blocks per tick
+    Wind(Vec3),
+    //air rising at the given speed. fast enough to carry bodies with reduced gravity up
+    Updraft(f32),
+    //pushes bodies away from the center, weaker further out
+    Repulsor(f32),
+}
+
+impl Default for ForceFieldKind {
+    fn default() -> Self {
+        ForceFieldKind::Updraft(0.5)
+    }
+}
+
+//a box centered on the entity's GlobalTransform, or the block's center for placed dynamic blocks, moved by offset
+//this isn't an Aabb so the volume doesn't get in the way of raycasts and spatial queries
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct ForceField {
+    pub half_extents: Vec3,
+    //lets a block fill the air above it instead of the volume around it
+    #[reflect(default)]
+    #[serde(default)]
+    pub offset: Vec3,
+    pub kind: ForceFieldKind,
+}
+
+impl Default for ForceField {
+    fn default() -> Self {
+        Self {
+            half_extents: Vec3::splat(2.0),
+            offset: Vec3::ZERO,
+            kind: ForceFieldKind::default(),
+        }
+    }
+}
+
+impl ForceField {
+    pub fn new(half_extents: Vec3, kind: ForceFieldKind) -> Self {
+        Self {
+            half_extents,
+            offset: Vec3::ZERO,
+            kind,
+        }
+    }
+
+    pub fn contains(&self, center: Vec3, point: Vec3) -> bool {
+        (point - center).abs().cmple(self.half_extents).all()
+    }
+
+    //(air velocity, force) at point, both zero outside of the field
+    pub fn effect_at(&self, center: Vec3, point: Vec3) -> (Vec3, Vec3) {
+        if !self.contains(center, point) {
+            return (Vec3::ZERO, Vec3::ZERO);
+        }
+        match self.kind {
+            ForceFieldKind::Wind(v) => (v, Vec3::ZERO),
+            ForceFieldKind::Updraft(speed) => (Vec3::Y * speed, Vec3::ZERO),
+            ForceFieldKind::Repulsor(strength) => {
+                let offset = point - center;
+                let falloff = 1.0 - (offset / self.half_extents).abs().max_element();
+                (
+                    Vec3::ZERO,
+                    offset.normalize_or(Vec3::Y) * strength * falloff,
+                )
+            }
+        }
+    }
+}
+
+//put on a dynamic block to push entities away from it. only placed blocks push, the template doesn't
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[reflect(Component, FromWorld)]
+pub struct RepulsorBlock {
+    pub strength: f32,
+    //capped at MAX_REPULSOR_RANGE
+    pub range: f32,
+}
+
+impl Default for RepulsorBlock {
+    fn default() -> Self {
+        Self {
+            strength: 0.02,
+            range: 3.0,
+        }
+    }
+}
+
+impl RepulsorBlock {
+    pub fn force_at(&self, block_center: Vec3, point: Vec3) -> Vec3 {
+        let offset = point - block_center;
+        let range = self.range.min(MAX_REPULSOR_RANGE);
+        let dist = offset.length();
+        if dist >= range {
+            return Vec3::ZERO;
+        }
+        offset.normalize_or(Vec3::Y) * self.strength * (1.0 - dist / range)
+    }
+}
+
+//pushes the body towards the air's velocity, never slows it down if it's already moving with the air faster
+pub fn air_push(air: Vec3, v: Vec3) -> Vec3 {
+    let Some(dir) = air.try_normalize() else {
+        return Vec3::ZERO;
+    };
+    dir * (air.length() - v.dot(dir)).max(0.0) * AIR_COUPLING
+}
+
+fn update_wind(
+    mut wind: ResMut<Wind>,
+    weather: Option<Res<Weather>>,
+    calendar: Option<Res<Calendar>>,
+    tick_manager: Option<Res<TickManager>>,
+) {
+    if let Some(tick_manager) = tick_manager {
+        wind.tick = tick_manager.tick().0;
+    }
+    if let (Some(weather), Some(calendar)) = (weather, calendar) {
+        let target = weather.wind_velocity(&calendar);
+        let delta = target - wind.velocity;
+        wind.velocity += delta.clamp_length_max(WIND_CHANGE_PER_TICK);
+        wind.gustiness = wind.gustiness.lerp(weather.kind.gustiness(), 0.01);
+    }
+}
+
+pub fn apply_force_fields(
+    //children move with their parents, so only the parent gets pushed
+    mut body_query: Query<
+        (
+            &GlobalTransform,
+            &Aabb,
+            &Velocity,
+            &mut Acceleration,
+            &Mass,
+            Option<&ForceFieldResponse>,
+            Option<&mut Airflow>,
+        ),
+        Without<Parent>,
+    >,
+    //the template of a dynamic block has neither, so it doesn't make a field
+    field_query: Query<
+        (Option<&GlobalTransform>, Option<&BlockCoord>, &ForceField),
+        Without<RemovedBlock>,
+    >,
+    //placed repulsors have a BlockCoord, the template they're copied from doesn't
+    repulsor_query: Query<(&BlockCoord, &RepulsorBlock), Without<RemovedBlock>>,
+    wind: Res<Wind>,
+) {
+    let fields: Vec<(Vec3, &ForceField)> = field_query
+        .iter()
+        .filter_map(|(opt_gtf, opt_coord, field)| {
+            let origin = opt_gtf
+                .map(|gtf| gtf.translation())
+                .or(opt_coord.map(|coord| coord.center()))?;
+            Some((origin + field.offset, field))
+        })
+        .collect();
+    for (gtf, aabb, v, mut a, mass, opt_response, opt_airflow) in body_query.iter_mut() {
+        let response = opt_response.copied().unwrap_or_default();
+        if response.0 == 0.0 {
+            continue;
+        }
+        let center = aabb.world_center(gtf.translation());
+        let mut air = wind.at(center);
+        let mut force = Vec3::ZERO;
+        for (field_center, field) in fields.iter() {
+            let (field_air, field_force) = field.effect_at(*field_center, center);
+            air += field_air;
+            force += field_force;
+        }
+        for (coord, repulsor) in repulsor_query.iter() {
+            force += repulsor.force_at(coord.center(), center);
+        }
+        if let Some(mut airflow) = opt_airflow {
+            airflow.0 = air;
+        }
+        mass.add_force((air_push(air, v.0) + force) * response.0, &mut a);
+    }
+}
@@ -3,7 +3,7 @@ use bevy::prelude::*;
 use world::{block::BlockCoord, level::Level};
 
 use super::{
-    movement::{Acceleration, Mass, Velocity},
+    constraint::{Constraint, ConstraintAnchor},
     query::{raycast, Raycast},
 };
 use interfaces::{components::UseHand, scheduling::*};
@@ -16,8 +16,8 @@ impl Plugin for GrapplePlugin {
         app.add_systems(Startup, init)
             .add_systems(
                 FixedUpdate,
-                (update_grapple, update_force)
-                    .chain()
+                update_grapple
+                    .before(crate::constraint::solve_constraints)
                     .in_set(PhysicsLevelSet::Main),
             )
             .add_systems(
@@ -27,7 +27,7 @@ impl Plugin for GrapplePlugin {
                     .after(ItemSystemSet::UsageProcessing),
             )
             .add_systems(Update, update_visual.in_set(LevelSystemSet::Main))
-            .add_event::<UpdateAccelEvent>()
+            .add_observer(remove_grapple)
             .add_event::<ShootGrappleEvent>();
     }
 }
@@ -47,12 +47,18 @@ pub enum GrappleTarget {
 #[derive(Component)]
 pub struct Grappled {
     target: GrappleTarget,
-    strength: f32, //ignores mass
-    max_speed: f32,
+    constraint: Entity,
     remove_distance: Option<f32>,
     visual: Entity,
 }
 
+impl Grappled {
+    //the reel constraint pulling the grappled entity
+    pub fn constraint(&self) -> Entity {
+        self.constraint
+    }
+}
+
 #[derive(Component)]
 struct GrappleVisual {
     user: Entity,
@@ -70,14 +76,11 @@ struct VisualResources {
 pub struct ShootGrappleEvent {
     pub ray: Raycast,
     pub owner: Entity,
-    pub strength: f32,
+    pub strength: f32, //ignores mass
     pub max_speed: f32,
     pub remove_distance: Option<f32>,
 }
 
-#[derive(Event, Clone, Copy)]
-struct UpdateAccelEvent(Entity, Vec3);
-
 fn init(
     mut commands: Commands,
     mut materials: ResMut<Assets<StandardMaterial>>,
@@ -111,159 +114,112 @@ fn shoot_grapple(
         let Some(hit) = raycast(*ray, &level, &physics_query, &object_query, &[*owner]) else {
             continue;
         };
+        let (target, anchor) = match hit {
+            super::query::RaycastHit::Block(coord, pos) => (
+                GrappleTarget::Block {
+                    block_coord: coord,
+                    anchor_pos: pos.hit_pos,
+                },
+                ConstraintAnchor::Point(pos.hit_pos),
+            ),
+            super::query::RaycastHit::Object(hit) => {
+                let Ok((_, gtf, _)) = object_query.get(hit.entity) else {
+                    continue;
+                };
+                let anchor_offset = gtf.affine().inverse().transform_point3(hit.hit_pos);
+                (
+                    GrappleTarget::Entity {
+                        target: hit.entity,
+                        anchor_offset,
+                    },
+                    ConstraintAnchor::Entity {
+                        entity: hit.entity,
+                        offset: anchor_offset,
+                    },
+                )
+            }
+        };
         let visual = GrappleVisual {
             visual_origin: hand_query.get(*owner).map_or(*owner, |hand| hand.hand),
             user: *owner,
             width: 0.1,
         };
         let visual_entity = commands.spawn((StateScoped(GameState::Game), visual)).id();
+        let constraint = commands
+            .spawn((
+                StateScoped(GameState::Game),
+                Constraint::reel(
+                    ConstraintAnchor::entity(*owner),
+                    anchor,
+                    *strength,
+                    *max_speed,
+                ),
+            ))
+            .id();
         let Some(mut ec) = commands.get_entity(*owner) else {
             continue;
         };
-        match hit {
-            super::query::RaycastHit::Block(coord, pos) => {
-                ec.try_insert(Grappled {
-                    target: GrappleTarget::Block {
-                        block_coord: coord,
-                        anchor_pos: pos.hit_pos,
-                    },
-                    strength: *strength,
-                    remove_distance: *remove_distance,
-                    max_speed: *max_speed,
-                    visual: visual_entity,
-                });
-            }
-            super::query::RaycastHit::Object(hit) => {
-                let Ok((_, gtf, _)) = object_query.get(hit.entity) else {
-                    continue;
-                };
-                ec.try_insert(Grappled {
-                    target: GrappleTarget::Entity {
-                        target: hit.entity,
-                        anchor_offset: gtf.affine().inverse().transform_point3(hit.hit_pos),
-                    },
-                    strength: *strength,
-                    remove_distance: *remove_distance,
-                    max_speed: *max_speed,
-                    visual: visual_entity,
-                });
-            }
-        }
+        ec.try_insert(Grappled {
+            target,
+            constraint,
+            remove_distance: *remove_distance,
+            visual: visual_entity,
+        });
     }
 }
 
-//couldn't figure out paramset here, since there's two queries that need read access at the same time
+//the pull itself is a reel constraint, this removes it once the anchor is gone or the user is close enough
 fn update_grapple(
-    grapple_query: Query<(
-        Entity,
-        &Grappled,
-        &GlobalTransform,
-        &Velocity,
-        Option<&Mass>,
-    )>,
-    hit_entity_query: Query<(&GlobalTransform, Option<&Mass>)>,
+    grapple_query: Query<(Entity, &Grappled, &GlobalTransform)>,
+    hit_entity_query: Query<&GlobalTransform>,
+    constraint_query: Query<(), With<Constraint>>,
     level: Res<Level>,
-    mut writer: EventWriter<UpdateAccelEvent>,
     mut commands: Commands,
 ) {
-    for (entity, grapple, gtf, v, mass_opt) in grapple_query.iter() {
-        match grapple.target {
+    for (entity, grapple, gtf) in grapple_query.iter() {
+        let anchor_pos = match grapple.target {
             GrappleTarget::Block {
                 block_coord,
                 anchor_pos,
-            } => {
-                if level.get_block_entity(block_coord).is_none()
-                    || grapple
-                        .remove_distance
-                        .map(|limit| gtf.translation().distance(anchor_pos) <= limit)
-                        .unwrap_or(false)
-                {
-                    //block removed or too close
-                    if let Some(mut ec) = commands.get_entity(entity) {
-                        ec.remove::<Grappled>();
-                    }
-                    continue;
-                }
-                let a = grapple_force(
-                    gtf.translation(),
-                    anchor_pos,
-                    grapple.strength,
-                    v.0,
-                    grapple.max_speed,
-                );
-                writer.send(UpdateAccelEvent(entity, a));
-            }
-            GrappleTarget::Entity {
-                target,
-                anchor_offset,
-            } => {
-                let Ok((target_gtf, target_mass_opt)) = hit_entity_query.get(target) else {
-                    //entity removed
-                    if let Some(mut ec) = commands.get_entity(entity) {
-                        ec.remove::<Grappled>();
-                    }
-                    if let Some(mut ec) = commands.get_entity(grapple.visual) {
-                        ec.despawn();
-                    }
-                    continue;
-                };
-                if grapple
-                    .remove_distance
-                    .map(|limit| gtf.translation().distance(target_gtf.translation()) <= limit)
-                    .unwrap_or(false)
-                {
-                    //too close
-                    if let Some(mut ec) = commands.get_entity(entity) {
-                        ec.remove::<Grappled>();
-                    }
-                    if let Some(mut ec) = commands.get_entity(grapple.visual) {
-                        ec.despawn();
-                    }
-                    continue;
-                }
-                let f = grapple_force(
-                    gtf.translation(),
-                    target_gtf.transform_point(anchor_offset),
-                    grapple.strength,
-                    v.0,
-                    grapple.max_speed,
-                );
-                let mass_ratio = Mass(
-                    mass_opt.copied().unwrap_or_default().0
-                        / target_mass_opt.copied().unwrap_or_default().0,
-                );
-                let target_a = mass_ratio.get_force(-f * mass_ratio.0);
-                writer.send(UpdateAccelEvent(entity, f));
-                writer.send(UpdateAccelEvent(target, target_a));
+            } => level
+                .get_block_entity(block_coord)
+                .is_some()
+                .then_some(anchor_pos),
+            GrappleTarget::Entity { target, .. } => hit_entity_query
+                .get(target)
+                .ok()
+                .map(|target_gtf| target_gtf.translation()),
+        };
+        //anchor removed, too close, or the constraint broke
+        if anchor_pos.is_none_or(|anchor_pos| {
+            grapple
+                .remove_distance
+                .is_some_and(|limit| gtf.translation().distance(anchor_pos) <= limit)
+        }) || !constraint_query.contains(grapple.constraint)
+        {
+            if let Some(mut ec) = commands.get_entity(entity) {
+                ec.remove::<Grappled>();
             }
         }
     }
 }
 
-fn update_force(mut reader: EventReader<UpdateAccelEvent>, mut query: Query<&mut Acceleration>) {
-    for UpdateAccelEvent(e, da) in reader.read() {
-        if let Ok(mut a) = query.get_mut(*e) {
-            a.0 += *da;
+//however the grapple is released or replaced, its constraint and visual go with it
+fn remove_grapple(
+    trigger: Trigger<OnReplace, Grappled>,
+    query: Query<&Grappled>,
+    mut commands: Commands,
+) {
+    let Ok(grapple) = query.get(trigger.entity()) else {
+        return;
+    };
+    for entity in [grapple.constraint, grapple.visual] {
+        if let Some(mut ec) = commands.get_entity(entity) {
+            ec.despawn();
         }
     }
 }
 
-fn grapple_force(
-    pos: Vec3,
-    anchor_pos: Vec3,
-    strength: f32,
-    current_v: Vec3,
-    max_speed: f32,
-) -> Vec3 {
-    let line = (anchor_pos - pos).normalize_or_zero();
-    let v_in_line = current_v.project_onto_normalized(line);
-    if !v_in_line.is_finite() || v_in_line.length_squared() > max_speed * max_speed {
-        Vec3::ZERO
-    } else {
-        line * strength
-    }
-}
-
 fn update_visual(
     uninit_query: Query<(Entity, &GrappleVisual), Without<Transform>>,
     mut init_query: Query<(&mut Transform, &GrappleVisual)>,
@@ -7,12 +7,16 @@ use world::block::BlockCoord;
 
 use self::{collision::IgnoreTerrainCollision, movement::Drag};
 
+pub mod ccd;
 pub mod collision;
+pub mod constraint;
+pub mod entity_collision;
+pub mod force_field;
 pub mod grapple;
 pub mod interpolation;
 pub mod movement;
 pub mod query;
-pub mod spring;
+pub mod spatial;
 mod test;
 
 const SPAWN_CHUNK_TIME_BUDGET_COUNT: u32 = 1000;
@@ -27,9 +31,12 @@ impl Plugin for PhysicsPlugin {
         app.add_plugins((
             movement::MovementPlugin,
             collision::CollisionPlugin,
+            entity_collision::EntityCollisionPlugin,
             grapple::GrapplePlugin,
-            spring::SpringPlugin,
+            constraint::ConstraintPlugin,
+            force_field::ForceFieldPlugin,
             interpolation::InterpolationPlugin,
+            spatial::SpatialIndexPlugin,
         ))
         .insert_resource(Time::<Fixed>::from_hz(TPS))
         .insert_resource(DebugBlockHitboxes::default());
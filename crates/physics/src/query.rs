@@ -49,12 +49,8 @@ pub fn raycast(
         let test_block_coord = BlockCoord::from(test_point);
         if let Some(block_entity) = level.get_block_entity(test_block_coord) {
             if !exclude.contains(&block_entity) {
-                if let Some(collider) = physics_query
-                    .get(block_entity)
-                    .ok()
-                    .and_then(Aabb::from_block)
-                {
-                    if collider.intersects_point(test_block_coord.to_vec3(), test_point) {
+                if let Ok(physics) = physics_query.get(block_entity) {
+                    if physics.intersects_point(test_block_coord.to_vec3(), test_point) {
                         //our point intersects the block
                         return Some(RaycastHit::Block(
                             test_block_coord,
@@ -0,0 +1,237 @@
+use ahash::HashMap;
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use interfaces::scheduling::PhysicsSystemSet;
+use util::direction::Direction;
+
+use crate::{
+    collision::Aabb,
+    query::{RayCastHitEntity, Raycast},
+};
+
+//broad phase for entity queries, so finding what's near something doesn't scan every entity
+pub struct SpatialIndexPlugin;
+
+impl Plugin for SpatialIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialIndex>().add_systems(
+            FixedUpdate,
+            rebuild_spatial_index
+                .in_set(PhysicsSystemSet::UpdateDerivatives)
+                .after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+//side length of a grid cell, in blocks
+pub const CELL_SIZE: f32 = 8.0;
+//indexed bounds are grown by this much so entities that moved since the last rebuild are still found
+pub const INDEX_MARGIN: f32 = 1.0;
+
+//hash grid of every entity with a collider, rebuilt each fixed tick
+//entities spawned since the last rebuild aren't in it yet
+#[derive(Resource, Default)]
+pub struct SpatialIndex {
+    cells: HashMap<IVec3, Vec<Entity>>,
+    //world space (min, max) when indexed, including the margin
+    bounds: HashMap<Entity, (Vec3, Vec3)>,
+}
+
+fn cell_of(point: Vec3) -> IVec3 {
+    (point / CELL_SIZE).floor().as_ivec3()
+}
+
+fn cells_between(min: IVec3, max: IVec3) -> impl Iterator<Item = IVec3> {
+    (min.x..=max.x).flat_map(move |x| {
+        (min.y..=max.y).flat_map(move |y| (min.z..=max.z).map(move |z| IVec3::new(x, y, z)))
+    })
+}
+
+fn overlaps(a_min: Vec3, a_max: Vec3, b_min: Vec3, b_max: Vec3) -> bool {
+    a_min.cmple(b_max).all() && b_min.cmple(a_max).all()
+}
+
+//time of impact in [0, 1] of the segment start..start+delta with the box. 0 if it starts inside
+pub fn ray_box_toi(start: Vec3, delta: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    let mut t_near = 0.0f32;
+    let mut t_far = 1.0f32;
+    for axis in 0..3 {
+        if delta[axis] == 0.0 {
+            if start[axis] < min[axis] || start[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let a = (min[axis] - start[axis]) / delta[axis];
+        let b = (max[axis] - start[axis]) / delta[axis];
+        t_near = t_near.max(a.min(b));
+        t_far = t_far.min(a.max(b));
+        if t_near > t_far {
+            return None;
+        }
+    }
+    Some(t_near)
+}
+
+impl SpatialIndex {
+    pub fn clear(&mut self) {
+        //cells that stayed empty for a whole rebuild are dropped, the rest keep their allocation
+        self.cells.retain(|_, entities| {
+            let keep = !entities.is_empty();
+            entities.clear();
+            keep
+        });
+        self.bounds.clear();
+    }
+
+    pub fn insert(&mut self, entity: Entity, min: Vec3, max: Vec3) {
+        self.remove(entity);
+        let min = min - Vec3::splat(INDEX_MARGIN);
+        let max = max + Vec3::splat(INDEX_MARGIN);
+        for cell in cells_between(cell_of(min), cell_of(max)) {
+            self.cells.entry(cell).or_default().push(entity);
+        }
+        self.bounds.insert(entity, (min, max));
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        let Some((min, max)) = self.bounds.remove(&entity) else {
+            return;
+        };
+        for cell in cells_between(cell_of(min), cell_of(max)) {
+            if let Some(entities) = self.cells.get_mut(&cell) {
+                entities.retain(|e| *e != entity);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    //entities whose indexed bounds overlap the box. each entity is returned once
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> impl Iterator<Item = Entity> + '_ {
+        let min_cell = cell_of(min);
+        cells_between(min_cell, cell_of(max)).flat_map(move |cell| {
+            self.cells
+                .get(&cell)
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(move |entity| {
+                    let (entity_min, entity_max) = self.bounds[entity];
+                    //entities spanning several cells are only reported from the first cell shared with the query
+                    cell == min_cell.max(cell_of(entity_min))
+                        && overlaps(entity_min, entity_max, min, max)
+                })
+        })
+    }
+
+    pub fn query_radius(&self, center: Vec3, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        self.query_aabb(center - Vec3::splat(radius), center + Vec3::splat(radius))
+            .filter(move |entity| {
+                let (min, max) = self.bounds[entity];
+                center.clamp(min, max).distance_squared(center) <= radius * radius
+            })
+    }
+
+    //entities whose indexed bounds the ray passes through, in no particular order
+    pub fn query_ray(&self, ray: Raycast) -> impl Iterator<Item = Entity> + '_ {
+        let delta = ray.direction * ray.length;
+        let end = ray.origin + delta;
+        self.query_aabb(ray.origin.min(end), ray.origin.max(end))
+            .filter(move |entity| {
+                let (min, max) = self.bounds[entity];
+                ray_box_toi(ray.origin, delta, min, max).is_some()
+            })
+    }
+}
+
+fn rebuild_spatial_index(
+    mut index: ResMut<SpatialIndex>,
+    query: Query<(Entity, &GlobalTransform, &Aabb)>,
+) {
+    index.clear();
+    for (entity, gtf, aabb) in query.iter() {
+        let pos = gtf.translation();
+        //also covers the position itself so queries by position find the entity
+        index.insert(
+            entity,
+            aabb.world_min(pos).min(pos),
+            aabb.world_max(pos).max(pos),
+        );
+    }
+}
+
+//exact entity queries against current transforms, using the index to find candidates
+#[derive(SystemParam)]
+pub struct SpatialQuery<'w, 's> {
+    pub index: Res<'w, SpatialIndex>,
+    pub objects: Query<'w, 's, (Entity, &'static GlobalTransform, &'static Aabb)>,
+}
+
+impl SpatialQuery<'_, '_> {
+    //entities with a collider touching the sphere
+    pub fn in_radius(&self, center: Vec3, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        self.index
+            .query_radius(center, radius)
+            .filter(move |entity| {
+                self.objects.get(*entity).is_ok_and(|(_, gtf, aabb)| {
+                    let pos = gtf.translation();
+                    center
+                        .clamp(aabb.world_min(pos), aabb.world_max(pos))
+                        .distance_squared(center)
+                        <= radius * radius
+                })
+            })
+    }
+
+    //entities with a collider intersecting aabb placed at pos
+    pub fn in_aabb(&self, pos: Vec3, aabb: Aabb) -> impl Iterator<Item = Entity> + '_ {
+        self.index
+            .query_aabb(aabb.world_min(pos), aabb.world_max(pos))
+            .filter(move |entity| {
+                self.objects.get(*entity).is_ok_and(|(_, gtf, other)| {
+                    aabb.intersects_aabb(pos, *other, gtf.translation())
+                })
+            })
+    }
+
+    //entities with a collider containing the point
+    pub fn at_point(&self, point: Vec3) -> impl Iterator<Item = Entity> + '_ {
+        self.index.query_aabb(point, point).filter(move |entity| {
+            self.objects
+                .get(*entity)
+                .is_ok_and(|(_, gtf, aabb)| aabb.intersects_point(gtf.translation(), point))
+        })
+    }
+
+    //closest entity hit by the ray. only entities, blocks are ignored
+    pub fn raycast(&self, ray: Raycast, exclude: &[Entity]) -> Option<RayCastHitEntity> {
+        let delta = ray.direction * ray.length;
+        let (entity, toi, center, size) = self
+            .index
+            .query_ray(ray)
+            .filter(|entity| !exclude.contains(entity))
+            .filter_map(|entity| {
+                let (_, gtf, aabb) = self.objects.get(entity).ok()?;
+                let pos = gtf.translation();
+                let toi = ray_box_toi(ray.origin, delta, aabb.world_min(pos), aabb.world_max(pos))?;
+                Some((entity, toi, aabb.world_center(pos), aabb.size))
+            })
+            .min_by(|(_, a, ..), (_, b, ..)| a.total_cmp(b))?;
+        let hit_pos = ray.origin + delta * toi;
+        Some(RayCastHitEntity {
+            hit_pos,
+            //the face closest to the hit point, relative to the box's size
+            normal: Direction::from(util::max_component_norm(
+                (hit_pos - center) / size.max(Vec3::splat(f32::EPSILON)),
+            )),
+            entity,
+        })
+    }
+}
@@ -0,0 +1,46 @@
+#[allow(unused_imports)]
+mod sweep {
+    use crate::{ccd::sweep_entities, collision::Aabb, spatial::SpatialIndex};
+    use bevy::prelude::*;
+    use util::direction::Direction;
+
+    fn index_with(entities: &[(Entity, Vec3, Aabb)]) -> SpatialIndex {
+        let mut index = SpatialIndex::default();
+        for (entity, pos, aabb) in entities {
+            index.insert(*entity, aabb.world_min(*pos), aabb.world_max(*pos));
+        }
+        index
+    }
+
+    #[test]
+    fn test_sweep_hits_first_thin_entity() {
+        let thin = Aabb::centered(Vec3::new(0.1, 2.0, 2.0));
+        let near = Entity::from_raw(0);
+        let far = Entity::from_raw(1);
+        let entities = [
+            (near, Vec3::new(5.0, 0.0, 0.0), thin),
+            (far, Vec3::new(10.0, 0.0, 0.0), thin),
+        ];
+        let index = index_with(&entities);
+        let lookup = |entity: Entity| {
+            entities
+                .iter()
+                .find(|(e, ..)| *e == entity)
+                .map(|(_, pos, aabb)| (*pos, *aabb))
+        };
+        let bullet = Aabb::centered(Vec3::splat(0.2));
+        //both ends of the path are clear of the entities
+        let hit = sweep_entities(bullet, Vec3::ZERO, Vec3::X * 20.0, &index, lookup).unwrap();
+        assert_eq!(hit.entity, near);
+        assert_eq!(hit.normal, Direction::NegX);
+        assert!((hit.position.x - 4.85).abs() < 1e-4);
+        //filtered out entities are passed through
+        let hit = sweep_entities(bullet, Vec3::ZERO, Vec3::X * 20.0, &index, |entity| {
+            lookup(entity).filter(|_| entity != near)
+        })
+        .unwrap();
+        assert_eq!(hit.entity, far);
+        //too short to reach either
+        assert!(sweep_entities(bullet, Vec3::ZERO, Vec3::X * 4.0, &index, lookup).is_none());
+    }
+}
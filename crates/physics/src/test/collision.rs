@@ -51,3 +51,151 @@ mod aabb {
         assert_eq!(volume.min_corner, IVec3::new(0, 0, 0));
     }
 }
+
+#[allow(unused_imports)]
+mod block_physics {
+    use crate::collision::{Aabb, BlockOrientation, BlockPhysics};
+    use bevy::prelude::*;
+
+    //lower half plus the back quarter of the upper half
+    fn stairs() -> BlockPhysics {
+        turned_stairs(0)
+    }
+
+    fn turned_stairs(turns: u8) -> BlockPhysics {
+        BlockPhysics::Compound(
+            vec![
+                Aabb::new(Vec3::new(1.0, 0.5, 1.0), Vec3::ZERO),
+                Aabb::new(Vec3::new(1.0, 0.5, 0.5), Vec3::new(0.0, 0.5, 0.5)),
+            ],
+            BlockOrientation(turns),
+        )
+    }
+
+    #[test]
+    fn test_compound_intersects_point() {
+        let stairs = stairs();
+        assert!(stairs.intersects_point(Vec3::ZERO, Vec3::new(0.5, 0.25, 0.25)));
+        assert!(stairs.intersects_point(Vec3::ZERO, Vec3::new(0.5, 0.75, 0.75)));
+        assert!(!stairs.intersects_point(Vec3::ZERO, Vec3::new(0.5, 0.75, 0.25)));
+    }
+
+    #[test]
+    fn test_from_block_is_bounding_box() {
+        assert_eq!(
+            Aabb::from_block(&stairs()),
+            Some(Aabb::new(Vec3::ONE, Vec3::ZERO))
+        );
+        assert_eq!(Aabb::from_block(&BlockPhysics::Empty), None);
+        assert_eq!(Aabb::from_block(&BlockPhysics::FromMesh), None);
+    }
+
+    #[test]
+    fn test_compound_is_rotated() {
+        //the upper step moves from the back (+z) to +x, then to the front, then to -x
+        let upper = [
+            Vec3::new(0.5, 0.75, 0.9),
+            Vec3::new(0.9, 0.75, 0.5),
+            Vec3::new(0.5, 0.75, 0.1),
+            Vec3::new(0.1, 0.75, 0.5),
+        ];
+        for turns in 0..4 {
+            let stairs = turned_stairs(turns);
+            let step = upper[turns as usize];
+            let opposite = upper[(turns as usize + 2) % 4];
+            assert!(stairs.intersects_point(Vec3::ZERO, step), "{} turns", turns);
+            assert!(
+                !stairs.intersects_point(Vec3::ZERO, opposite),
+                "{} turns",
+                turns
+            );
+            //the lower half and the bounding box don't change
+            assert!(stairs.intersects_point(Vec3::ZERO, Vec3::new(0.1, 0.25, 0.1)));
+            assert_eq!(
+                Aabb::from_block(&stairs),
+                Some(Aabb::new(Vec3::ONE, Vec3::ZERO))
+            );
+        }
+        assert_eq!(
+            turned_stairs(4).parts().collect::<Vec<_>>(),
+            stairs().parts().collect::<Vec<_>>()
+        );
+    }
+}
+
+#[allow(unused_imports)]
+mod step_up {
+    use std::sync::Arc;
+
+    use bevy::prelude::*;
+    use interfaces::testing::{start_level, test_app};
+    use world::{
+        block::BlockType,
+        chunk::{ArrayChunk, ChunkCoord, ChunkIdx, ChunkTrait, ChunkType, CHUNK_SIZE_U8},
+        level::{Level, LevelData},
+    };
+
+    use crate::{
+        collision::{Aabb, BlockPhysics, CollisionPlugin, Friction, StepUp},
+        movement::{MovementPlugin, Velocity},
+        DebugBlockHitboxes, PhysicsBundle,
+    };
+
+    //walks a body along +x over a floor into a block with the given collider, returns where it ends up
+    fn walk_into(obstacle: BlockPhysics) -> Vec3 {
+        let mut app = test_app();
+        app.add_plugins((TransformPlugin, MovementPlugin, CollisionPlugin))
+            .init_resource::<DebugBlockHitboxes>();
+        let floor = app
+            .world_mut()
+            .spawn((BlockPhysics::Solid, Friction::default()))
+            .id();
+        let obstacle = app.world_mut().spawn((obstacle, Friction::default())).id();
+        let position = ChunkCoord::new(0, 0, 0);
+        let mut chunk = ArrayChunk::new(position, Entity::PLACEHOLDER);
+        for i in 0..CHUNK_SIZE_U8 {
+            for k in 0..CHUNK_SIZE_U8 {
+                chunk.set_block(ChunkIdx::new(i, 0, k).into(), BlockType::Filled(floor));
+            }
+        }
+        chunk.set_block(ChunkIdx::new(4, 1, 1).into(), BlockType::Filled(obstacle));
+        let level = Level(Arc::new(LevelData::new("step_up", 0)));
+        level.add_chunk(position, ChunkType::Full(chunk));
+        app.insert_resource(level);
+        let body = app
+            .world_mut()
+            .spawn((
+                Transform::from_translation(Vec3::new(2.5, 1.5, 1.5)),
+                PhysicsBundle {
+                    collider: Aabb::centered(Vec3::new(0.8, 1.0, 0.8)),
+                    ..default()
+                },
+                StepUp::default(),
+            ))
+            .id();
+        start_level(&mut app);
+        for _ in 0..100 {
+            app.world_mut().get_mut::<Velocity>(body).unwrap().0.x = 0.1;
+            app.world_mut().run_schedule(FixedUpdate);
+        }
+        app.world().get::<Transform>(body).unwrap().translation
+    }
+
+    #[test]
+    fn test_steps_onto_slab() {
+        let end = walk_into(BlockPhysics::Aabb(Aabb::new(
+            Vec3::new(1.0, 0.5, 1.0),
+            Vec3::ZERO,
+        )));
+        //standing on top of it
+        assert!(end.x > 4.0, "stopped at {}", end);
+        assert!(end.y > 1.9, "didn't climb, at {}", end);
+    }
+
+    #[test]
+    fn test_doesnt_step_onto_full_block() {
+        let end = walk_into(BlockPhysics::Solid);
+        assert!(end.x < 3.7, "walked to {}", end);
+        assert!(end.y < 2.0, "climbed to {}", end);
+    }
+}
@@ -0,0 +1,63 @@
+#[allow(unused_imports)]
+mod constraint {
+    use bevy::prelude::*;
+
+    use crate::constraint::{length_correction, Constraint, ConstraintAnchor, ConstraintKind};
+
+    #[test]
+    fn test_length_correction_stretched() {
+        //equal masses 3 apart, held to 2
+        let (a_dv, b_dv) =
+            length_correction(Vec3::ZERO, 1.0, Vec3::X * 3.0, 1.0, 2.0, 2.0, 1.0).unwrap();
+        assert_eq!(a_dv, Vec3::X * 0.5);
+        assert_eq!(b_dv, Vec3::NEG_X * 0.5);
+        //an immovable b leaves all the correction to a
+        let (a_dv, b_dv) =
+            length_correction(Vec3::ZERO, 1.0, Vec3::X * 3.0, 0.0, 2.0, 2.0, 1.0).unwrap();
+        assert_eq!(a_dv, Vec3::X);
+        assert_eq!(b_dv, Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_length_correction_rope_is_slack() {
+        //ropes only pull
+        assert!(length_correction(Vec3::ZERO, 1.0, Vec3::Y, 1.0, 0.0, 2.0, 1.0).is_none());
+        //rigid distances push too
+        let (a_dv, _) = length_correction(Vec3::ZERO, 1.0, Vec3::Y, 0.0, 2.0, 2.0, 1.0).unwrap();
+        assert_eq!(a_dv, Vec3::NEG_Y);
+        //nothing can move
+        assert!(length_correction(Vec3::ZERO, 0.0, Vec3::Y * 5.0, 0.0, 0.0, 2.0, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_length_correction_lighter_moves_further() {
+        //a weighs 3 times what b does
+        let (a_dv, b_dv) =
+            length_correction(Vec3::ZERO, 1.0 / 3.0, Vec3::Z * 2.0, 1.0, 1.0, 1.0, 1.0).unwrap();
+        assert!((a_dv.z - 0.25).abs() < 1e-5);
+        assert!((b_dv.z + 0.75).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_chain() {
+        let anchors: Vec<_> = (0..4)
+            .map(|i| ConstraintAnchor::Point(Vec3::X * i as f32))
+            .collect();
+        let links = Constraint::chain(&anchors, 1.0, None);
+        assert_eq!(links.len(), 3);
+        //each anchor follows the one before it
+        assert_eq!(links[0].a, anchors[1]);
+        assert_eq!(links[0].b, anchors[0]);
+        assert_eq!(links[2].kind, ConstraintKind::Length { min: 1.0, max: 1.0 });
+        let hinged = Constraint::chain(&anchors, 1.0, Some(std::f32::consts::FRAC_PI_2));
+        assert_eq!(hinged.len(), 5);
+        let ConstraintKind::Length { min, max } = hinged[3].kind else {
+            panic!("bend limits are length constraints");
+        };
+        //links at right angles are sqrt(2) apart at the ends
+        assert!((min - std::f32::consts::SQRT_2).abs() < 1e-5);
+        assert_eq!(max, f32::INFINITY);
+        assert_eq!(hinged[3].a, anchors[2]);
+        assert_eq!(hinged[3].b, anchors[0]);
+    }
+}
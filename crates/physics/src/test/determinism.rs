@@ -0,0 +1,244 @@
+#[allow(unused_imports)]
+mod determinism {
+    use std::sync::Arc;
+
+    use bevy::prelude::*;
+    use interfaces::testing::{start_level, test_app};
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+    use world::{
+        block::BlockType,
+        chunk::{ArrayChunk, ChunkCoord, ChunkIdx, ChunkTrait, ChunkType, CHUNK_SIZE_U8},
+        level::{Level, LevelData},
+    };
+
+    use crate::{
+        collision::{Aabb, BlockPhysics, CollisionPlugin, Friction, StepUp},
+        constraint::{Constraint, ConstraintAnchor, ConstraintPlugin},
+        entity_collision::{CollisionLayers, EntityCollisionPlugin},
+        movement::{Acceleration, Mass, MovementPlugin, Velocity},
+        spatial::SpatialIndexPlugin,
+        DebugBlockHitboxes, PhysicsBundle,
+    };
+
+    const TICKS: usize = 256;
+    const ROLLBACK_TICK: usize = 100;
+    const BODIES: usize = 6;
+
+    //(translation, velocity, acceleration) of each body
+    type Snapshot = Vec<(Vec3, Vec3, Vec3)>;
+
+    //acceleration a controller would add to each body each tick, with the odd jump
+    fn record_inputs() -> Vec<[Vec3; BODIES]> {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..TICKS)
+            .map(|_| {
+                std::array::from_fn(|_| {
+                    let jump = if rng.gen_ratio(1, 32) { 0.2 } else { 0.0 };
+                    Vec3::new(rng.gen_range(-0.02..0.02), jump, rng.gen_range(-0.02..0.02))
+                })
+            })
+            .collect()
+    }
+
+    //physics on a flat floor, nothing spawned on it yet
+    fn floor_app() -> App {
+        let mut app = test_app();
+        app.add_plugins((
+            TransformPlugin,
+            MovementPlugin,
+            CollisionPlugin,
+            EntityCollisionPlugin,
+            SpatialIndexPlugin,
+            ConstraintPlugin,
+        ))
+        .init_resource::<DebugBlockHitboxes>();
+
+        let floor = app
+            .world_mut()
+            .spawn((BlockPhysics::Solid, Friction::default()))
+            .id();
+        let level = Level(Arc::new(LevelData::new("determinism", 0)));
+        for x in -1..=1 {
+            for z in -1..=1 {
+                let position = ChunkCoord::new(x, -1, z);
+                let mut chunk = ArrayChunk::new(position, Entity::PLACEHOLDER);
+                for i in 0..CHUNK_SIZE_U8 {
+                    for k in 0..CHUNK_SIZE_U8 {
+                        let idx = ChunkIdx::new(i, CHUNK_SIZE_U8 - 1, k);
+                        chunk.set_block(idx.into(), BlockType::Filled(floor));
+                    }
+                }
+                level.add_chunk(position, ChunkType::Full(chunk));
+            }
+        }
+        app.insert_resource(level);
+        app
+    }
+
+    //bodies packed closely enough to push each other around
+    //bodies are spawned in spawn_order, but returned in body order
+    fn physics_app(spawn_order: &[usize]) -> (App, Vec<Entity>) {
+        let mut app = floor_app();
+        let mut bodies = vec![Entity::PLACEHOLDER; BODIES];
+        for &i in spawn_order {
+            bodies[i] = app
+                .world_mut()
+                .spawn((
+                    Transform::from_translation(Vec3::new(i as f32 * 0.7, 0.5, 0.0)),
+                    PhysicsBundle {
+                        collider: Aabb::centered(Vec3::new(0.8, 1.0, 0.8)),
+                        mass: Mass(1.0 + i as f32),
+                        ..default()
+                    },
+                    CollisionLayers::ENEMY,
+                    StepUp::default(),
+                ))
+                .id();
+        }
+        start_level(&mut app);
+        (app, bodies)
+    }
+
+    //a hinged chain hanging from a point above the floor, swinging down from horizontal
+    //links and bodies are both spawned in spawn_order, bodies are returned in chain order
+    fn chain_app(spawn_order: &[usize]) -> (App, Vec<Entity>) {
+        const LINK_LENGTH: f32 = 0.6;
+        let mut app = floor_app();
+        let mut bodies = vec![Entity::PLACEHOLDER; BODIES];
+        for &i in spawn_order {
+            bodies[i] = app
+                .world_mut()
+                .spawn((
+                    Transform::from_translation(Vec3::new((i + 1) as f32 * LINK_LENGTH, 6.0, 0.0)),
+                    PhysicsBundle {
+                        collider: Aabb::centered(Vec3::splat(0.2)),
+                        mass: Mass(1.0 + i as f32),
+                        ..default()
+                    },
+                ))
+                .id();
+        }
+        let anchors: Vec<_> = std::iter::once(ConstraintAnchor::Point(Vec3::Y * 6.0))
+            .chain(bodies.iter().map(|body| ConstraintAnchor::entity(*body)))
+            .collect();
+        let links = Constraint::chain(&anchors, LINK_LENGTH, Some(std::f32::consts::FRAC_PI_2));
+        let mut link_order: Vec<usize> = (0..links.len()).collect();
+        link_order.shuffle(&mut StdRng::seed_from_u64(spawn_order[0] as u64));
+        for i in link_order {
+            app.world_mut().spawn(links[i]);
+        }
+        start_level(&mut app);
+        (app, bodies)
+    }
+
+    fn tick(app: &mut App, bodies: &[Entity], inputs: &[Vec3; BODIES]) {
+        for (body, input) in bodies.iter().zip(inputs) {
+            app.world_mut().get_mut::<Acceleration>(*body).unwrap().0 += *input;
+        }
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+
+    fn snapshot(app: &App, bodies: &[Entity]) -> Snapshot {
+        bodies
+            .iter()
+            .map(|body| {
+                let world = app.world();
+                (
+                    world.get::<Transform>(*body).unwrap().translation,
+                    world.get::<Velocity>(*body).unwrap().0,
+                    world.get::<Acceleration>(*body).unwrap().0,
+                )
+            })
+            .collect()
+    }
+
+    //what a rollback restores, everything else has to be derived from it
+    fn restore(app: &mut App, bodies: &[Entity], snapshot: &Snapshot) {
+        for (body, (translation, v, a)) in bodies.iter().zip(snapshot) {
+            let mut entity = app.world_mut().entity_mut(*body);
+            entity.get_mut::<Transform>().unwrap().translation = *translation;
+            entity.get_mut::<Velocity>().unwrap().0 = *v;
+            entity.get_mut::<Acceleration>().unwrap().0 = *a;
+        }
+    }
+
+    fn in_order() -> Vec<usize> {
+        (0..BODIES).collect()
+    }
+
+    fn replay(inputs: &[[Vec3; BODIES]], spawn_order: &[usize]) -> Vec<Snapshot> {
+        let (mut app, bodies) = physics_app(spawn_order);
+        inputs
+            .iter()
+            .map(|tick_inputs| {
+                tick(&mut app, &bodies, tick_inputs);
+                snapshot(&app, &bodies)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replay_is_deterministic() {
+        let inputs = record_inputs();
+        let first = replay(&inputs, &in_order());
+        //entity ids and query order change with spawn order, neither should change the outcome
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut shuffled = in_order();
+        while shuffled == in_order() {
+            shuffled.shuffle(&mut rng);
+        }
+        let second = replay(&inputs, &shuffled);
+        for (tick, (a, b)) in first.iter().zip(second.iter()).enumerate() {
+            assert_eq!(a, b, "replays diverged on tick {}", tick);
+        }
+        //make sure the bodies actually moved and touched
+        assert_ne!(first[0], first[TICKS - 1]);
+    }
+
+    #[test]
+    fn test_rollback_resimulates_the_same() {
+        let inputs = record_inputs();
+        let (mut app, bodies) = physics_app(&in_order());
+        let mut history = Vec::with_capacity(TICKS);
+        for tick_inputs in inputs.iter() {
+            tick(&mut app, &bodies, tick_inputs);
+            history.push(snapshot(&app, &bodies));
+        }
+        //derived state like the spatial index and contacts is now from the last tick, not the rollback tick
+        restore(&mut app, &bodies, &history[ROLLBACK_TICK]);
+        for (tick_number, tick_inputs) in inputs.iter().enumerate().skip(ROLLBACK_TICK + 1) {
+            tick(&mut app, &bodies, tick_inputs);
+            assert_eq!(
+                snapshot(&app, &bodies),
+                history[tick_number],
+                "resimulation diverged on tick {}",
+                tick_number
+            );
+        }
+    }
+
+    #[test]
+    fn test_constraint_chain_is_deterministic() {
+        let no_inputs = [Vec3::ZERO; BODIES];
+        let run = |spawn_order: &[usize]| {
+            let (mut app, bodies) = chain_app(spawn_order);
+            (0..TICKS)
+                .map(|_| {
+                    tick(&mut app, &bodies, &no_inputs);
+                    snapshot(&app, &bodies)
+                })
+                .collect::<Vec<_>>()
+        };
+        let first = run(&in_order());
+        let mut shuffled = in_order();
+        shuffled.reverse();
+        let second = run(&shuffled);
+        for (tick, (a, b)) in first.iter().zip(second.iter()).enumerate() {
+            assert_eq!(a, b, "chains diverged on tick {}", tick);
+        }
+        //the chain swung down but stayed in one piece
+        let (end, ..) = first[TICKS - 1][BODIES - 1];
+        assert!(end.y < 6.0);
+        assert!(end.distance(Vec3::Y * 6.0) < BODIES as f32 * 0.6 + 0.1);
+    }
+}
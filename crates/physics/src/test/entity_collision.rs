@@ -0,0 +1,33 @@
+#[allow(unused_imports)]
+mod entity_collision {
+    use crate::{
+        collision::Aabb,
+        entity_collision::{overlap, CollisionLayer, CollisionLayers},
+    };
+    use bevy::prelude::*;
+
+    #[test]
+    fn test_collides_with() {
+        assert!(CollisionLayers::PLAYER.collides_with(CollisionLayers::ENEMY));
+        assert!(CollisionLayers::ENEMY.collides_with(CollisionLayers::ENEMY));
+        assert!(!CollisionLayers::PLAYER.collides_with(CollisionLayers::ITEM));
+        //one sided masks don't collide
+        let ghost = CollisionLayers::new(CollisionLayer::Enemy, CollisionLayer::empty());
+        assert!(!CollisionLayers::PLAYER.collides_with(ghost));
+    }
+
+    #[test]
+    fn test_overlap_least_penetration() {
+        let aabb = Aabb::centered(Vec3::ONE);
+        //standing slightly inside the top of the other box
+        let (axis, normal, depth) =
+            overlap(aabb, Vec3::new(0.1, 0.9, 0.0), aabb, Vec3::ZERO).unwrap();
+        assert_eq!(axis, 1);
+        assert_eq!(normal, Vec3::Y);
+        assert!((depth - 0.1).abs() < 1e-5);
+        let (_, normal, _) = overlap(aabb, Vec3::new(-0.8, 0.0, 0.1), aabb, Vec3::ZERO).unwrap();
+        assert_eq!(normal, Vec3::NEG_X);
+        //touching isn't overlapping
+        assert!(overlap(aabb, Vec3::new(1.0, 0.0, 0.0), aabb, Vec3::ZERO).is_none());
+    }
+}
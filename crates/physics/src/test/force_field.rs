@@ -0,0 +1,98 @@
+#[allow(unused_imports)]
+mod force_field {
+    use bevy::prelude::*;
+
+    use crate::force_field::{
+        air_push, ForceField, ForceFieldKind, RepulsorBlock, Wind, AIR_COUPLING, MAX_REPULSOR_RANGE,
+    };
+
+    #[test]
+    fn test_air_push() {
+        //still body in wind gets pushed along with it
+        let push = air_push(Vec3::X, Vec3::ZERO);
+        assert_eq!(push, Vec3::X * AIR_COUPLING);
+        //moving with the wind already, nothing to do
+        assert_eq!(air_push(Vec3::X, Vec3::X * 2.0), Vec3::ZERO);
+        //wind doesn't slow down movement across it
+        assert_eq!(air_push(Vec3::X, Vec3::Z), Vec3::X * AIR_COUPLING);
+        //still air doesn't push at all
+        assert_eq!(air_push(Vec3::ZERO, Vec3::Y), Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_wind_altitude() {
+        let wind = Wind {
+            velocity: Vec3::X,
+            ..default()
+        };
+        assert_eq!(wind.altitude_mult(wind.ground_altitude), 1.0);
+        //sheltered deep underground, capped high up
+        assert_eq!(wind.altitude_mult(-1000.0), 0.0);
+        assert_eq!(wind.altitude_mult(1000.0), wind.max_altitude_mult);
+        assert!(wind.at(Vec3::Y * 20.0).x > wind.at(Vec3::ZERO).x);
+        //no gusts by default
+        assert_eq!(wind.at(Vec3::new(5.0, 0.0, 3.0)), Vec3::X);
+    }
+
+    #[test]
+    fn test_gusts_follow_tick() {
+        let mut wind = Wind {
+            velocity: Vec3::X,
+            gustiness: 0.5,
+            tick: 100,
+            ..default()
+        };
+        let position = Vec3::new(3.0, 0.0, -7.0);
+        //everyone on the same tick feels the same gust, however long they've been running
+        let gust = wind.gust_mult(position);
+        assert!(gust != 1.0);
+        assert_eq!(gust, Wind { ..wind }.gust_mult(position));
+        //the gust doesn't jump when the tick wraps around
+        wind.tick = u16::MAX;
+        let before_wrap = wind.gust_mult(position);
+        wind.tick = 0;
+        let after_wrap = wind.gust_mult(position);
+        assert!((before_wrap - after_wrap).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_force_field_volume() {
+        let field = ForceField::new(Vec3::splat(2.0), ForceFieldKind::Updraft(0.5));
+        let center = Vec3::new(10.0, 0.0, 0.0);
+        assert_eq!(
+            field.effect_at(center, center + Vec3::new(1.0, 1.9, -2.0)),
+            (Vec3::Y * 0.5, Vec3::ZERO)
+        );
+        assert_eq!(
+            field.effect_at(center, center + Vec3::new(0.0, 2.1, 0.0)),
+            (Vec3::ZERO, Vec3::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_repulsor_field_falloff() {
+        let field = ForceField::new(Vec3::splat(4.0), ForceFieldKind::Repulsor(1.0));
+        let (air, near) = field.effect_at(Vec3::ZERO, Vec3::X);
+        assert_eq!(air, Vec3::ZERO);
+        assert!((near - Vec3::X * 0.75).length() < 1e-5);
+        let (_, far) = field.effect_at(Vec3::ZERO, Vec3::NEG_Z * 3.0);
+        assert!((far - Vec3::NEG_Z * 0.25).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_repulsor_block() {
+        let repulsor = RepulsorBlock {
+            strength: 1.0,
+            range: 100.0,
+        };
+        //range is capped
+        assert_eq!(
+            repulsor.force_at(Vec3::ZERO, Vec3::Y * MAX_REPULSOR_RANGE),
+            Vec3::ZERO
+        );
+        let force = repulsor.force_at(Vec3::ZERO, Vec3::Y * MAX_REPULSOR_RANGE / 2.0);
+        assert!((force - Vec3::Y * 0.5).length() < 1e-5);
+        //something sitting right on the block gets pushed up
+        assert_eq!(repulsor.force_at(Vec3::ZERO, Vec3::ZERO), Vec3::Y);
+    }
+}
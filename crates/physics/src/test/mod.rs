@@ -1 +1,7 @@
-mod collision;
\ No newline at end of file
+mod ccd;
+mod collision;
+mod constraint;
+mod determinism;
+mod entity_collision;
+mod force_field;
+mod spatial;
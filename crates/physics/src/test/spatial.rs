@@ -0,0 +1,112 @@
+#[allow(unused_imports)]
+mod spatial_index {
+    use crate::{
+        query::Raycast,
+        spatial::{ray_box_toi, SpatialIndex, CELL_SIZE},
+    };
+    use bevy::prelude::*;
+
+    fn sorted(iter: impl Iterator<Item = Entity>) -> Vec<Entity> {
+        let mut v: Vec<Entity> = iter.collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn test_query_aabb_no_duplicates() {
+        let mut index = SpatialIndex::default();
+        let big = Entity::from_raw(0);
+        let small = Entity::from_raw(1);
+        //spans many cells
+        index.insert(big, Vec3::splat(-20.0), Vec3::splat(20.0));
+        index.insert(small, Vec3::splat(0.0), Vec3::splat(0.5));
+        let found = sorted(index.query_aabb(Vec3::splat(-30.0), Vec3::splat(30.0)));
+        assert_eq!(found, vec![big, small]);
+    }
+
+    #[test]
+    fn test_query_radius() {
+        let mut index = SpatialIndex::default();
+        let near = Entity::from_raw(0);
+        let far = Entity::from_raw(1);
+        index.insert(near, Vec3::new(3.0, 0.0, 0.0), Vec3::new(3.5, 0.5, 0.5));
+        index.insert(
+            far,
+            Vec3::new(3.0 * CELL_SIZE, 0.0, 0.0),
+            Vec3::new(3.0 * CELL_SIZE + 0.5, 0.5, 0.5),
+        );
+        assert_eq!(sorted(index.query_radius(Vec3::ZERO, 5.0)), vec![near]);
+        assert_eq!(
+            sorted(index.query_radius(Vec3::ZERO, 4.0 * CELL_SIZE)),
+            vec![near, far]
+        );
+    }
+
+    #[test]
+    fn test_remove_and_reinsert() {
+        let mut index = SpatialIndex::default();
+        let entity = Entity::from_raw(0);
+        index.insert(entity, Vec3::ZERO, Vec3::ONE);
+        index.insert(entity, Vec3::splat(100.0), Vec3::splat(101.0));
+        assert_eq!(index.len(), 1);
+        assert!(index.query_radius(Vec3::ZERO, 2.0).next().is_none());
+        assert_eq!(
+            sorted(index.query_radius(Vec3::splat(100.5), 2.0)),
+            vec![entity]
+        );
+        index.remove(entity);
+        assert!(index.is_empty());
+        assert!(index.query_radius(Vec3::splat(100.5), 2.0).next().is_none());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut index = SpatialIndex::default();
+        index.insert(Entity::from_raw(0), Vec3::ZERO, Vec3::ONE);
+        index.clear();
+        assert!(index.is_empty());
+        assert!(index
+            .query_aabb(Vec3::splat(-10.0), Vec3::splat(10.0))
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_query_ray() {
+        let mut index = SpatialIndex::default();
+        let hit = Entity::from_raw(0);
+        let miss = Entity::from_raw(1);
+        index.insert(hit, Vec3::new(10.0, -0.5, -0.5), Vec3::new(11.0, 0.5, 0.5));
+        index.insert(
+            miss,
+            Vec3::new(10.0, 10.0, 10.0),
+            Vec3::new(11.0, 11.0, 11.0),
+        );
+        let ray = Raycast::new(Vec3::ZERO, Dir3::X, 20.0);
+        assert_eq!(sorted(index.query_ray(ray)), vec![hit]);
+        let short = Raycast::new(Vec3::ZERO, Dir3::X, 5.0);
+        assert!(index.query_ray(short).next().is_none());
+    }
+
+    #[test]
+    fn test_ray_box_toi() {
+        let min = Vec3::new(2.0, -1.0, -1.0);
+        let max = Vec3::new(4.0, 1.0, 1.0);
+        assert_eq!(
+            ray_box_toi(Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0), min, max),
+            Some(0.2)
+        );
+        //starting inside
+        assert_eq!(
+            ray_box_toi(Vec3::new(3.0, 0.0, 0.0), Vec3::X, min, max),
+            Some(0.0)
+        );
+        //parallel and outside
+        assert_eq!(
+            ray_box_toi(Vec3::new(0.0, 5.0, 0.0), Vec3::X * 10.0, min, max),
+            None
+        );
+        //stops short
+        assert_eq!(ray_box_toi(Vec3::ZERO, Vec3::X, min, max), None);
+    }
+}
@@ -0,0 +1,170 @@
+use bevy::prelude::*;
+use engine::items::{
+    container::{ContainerBlock, ContainerLoot},
+    inventory::Inventory,
+    metadata::ItemMetadata,
+    ItemId, ItemResources, ItemStack,
+};
+use interfaces::serialization::{LoadedToSavedIdMap, SavedToLoadedIdMap};
+use world::block::{BlockCoord, RemovedBlock};
+
+use crate::{
+    db::{LevelDB, LevelDBErr},
+    queries::{DELETE_BLOCK_INVENTORY, LOAD_BLOCK_INVENTORY, SAVE_BLOCK_INVENTORY},
+};
+
+//(item, count, metadata), using the item ids from the level's item palette
+pub(crate) type SavedStack = (ItemId, u32, Option<ItemMetadata>);
+//one entry per slot
+pub(crate) type SavedInventory = Vec<Option<SavedStack>>;
+
+pub(crate) fn save_stack(
+    stack: &ItemStack,
+    item_query: &Query<&ItemId>,
+    meta_query: &Query<&ItemMetadata>,
+    id_map: &LoadedToSavedIdMap<ItemId>,
+) -> Option<SavedStack> {
+    let id = item_query.get(stack.id).ok()?;
+    let meta = stack
+        .meta
+        .and_then(|meta| meta_query.get(meta).ok())
+        .cloned();
+    Some((id_map.get(id)?, stack.size, meta))
+}
+
+//spawns a new metadata entity for the stack, if it had any
+pub(crate) fn load_stack(
+    (saved_id, size, meta): SavedStack,
+    id_map: &SavedToLoadedIdMap<ItemId>,
+    items: &ItemResources,
+    commands: &mut Commands,
+) -> Option<ItemStack> {
+    let Some(item) = id_map
+        .get(&saved_id)
+        .and_then(|id| items.registry.get_entity(id, commands))
+    else {
+        warn!("unknown saved item id {:?}", saved_id);
+        return None;
+    };
+    let meta = meta.map(|meta| meta.spawn(commands));
+    Some(ItemStack::new(item, size).with_meta(meta))
+}
+
+pub(crate) fn save_inventory(
+    inventory: &Inventory,
+    item_query: &Query<&ItemId>,
+    meta_query: &Query<&ItemMetadata>,
+    id_map: &LoadedToSavedIdMap<ItemId>,
+) -> SavedInventory {
+    inventory
+        .iter()
+        .map(|slot| {
+            slot.as_ref()
+                .and_then(|(stack, _)| save_stack(stack, item_query, meta_query, id_map))
+        })
+        .collect()
+}
+
+//fills in containers as they are placed or loaded with their chunk
+pub fn load_container_inventories(
+    mut query: Query<
+        (Entity, &mut Inventory, &BlockCoord),
+        (With<ContainerBlock>, Added<Inventory>),
+    >,
+    mut db: ResMut<LevelDB>,
+    id_map: Res<SavedToLoadedIdMap<ItemId>>,
+    items: Res<ItemResources>,
+    mut commands: Commands,
+) {
+    for (entity, mut inventory, coord) in query.iter_mut() {
+        let saved = match db.execute_query_sync(
+            LOAD_BLOCK_INVENTORY,
+            rusqlite::params![coord.x, coord.y, coord.z],
+            |row| row.get::<_, Vec<u8>>(0),
+        ) {
+            Ok(data) => match bincode::deserialize::<SavedInventory>(&data) {
+                Ok(saved) => saved,
+                Err(e) => {
+                    error!("Corrupt container inventory at {:?}: {:?}", coord, e);
+                    continue;
+                }
+            },
+            //nothing has been put in this container yet
+            Err(LevelDBErr::Sqlite(rusqlite::Error::QueryReturnedNoRows)) => continue,
+            Err(e) => {
+                error!("Error loading container inventory at {:?}: {:?}", coord, e);
+                continue;
+            }
+        };
+        //loot was already rolled the first time this container was opened
+        commands.entity(entity).remove::<ContainerLoot>();
+        //don't trigger a save for what we just loaded
+        let inventory = inventory.bypass_change_detection();
+        for (slot, entry) in saved.into_iter().enumerate() {
+            let Some(entry) = entry else {
+                continue;
+            };
+            if slot >= inventory.len() {
+                warn!(
+                    "container at {:?} has more saved slots than it can hold",
+                    coord
+                );
+                break;
+            }
+            if let Some(stack) = load_stack(entry, &id_map, &items, &mut commands) {
+                inventory.set_slot_no_events(slot, stack);
+            }
+        }
+    }
+}
+
+pub fn save_container_inventories(
+    query: Query<(Ref<Inventory>, &BlockCoord), With<ContainerBlock>>,
+    item_query: Query<&ItemId>,
+    meta_query: Query<&ItemMetadata>,
+    id_map: Res<LoadedToSavedIdMap<ItemId>>,
+    mut db: ResMut<LevelDB>,
+) {
+    for (inventory, coord) in query.iter() {
+        if !inventory.is_changed() || inventory.is_added() {
+            continue;
+        }
+        let saved = save_inventory(&inventory, &item_query, &meta_query, &id_map);
+        if let Some(err) = db.execute_command_sync(|sql| {
+            sql.execute(
+                SAVE_BLOCK_INVENTORY,
+                rusqlite::params![
+                    coord.x,
+                    coord.y,
+                    coord.z,
+                    bincode::serialize(&saved).unwrap()
+                ],
+            )
+        }) {
+            error!("Error saving container inventory at {:?}: {:?}", coord, err);
+        }
+    }
+}
+
+//the contents are dropped into the world when the container is broken, so forget them
+//unloaded containers keep their saved contents
+pub fn delete_container_inventory(
+    trigger: Trigger<OnRemove, ContainerBlock>,
+    query: Query<&BlockCoord, (With<Inventory>, With<RemovedBlock>)>,
+    db: Option<ResMut<LevelDB>>,
+) {
+    let (Ok(coord), Some(mut db)) = (query.get(trigger.entity()), db) else {
+        return;
+    };
+    if let Some(err) = db.execute_command_sync(|sql| {
+        sql.execute(
+            DELETE_BLOCK_INVENTORY,
+            rusqlite::params![coord.x, coord.y, coord.z],
+        )
+    }) {
+        error!(
+            "Error deleting container inventory at {:?}: {:?}",
+            coord, err
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use engine::items::{
+    metadata::ItemMetadata, DroppedItem, ItemId, ItemResources, SpawnDroppedItemEvent,
+};
+use interfaces::serialization::{LoadedToSavedIdMap, SavedToLoadedIdMap};
+
+use crate::{
+    containers::{load_stack, save_stack, SavedStack},
+    db::{LevelDB, LevelDBErr},
+    queries::{INSERT_WORLD_INFO, LOAD_WORLD_INFO},
+};
+
+const DROPPED_ITEMS_KEY: &str = "dropped_items";
+
+//(position, stack) for every item lying in the world
+type SavedDroppedItems = Vec<(Vec3, SavedStack)>;
+
+//drops the items that were lying around when the level was saved back where they were
+pub fn load_dropped_items(
+    mut db: ResMut<LevelDB>,
+    id_map: Res<SavedToLoadedIdMap<ItemId>>,
+    items: Res<ItemResources>,
+    mut drop_writer: EventWriter<SpawnDroppedItemEvent>,
+    mut commands: Commands,
+) {
+    let saved = match db.execute_query_sync(
+        LOAD_WORLD_INFO,
+        rusqlite::params![DROPPED_ITEMS_KEY],
+        |row| row.get::<_, Vec<u8>>(0),
+    ) {
+        Ok(data) => match bincode::deserialize::<SavedDroppedItems>(&data) {
+            Ok(saved) => saved,
+            Err(e) => {
+                error!("Corrupt dropped items: {:?}", e);
+                return;
+            }
+        },
+        //nothing has been dropped in this level yet
+        Err(LevelDBErr::Sqlite(rusqlite::Error::QueryReturnedNoRows)) => return,
+        Err(e) => {
+            error!("Error getting dropped items from db: {:?}", e);
+            return;
+        }
+    };
+    for (position, saved_stack) in saved {
+        if let Some(stack) = load_stack(saved_stack, &id_map, &items, &mut commands) {
+            drop_writer.send(SpawnDroppedItemEvent {
+                postion: position,
+                velocity: Vec3::ZERO,
+                stack,
+            });
+        }
+    }
+}
+
+//items are dropped and picked up a lot less often than they move, so only save when one appears or disappears
+pub fn save_dropped_items(
+    query: Query<(&Transform, &DroppedItem)>,
+    added_query: Query<(), Added<DroppedItem>>,
+    mut removed: RemovedComponents<DroppedItem>,
+    item_query: Query<&ItemId>,
+    meta_query: Query<&ItemMetadata>,
+    id_map: Res<LoadedToSavedIdMap<ItemId>>,
+    mut db: ResMut<LevelDB>,
+) {
+    let any_removed = removed.read().count() > 0;
+    if added_query.is_empty() && !any_removed {
+        return;
+    }
+    let saved: SavedDroppedItems = query
+        .iter()
+        .filter_map(|(tf, dropped)| {
+            save_stack(&dropped.stack, &item_query, &meta_query, &id_map)
+                .map(|stack| (tf.translation, stack))
+        })
+        .collect();
+    if let Some(err) = db.execute_command_sync(|sql| {
+        sql.execute(
+            INSERT_WORLD_INFO,
+            rusqlite::params![DROPPED_ITEMS_KEY, bincode::serialize(&saved).unwrap()],
+        )
+    }) {
+        error!("Error saving dropped items: {:?}", err);
+    }
+}
@@ -2,16 +2,20 @@ use std::path::PathBuf;
 
 use bevy::{prelude::*, utils::HashMap};
 
-use interfaces::scheduling::{LevelSystemSet, NetworkType};
+use interfaces::scheduling::{LevelLoadState, LevelSystemSet, NetworkType};
 use world::chunk::ChunkCoord;
 
 pub struct SerializationPlugin;
 
+mod containers;
 pub mod db;
+mod dropped_items;
 mod loading;
+mod player_data;
 pub mod queries;
 mod saving;
 mod setup;
+mod shops;
 pub mod state;
 
 impl Plugin for SerializationPlugin {
@@ -27,14 +31,34 @@ impl Plugin for SerializationPlugin {
                     db::tick_db,
                     saving::do_saving,
                     saving::save_all,
+                    player_data::load_known_recipes,
+                    player_data::save_known_recipes,
+                    player_data::load_player_items,
+                    player_data::save_player_items,
+                    containers::load_container_inventories,
+                    containers::save_container_inventories,
+                    shops::load_shop_stocks,
+                    shops::save_shop_stocks,
+                    dropped_items::save_dropped_items,
                 )
                     .in_set(LevelSystemSet::AfterLoadingAndMain)
                     .run_if(not(in_state(NetworkType::Client))),
             )
+            .add_systems(
+                OnEnter(LevelLoadState::Loading),
+                player_data::reset_loaded_player_items,
+            )
+            .add_systems(
+                OnEnter(LevelLoadState::Loaded),
+                dropped_items::load_dropped_items.run_if(not(in_state(NetworkType::Client))),
+            )
+            .add_observer(containers::delete_container_inventory)
+            .add_observer(shops::delete_shop_stock)
             .add_event::<SaveChunkEvent>()
             .add_event::<db::DataFromDBEvent>()
             .insert_resource(SaveTimer(Timer::from_seconds(0.1, TimerMode::Repeating)))
-            .init_resource::<LevelCreationInput>();
+            .init_resource::<LevelCreationInput>()
+            .init_resource::<player_data::LoadedPlayerItems>();
     }
 }
 
@@ -0,0 +1,221 @@
+use ahash::HashSet;
+use bevy::prelude::*;
+use crafting::{discovery::KnownRecipes, Recipe};
+use engine::{
+    actors::{LocalPlayer, Player},
+    items::{equipment::Equipment, inventory::Inventory, metadata::ItemMetadata, ItemId, ItemResources},
+};
+use interfaces::{
+    components::RemoteClient,
+    serialization::{LoadedToSavedIdMap, SavedToLoadedIdMap},
+};
+
+use crate::{
+    containers::{load_stack, save_inventory, save_stack, SavedInventory, SavedStack},
+    db::{LevelDB, LevelDBErr},
+    queries::{INSERT_WORLD_INFO, LOAD_WORLD_INFO},
+};
+
+const KNOWN_RECIPES_KEY: &str = "known_recipes";
+const INVENTORY_KEY: &str = "inventory";
+
+//(inventory slots, equipment slots)
+type SavedPlayerItems = (SavedInventory, Vec<Option<SavedStack>>);
+
+//the host keeps the unsuffixed key so saves from before multiplayer still load
+fn player_key(base: &str, remote: Option<&RemoteClient>) -> String {
+    match remote {
+        Some(RemoteClient(client_id)) => format!("{}_{:?}", base, client_id),
+        None => base.to_string(),
+    }
+}
+
+fn known_recipes_key(remote: Option<&RemoteClient>) -> String {
+    player_key(KNOWN_RECIPES_KEY, remote)
+}
+
+//players whose items were loaded this level. respawning starts over with a fresh inventory instead of the saved one
+#[derive(Resource, Default)]
+pub struct LoadedPlayerItems(HashSet<String>);
+
+pub fn reset_loaded_player_items(mut loaded: ResMut<LoadedPlayerItems>) {
+    loaded.0.clear();
+}
+
+//gives each player the recipes they discovered last time they played this level
+pub fn load_known_recipes(
+    query: Query<
+        (Entity, Option<&RemoteClient>),
+        (
+            With<Player>,
+            Or<(With<LocalPlayer>, With<RemoteClient>)>,
+            Without<KnownRecipes>,
+        ),
+    >,
+    mut db: ResMut<LevelDB>,
+    mut commands: Commands,
+) {
+    for (player, remote) in query.iter() {
+        let key = known_recipes_key(remote);
+        let known = match db.execute_query_sync(LOAD_WORLD_INFO, rusqlite::params![key], |row| {
+            row.get::<_, Vec<u8>>(0)
+        }) {
+            Ok(data) => match bincode::deserialize::<Vec<Recipe>>(&data) {
+                Ok(recipes) => {
+                    info!("loaded {} known recipes for {}", recipes.len(), key);
+                    KnownRecipes::new(recipes)
+                }
+                Err(e) => {
+                    error!("Corrupt known recipes for {}: {:?}", key, e);
+                    KnownRecipes::default()
+                }
+            },
+            //new player or level, nothing discovered yet
+            Err(LevelDBErr::Sqlite(rusqlite::Error::QueryReturnedNoRows)) => {
+                KnownRecipes::default()
+            }
+            Err(e) => {
+                error!("Error getting known recipes for {} from db: {:?}", key, e);
+                KnownRecipes::default()
+            }
+        };
+        if let Some(mut ec) = commands.get_entity(player) {
+            ec.try_insert(known);
+        }
+    }
+}
+
+//discoveries are rare, so just write the whole list whenever it changes
+pub fn save_known_recipes(
+    query: Query<
+        (Ref<KnownRecipes>, Option<&RemoteClient>),
+        Or<(With<LocalPlayer>, With<RemoteClient>)>,
+    >,
+    mut db: ResMut<LevelDB>,
+) {
+    for (known, remote) in query.iter() {
+        //don't overwrite the save with what we just loaded
+        if !known.is_changed() || known.is_added() {
+            continue;
+        }
+        let key = known_recipes_key(remote);
+        let recipes = known.iter().cloned().collect::<Vec<_>>();
+        if let Some(err) = db.execute_command_sync(|sql| {
+            sql.execute(
+                INSERT_WORLD_INFO,
+                rusqlite::params![key, bincode::serialize(&recipes).unwrap()],
+            )
+        }) {
+            error!("Error saving known recipes for {}: {:?}", key, err);
+        }
+    }
+}
+
+//gives players back the items and gear they had when the level was saved
+pub fn load_player_items(
+    mut query: Query<
+        (&mut Inventory, Option<&mut Equipment>, Option<&RemoteClient>),
+        (With<Player>, Added<Inventory>),
+    >,
+    mut db: ResMut<LevelDB>,
+    mut loaded: ResMut<LoadedPlayerItems>,
+    id_map: Res<SavedToLoadedIdMap<ItemId>>,
+    items: Res<ItemResources>,
+    mut commands: Commands,
+) {
+    for (mut inventory, equipment, remote) in query.iter_mut() {
+        let key = player_key(INVENTORY_KEY, remote);
+        if !loaded.0.insert(key.clone()) {
+            continue;
+        }
+        let (saved_inventory, saved_equipment) = match db.execute_query_sync(
+            LOAD_WORLD_INFO,
+            rusqlite::params![key],
+            |row| row.get::<_, Vec<u8>>(0),
+        ) {
+            Ok(data) => match bincode::deserialize::<SavedPlayerItems>(&data) {
+                Ok(saved) => saved,
+                Err(e) => {
+                    error!("Corrupt player items for {}: {:?}", key, e);
+                    continue;
+                }
+            },
+            //new player or level, keep the starting items
+            Err(LevelDBErr::Sqlite(rusqlite::Error::QueryReturnedNoRows)) => continue,
+            Err(e) => {
+                error!("Error getting player items for {} from db: {:?}", key, e);
+                continue;
+            }
+        };
+        for slot in 0..inventory.len() {
+            let stack = saved_inventory
+                .get(slot)
+                .cloned()
+                .flatten()
+                .and_then(|saved| load_stack(saved, &id_map, &items, &mut commands));
+            match stack {
+                Some(stack) => inventory.set_slot_no_events(slot, stack),
+                None => {
+                    inventory.drop_slot(slot);
+                }
+            }
+        }
+        if let Some(mut equipment) = equipment {
+            for slot in 0..equipment.len() {
+                let stack = saved_equipment
+                    .get(slot)
+                    .cloned()
+                    .flatten()
+                    .and_then(|saved| load_stack(saved, &id_map, &items, &mut commands));
+                equipment.set(slot, stack);
+            }
+        }
+        info!("loaded items for {}", key);
+    }
+}
+
+//durability and modifiers live on metadata entities, so they're saved along with the stacks
+pub fn save_player_items(
+    query: Query<
+        (Ref<Inventory>, Option<Ref<Equipment>>, Option<&RemoteClient>),
+        (With<Player>, Or<(With<LocalPlayer>, With<RemoteClient>)>),
+    >,
+    item_query: Query<&ItemId>,
+    meta_query: Query<&ItemMetadata>,
+    id_map: Res<LoadedToSavedIdMap<ItemId>>,
+    mut db: ResMut<LevelDB>,
+) {
+    for (inventory, equipment, remote) in query.iter() {
+        let equipment_changed = equipment
+            .as_ref()
+            .is_some_and(|equipment| equipment.is_changed());
+        //a fresh inventory is either about to be loaded or the starting items
+        if inventory.is_added() || !(inventory.is_changed() || equipment_changed) {
+            continue;
+        }
+        let key = player_key(INVENTORY_KEY, remote);
+        let saved: SavedPlayerItems = (
+            save_inventory(&inventory, &item_query, &meta_query, &id_map),
+            equipment
+                .map(|equipment| {
+                    equipment
+                        .iter()
+                        .map(|(_, stack)| {
+                            stack.and_then(|stack| {
+                                save_stack(&stack, &item_query, &meta_query, &id_map)
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        );
+        if let Some(err) = db.execute_command_sync(|sql| {
+            sql.execute(
+                INSERT_WORLD_INFO,
+                rusqlite::params![key, bincode::serialize(&saved).unwrap()],
+            )
+        }) {
+            error!("Error saving player items for {}: {:?}", key, err);
+        }
+    }
+}
@@ -24,4 +24,38 @@ pub const INSERT_WORLD_INFO: &str = "
             VALUES (?1,?2)";
 pub const LOAD_WORLD_INFO: &str = "
             SELECT value FROM world_info
-            WHERE key = ?1";
\ No newline at end of file
+            WHERE key = ?1";
+pub const CREATE_BLOCK_INVENTORY_TABLE: &str = "
+        CREATE TABLE IF NOT EXISTS block_inventories (
+            x INTEGER NOT NULL,
+            y INTEGER NOT NULL,
+            z INTEGER NOT NULL,
+            data BLOB NOT NULL,
+            PRIMARY KEY (x,y,z)
+        ) STRICT";
+pub const SAVE_BLOCK_INVENTORY: &str = "
+            INSERT OR REPLACE INTO block_inventories (x, y, z, data)
+            VALUES (?1,?2,?3,?4)";
+pub const LOAD_BLOCK_INVENTORY: &str = "
+            SELECT data FROM block_inventories
+            WHERE x = ?1 AND y = ?2 AND z = ?3";
+pub const DELETE_BLOCK_INVENTORY: &str = "
+            DELETE FROM block_inventories
+            WHERE x = ?1 AND y = ?2 AND z = ?3";
+pub const CREATE_SHOP_STOCK_TABLE: &str = "
+        CREATE TABLE IF NOT EXISTS shop_stocks (
+            x INTEGER NOT NULL,
+            y INTEGER NOT NULL,
+            z INTEGER NOT NULL,
+            data BLOB NOT NULL,
+            PRIMARY KEY (x,y,z)
+        ) STRICT";
+pub const SAVE_SHOP_STOCK: &str = "
+            INSERT OR REPLACE INTO shop_stocks (x, y, z, data)
+            VALUES (?1,?2,?3,?4)";
+pub const LOAD_SHOP_STOCK: &str = "
+            SELECT data FROM shop_stocks
+            WHERE x = ?1 AND y = ?2 AND z = ?3";
+pub const DELETE_SHOP_STOCK: &str = "
+            DELETE FROM shop_stocks
+            WHERE x = ?1 AND y = ?2 AND z = ?3";
@@ -4,6 +4,7 @@ use bevy::utils::HashMap;
 use interfaces::components::Id;
 use interfaces::serialization::{LoadedToSavedIdMap, SavedToLoadedIdMap};
 use itertools::Itertools;
+use physics::collision::BlockPhysics;
 use rand::RngCore;
 use world::block::{BlockTextureMap, NamedBlockMesh};
 
@@ -14,7 +15,8 @@ use std::time::SystemTime;
 
 use crate::db::{LevelDB, LevelDBErr};
 use crate::queries::{
-    CREATE_CHUNK_TABLE, CREATE_WORLD_INFO_TABLE, INSERT_WORLD_INFO, LOAD_WORLD_INFO,
+    CREATE_BLOCK_INVENTORY_TABLE, CREATE_CHUNK_TABLE, CREATE_SHOP_STOCK_TABLE,
+    CREATE_WORLD_INFO_TABLE, INSERT_WORLD_INFO, LOAD_WORLD_INFO,
 };
 use crate::{LevelCreationInput, LoadingBlocks, LoadingItems, SavedLevelInfo};
 use engine::items::{
@@ -26,7 +28,10 @@ use util::string::Version;
 use world::mesher::{mesh_single_block, TerrainTexture};
 use world::settings::GraphicsSettings;
 use world::{
-    block::{BlockId, BlockName, BlockNameIdMap, BlockRegistry, BlockResources},
+    block::{
+        BlockId, BlockName, BlockNameIdMap, BlockRegistry, BlockResources, DynamicBlock,
+        TemplateBlockGenerator,
+    },
     level::LevelData,
 };
 use world::{level::Level, settings::Settings};
@@ -270,6 +275,8 @@ fn load_block_registry(
     loading_blocks: Query<(Entity, Option<&Children>), With<LoadingBlocks>>,
     block_name_query: Query<&BlockName>,
     name_resolution_query: Query<&NamedBlockMesh>,
+    physics_query: Query<&BlockPhysics>,
+    dynamic_query: Query<(), With<DynamicBlock>>,
     mut block_resources: ResMut<BlockResources>,
 ) {
     //make sure there are no still loading block scenes before we make the registry
@@ -294,12 +301,28 @@ fn load_block_registry(
                 let mut mesh = named_mesh.clone().into_block_mesh(&texture_map);
                 mesh.single_mesh = mesh_single_block(&mesh, &mut meshes);
                 single_mesh = mesh.single_mesh.clone();
+                if let Ok(BlockPhysics::FromMesh) = physics_query.get(*child) {
+                    commands
+                        .entity(*child)
+                        .insert(BlockPhysics::from_mesh_shape(&mesh.shape));
+                }
                 commands
                     .entity(*child)
                     .insert(mesh)
                     .remove::<NamedBlockMesh>();
             }
             match block_name_query.get(*child) {
+                Ok(name) if dynamic_query.contains(*child) => {
+                    //the loaded entity becomes the template for each placed block
+                    let id = registry.add_dynamic(
+                        name.clone(),
+                        Arc::new(TemplateBlockGenerator { template: *child }),
+                    );
+                    match single_mesh {
+                        Some(mesh) => commands.entity(*child).insert((id, mesh)),
+                        None => commands.entity(*child).insert(id),
+                    };
+                }
                 Ok(name) => registry.add_basic(name.clone(), single_mesh, *child, &mut commands),
                 Err(e) => warn!("Block doesn't have a name! Error {:?}", e),
             }
@@ -397,6 +420,20 @@ fn on_level_created(
                 next_game_state.set(GameState::Menu);
                 return;
             }
+            if let Some(err) =
+                db.execute_command_sync(|sql| sql.execute(CREATE_BLOCK_INVENTORY_TABLE, []))
+            {
+                error!("Error creating block inventory table: {:?}", err);
+                next_game_state.set(GameState::Menu);
+                return;
+            }
+            if let Some(err) =
+                db.execute_command_sync(|sql| sql.execute(CREATE_SHOP_STOCK_TABLE, []))
+            {
+                error!("Error creating shop stock table: {:?}", err);
+                next_game_state.set(GameState::Menu);
+                return;
+            }
             if let Err(err) = check_level_version(&mut db) {
                 error!("Error checking level version: {:?}", err);
                 next_game_state.set(GameState::Menu);
@@ -0,0 +1,128 @@
+use bevy::prelude::*;
+use engine::items::{
+    shop::{ActiveOffer, ShopBlock, ShopOfferKind, ShopStock},
+    ItemId, ItemResources,
+};
+use interfaces::serialization::{LoadedToSavedIdMap, SavedToLoadedIdMap};
+use world::block::{BlockCoord, RemovedBlock};
+
+use crate::{
+    db::{LevelDB, LevelDBErr},
+    queries::{DELETE_SHOP_STOCK, LOAD_SHOP_STOCK, SAVE_SHOP_STOCK},
+};
+
+//(day, (item, count, price, kind, uses left) for each offer), using the item ids from the level's item palette
+type SavedShopStock = (u64, Vec<(ItemId, u32, u32, ShopOfferKind, u32)>);
+
+//gives shops back the stock they had when they were unloaded, so sold out offers stay sold out
+//stock from an earlier day is replaced by the next restock
+pub fn load_shop_stocks(
+    query: Query<(Entity, &BlockCoord), Added<ShopBlock>>,
+    mut db: ResMut<LevelDB>,
+    id_map: Res<SavedToLoadedIdMap<ItemId>>,
+    items: Res<ItemResources>,
+    mut commands: Commands,
+) {
+    for (entity, coord) in query.iter() {
+        let (day, saved) = match db.execute_query_sync(
+            LOAD_SHOP_STOCK,
+            rusqlite::params![coord.x, coord.y, coord.z],
+            |row| row.get::<_, Vec<u8>>(0),
+        ) {
+            Ok(data) => match bincode::deserialize::<SavedShopStock>(&data) {
+                Ok(saved) => saved,
+                Err(e) => {
+                    error!("Corrupt shop stock at {:?}: {:?}", coord, e);
+                    continue;
+                }
+            },
+            //nobody has traded with this shop yet
+            Err(LevelDBErr::Sqlite(rusqlite::Error::QueryReturnedNoRows)) => continue,
+            Err(e) => {
+                error!("Error loading shop stock at {:?}: {:?}", coord, e);
+                continue;
+            }
+        };
+        let mut offers = Vec::with_capacity(saved.len());
+        for (saved_id, count, price, kind, uses_left) in saved {
+            match id_map
+                .get(&saved_id)
+                .and_then(|id| items.registry.get_entity(id, &mut commands))
+            {
+                Some(item) => offers.push(ActiveOffer {
+                    item,
+                    count,
+                    price,
+                    kind,
+                    uses_left,
+                }),
+                None => warn!("unknown saved item id {:?} in shop", saved_id),
+            }
+        }
+        if let Some(mut ec) = commands.get_entity(entity) {
+            ec.try_insert(ShopStock { day, offers });
+        }
+    }
+}
+
+pub fn save_shop_stocks(
+    query: Query<(Ref<ShopStock>, &BlockCoord)>,
+    item_query: Query<&ItemId>,
+    id_map: Res<LoadedToSavedIdMap<ItemId>>,
+    mut db: ResMut<LevelDB>,
+) {
+    for (stock, coord) in query.iter() {
+        if !stock.is_changed() || stock.is_added() {
+            continue;
+        }
+        let saved: SavedShopStock = (
+            stock.day,
+            stock
+                .offers
+                .iter()
+                .filter_map(|offer| {
+                    let id = item_query.get(offer.item).ok()?;
+                    Some((
+                        id_map.get(id)?,
+                        offer.count,
+                        offer.price,
+                        offer.kind,
+                        offer.uses_left,
+                    ))
+                })
+                .collect(),
+        );
+        if let Some(err) = db.execute_command_sync(|sql| {
+            sql.execute(
+                SAVE_SHOP_STOCK,
+                rusqlite::params![
+                    coord.x,
+                    coord.y,
+                    coord.z,
+                    bincode::serialize(&saved).unwrap()
+                ],
+            )
+        }) {
+            error!("Error saving shop stock at {:?}: {:?}", coord, err);
+        }
+    }
+}
+
+//a shop placed in the same spot later starts fresh
+pub fn delete_shop_stock(
+    trigger: Trigger<OnRemove, ShopBlock>,
+    query: Query<&BlockCoord, With<RemovedBlock>>,
+    db: Option<ResMut<LevelDB>>,
+) {
+    let (Ok(coord), Some(mut db)) = (query.get(trigger.entity()), db) else {
+        return;
+    };
+    if let Some(err) = db.execute_command_sync(|sql| {
+        sql.execute(
+            DELETE_SHOP_STOCK,
+            rusqlite::params![coord.x, coord.y, coord.z],
+        )
+    }) {
+        error!("Error deleting shop stock at {:?}: {:?}", coord, err);
+    }
+}
@@ -10,10 +10,12 @@ impl Plugin for UIPlugin {
             ui_inventory::UIInventoryPlugin,
             ui_combat::PlayerStatsUiPlugin,
             ui_crafting::UICraftingPlugin,
+            ui_shop::UIShopPlugin,
             ui_menu::MainMenuPlugin,
             ui_crosshair::CrosshairPlugin,
             ui_waves::WavesPlugin,
             ui_game_over::UIGameOverPlugin,
+            ui_citizens::UICitizensPlugin,
         ));
     }
 }
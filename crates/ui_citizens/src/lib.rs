@@ -0,0 +1,248 @@
+use ahash::HashMap;
+use bevy::prelude::*;
+
+use actors::wisp::citizen::{Citizen, CitizenJob, SetCitizenJobEvent, SpawnCitizenEvent};
+use engine::actors::world_anchor::{CitizenCap, WorldAnchor};
+use interfaces::scheduling::{GameState, LevelSystemSet};
+use ui_core::{get_small_text_style, get_text_style, ButtonColors};
+use ui_state::UIState;
+
+pub struct UICitizensPlugin;
+
+impl Plugin for UICitizensPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_citizens_ui,
+                update_citizen_rows,
+                (update_citizen_count, update_citizen_job_text),
+            )
+                .chain()
+                .in_set(LevelSystemSet::Main),
+        )
+        .add_systems(OnEnter(UIState::Inventory), show)
+        .add_systems(OnExit(UIState::Inventory), hide);
+    }
+}
+
+const UI_WIDTH_PX: f32 = 300.;
+const ROW_MARGIN_PX: f32 = 4.;
+
+#[derive(Component)]
+struct CitizensUI;
+
+#[derive(Component)]
+struct CitizenList;
+
+#[derive(Component)]
+struct CitizenCountText;
+
+#[derive(Component)]
+struct CitizenRow {
+    citizen: Entity,
+}
+
+#[derive(Component)]
+struct CitizenJobText {
+    citizen: Entity,
+}
+
+#[derive(Component)]
+struct CitizenJobButton {
+    citizen: Entity,
+    job: CitizenJob,
+}
+
+fn spawn_button<'a>(
+    parent: &'a mut ChildBuilder,
+    text: impl Into<String>,
+    text_style: impl Bundle,
+) -> EntityCommands<'a> {
+    let mut ec = parent.spawn((
+        Button,
+        ButtonColors::default(),
+        BorderColor(ButtonColors::default().default_border),
+        BackgroundColor(ButtonColors::default().default_background),
+        Node {
+            border: UiRect::all(Val::Px(2.0)),
+            align_items: AlignItems::Center,
+            margin: UiRect::all(Val::Px(1.)),
+            padding: UiRect::horizontal(Val::Px(3.)),
+            ..default()
+        },
+    ));
+    ec.with_children(|button_text| {
+        button_text.spawn((Text(text.into()), text_style));
+    });
+    ec
+}
+
+fn spawn_citizens_ui(
+    mut commands: Commands,
+    ui_query: Query<(), With<CitizensUI>>,
+    asset_server: Res<AssetServer>,
+) {
+    if !ui_query.is_empty() {
+        return;
+    }
+    let text_style = get_text_style(&asset_server);
+    commands
+        .spawn((
+            Name::new("citizens ui"),
+            Node {
+                justify_self: JustifySelf::Start,
+                position_type: PositionType::Absolute,
+                width: Val::Px(UI_WIDTH_PX),
+                height: Val::Percent(100.),
+                border: UiRect::all(Val::Px(2.)),
+                display: Display::Flex,
+                flex_direction: FlexDirection::Column,
+                overflow: Overflow::scroll_y(),
+                ..default()
+            },
+            Visibility::Hidden,
+            BackgroundColor(Color::hsla(0., 0., 0.5, 0.7)),
+            BorderColor(Color::hsla(0., 0., 0.3, 1.0)),
+            CitizensUI,
+            StateScoped(GameState::Game),
+        ))
+        .with_children(|root| {
+            root.spawn(Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                margin: UiRect::all(Val::Px(ROW_MARGIN_PX)),
+                ..default()
+            })
+            .with_children(|header| {
+                header.spawn((
+                    Text("Citizens".to_string()),
+                    text_style.clone(),
+                    CitizenCountText,
+                ));
+                spawn_button(header, "Summon", text_style.clone()).observe(
+                    |_: Trigger<Pointer<Down>>,
+                     mut writer: EventWriter<SpawnCitizenEvent>,
+                     anchor_query: Query<Entity, (With<WorldAnchor>, With<CitizenCap>)>| {
+                        if let Some(anchor) = anchor_query.iter().next() {
+                            writer.send(SpawnCitizenEvent { anchor });
+                        }
+                    },
+                );
+            });
+            root.spawn((
+                CitizenList,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn update_citizen_rows(
+    citizen_query: Query<Entity, With<Citizen>>,
+    row_query: Query<(Entity, &CitizenRow)>,
+    list_query: Query<Entity, With<CitizenList>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut current_rows: Local<HashMap<Entity, Entity>>,
+) {
+    let Ok(list) = list_query.get_single() else {
+        return;
+    };
+    current_rows.clear();
+    // map from citizen to UI element
+    current_rows.extend(row_query.iter().map(|(row, r)| (r.citizen, row)));
+    let text_style = get_text_style(&asset_server);
+    let small_text_style = get_small_text_style(&asset_server);
+    //remove all found citizens, so that current_rows will contain excess rows for us to remove at the end
+    for citizen in citizen_query.iter() {
+        if current_rows.remove(&citizen).is_some() {
+            continue;
+        }
+        commands.entity(list).with_children(|rows| {
+            rows.spawn((
+                Name::new("citizen row"),
+                CitizenRow { citizen },
+                Node {
+                    margin: UiRect::all(Val::Px(ROW_MARGIN_PX)),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                BackgroundColor(Color::hsla(0., 0., 0.2, 1.0)),
+            ))
+            .with_children(|row| {
+                row.spawn((
+                    Text::default(),
+                    text_style.clone(),
+                    CitizenJobText { citizen },
+                ));
+                row.spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    flex_wrap: FlexWrap::Wrap,
+                    ..default()
+                })
+                .with_children(|buttons| {
+                    for job in CitizenJob::ALL {
+                        spawn_button(buttons, job.display_name(), small_text_style.clone())
+                            .insert(CitizenJobButton { citizen, job })
+                            .observe(
+                                |click: Trigger<Pointer<Down>>,
+                                 button_query: Query<&CitizenJobButton>,
+                                 mut writer: EventWriter<SetCitizenJobEvent>| {
+                                    if let Ok(button) = button_query.get(click.entity()) {
+                                        writer.send(SetCitizenJobEvent {
+                                            citizen: button.citizen,
+                                            job: button.job,
+                                        });
+                                    }
+                                },
+                            );
+                    }
+                });
+            });
+        });
+    }
+    // now current_rows contains all the rows for citizens that are gone
+    for (_, row) in current_rows.drain() {
+        commands.entity(row).despawn_recursive();
+    }
+}
+
+fn update_citizen_count(
+    mut text_query: Query<&mut Text, With<CitizenCountText>>,
+    anchor_query: Query<(Entity, &CitizenCap), With<WorldAnchor>>,
+    citizen_query: Query<&Citizen>,
+) {
+    let Some((anchor, cap)) = anchor_query.iter().next() else {
+        return;
+    };
+    let count = citizen_query.iter().filter(|c| c.anchor == anchor).count();
+    for mut text in text_query.iter_mut() {
+        text.0 = format!("Citizens {}/{}", count, cap.0);
+    }
+}
+
+fn update_citizen_job_text(
+    mut text_query: Query<(&mut Text, &CitizenJobText)>,
+    job_query: Query<&CitizenJob>,
+) {
+    for (mut text, job_text) in text_query.iter_mut() {
+        if let Ok(job) = job_query.get(job_text.citizen) {
+            text.0 = format!("Wisp - {}", job.display_name());
+        }
+    }
+}
+
+fn show(mut query: Query<&mut Visibility, With<CitizensUI>>) {
+    for mut vis in query.iter_mut() {
+        *vis.as_mut() = Visibility::Inherited;
+    }
+}
+
+fn hide(mut query: Query<&mut Visibility, With<CitizensUI>>) {
+    for mut vis in query.iter_mut() {
+        *vis.as_mut() = Visibility::Hidden;
+    }
+}
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use actors::boss::{Boss, BossPhases};
+use engine::actors::Combatant;
+use interfaces::scheduling::{GameState, LevelSystemSet};
+
+use ui_core::{get_text_style, MainCameraUIRoot};
+
+pub struct BossHealthBarPlugin;
+
+impl Plugin for BossHealthBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Game), spawn_boss_bar)
+            .add_systems(Update, update_boss_bar.in_set(LevelSystemSet::Main));
+    }
+}
+
+const BAR_WIDTH_PX: f32 = 480.0;
+const BAR_HEIGHT_PX: f32 = 14.0;
+
+#[derive(Component)]
+struct BossBarContainer;
+
+#[derive(Component)]
+struct BossBarFill;
+
+#[derive(Component)]
+struct BossBarLabel;
+
+fn spawn_boss_bar(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            StateScoped(GameState::Game),
+            BossBarContainer,
+            MainCameraUIRoot,
+            PickingBehavior::IGNORE,
+            Visibility::Hidden,
+            Node {
+                width: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                top: Val::Px(24.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            Name::new("UI boss bar"),
+        ))
+        .with_children(|children| {
+            children.spawn((BossBarLabel, Text::default(), get_text_style(&asset_server)));
+            children
+                .spawn((
+                    Node {
+                        width: Val::Px(BAR_WIDTH_PX),
+                        height: Val::Px(BAR_HEIGHT_PX),
+                        justify_content: JustifyContent::FlexStart,
+                        ..default()
+                    },
+                    BackgroundColor(Color::Srgba(Srgba::hex("202e37").unwrap())),
+                ))
+                .with_children(|bar| {
+                    bar.spawn((
+                        BossBarFill,
+                        Node {
+                            width: Val::Percent(100.),
+                            height: Val::Percent(100.),
+                            position_type: PositionType::Absolute,
+                            ..default()
+                        },
+                        BackgroundColor(Color::Srgba(Srgba::hex("a53030").unwrap())),
+                    ));
+                });
+        });
+}
+
+//only shows one boss at a time, whichever one the query finds first
+fn update_boss_bar(
+    boss_query: Query<(&Boss, &Combatant, Option<&BossPhases>)>,
+    mut container_query: Query<&mut Visibility, With<BossBarContainer>>,
+    mut fill_query: Query<&mut Node, With<BossBarFill>>,
+    mut label_query: Query<&mut Text, With<BossBarLabel>>,
+) {
+    let boss = boss_query.iter().find_map(|(boss, combatant, phases)| {
+        let Combatant::Root { health, .. } = combatant else {
+            return None;
+        };
+        Some((boss, *health, phases))
+    });
+    let Some((boss, health, phases)) = boss else {
+        for mut vis in container_query.iter_mut() {
+            *vis.as_mut() = Visibility::Hidden;
+        }
+        return;
+    };
+    for mut vis in container_query.iter_mut() {
+        *vis.as_mut() = Visibility::Inherited;
+    }
+    let fraction = if health.max > 0.0 {
+        (health.current / health.max).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    for mut node in fill_query.iter_mut() {
+        node.width = Val::Percent(100.0 * fraction);
+    }
+    let label = match phases.and_then(|phases| phases.current_phase()) {
+        Some(phase) if !phase.name.is_empty() => format!("{} - {}", boss.display_name, phase.name),
+        _ => boss.display_name.clone(),
+    };
+    for mut text in label_query.iter_mut() {
+        if text.0 != label {
+            text.0.clone_from(&label);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+use engine::{
+    actors::{DamageTakenEvent, LocalPlayer},
+    camera::MainCamera,
+};
+use interfaces::scheduling::{GameState, LevelSystemSet};
+
+use ui_core::MainCameraUIRoot;
+
+//marks around the crosshair pointing towards whatever hit the player
+pub struct HitIndicatorPlugin;
+
+impl Plugin for HitIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Game), spawn_indicator_container)
+            .add_systems(Update, spawn_hit_indicators.in_set(LevelSystemSet::Main))
+            .add_systems(
+                Update,
+                update_hit_indicators.in_set(LevelSystemSet::Despawn),
+            );
+    }
+}
+
+const INDICATOR_SECS: f32 = 1.0;
+//distance from the center of the screen, in percent of the screen size
+const INDICATOR_RADIUS: f32 = 15.0;
+const INDICATOR_WIDTH_PX: f32 = 8.0;
+const INDICATOR_LENGTH_PX: f32 = 32.0;
+
+#[derive(Component)]
+struct HitIndicatorContainer;
+
+#[derive(Component)]
+struct HitIndicator {
+    //where the hit came from, so the indicator keeps pointing at it as the player turns
+    source: Vec3,
+    timer: Timer,
+}
+
+fn spawn_indicator_container(mut commands: Commands) {
+    commands.spawn((
+        StateScoped(GameState::Game),
+        HitIndicatorContainer,
+        MainCameraUIRoot,
+        PickingBehavior::IGNORE,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        Name::new("UI hit indicators"),
+    ));
+}
+
+fn spawn_hit_indicators(
+    mut reader: EventReader<DamageTakenEvent>,
+    player_query: Query<&GlobalTransform, With<LocalPlayer>>,
+    attacker_query: Query<&GlobalTransform>,
+    container_query: Query<Entity, With<HitIndicatorContainer>>,
+    mut commands: Commands,
+) {
+    let (Ok(player_gtf), Ok(container)) = (player_query.get_single(), container_query.get_single())
+    else {
+        reader.clear();
+        return;
+    };
+    for event in reader.read() {
+        if !player_query.contains(event.target) {
+            continue;
+        }
+        //knockback points away from the attacker, for when the attacker is already gone
+        let source = attacker_query
+            .get(event.attacker)
+            .map(|gtf| gtf.translation())
+            .unwrap_or(player_gtf.translation() - event.knockback_impulse);
+        if (source - player_gtf.translation()).xz() == Vec2::ZERO {
+            continue;
+        }
+        commands.entity(container).with_children(|children| {
+            children.spawn((
+                HitIndicator {
+                    source,
+                    timer: Timer::from_seconds(INDICATOR_SECS, TimerMode::Once),
+                },
+                Node {
+                    width: Val::Px(INDICATOR_WIDTH_PX),
+                    height: Val::Px(INDICATOR_LENGTH_PX),
+                    position_type: PositionType::Absolute,
+                    margin: UiRect::new(
+                        Val::Px(-INDICATOR_WIDTH_PX / 2.0),
+                        Val::ZERO,
+                        Val::Px(-INDICATOR_LENGTH_PX / 2.0),
+                        Val::ZERO,
+                    ),
+                    ..default()
+                },
+                BackgroundColor(Color::Srgba(Srgba::hex("a53030").unwrap())),
+                PickingBehavior::IGNORE,
+            ));
+        });
+    }
+}
+
+fn update_hit_indicators(
+    mut indicator_query: Query<(
+        Entity,
+        &mut HitIndicator,
+        &mut Node,
+        &mut Transform,
+        &mut BackgroundColor,
+    )>,
+    camera_query: Query<&GlobalTransform>,
+    camera: Res<MainCamera>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    let Ok(camera_gtf) = camera_query.get(camera.0) else {
+        return;
+    };
+    let forward = camera_gtf.forward().xz().normalize_or_zero();
+    let right = camera_gtf.right().xz().normalize_or_zero();
+    for (entity, mut indicator, mut node, mut tf, mut color) in indicator_query.iter_mut() {
+        indicator.timer.tick(time.delta());
+        if indicator.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        //clockwise from the top of the screen
+        let to_source = (indicator.source - camera_gtf.translation()).xz();
+        let angle = to_source.dot(right).atan2(to_source.dot(forward));
+        node.left = Val::Percent(50.0 + INDICATOR_RADIUS * angle.sin());
+        node.top = Val::Percent(50.0 - INDICATOR_RADIUS * angle.cos());
+        tf.rotation = Quat::from_rotation_z(-angle);
+        color.0 = color.0.with_alpha(indicator.timer.fraction_remaining());
+    }
+}
@@ -16,26 +16,33 @@ use ui_core::MainCameraUIRoot;
 
 use ui_state::UIState;
 
+mod boss_bar;
+mod hit_indicator;
+
 pub struct PlayerStatsUiPlugin;
 
 impl Plugin for PlayerStatsUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, init)
-            .add_systems(OnEnter(GameState::Game), spawn_ui)
-            .add_systems(
-                PostUpdate,
-                (
-                    // todo - create system set for stat updates and do this after to avoid 1 frame lag
-                    flash_hearts,
-                    flash_stamina.after(send_stamina_updated_events),
-                ),
-            )
-            .add_systems(OnEnter(UIState::Default), show_player_stat_ui)
-            .add_systems(OnExit(UIState::Default), hide_player_stat_ui)
-            .add_systems(
-                Update,
-                (spawn_heart, spawn_stamina).run_if(in_state(LevelLoadState::Loaded)),
-            );
+        app.add_plugins((
+            boss_bar::BossHealthBarPlugin,
+            hit_indicator::HitIndicatorPlugin,
+        ))
+        .add_systems(Startup, init)
+        .add_systems(OnEnter(GameState::Game), spawn_ui)
+        .add_systems(
+            PostUpdate,
+            (
+                // todo - create system set for stat updates and do this after to avoid 1 frame lag
+                flash_hearts,
+                flash_stamina.after(send_stamina_updated_events),
+            ),
+        )
+        .add_systems(OnEnter(UIState::Default), show_player_stat_ui)
+        .add_systems(OnExit(UIState::Default), hide_player_stat_ui)
+        .add_systems(
+            Update,
+            (spawn_heart, spawn_stamina).run_if(in_state(LevelLoadState::Loaded)),
+        );
     }
 }
 
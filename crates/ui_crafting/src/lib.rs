@@ -1,14 +1,20 @@
 use ahash::HashMap;
 use bevy::prelude::*;
-use crafting::*;
+use crafting::{
+    discovery::KnownRecipes,
+    repair::{CachedRepairRecipe, RepairEvent},
+    stations::{CraftingStation, NearbyCraftingStations},
+    *,
+};
 use engine::{
     actors::LocalPlayer,
-    items::{inventory::Inventory, ItemStack},
+    items::{inventory::Inventory, metadata::ItemMetadata, ItemStack},
 };
 use interfaces::scheduling::{GameState, LevelSystemSet};
 use ui_core::{get_text_style, ButtonColors, ExpandOnHover};
 use ui_inventory::{default_slot_background, InventoryResources, SetIconEvent, SLOT_PX};
 use ui_state::UIState;
+use world::events::BlockUsedEvent;
 
 pub struct UICraftingPlugin;
 
@@ -19,11 +25,24 @@ impl Plugin for UICraftingPlugin {
             (
                 spawn_crafting_ui,
                 update_available_recipes,
-                (set_recipe_icon, set_recipe_count),
+                (
+                    set_recipe_icon,
+                    set_recipe_count,
+                    update_recipe_station_text,
+                ),
             )
                 .chain()
                 .in_set(LevelSystemSet::Main),
         )
+        .add_systems(
+            Update,
+            (
+                spawn_repair_rows.after(spawn_crafting_ui),
+                update_repair_rows,
+                open_at_station,
+            )
+                .in_set(LevelSystemSet::Main),
+        )
         .add_systems(OnEnter(UIState::Inventory), show)
         .add_systems(OnExit(UIState::Inventory), hide);
     }
@@ -79,15 +98,26 @@ struct RecipeItemUI {
     stack: ItemStack,
 }
 
+#[derive(Component)]
+struct RecipeStationText {
+    cached_recipe: Entity,
+}
+
 fn update_available_recipes(
-    recipe_query: Query<(Entity, &CachedEntityRecipe)>,
-    inv_query: Query<&Inventory, (With<LocalPlayer>, Changed<Inventory>)>,
+    recipe_query: Query<(Entity, &Recipe, &CachedEntityRecipe)>,
+    inv_query: Query<
+        (&Inventory, Option<&KnownRecipes>),
+        (
+            With<LocalPlayer>,
+            Or<(Changed<Inventory>, Changed<KnownRecipes>)>,
+        ),
+    >,
     recipe_row_query: Query<(Entity, &RecipeRow)>,
     ui_root_query: Query<Entity, With<CraftingUI>>,
     mut commands: Commands,
     mut current_recipe_list: Local<HashMap<Entity, Entity>>,
 ) {
-    let Ok(inv) = inv_query.get_single() else {
+    let Ok((inv, known)) = inv_query.get_single() else {
         return;
     };
     let Ok(ui_root) = ui_root_query.get_single() else {
@@ -101,11 +131,16 @@ fn update_available_recipes(
             .iter()
             .map(|(entity, recipe)| (recipe.cached_recipe, entity)),
     );
-    // display a recipe when we have any input
-    let available_recipes = recipe_query.iter().filter(|(_, r)| r.has_any_input(inv));
+    // display the recipes we've discovered, or any recipe we have an input for if we can't discover recipes
+    let available_recipes = recipe_query
+        .iter()
+        .filter(|(_, recipe, cached)| match known {
+            Some(known) => known.contains(recipe),
+            None => cached.has_any_input(inv),
+        });
     //the idea is to remove all found recipes, so that current_recipe_list will contain excess recipes for us to remove at the end
     let mut root_ec = commands.entity(ui_root);
-    for (recipe_entity, cached_recipe) in available_recipes {
+    for (recipe_entity, _, cached_recipe) in available_recipes {
         if current_recipe_list.remove(&recipe_entity).is_none() {
             //UI row for recipe wasn't found, spawn it
             root_ec.with_child((
@@ -159,9 +194,8 @@ impl Component for RecipeRow {
                 .get::<CachedEntityRecipe>(row.cached_recipe)
                 .cloned()
                 .unwrap();
-            let output = cached_recipe.output;
-            let output_entity = output.id;
-            let output_name = world.get::<Name>(output_entity).unwrap().to_string();
+            let outputs = cached_recipe.outputs.clone();
+            let output_name = world.get::<Name>(outputs[0].id).unwrap().to_string();
             let resources = world.get_resource::<InventoryResources>().cloned().unwrap();
             let text_style = get_text_style(world.get_resource::<AssetServer>().unwrap());
             let mut commands = world.commands();
@@ -184,21 +218,33 @@ impl Component for RecipeRow {
                     },
                 ))
                 .with_children(|children| {
-                    ui_inventory::spawn_item_slot(
-                        children.spawn_empty(),
+                    for output in outputs.iter() {
+                        ui_inventory::spawn_item_slot(
+                            children.spawn_empty(),
+                            Node {
+                                position_type: PositionType::Relative,
+                                margin: UiRect::right(Val::Px(2.)),
+                                ..default_slot_background()
+                            },
+                            PickingBehavior {
+                                // want to be able to scroll the background
+                                should_block_lower: false,
+                                is_hoverable: true,
+                            },
+                            RecipeItemUI { stack: *output },
+                            &resources,
+                        );
+                    }
+                    children.spawn((
                         Node {
-                            position_type: PositionType::Relative,
-                            margin: UiRect::ZERO,
-                            ..default_slot_background()
-                        },
-                        PickingBehavior {
-                            // want to be able to scroll the background
-                            should_block_lower: false,
-                            is_hoverable: true,
+                            height: Val::Px(32.),
+                            bottom: Val::Px(6.),
+                            margin: UiRect::all(Val::Px(2.)),
+                            ..default()
                         },
-                        RecipeItemUI { stack: output },
-                        &resources,
-                    );
+                        Text(output_name),
+                        text_style.clone(),
+                    ));
                     children.spawn((
                         Node {
                             height: Val::Px(32.),
@@ -206,8 +252,11 @@ impl Component for RecipeRow {
                             margin: UiRect::all(Val::Px(2.)),
                             ..default()
                         },
-                        Text(output_name),
+                        Text::default(),
                         text_style.clone(),
+                        RecipeStationText {
+                            cached_recipe: row.cached_recipe,
+                        },
                     ));
                     children
                         .spawn((
@@ -239,18 +288,14 @@ impl Component for RecipeRow {
                             |click: Trigger<Pointer<Down>>,
                              mut writer: EventWriter<CraftEvent>,
                              button_query: Query<&CraftRecipeButton>,
-                             player_query: Query<Entity, With<LocalPlayer>>,
-                             recipe_query: Query<&CachedEntityRecipe>| {
+                             player_query: Query<Entity, With<LocalPlayer>>| {
                                 info!("craft clicked! {:?}", click.entity());
                                 if let Ok(button) = button_query.get(click.entity()) {
                                     if let Ok(player) = player_query.get_single() {
-                                        // would be cool if I could just send the entity as the event (see comment on CraftEvent)
-                                        if let Ok(recipe) = recipe_query.get(button.cached_recipe) {
-                                            writer.send(CraftEvent {
-                                                crafter: player,
-                                                recipe: recipe.clone(),
-                                            });
-                                        }
+                                        writer.send(CraftEvent {
+                                            crafter: player,
+                                            recipe: button.cached_recipe,
+                                        });
                                     }
                                 }
                             },
@@ -288,7 +333,9 @@ impl Component for RecipeRow {
                                 should_block_lower: false,
                                 is_hoverable: true,
                             },
-                            RecipeItemUI { stack: *input },
+                            RecipeItemUI {
+                                stack: input.display_stack(),
+                            },
                             &resources,
                         );
                     }
@@ -297,6 +344,136 @@ impl Component for RecipeRow {
         });
     }
 }
+//shown at the top of the menu while the selected item can be repaired
+#[derive(Component)]
+struct RepairRow {
+    cached_recipe: Entity,
+}
+
+fn spawn_repair_rows(
+    recipe_query: Query<(Entity, &CachedRepairRecipe)>,
+    row_query: Query<&RepairRow>,
+    ui_root_query: Query<Entity, With<CraftingUI>>,
+    resources: Res<InventoryResources>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Ok(ui_root) = ui_root_query.get_single() else {
+        return;
+    };
+    let text_style = get_text_style(&asset_server);
+    for (recipe_entity, cached) in recipe_query.iter() {
+        if row_query
+            .iter()
+            .any(|row| row.cached_recipe == recipe_entity)
+        {
+            continue;
+        }
+        let row = commands
+            .spawn((
+                Name::new("repair row"),
+                Node {
+                    height: Val::Px(BASE_HEIGHT_PX),
+                    margin: UiRect::all(Val::Px(RECIPE_MARGIN_ALL_PX)),
+                    padding: UiRect::all(Val::Px(2.)),
+                    flex_direction: FlexDirection::Row,
+                    display: Display::None,
+                    ..default()
+                },
+                BackgroundColor(Color::hsla(120., 0.3, 0.2, 1.0)),
+                RepairRow {
+                    cached_recipe: recipe_entity,
+                },
+            ))
+            .with_children(|children| {
+                ui_inventory::spawn_item_slot(
+                    children.spawn_empty(),
+                    Node {
+                        position_type: PositionType::Relative,
+                        margin: UiRect::right(Val::Px(2.)),
+                        ..default_slot_background()
+                    },
+                    PickingBehavior::default(),
+                    RecipeItemUI {
+                        stack: cached.material.display_stack(),
+                    },
+                    &resources,
+                );
+                children.spawn((
+                    Node {
+                        height: Val::Px(32.),
+                        bottom: Val::Px(6.),
+                        margin: UiRect::all(Val::Px(2.)),
+                        ..default()
+                    },
+                    Text(format!("+{} durability", cached.amount)),
+                    text_style.clone(),
+                ));
+                children
+                    .spawn((
+                        Button,
+                        ButtonColors::default(),
+                        BorderColor(ButtonColors::default().default_border),
+                        BackgroundColor(ButtonColors::default().default_background),
+                        Node {
+                            border: UiRect::all(Val::Px(2.0)),
+                            align_items: AlignItems::Center,
+                            margin: UiRect::left(Val::Auto),
+                            height: Val::Px(32.),
+                            padding: UiRect::horizontal(Val::Px(5.)),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|button_text| {
+                        button_text.spawn((Text("Repair".to_string()), text_style.clone()));
+                    })
+                    .observe(
+                        move |_: Trigger<Pointer<Down>>,
+                              mut writer: EventWriter<RepairEvent>,
+                              player_query: Query<(Entity, &Inventory), With<LocalPlayer>>| {
+                            if let Ok((player, inventory)) = player_query.get_single() {
+                                writer.send(RepairEvent {
+                                    crafter: player,
+                                    recipe: recipe_entity,
+                                    inventory_slot: inventory.selected_slot(),
+                                });
+                            }
+                        },
+                    );
+            })
+            .id();
+        //repairs go above the crafting recipes
+        commands.entity(ui_root).insert_children(0, &[row]);
+    }
+}
+
+fn update_repair_rows(
+    mut row_query: Query<(&mut Node, &RepairRow)>,
+    recipe_query: Query<&CachedRepairRecipe>,
+    player_query: Query<&Inventory, With<LocalPlayer>>,
+    meta_query: Query<&ItemMetadata>,
+) {
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+    let selected = inventory.selected_item();
+    for (mut node, row) in row_query.iter_mut() {
+        let repairable = recipe_query.get(row.cached_recipe).is_ok_and(|cached| {
+            selected.is_some_and(|stack| {
+                cached.repairs(stack, stack.meta.and_then(|meta| meta_query.get(meta).ok()))
+            })
+        });
+        let display = if repairable {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
 fn set_recipe_icon(
     added: Query<(Entity, &RecipeItemUI), (Added<RecipeItemUI>, With<ImageNode>)>,
     mut writer: EventWriter<SetIconEvent>,
@@ -318,6 +495,52 @@ fn set_recipe_count(
     }
 }
 
+//shows the station the recipe needs (red if it isn't nearby) and how long it takes
+fn update_recipe_station_text(
+    mut text_query: Query<(&mut Text, &mut TextColor, &RecipeStationText)>,
+    recipe_query: Query<&CachedEntityRecipe>,
+    player_query: Query<Option<&NearbyCraftingStations>, With<LocalPlayer>>,
+) {
+    let nearby = player_query.get_single().ok().flatten();
+    for (mut text, mut color, station_text) in text_query.iter_mut() {
+        let Ok(recipe) = recipe_query.get(station_text.cached_recipe) else {
+            continue;
+        };
+        let mut label = match &recipe.station {
+            Some(station) => format!("@{}", station),
+            None => String::new(),
+        };
+        if !recipe.craft_time.is_zero() {
+            label.push_str(&format!(" {:.1}s", recipe.craft_time.as_secs_f32()));
+        }
+        if text.0 != label {
+            text.0 = label;
+        }
+        let new_color = if recipe.has_station(nearby) {
+            TextColor::WHITE
+        } else {
+            TextColor(Color::srgb(1.0, 0.4, 0.4))
+        };
+        if *color != new_color {
+            *color = new_color;
+        }
+    }
+}
+
+//using a crafting station block opens the crafting menu
+fn open_at_station(
+    mut reader: EventReader<BlockUsedEvent>,
+    station_query: Query<(), With<CraftingStation>>,
+    player_query: Query<(), With<LocalPlayer>>,
+    mut next_state: ResMut<NextState<UIState>>,
+) {
+    for used in reader.read() {
+        if station_query.contains(used.block_used) && player_query.contains(used.user) {
+            next_state.set(UIState::Inventory);
+        }
+    }
+}
+
 fn show(mut query: Query<&mut Visibility, With<CraftingUI>>) {
     for mut vis in query.iter_mut() {
         *vis.as_mut() = Visibility::Inherited;
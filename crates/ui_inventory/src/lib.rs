@@ -2,7 +2,7 @@
 
 use ahash::HashMap;
 use bevy::{
-    pbr::ExtendedMaterial,
+    pbr::{ExtendedMaterial, NotShadowCaster},
     prelude::*,
     render::{
         camera::RenderTarget,
@@ -14,17 +14,32 @@ use bevy::{
     ui::RelativeCursorPosition,
     window::PrimaryWindow,
 };
-use interfaces::scheduling::{GameState, LevelSystemSet};
+use interfaces::scheduling::{GameState, LevelLoadState, LevelSystemSet};
 use leafwing_input_manager::prelude::ActionState;
 
 use debug::TextStyle;
 use engine::{
     actors::{LocalPlayer, LocalPlayerSpawnedEvent},
     controllers::{player_controller::CursorLocked, Action},
-    items::{block_item::BlockItem, inventory::Inventory, ItemIcon, ItemStack, MaxStackSize},
+    items::{
+        block_item::BlockItem,
+        building::BuildPreview,
+        container::OpenContainer,
+        equipment::{EquipItemEvent, Equipment, EquipmentSlot, UnequipItemEvent},
+        inventory::{Inventory, InventoryLayout},
+        inventory_actions::{
+            CompactInventoryEvent, QuickStackEvent, SortInventoryEvent, SortOrder, SplitStackEvent,
+            TransferStackEvent,
+        },
+        metadata::ItemMetadata,
+        ItemIcon, ItemStack, MaxStackSize,
+    },
 };
 use materials::TextureArrayExtension;
-use world::{block::BlockMesh, mesher::ChunkMaterial};
+use world::{
+    block::{BlockCoord, BlockMesh},
+    mesher::ChunkMaterial,
+};
 
 use ui_core::MainCameraUIRoot;
 
@@ -32,13 +47,16 @@ use ui_core::get_small_text_style;
 use ui_state::UIState;
 
 pub const SLOTS_PER_ROW: usize = 10;
-pub const HOTBAR_SLOTS: usize = SLOTS_PER_ROW;
 pub const BACKGROUND_COLOR: Color = Color::srgba(0.15, 0.15, 0.15, 0.25);
 
 const MARGIN_PX: f32 = 1.0;
 pub const SLOT_PX: f32 = 32.0;
 const SELECTOR_PADDING_PX: f32 = 1.0;
 const STACK_SIZE_LABEL_PADDING_PX: f32 = 3.0;
+//gap between the inventory grid and the equipment column
+const EQUIPMENT_PANEL_GAP_PX: f32 = 8.0;
+//room for the equipment slots and their labels, open containers go to the right of this
+const EQUIPMENT_PANEL_WIDTH_PX: f32 = 120.0;
 
 pub const BLOCK_PREVIEW_LAYER: RenderLayers = RenderLayers::layer(1);
 
@@ -59,13 +77,20 @@ impl Plugin for UIInventoryPlugin {
                 update_icons,
                 update_mouse_display,
                 player_scroll_inventory,
+                player_hotbar_keys,
+                change_backpack_page.run_if(in_state(UIState::Inventory)),
+                inventory_hotkeys.run_if(in_state(UIState::Inventory)),
+                (spawn_container_ui, despawn_closed_container_ui),
+                (update_build_preview, draw_build_preview_outlines),
             )
                 .in_set(LevelSystemSet::Main),
         )
         .add_systems(PostUpdate, update_icon.in_set(LevelSystemSet::PostUpdate))
         .add_event::<SetIconEvent>()
         .init_resource::<MouseInventory>()
+        .init_resource::<BackpackPage>()
         .add_systems(OnEnter(UIState::Inventory), show_inventory)
+        .add_systems(OnExit(UIState::Inventory), close_container)
         .add_systems(OnEnter(UIState::Default), hide_inventory::<false>)
         .add_systems(OnEnter(UIState::Hidden), hide_inventory::<true>)
         .add_systems(Startup, init);
@@ -89,10 +114,33 @@ struct InventoryUISlotBackground {
     slot: usize,
 }
 
+//backpack slots are only shown with the full inventory, one page at a time
+#[derive(Component, Clone, Copy)]
+struct BackpackPageSlot(usize);
+
+#[derive(Resource, Default)]
+struct BackpackPage(usize);
+
+#[derive(Component)]
+struct BackpackPageLabel;
+
+#[derive(Component)]
+struct EquipmentUI;
+
+#[derive(Component)]
+struct ContainerUI;
+
+#[derive(Component, Clone)]
+struct EquipmentUISlotBackground {
+    owner: Entity,
+    slot: usize,
+}
+
 #[derive(Component, Clone, Copy)]
 enum InventoryUISlot {
     Mouse,
     Entity { inventory: Entity, slot: usize },
+    Equipment { owner: Entity, slot: usize },
 }
 
 impl InventoryUISlot {
@@ -100,9 +148,14 @@ impl InventoryUISlot {
         self,
         mouse: &MouseInventory,
         inventory_query: &Query<&Inventory>,
+        equipment_query: &Query<&Equipment>,
     ) -> Option<ItemStack> {
         match self {
             InventoryUISlot::Mouse => mouse.selected.map(|m| m.stack),
+            InventoryUISlot::Equipment { owner, slot } => equipment_query
+                .get(owner)
+                .ok()
+                .and_then(|equipment| equipment.get(slot)),
             InventoryUISlot::Entity { inventory, slot } => inventory_query
                 .get(inventory)
                 .ok()
@@ -117,7 +170,7 @@ impl InventoryUISlot {
                                 && selected.stack.id == stack.id
                             {
                                 if new_stack_size > 0 {
-                                    Some(ItemStack::new(stack.id, new_stack_size))
+                                    Some(stack.with_size(new_stack_size))
                                 } else {
                                     None
                                 }
@@ -189,19 +242,29 @@ fn toggle_inventory(
 fn spawn_inventory_system(
     mut event_reader: EventReader<LocalPlayerSpawnedEvent>,
     inventory_ui_query: Query<Entity, With<InventoryUI>>,
-    inventory_query: Query<&Inventory, With<LocalPlayer>>,
+    inventory_query: Query<(&Inventory, Option<&Equipment>), With<LocalPlayer>>,
     mut commands: Commands,
     resources: Res<InventoryResources>,
     state: Res<State<UIState>>,
 ) {
     for LocalPlayerSpawnedEvent(id) in event_reader.read() {
         info!("inventory UI trying to spawn from LocalPlayerSpawned event");
-        if let Ok(inv) = inventory_query.get(*id) {
+        if let Ok((inv, equipment)) = inventory_query.get(*id) {
             info!("spawning inventory UI with {} slots!", inv.len());
             for entity in inventory_ui_query.iter() {
                 commands.entity(entity).despawn_recursive();
             }
-            spawn_inventory(&mut commands, *id, inv.len(), &resources);
+            let equipment_slots: Vec<EquipmentSlot> = equipment
+                .map(|equipment| equipment.iter().map(|(slot, _)| slot).collect())
+                .unwrap_or_default();
+            commands.insert_resource(BackpackPage::default());
+            spawn_inventory(
+                &mut commands,
+                *id,
+                inv.layout(),
+                &equipment_slots,
+                &resources,
+            );
             match state.get() {
                 UIState::Hidden => commands.run_system_cached(hide_inventory::<true>),
                 UIState::Default => commands.run_system_cached(hide_inventory::<false>),
@@ -219,25 +282,58 @@ fn show_inventory(
         &mut Visibility,
         (With<InventoryUISlotBackground>, With<InventoryUI>),
     >,
-    mut slot_query: Query<&mut Visibility, (With<InventoryUISlotBackground>, Without<InventoryUI>)>,
+    mut slot_query: Query<
+        (&mut Visibility, Option<&BackpackPageSlot>),
+        (With<InventoryUISlotBackground>, Without<InventoryUI>),
+    >,
+    mut equipment_query: Query<
+        &mut Visibility,
+        (
+            With<EquipmentUI>,
+            Without<InventoryUI>,
+            Without<InventoryUISlotBackground>,
+        ),
+    >,
+    page: Res<BackpackPage>,
 ) {
     for mut vis in inventory_query.iter_mut() {
         info!("showing inventory");
         *vis.as_mut() = Visibility::Inherited;
     }
-    //display all slots
-    for mut slot in slot_query.iter_mut() {
-        *slot.as_mut() = Visibility::Inherited;
+    for mut vis in equipment_query.iter_mut() {
+        *vis.as_mut() = Visibility::Inherited;
+    }
+    //display all slots except other backpack pages
+    for (mut vis, backpack_page) in slot_query.iter_mut() {
+        *vis.as_mut() = match backpack_page {
+            Some(BackpackPageSlot(slot_page)) if *slot_page != page.0 => Visibility::Hidden,
+            _ => Visibility::Inherited,
+        };
     }
 }
 
 fn hide_inventory<const HIDE_HOTBAR: bool>(
-    mut slot_query: Query<(&mut Visibility, &InventoryUISlotBackground), Without<InventoryUI>>,
+    mut slot_query: Query<
+        (&mut Visibility, Has<BackpackPageSlot>),
+        (With<InventoryUISlotBackground>, Without<InventoryUI>),
+    >,
     mut inventory_query: Query<
         &mut Visibility,
         (Without<InventoryUISlotBackground>, With<InventoryUI>),
     >,
+    mut equipment_query: Query<
+        &mut Visibility,
+        (
+            With<EquipmentUI>,
+            Without<InventoryUI>,
+            Without<InventoryUISlotBackground>,
+        ),
+    >,
 ) {
+    //equipment is only shown with the full inventory
+    for mut vis in equipment_query.iter_mut() {
+        *vis.as_mut() = Visibility::Hidden;
+    }
     for mut vis in inventory_query.iter_mut() {
         info!("hiding inventory");
         *vis.as_mut() = if HIDE_HOTBAR {
@@ -246,9 +342,9 @@ fn hide_inventory<const HIDE_HOTBAR: bool>(
             Visibility::Inherited
         };
     }
-    for (mut vis, slot) in slot_query.iter_mut() {
+    for (mut vis, backpack) in slot_query.iter_mut() {
         //make sure hotbar slots are shown if needed
-        if !HIDE_HOTBAR && slot.slot < HOTBAR_SLOTS {
+        if !HIDE_HOTBAR && !backpack {
             *vis.as_mut() = Visibility::Inherited;
         } else {
             //hide everything else (or hotbar slots too if HIDE_HOTBAR is true)
@@ -331,7 +427,8 @@ pub fn default_slot_background() -> Node {
 fn spawn_inventory(
     commands: &mut Commands,
     owner: Entity,
-    slots: usize,
+    layout: InventoryLayout,
+    equipment_slots: &[EquipmentSlot],
     resources: &InventoryResources,
 ) {
     let background = default_slot_background();
@@ -374,10 +471,14 @@ fn spawn_inventory(
         ))
         .with_children(|slot_background| {
             //spawn rows - round up number of rows
-            for slot in 0..slots {
-                let slot_coords = get_slot_coords(slot, 0.0);
+            for slot in 0..layout.slots() {
+                let slot_coords = get_slot_coords(slot_display_index(layout, slot), 0.0);
+                let mut ec = slot_background.spawn_empty();
+                if let Some(page) = layout.page_of(slot) {
+                    ec.insert(BackpackPageSlot(page));
+                }
                 spawn_item_slot(
-                    slot_background.spawn_empty(),
+                    ec,
                     Node {
                         left: slot_coords.left,
                         right: slot_coords.right,
@@ -398,6 +499,65 @@ fn spawn_inventory(
                 .observe(slot_clicked);
             }
         })
+        .with_children(|equipment_panel| {
+            const STRIDE: f32 = MARGIN_PX + SLOT_PX;
+            equipment_panel
+                .spawn((
+                    Name::new("Equipment UI"),
+                    EquipmentUI,
+                    Visibility::Hidden,
+                    PickingBehavior::IGNORE,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(SLOTS_PER_ROW as f32 * STRIDE + EQUIPMENT_PANEL_GAP_PX),
+                        ..default()
+                    },
+                ))
+                .with_children(|equipment_slot| {
+                    for (slot, slot_type) in equipment_slots.iter().enumerate() {
+                        let top = Val::Px(MARGIN_PX + slot as f32 * STRIDE);
+                        spawn_item_slot(
+                            equipment_slot.spawn_empty(),
+                            Node {
+                                left: Val::Px(MARGIN_PX),
+                                top,
+                                ..background.clone()
+                            },
+                            (EquipmentUISlotBackground { owner, slot },),
+                            InventoryUISlot::Equipment { owner, slot },
+                            resources,
+                        )
+                        .observe(equipment_slot_clicked);
+                        equipment_slot.spawn((
+                            Text::new(slot_type.display_name()),
+                            resources.item_counts.clone(),
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(MARGIN_PX + STRIDE + MARGIN_PX),
+                                top,
+                                ..default()
+                            },
+                            PickingBehavior::IGNORE,
+                        ));
+                    }
+                    if layout.pages > 1 {
+                        equipment_slot.spawn((
+                            Text::new(page_label(0, layout.pages)),
+                            resources.item_counts.clone(),
+                            BackpackPageLabel,
+                            Node {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(MARGIN_PX),
+                                top: Val::Px(
+                                    MARGIN_PX + equipment_slots.len() as f32 * STRIDE + MARGIN_PX,
+                                ),
+                                ..default()
+                            },
+                            PickingBehavior::IGNORE,
+                        ));
+                    }
+                });
+        })
         .with_children(|selector| {
             selector.spawn((
                 Node {
@@ -414,6 +574,116 @@ fn spawn_inventory(
     info!("inventory spawned!")
 }
 
+//shows the container the local player opened next to their inventory
+fn spawn_container_ui(
+    player_query: Query<&OpenContainer, (With<LocalPlayer>, Changed<OpenContainer>)>,
+    container_query: Query<&Inventory>,
+    inventory_ui_query: Query<Entity, With<InventoryUI>>,
+    container_ui_query: Query<Entity, With<ContainerUI>>,
+    resources: Res<InventoryResources>,
+    mut next_state: ResMut<NextState<UIState>>,
+    mut commands: Commands,
+) {
+    let Ok(OpenContainer(container)) = player_query.get_single() else {
+        return;
+    };
+    let (Ok(inventory), Ok(inventory_ui)) = (
+        container_query.get(*container),
+        inventory_ui_query.get_single(),
+    ) else {
+        return;
+    };
+    for entity in container_ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    const STRIDE: f32 = MARGIN_PX + SLOT_PX;
+    let background = default_slot_background();
+    commands.entity(inventory_ui).with_children(|panel| {
+        panel
+            .spawn((
+                Name::new("Container UI"),
+                ContainerUI,
+                PickingBehavior::IGNORE,
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(
+                        SLOTS_PER_ROW as f32 * STRIDE
+                            + EQUIPMENT_PANEL_GAP_PX
+                            + EQUIPMENT_PANEL_WIDTH_PX,
+                    ),
+                    ..default()
+                },
+            ))
+            .with_children(|slot_background| {
+                for slot in 0..inventory.len() {
+                    let slot_coords = get_slot_coords(slot, 0.0);
+                    spawn_item_slot(
+                        slot_background.spawn_empty(),
+                        Node {
+                            left: slot_coords.left,
+                            top: slot_coords.top,
+                            ..background.clone()
+                        },
+                        (InventoryUISlotBackground {
+                            slot,
+                            inventory: *container,
+                        },),
+                        InventoryUISlot::Entity {
+                            slot,
+                            inventory: *container,
+                        },
+                        &resources,
+                    )
+                    .observe(slot_clicked);
+                }
+            });
+    });
+    next_state.set(UIState::Inventory);
+}
+
+//containers can also be closed by walking away or the block being removed
+fn despawn_closed_container_ui(
+    mut removed: RemovedComponents<OpenContainer>,
+    player_query: Query<(), (With<LocalPlayer>, Without<OpenContainer>)>,
+    container_ui_query: Query<Entity, With<ContainerUI>>,
+    mut mouse: ResMut<MouseInventory>,
+    mut commands: Commands,
+) {
+    if !removed.read().any(|entity| player_query.contains(entity)) {
+        return;
+    }
+    for entity in container_ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    //the mouse could be holding items from the container
+    mouse.clear();
+}
+
+fn close_container(
+    player_query: Query<Entity, (With<LocalPlayer>, With<OpenContainer>)>,
+    container_ui_query: Query<Entity, With<ContainerUI>>,
+    mut commands: Commands,
+) {
+    for entity in player_query.iter() {
+        commands.entity(entity).remove::<OpenContainer>();
+    }
+    //despawn now instead of waiting for the removal so the slots don't show up with the hotbar
+    for entity in container_ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+//where the slot goes in the grid. every backpack page is drawn in the same place under the hotbar
+fn slot_display_index(layout: InventoryLayout, slot: usize) -> usize {
+    match layout.page_of(slot) {
+        Some(page) => {
+            layout.hotbar_slots.div_ceil(SLOTS_PER_ROW) * SLOTS_PER_ROW + slot
+                - layout.page_range(page).start
+        }
+        None => slot,
+    }
+}
+
 fn get_slot_coords(slot: usize, offset_px: f32) -> UiRect {
     let row = slot / SLOTS_PER_ROW;
     let col = slot % SLOTS_PER_ROW;
@@ -448,10 +718,11 @@ fn update_counts(
         &InventoryUISlotText,
     )>,
     inventory_query: Query<&Inventory>,
+    equipment_query: Query<&Equipment>,
     mouse: Res<MouseInventory>,
 ) {
     for (mut vis, mut text, ui_slot, prev_value) in label_query.iter_mut() {
-        match ui_slot.get_stack(&mouse, &inventory_query) {
+        match ui_slot.get_stack(&mouse, &inventory_query, &equipment_query) {
             Some(stack) => {
                 *vis.as_mut() = Visibility::Inherited;
                 if prev_value.0 != stack.size {
@@ -473,11 +744,12 @@ fn update_icons(
         &mut InventoryUISlotIcon,
     )>,
     inventory_query: Query<&Inventory>,
+    equipment_query: Query<&Equipment>,
     mouse: Res<MouseInventory>,
     mut set_icon_writer: EventWriter<SetIconEvent>,
 ) {
     for (ui_entity, mut vis, ui_slot, mut icon) in icon_query.iter_mut() {
-        let Some(stack) = ui_slot.get_stack(&mouse, &inventory_query) else {
+        let Some(stack) = ui_slot.get_stack(&mouse, &inventory_query, &equipment_query) else {
             icon.0 = None;
             *vis.as_mut() = Visibility::Hidden;
             continue;
@@ -637,6 +909,109 @@ fn spawn_block_preview(
     (entity, image_handle)
 }
 
+//ghost blocks in the world where the held block item would be placed
+#[derive(Component)]
+struct BuildPreviewGhost;
+
+//slightly smaller than a block so the ghosts don't z-fight with neighbouring terrain
+const BUILD_PREVIEW_SCALE: f32 = 0.9;
+
+fn update_build_preview(
+    preview_query: Query<&BuildPreview, (With<LocalPlayer>, Changed<BuildPreview>)>,
+    mut ghost_query: Query<
+        (
+            &mut BlockPreview,
+            &mut Mesh3d,
+            &mut MeshMaterial3d<ExtendedMaterial<StandardMaterial, TextureArrayExtension>>,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        With<BuildPreviewGhost>,
+    >,
+    block_mesh_query: Query<&BlockMesh>,
+    materials: Res<ChunkMaterial>,
+    mut commands: Commands,
+) {
+    let Ok(preview) = preview_query.get_single() else {
+        return;
+    };
+    let mesh = preview.block.and_then(|block| {
+        block_mesh_query.get(block).ok().and_then(|block_mesh| {
+            let material = if block_mesh.use_transparent_shader {
+                materials.transparent_material.clone()
+            } else {
+                materials.opaque_material.clone()
+            };
+            Some((block, block_mesh.single_mesh.as_ref()?.0.clone(), material?))
+        })
+    });
+    //blocked cells get an outline instead
+    let mut free_cells = preview
+        .cells
+        .iter()
+        .filter(|(_, free)| *free)
+        .map(|(coord, _)| *coord);
+    let ghost_transform = |coord: BlockCoord| {
+        Transform::from_translation(
+            coord.to_vec3() + Vec3::splat(0.5 * (1.0 - BUILD_PREVIEW_SCALE)),
+        )
+        .with_scale(Vec3::splat(BUILD_PREVIEW_SCALE))
+    };
+    //reuse the ghosts we already have, hiding any that are left over
+    for (mut block_preview, mut ghost_mesh, mut ghost_material, mut tf, mut vis) in
+        ghost_query.iter_mut()
+    {
+        match (&mesh, free_cells.next()) {
+            (Some((block, mesh, material)), Some(coord)) => {
+                block_preview.0 = *block;
+                ghost_mesh.0 = mesh.clone();
+                ghost_material.0 = material.clone();
+                *tf = ghost_transform(coord);
+                *vis = Visibility::Inherited;
+            }
+            _ => *vis = Visibility::Hidden,
+        }
+    }
+    let Some((block, mesh, material)) = mesh else {
+        return;
+    };
+    for coord in free_cells {
+        commands.spawn((
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            ghost_transform(coord),
+            BlockPreview(block),
+            BuildPreviewGhost,
+            NotShadowCaster,
+            StateScoped(LevelLoadState::Loaded),
+        ));
+    }
+}
+
+//outlines the cells that are already filled and the anchor of the fill modes
+fn draw_build_preview_outlines(
+    preview_query: Query<&BuildPreview, With<LocalPlayer>>,
+    mut gizmos: Gizmos,
+) {
+    const BLOCKED_COLOR: Color = Color::srgba(1.0, 0.2, 0.2, 0.5);
+    const ANCHOR_COLOR: Color = Color::srgba(0.2, 0.6, 1.0, 0.8);
+    let Ok(preview) = preview_query.get_single() else {
+        return;
+    };
+    if preview.block.is_none() {
+        return;
+    }
+    for (coord, _) in preview.cells.iter().filter(|(_, free)| !*free) {
+        gizmos.cuboid(Transform::from_translation(coord.center()), BLOCKED_COLOR);
+    }
+    if let Some(anchor) = preview.anchor {
+        gizmos.cuboid(
+            Transform::from_translation(anchor.center()).with_scale(Vec3::splat(1.05)),
+            ANCHOR_COLOR,
+        );
+    }
+}
+
 fn update_mouse_display(
     mouse: Res<MouseInventory>,
     mut parent_query: Query<&mut Node, With<MouseInventoryVisual>>,
@@ -666,6 +1041,12 @@ fn slot_clicked(
     mut mouse: ResMut<MouseInventory>,
     stack_query: Query<&MaxStackSize>,
     focused: Res<CursorLocked>,
+    player_query: Query<(Entity, Option<&OpenContainer>), With<LocalPlayer>>,
+    action: Res<ActionState<Action>>,
+    mut transfer_writer: EventWriter<TransferStackEvent>,
+    mut split_writer: EventWriter<SplitStackEvent>,
+    meta_query: Query<&ItemMetadata>,
+    mut commands: Commands,
 ) {
     if focused.0 {
         return;
@@ -673,6 +1054,37 @@ fn slot_clicked(
     let Ok((target_slot, cursor_offset, slot_node)) = slot_query.get(trigger.entity()) else {
         return;
     };
+    //modified clicks on an empty mouse act on the whole slot instead of picking it up
+    if mouse.selected.is_none()
+        && trigger.button == PointerButton::Primary
+        && let Ok((player, open_container)) = player_query.get_single()
+    {
+        if action.pressed(&Action::QuickMoveStack) {
+            //alt-click moves between the player and the open container
+            let to = if target_slot.inventory == player {
+                open_container.map(|OpenContainer(container)| *container)
+            } else {
+                Some(player)
+            };
+            if let Some(to) = to {
+                transfer_writer.send(TransferStackEvent {
+                    user: player,
+                    from: target_slot.inventory,
+                    slot: target_slot.slot,
+                    to,
+                });
+            }
+            return;
+        }
+        if action.pressed(&Action::SplitStack) {
+            split_writer.send(SplitStackEvent {
+                user: player,
+                inventory: target_slot.inventory,
+                slot: target_slot.slot,
+            });
+            return;
+        }
+    }
     let mut moved_out_of_mouse = 0;
     match mouse.selected.as_mut() {
         Some(selected) => {
@@ -682,6 +1094,12 @@ fn slot_clicked(
             // middle click = stack half?
             // feels weird since middle/right click are reversed, but feel like it's more useful to pick up half a stack and drop one item at a time.
 
+            let desired_move_count = match trigger.button {
+                PointerButton::Primary => u32::MAX,
+                PointerButton::Secondary => 1,
+                PointerButton::Middle => selected.stack.size.div_ceil(2),
+            }
+            .min(selected.stack.size);
             // 2 cases - either swapping within one inventory, or moving between two inventories:
             if selected.inventory == target_slot.inventory {
                 // only 1 inventory involved
@@ -697,18 +1115,14 @@ fn slot_clicked(
                     return;
                 }
                 let target_item = inventory.get(target_slot.slot);
-                let desired_move_count = match trigger.button {
-                    PointerButton::Primary => u32::MAX,
-                    PointerButton::Secondary => 1,
-                    PointerButton::Middle => selected.stack.size.div_ceil(2),
-                }
-                .min(selected.stack.size);
                 moved_out_of_mouse = if selected.slot != target_slot.slot {
                     inventory.move_items(
                         selected.slot,
                         target_slot.slot,
                         desired_move_count,
                         &stack_query,
+                        &meta_query,
+                        &mut commands,
                     )
                 } else {
                     // if we're moving back to the spot we picked up from, we only have to update the mouse
@@ -728,15 +1142,44 @@ fn slot_clicked(
                     }
                 }
             } else {
-                // swapping between two inventories
-                let Ok([_target_inventory, _mouse_inventory]) =
+                // swapping between two inventories (like the player and an open container)
+                let Ok([mut target_inventory, mut mouse_inventory]) =
                     inventory_query.get_many_mut([target_slot.inventory, selected.inventory])
                 else {
                     //invalid inventory, clear the mouse
                     mouse.clear();
                     return;
                 };
-                todo!("Inventory UI needs to support multiple inventories");
+                if !matches!(mouse_inventory.get(selected.slot), Some(x) if x.id == selected.stack.id)
+                {
+                    mouse.clear();
+                    return;
+                }
+                let target_item = target_inventory.get(target_slot.slot);
+                moved_out_of_mouse = mouse_inventory.move_items_to(
+                    selected.slot,
+                    &mut target_inventory,
+                    target_slot.slot,
+                    desired_move_count,
+                    &stack_query,
+                    &meta_query,
+                    &mut commands,
+                );
+                if moved_out_of_mouse == 0 && trigger.button == PointerButton::Primary {
+                    //the mouse keeps pointing at the slot it picked up from, which now has the target's items
+                    mouse_inventory.swap_slot_with(
+                        selected.slot,
+                        &mut target_inventory,
+                        target_slot.slot,
+                    );
+                    if let Some(target) = target_item {
+                        selected.stack = target;
+                        selected.click_offset =
+                            cursor_offset.normalized.unwrap_or_default() * slot_node.size();
+                    } else {
+                        moved_out_of_mouse = u32::MAX;
+                    }
+                }
             }
         }
         None => {
@@ -756,7 +1199,7 @@ fn slot_clicked(
                 mouse.selected = if new_size > 0 {
                     Some(MouseInventorySelected {
                         slot: target_slot.slot,
-                        stack: ItemStack::new(stack.id, new_size),
+                        stack: stack.with_size(new_size),
                         inventory: target_slot.inventory,
                         click_offset: cursor_offset.normalized.unwrap_or_default()
                             * slot_node.size(),
@@ -776,7 +1219,7 @@ fn slot_clicked(
                     None
                 } else {
                     Some(MouseInventorySelected {
-                        stack: ItemStack::new(selected.stack.id, new_size),
+                        stack: selected.stack.with_size(new_size),
                         ..selected
                     })
                 }
@@ -786,6 +1229,98 @@ fn slot_clicked(
     }
 }
 
+fn equipment_slot_clicked(
+    trigger: Trigger<Pointer<Click>>,
+    slot_query: Query<&EquipmentUISlotBackground>,
+    mut mouse: ResMut<MouseInventory>,
+    focused: Res<CursorLocked>,
+    mut equip_writer: EventWriter<EquipItemEvent>,
+    mut unequip_writer: EventWriter<UnequipItemEvent>,
+) {
+    if focused.0 {
+        return;
+    }
+    let Ok(target_slot) = slot_query.get(trigger.entity()) else {
+        return;
+    };
+    match mouse.selected {
+        Some(selected) => {
+            if selected.inventory != target_slot.owner {
+                return;
+            }
+            //items that don't fit the slot are rejected by the equip system and just stay in the inventory
+            equip_writer.send(EquipItemEvent {
+                entity: target_slot.owner,
+                inventory_slot: selected.slot,
+                equipment_slot: Some(target_slot.slot),
+            });
+            mouse.clear();
+        }
+        None => {
+            unequip_writer.send(UnequipItemEvent {
+                entity: target_slot.owner,
+                equipment_slot: target_slot.slot,
+                inventory_slot: None,
+            });
+        }
+    }
+}
+
+//sorting and compacting apply to the player's backpack and any open container
+fn inventory_hotkeys(
+    player_query: Query<(Entity, &Inventory, Option<&OpenContainer>), With<LocalPlayer>>,
+    action: Res<ActionState<Action>>,
+    mut sort_writer: EventWriter<SortInventoryEvent>,
+    mut compact_writer: EventWriter<CompactInventoryEvent>,
+    mut quick_stack_writer: EventWriter<QuickStackEvent>,
+    mut mouse: ResMut<MouseInventory>,
+) {
+    let Ok((player, inventory, open_container)) = player_query.get_single() else {
+        return;
+    };
+    let hotbar_slots = inventory.layout().hotbar_slots;
+    let targets = [
+        Some((player, hotbar_slots)),
+        open_container.map(|c| (c.0, 0)),
+    ];
+    if action.just_pressed(&Action::SortInventory) || action.just_pressed(&Action::CompactInventory)
+    {
+        //the held slot is about to move
+        mouse.clear();
+    }
+    if action.just_pressed(&Action::SortInventory) {
+        //hold the quick move key to sort alphabetically
+        let order = if action.pressed(&Action::QuickMoveStack) {
+            SortOrder::Name
+        } else {
+            SortOrder::Category
+        };
+        for (inventory, first_slot) in targets.into_iter().flatten() {
+            sort_writer.send(SortInventoryEvent {
+                user: player,
+                inventory,
+                order,
+                first_slot,
+            });
+        }
+    }
+    if action.just_pressed(&Action::CompactInventory) {
+        for (inventory, first_slot) in targets.into_iter().flatten() {
+            compact_writer.send(CompactInventoryEvent {
+                user: player,
+                inventory,
+                first_slot,
+            });
+        }
+    }
+    if action.just_pressed(&Action::QuickStack) {
+        quick_stack_writer.send(QuickStackEvent {
+            user: player,
+            first_slot: hotbar_slots,
+        });
+    }
+}
+
 fn player_scroll_inventory(
     mut query: Query<&mut Inventory, With<LocalPlayer>>,
     focused: Res<CursorLocked>,
@@ -804,8 +1339,67 @@ fn player_scroll_inventory(
         } else {
             0
         };
-        let curr_slot = inv.selected_slot();
-        let new_slot = (curr_slot as i32 + slot_diff).rem_euclid(HOTBAR_SLOTS as i32);
-        inv.select_slot(new_slot);
+        if slot_diff != 0 {
+            let curr_slot = inv.selected_slot();
+            inv.select_slot(curr_slot as i32 + slot_diff);
+        }
     }
 }
+
+fn player_hotbar_keys(
+    mut query: Query<&mut Inventory, With<LocalPlayer>>,
+    action: Res<ActionState<Action>>,
+) {
+    let Ok(mut inv) = query.get_single_mut() else {
+        return;
+    };
+    for slot in 0..inv.layout().hotbar_slots.min(u8::MAX as usize) {
+        if action.just_pressed(&Action::SelectHotbarSlot(slot as u8)) {
+            inv.select_slot(slot as i32);
+        }
+    }
+}
+
+//scrolling with the full inventory open flips through backpack pages
+fn change_backpack_page(
+    inventory_query: Query<&Inventory, With<LocalPlayer>>,
+    mut slot_query: Query<(&mut Visibility, &BackpackPageSlot)>,
+    mut label_query: Query<&mut Text, With<BackpackPageLabel>>,
+    mut page: ResMut<BackpackPage>,
+    action: Res<ActionState<Action>>,
+) {
+    let Ok(inv) = inventory_query.get_single() else {
+        return;
+    };
+    let pages = inv.layout().pages;
+    if pages == 0 {
+        return;
+    }
+    const SCROLL_SENSITIVITY: f32 = 0.05;
+    let delta = action.value(&Action::Scroll);
+    let page_diff = if delta > SCROLL_SENSITIVITY {
+        -1
+    } else if delta < -SCROLL_SENSITIVITY {
+        1
+    } else {
+        0
+    };
+    let new_page = (page.0 as i32 + page_diff).rem_euclid(pages as i32) as usize;
+    if new_page != page.0 {
+        page.0 = new_page;
+        for (mut vis, BackpackPageSlot(slot_page)) in slot_query.iter_mut() {
+            *vis = if *slot_page == new_page {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+        for mut text in label_query.iter_mut() {
+            text.0 = page_label(new_page, pages);
+        }
+    }
+}
+
+fn page_label(page: usize, pages: usize) -> String {
+    format!("Page {}/{}", page + 1, pages)
+}
@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+use engine::{
+    actors::LocalPlayer,
+    items::{
+        inventory::Inventory,
+        shop::{Currency, OpenShop, ShopOfferKind, ShopStock, TradeEvent},
+        ItemStack,
+    },
+};
+use interfaces::scheduling::{GameState, LevelSystemSet};
+use ui_core::{get_small_text_style, get_text_style, ButtonColors};
+use ui_inventory::{default_slot_background, InventoryResources, SetIconEvent};
+use ui_state::UIState;
+
+pub struct UIShopPlugin;
+
+impl Plugin for UIShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_shop_ui,
+                despawn_closed_shop_ui,
+                (set_offer_icon, set_offer_count, update_coin_text),
+            )
+                .chain()
+                .in_set(LevelSystemSet::Main),
+        )
+        .add_systems(OnExit(UIState::Inventory), close_shop);
+    }
+}
+
+const UI_WIDTH_PX: f32 = 300.;
+//width of the crafting menu, which the shop sits next to
+const CRAFTING_UI_WIDTH_PX: f32 = 360.;
+const ROW_MARGIN_PX: f32 = 4.;
+
+#[derive(Component)]
+struct ShopUI;
+
+#[derive(Component)]
+struct ShopCoinText;
+
+#[derive(Component, Clone)]
+struct OfferItemUI {
+    stack: ItemStack,
+}
+
+#[derive(Component)]
+struct TradeButton {
+    shop: Entity,
+    offer: usize,
+}
+
+//rebuilt when the local player opens a shop or the shop's stock changes
+fn spawn_shop_ui(
+    player_query: Query<Ref<OpenShop>, With<LocalPlayer>>,
+    stock_query: Query<Ref<ShopStock>>,
+    name_query: Query<&Name>,
+    ui_query: Query<Entity, With<ShopUI>>,
+    resources: Res<InventoryResources>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<UIState>>,
+    mut commands: Commands,
+) {
+    let Ok(open) = player_query.get_single() else {
+        return;
+    };
+    let OpenShop(shop) = *open;
+    let Ok(stock) = stock_query.get(shop) else {
+        return;
+    };
+    if !open.is_changed() && !stock.is_changed() {
+        return;
+    }
+    if open.is_changed() {
+        next_state.set(UIState::Inventory);
+    }
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let text_style = get_text_style(&asset_server);
+    let small_text_style = get_small_text_style(&asset_server);
+    commands
+        .spawn((
+            Name::new("shop ui"),
+            Node {
+                position_type: PositionType::Absolute,
+                right: Val::Px(CRAFTING_UI_WIDTH_PX),
+                width: Val::Px(UI_WIDTH_PX),
+                max_height: Val::Percent(100.),
+                border: UiRect::all(Val::Px(2.)),
+                flex_direction: FlexDirection::Column,
+                overflow: Overflow::scroll_y(),
+                ..default()
+            },
+            BackgroundColor(Color::hsla(40., 0.3, 0.4, 0.7)),
+            BorderColor(Color::hsla(40., 0.3, 0.2, 1.0)),
+            ShopUI,
+            StateScoped(GameState::Game),
+        ))
+        .with_children(|root| {
+            root.spawn((
+                Node {
+                    margin: UiRect::all(Val::Px(ROW_MARGIN_PX)),
+                    ..default()
+                },
+                Text::default(),
+                text_style.clone(),
+                ShopCoinText,
+            ));
+            for (index, offer) in stock.offers.iter().enumerate() {
+                let name = name_query
+                    .get(offer.item)
+                    .map_or(String::new(), |name| name.to_string());
+                let (verb, price) = match offer.kind {
+                    ShopOfferKind::Buy => ("Buy", format!("costs {}", offer.price)),
+                    ShopOfferKind::Sell => ("Sell", format!("pays {}", offer.price)),
+                };
+                root.spawn((
+                    Name::new("offer row"),
+                    Node {
+                        margin: UiRect::all(Val::Px(ROW_MARGIN_PX)),
+                        padding: UiRect::all(Val::Px(2.)),
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(Color::hsla(0., 0., 0.2, 1.0)),
+                ))
+                .with_children(|row| {
+                    ui_inventory::spawn_item_slot(
+                        row.spawn_empty(),
+                        Node {
+                            position_type: PositionType::Relative,
+                            margin: UiRect::right(Val::Px(2.)),
+                            ..default_slot_background()
+                        },
+                        PickingBehavior::default(),
+                        OfferItemUI {
+                            stack: ItemStack::new(offer.item, offer.count),
+                        },
+                        &resources,
+                    );
+                    row.spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    })
+                    .with_children(|labels| {
+                        labels.spawn((Text(name), small_text_style.clone()));
+                        labels.spawn((
+                            Text(format!("{} ({} left)", price, offer.uses_left)),
+                            small_text_style.clone(),
+                        ));
+                    });
+                    row.spawn((
+                        Button,
+                        ButtonColors::default(),
+                        BorderColor(ButtonColors::default().default_border),
+                        BackgroundColor(ButtonColors::default().default_background),
+                        Node {
+                            border: UiRect::all(Val::Px(2.0)),
+                            align_items: AlignItems::Center,
+                            margin: UiRect::left(Val::Auto),
+                            height: Val::Px(32.),
+                            padding: UiRect::horizontal(Val::Px(5.)),
+                            ..default()
+                        },
+                        TradeButton { shop, offer: index },
+                    ))
+                    .with_children(|button_text| {
+                        button_text.spawn((Text(verb.to_string()), text_style.clone()));
+                    })
+                    .observe(
+                        |click: Trigger<Pointer<Down>>,
+                         mut writer: EventWriter<TradeEvent>,
+                         button_query: Query<&TradeButton>,
+                         player_query: Query<Entity, With<LocalPlayer>>| {
+                            if let (Ok(button), Ok(player)) =
+                                (button_query.get(click.entity()), player_query.get_single())
+                            {
+                                writer.send(TradeEvent {
+                                    user: player,
+                                    shop: button.shop,
+                                    offer: button.offer,
+                                });
+                            }
+                        },
+                    );
+                });
+            }
+        });
+}
+
+//shops can also be closed by walking away or the block being removed
+fn despawn_closed_shop_ui(
+    mut removed: RemovedComponents<OpenShop>,
+    player_query: Query<(), (With<LocalPlayer>, Without<OpenShop>)>,
+    ui_query: Query<Entity, With<ShopUI>>,
+    mut commands: Commands,
+) {
+    if !removed.read().any(|entity| player_query.contains(entity)) {
+        return;
+    }
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn close_shop(
+    player_query: Query<Entity, (With<LocalPlayer>, With<OpenShop>)>,
+    ui_query: Query<Entity, With<ShopUI>>,
+    mut commands: Commands,
+) {
+    for entity in player_query.iter() {
+        commands.entity(entity).remove::<OpenShop>();
+    }
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn set_offer_icon(
+    added: Query<(Entity, &OfferItemUI), (Added<OfferItemUI>, With<ImageNode>)>,
+    mut writer: EventWriter<SetIconEvent>,
+) {
+    for (icon, item) in added.iter() {
+        writer.send(SetIconEvent {
+            item: item.stack.id,
+            ui_image: icon,
+        });
+    }
+}
+
+fn set_offer_count(
+    mut added: Query<(&OfferItemUI, &mut Text, &mut Visibility), Added<OfferItemUI>>,
+) {
+    for (item, mut text, mut vis) in added.iter_mut() {
+        text.0 = item.stack.size.to_string();
+        *vis.as_mut() = Visibility::Inherited;
+    }
+}
+
+fn update_coin_text(
+    mut text_query: Query<&mut Text, With<ShopCoinText>>,
+    player_query: Query<&Inventory, With<LocalPlayer>>,
+    currency_query: Query<&Currency>,
+) {
+    let Ok(inventory) = player_query.get_single() else {
+        return;
+    };
+    let label = format!("Coins: {}", inventory.currency(&currency_query));
+    for mut text in text_query.iter_mut() {
+        if text.0 != label {
+            text.0 = label.clone();
+        }
+    }
+}
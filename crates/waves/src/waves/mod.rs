@@ -1,4 +1,4 @@
-use std::{f32::consts::PI, time::Duration};
+use std::{f32::consts::PI, sync::Arc, time::Duration};
 
 use bevy::prelude::*;
 use interfaces::scheduling::LevelSystemSet;
@@ -33,7 +33,38 @@ impl Plugin for WavesPlugin {
             FixedUpdate,
             despawn_assaults.in_set(LevelSystemSet::PostTick),
         )
-        .add_event::<WaveStartedEvent>();
+        .add_event::<WaveStartedEvent>()
+        .insert_resource(BossNights::default());
+    }
+}
+
+//every `every` nights, assaults get an extra wave with a boss in it
+#[derive(Resource)]
+pub struct BossNights {
+    pub every: u64,
+    //cycles through these in order, one per boss night
+    pub bosses: Vec<Arc<String>>,
+    //how long after the assault starts the boss shows up
+    pub delay: Duration,
+}
+
+impl Default for BossNights {
+    fn default() -> Self {
+        Self {
+            every: 5,
+            bosses: vec![Arc::new("slither_spine_boss".to_string())],
+            delay: Duration::from_secs(120),
+        }
+    }
+}
+
+impl BossNights {
+    pub fn get_boss(&self, day: u64) -> Option<Arc<String>> {
+        if self.every == 0 || day == 0 || day % self.every != 0 || self.bosses.is_empty() {
+            return None;
+        }
+        let milestone = (day / self.every - 1) as usize;
+        get_wrapping(&self.bosses, milestone).cloned()
     }
 }
 
@@ -46,6 +77,8 @@ pub struct Assault {
     //create by calling .compile(), sorted in descending time order (so you can pop)
     pub compiled: Vec<CompiledSpawn>,
     pub possible_spawns: Vec<SpawnableEntity>,
+    //only spawned by WaveSpawnType::Boss, never picked by strength
+    pub bosses: Vec<SpawnableEntity>,
     pub spawn_points: Vec<SpawnPoint>,
 }
 
@@ -63,6 +96,22 @@ impl Assault {
             .map(|(i, _)| i)
     }
 
+    //adds a wave that spawns a single boss
+    pub fn add_boss_wave(&mut self, boss: SpawnableEntity, start_time: Duration) {
+        self.bosses.push(boss);
+        self.waves.push(WaveInfo {
+            strength_mult: 1.0,
+            start_time,
+            visible: true,
+            spawned: false,
+            spawns: vec![WaveSpawn {
+                start_offset: Duration::ZERO,
+                spawn: WaveSpawnType::Boss(self.bosses.len() - 1),
+                strategy: SpawnStrategy::Burst { count: 1 },
+            }],
+        });
+    }
+
     pub fn compile(&self) -> Vec<CompiledSpawn> {
         let mut spawns = Vec::new();
         for wave in self.waves.iter() {
@@ -122,9 +171,13 @@ impl WaveSpawn {
                 };
                 dest.push(CompiledSpawn {
                     spawn_time: t,
-                    spawn_index,
+                    target: CompiledSpawnTarget::Strength(spawn_index),
                 })
             }
+            WaveSpawnType::Boss(boss_index) => dest.push(CompiledSpawn {
+                spawn_time: t,
+                target: CompiledSpawnTarget::Boss(*boss_index),
+            }),
         });
     }
 }
@@ -133,6 +186,8 @@ impl WaveSpawn {
 pub enum WaveSpawnType {
     Recursive(Box<WaveSpawn>),
     Strength(f32),
+    //index into Assault::bosses
+    Boss(usize),
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -161,7 +216,15 @@ impl SpawnStrategy {
 #[derive(Clone, Copy, Debug)]
 pub struct CompiledSpawn {
     spawn_time: Duration,
-    spawn_index: usize,
+    target: CompiledSpawnTarget,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum CompiledSpawnTarget {
+    //index into Assault::possible_spawns
+    Strength(usize),
+    //index into Assault::bosses
+    Boss(usize),
 }
 
 pub trait SpawnAction {
@@ -178,6 +241,7 @@ fn trigger_assault(
     calendar: Res<Calendar>,
     level: Res<Level>,
     anchor_query: Query<&GlobalTransform, (With<ActiveWorldAnchor>, Without<Assault>)>,
+    boss_nights: Res<BossNights>,
     mut commands: Commands,
 ) {
     if !calendar.in_night() {
@@ -255,9 +319,24 @@ fn trigger_assault(
                     );
                 }
             }
-            commands.entity(assault_entity).insert(ActiveAssault);
-            info!("Assault begins on night {}!", calendar.time.day);
         }
+        if anchor_query.is_empty() {
+            //nothing to attack, try again next frame
+            continue;
+        }
+        if let Some(boss) = boss_nights.get_boss(calendar.time.day) {
+            info!("Boss night! {} will arrive", boss);
+            assault.add_boss_wave(
+                SpawnableEntity {
+                    strength: 0.,
+                    action: Box::new(spawns::DefaultSpawn(boss)),
+                },
+                calendar.time.time + boss_nights.delay,
+            );
+            assault.compiled = assault.compile();
+        }
+        commands.entity(assault_entity).insert(ActiveAssault);
+        info!("Assault begins on night {}!", calendar.time.day);
     }
 }
 
@@ -338,7 +417,11 @@ fn spawn_wave(
             warn!("no spawnpoint!");
             return;
         };
-        if let Some(spawn) = assault.possible_spawns.get(spawn_info.spawn_index) {
+        let spawn = match spawn_info.target {
+            CompiledSpawnTarget::Strength(index) => assault.possible_spawns.get(index),
+            CompiledSpawnTarget::Boss(index) => assault.bosses.get(index),
+        };
+        if let Some(spawn) = spawn {
             info!("spawning entity with strength {}", spawn.strength);
             spawn.action.spawn(&mut commands, spawnpoint.location);
         }
@@ -8,6 +8,10 @@ use bevy::{
 };
 
 use interfaces::scheduling::GameState;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::level::Level;
 
 #[derive(Component, Reflect)]
 struct Sun {
@@ -70,6 +74,14 @@ impl Calendar {
     pub fn in_night(&self) -> bool {
         !self.in_day()
     }
+    //time since the current day or night started
+    pub fn time_in_half_day(&self) -> Duration {
+        if self.in_day() {
+            self.time.time
+        } else {
+            self.time.time - self.day_length
+        }
+    }
     //maps day_elapsed to [0,1] where [0, 0.5) is day and [0.5, 1] is night
     pub fn get_sun_progress(&self) -> f32 {
         let total_length = self.total_day_length().as_secs_f32();
@@ -176,6 +188,69 @@ pub struct SkipDays {
     end_time: Duration,
 }
 
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeatherKind {
+    #[default]
+    Calm,
+    Breezy,
+    Stormy,
+}
+
+impl WeatherKind {
+    //horizontal wind speed at ground level, in blocks per tick
+    pub fn wind_speed(self) -> f32 {
+        match self {
+            WeatherKind::Calm => 0.01,
+            WeatherKind::Breezy => 0.05,
+            WeatherKind::Stormy => 0.15,
+        }
+    }
+    //how much the wind speed varies in gusts, relative to wind_speed
+    pub fn gustiness(self) -> f32 {
+        match self {
+            WeatherKind::Calm => 0.1,
+            WeatherKind::Breezy => 0.3,
+            WeatherKind::Stormy => 0.6,
+        }
+    }
+}
+
+//only changes at the start of every day and night, rolled from the level seed so every player gets the same weather
+//the wind turns from the rolled direction as the day goes on, so clients work that out from their own calendar
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Weather {
+    pub kind: WeatherKind,
+    //direction the wind blows towards at the start of the day or night, on the xz plane
+    pub wind_direction: Vec2,
+    //radians per second the wind direction drifts
+    pub wind_turn_speed: f32,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            kind: WeatherKind::default(),
+            wind_direction: Vec2::X,
+            wind_turn_speed: 0.01,
+        }
+    }
+}
+
+impl Weather {
+    pub fn wind_direction_at(&self, calendar: &Calendar) -> Vec2 {
+        let turn = self.wind_turn_speed * calendar.time_in_half_day().as_secs_f32();
+        Vec2::from_angle(turn).rotate(self.wind_direction.normalize_or_zero())
+    }
+
+    pub fn wind_velocity(&self, calendar: &Calendar) -> Vec3 {
+        let dir = self.wind_direction_at(calendar) * self.kind.wind_speed();
+        Vec3::new(dir.x, 0.0, dir.y)
+    }
+}
+
+#[derive(Event)]
+pub struct WeatherChangedEvent(pub WeatherKind);
+
 #[derive(Event)]
 pub struct DayStartedEvent;
 #[derive(Event)]
@@ -195,12 +270,14 @@ impl Plugin for AtmospherePlugin {
             )
             .add_systems(
                 PreUpdate,
-                (speedup_time, update_calendar, update_sky).chain(),
+                (speedup_time, update_calendar, update_sky, roll_weather).chain(),
             )
             .add_event::<SkipDays>()
             .add_event::<DayStartedEvent>()
             .add_event::<NightStartedEvent>()
             .add_event::<SpeedupCalendarEvent>()
+            .add_event::<WeatherChangedEvent>()
+            .init_resource::<Weather>()
             .insert_resource(AmbientLight {
                 brightness: 100.,
                 ..default()
@@ -311,6 +388,35 @@ fn update_calendar(
     };
 }
 
+fn roll_weather(
+    mut day_reader: EventReader<DayStartedEvent>,
+    mut night_reader: EventReader<NightStartedEvent>,
+    mut weather: ResMut<Weather>,
+    mut writer: EventWriter<WeatherChangedEvent>,
+    calendar: Res<Calendar>,
+    level: Option<Res<Level>>,
+) {
+    if day_reader.read().count() + night_reader.read().count() == 0 {
+        return;
+    }
+    //days and nights get different weather
+    let half_day = calendar.time.day * 2 + calendar.in_night() as u64;
+    let seed = level.map_or(0, |level| level.seed);
+    let mut rng = StdRng::seed_from_u64(seed.wrapping_add(half_day));
+    //storms are rarer than breezes
+    let kind = match rng.gen_range(0..6) {
+        0..=2 => WeatherKind::Calm,
+        3..=4 => WeatherKind::Breezy,
+        _ => WeatherKind::Stormy,
+    };
+    weather.wind_direction = Vec2::from_angle(rng.gen_range(0.0..2.0 * PI));
+    if kind != weather.kind {
+        weather.kind = kind;
+        info!("weather changed to {:?}", kind);
+        writer.send(WeatherChangedEvent(kind));
+    }
+}
+
 fn speedup_time(mut reader: EventReader<SpeedupCalendarEvent>, mut speed: ResMut<CalendarSpeed>) {
     for SpeedupCalendarEvent(time) in reader.read() {
         info!(
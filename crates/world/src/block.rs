@@ -1,4 +1,4 @@
-use std::{ops::AddAssign, path::PathBuf, sync::Arc};
+use std::{any::TypeId, ops::AddAssign, path::PathBuf, sync::Arc};
 
 use super::chunk::{ChunkCoord, ChunkIdx, CHUNK_SIZE_I32};
 use bevy::{prelude::*, utils::HashMap};
@@ -90,6 +90,71 @@ pub trait BlockGenerator: Send + Sync {
     fn default(&self, block: Entity, commands: &mut Commands);
 }
 
+//blocks loaded from file with this get a separate entity for each placed block, so they can hold per-block state (like an inventory)
+//the loaded entity is kept as a template that placed blocks copy their components from
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, FromWorld)]
+pub struct DynamicBlock;
+
+//put on placed dynamic blocks, points to the template they were copied from
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockTemplate(pub Entity);
+
+//dynamic block that was taken out of the level and will be despawned after this tick
+#[derive(Component)]
+pub struct RemovedBlock;
+
+pub struct TemplateBlockGenerator {
+    pub template: Entity,
+}
+
+impl BlockGenerator for TemplateBlockGenerator {
+    fn generate(&self, block: Entity, position: BlockCoord, commands: &mut Commands) {
+        commands.entity(block).insert(position);
+        self.default(block, commands);
+    }
+
+    fn default(&self, block: Entity, commands: &mut Commands) {
+        let template = self.template;
+        commands.entity(block).insert(BlockTemplate(template));
+        commands.queue(move |world: &mut World| copy_block_template(world, template, block));
+    }
+}
+
+//copies every reflected component from the template, plus the block mesh (which is not reflected)
+fn copy_block_template(world: &mut World, template: Entity, block: Entity) {
+    let Ok(template_ref) = world.get_entity(template) else {
+        warn!("dynamic block template {:?} doesn't exist", template);
+        return;
+    };
+    let mesh = template_ref.get::<BlockMesh>().cloned();
+    let type_registry = world.resource::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let components = template_ref
+        .archetype()
+        .components()
+        .filter_map(|component_id| {
+            let type_id = world.components().get_info(component_id)?.type_id()?;
+            //hierarchy belongs to the template's scene, not the placed block
+            if type_id == TypeId::of::<Parent>() || type_id == TypeId::of::<Children>() {
+                return None;
+            }
+            let reflect_component = type_registry.get_type_data::<ReflectComponent>(type_id)?;
+            let value = reflect_component.reflect(template_ref)?.clone_value();
+            Some((reflect_component.clone(), value))
+        })
+        .collect::<Vec<_>>();
+    let Ok(mut block_mut) = world.get_entity_mut(block) else {
+        return;
+    };
+    for (reflect_component, value) in components {
+        reflect_component.insert(&mut block_mut, value.as_partial_reflect(), &type_registry);
+    }
+    if let Some(mesh) = mesh {
+        block_mut.insert(mesh);
+    }
+}
+
 #[derive(Component, Clone, PartialEq, Default, Reflect)]
 //controls visuals
 //loaded from file, converted to BlockMesh for use in game
@@ -253,10 +318,12 @@ impl BlockRegistry {
         self.basic_entities.push(entity);
         self.id_map.insert(name, id);
     }
-    pub fn add_dynamic(&mut self, name: BlockName, generator: Arc<dyn BlockGenerator>) {
+    pub fn add_dynamic(&mut self, name: BlockName, generator: Arc<dyn BlockGenerator>) -> BlockId {
+        info!("added dynamic block {:?}", name);
         let id = BlockId(Id::Dynamic(self.dynamic_generators.len() as u32));
         self.dynamic_generators.push(generator);
         self.id_map.insert(name, id);
+        id
     }
     pub fn get_basic(&self, name: &BlockName) -> Option<Entity> {
         let id = self.id_map.get(name)?;
@@ -326,7 +393,11 @@ impl BlockRegistry {
         match b {
             BlockType::Filled(entity) => match id_query.get(entity) {
                 Ok(BlockId(Id::Empty)) | Ok(BlockId(Id::Basic(_))) | Err(_) => {}
-                Ok(BlockId(Id::Dynamic(_))) => commands.entity(entity).despawn_recursive(),
+                Ok(BlockId(Id::Dynamic(_))) => {
+                    if let Some(mut ec) = commands.get_entity(entity) {
+                        ec.insert(RemovedBlock);
+                    }
+                }
             },
             BlockType::Empty => {}
         }
@@ -444,22 +444,35 @@ impl GeneratingChunk {
 
     pub fn to_array_chunk(&self, registry: &BlockRegistry, commands: &mut Commands) -> ArrayChunk {
         let mut mapped_palette = Vec::with_capacity(self.blocks.palette.len());
+        let mut has_dynamic = false;
         for (key, val, r) in self.blocks.palette.iter() {
             let block = match val {
-                BlockId(Id::Empty) => BlockType::Empty,
-                id @ BlockId(Id::Basic(_)) | id @ BlockId(Id::Dynamic(_)) => {
-                    match registry.get_entity(*id, commands) {
-                        Some(entity) => BlockType::Filled(entity),
-                        None => BlockType::Empty,
-                    }
-                }
+                //filled in per position below
+                BlockId(Id::Empty) | BlockId(Id::Dynamic(_)) => BlockType::Empty,
+                id @ BlockId(Id::Basic(_)) => match registry.get_entity(*id, commands) {
+                    Some(entity) => BlockType::Filled(entity),
+                    None => BlockType::Empty,
+                },
             };
+            has_dynamic |= matches!(val, BlockId(Id::Dynamic(_)));
             mapped_palette.push((*key, block, *r));
         }
-        self.with_storage(Box::new(BlockPalette {
+        let mut chunk = self.with_storage(Box::new(BlockPalette {
             data: self.blocks.data,
             palette: mapped_palette,
-        }))
+        }));
+        if has_dynamic {
+            //each dynamic block gets its own entity, so it can't share a palette entry
+            let origin = BlockCoord::from(self.position);
+            for idx in 0..BLOCKS_PER_CHUNK {
+                let id = self[idx];
+                if matches!(id, BlockId(Id::Dynamic(_))) {
+                    let position = origin + BlockCoord::from(ChunkIdx::from_usize(idx));
+                    chunk.set_block(idx, registry.generate_block_type(id, position, commands));
+                }
+            }
+        }
+        chunk
     }
 }
 
@@ -591,9 +604,12 @@ impl ChunkSaveFormat {
     ) -> ArrayChunk {
         let mut curr_idx = 0;
         let mut chunk = ArrayChunk::new(self.position, chunk_entity);
+        let origin = BlockCoord::from(self.position);
         for (block, length) in self.data.into_iter() {
             for idx in curr_idx..curr_idx + length as usize {
-                chunk.set_block(idx, registry.get_block_type(block, commands));
+                //dynamic blocks need to know where they are
+                let position = origin + BlockCoord::from(ChunkIdx::from_usize(idx));
+                chunk.set_block(idx, registry.generate_block_type(block, position, commands));
             }
             curr_idx += length as usize;
         }
@@ -1,6 +1,7 @@
 use bevy::{prelude::*, utils::HashMap};
 
 use crate::{
+    block::{BlockTemplate, BlockType},
     chunk::{ChunkCoord, ChunkType, LODChunk, LODChunkType},
     level::Level,
 };
@@ -58,6 +59,7 @@ pub fn do_loading(
     mut timer: ResMut<ChunkLoadingTimer>,
     time: Res<Time>,
     save_query: Query<&crate::chunk::NeedsSaving>,
+    dynamic_query: Query<(), With<BlockTemplate>>,
     network_type: Res<State<NetworkType>>,
 ) {
     let _my_span = info_span!("do_loading", name = "do_loading").entered();
@@ -118,7 +120,19 @@ pub fn do_loading(
         }
     }
     for (coord, entity) in to_unload {
-        if level.remove_chunk(coord).is_some() {
+        if let Some((_, chunk_type)) = level.remove_chunk(coord) {
+            //dynamic blocks have their own entities that aren't children of the chunk
+            //they aren't broken, so they don't get a RemovedBlock
+            if let ChunkType::Full(chunk) = chunk_type {
+                for (_, block, refs) in chunk.blocks.palette.iter() {
+                    if let BlockType::Filled(block_entity) = block
+                        && *refs > 0
+                        && dynamic_query.contains(*block_entity)
+                    {
+                        commands.entity(*block_entity).despawn_recursive();
+                    }
+                }
+            }
             despawn_writer.send(DespawnChunkEvent(entity));
         }
     }
@@ -661,4 +661,51 @@ impl LevelData {
         }
         None
     }
+
+    //flood fills the blocks connected to start and returns them if nothing is holding them up.
+    //reaching an anchor block, an unloaded chunk, a block in known_supported, or more than max_blocks
+    //counts as being connected to the ground, and the filled blocks are added to known_supported
+    pub fn find_unsupported_group(
+        &self,
+        start: BlockCoord,
+        max_blocks: usize,
+        known_supported: &mut HashSet<BlockCoord>,
+        mut is_anchor: impl FnMut(Entity) -> bool,
+    ) -> Option<Vec<(BlockCoord, Entity)>> {
+        let Some(BlockType::Filled(entity)) = self.get_block(start) else {
+            return None;
+        };
+        if known_supported.contains(&start) || is_anchor(entity) {
+            return None;
+        }
+        let mut group = vec![(start, entity)];
+        let mut in_group = HashSet::new();
+        in_group.insert(start);
+        let mut next = 0;
+        let mut supported = false;
+        while next < group.len() && !supported {
+            let (coord, _) = group[next];
+            next += 1;
+            for neighbor in Direction::iter().map(|dir| coord.offset(dir)) {
+                match self.get_block(neighbor) {
+                    None => supported = true,
+                    Some(BlockType::Empty) => {}
+                    Some(BlockType::Filled(entity)) => {
+                        if known_supported.contains(&neighbor) || is_anchor(entity) {
+                            supported = true;
+                        } else if in_group.insert(neighbor) {
+                            group.push((neighbor, entity));
+                        }
+                    }
+                }
+            }
+            supported |= group.len() > max_blocks;
+        }
+        if supported {
+            known_supported.extend(in_group);
+            None
+        } else {
+            Some(group)
+        }
+    }
 }
@@ -27,6 +27,7 @@ pub struct LevelPlugin;
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Update, check_chunk_boundary)
+            .add_systems(FixedPostUpdate, despawn_removed_blocks)
             .add_plugins((
                 effects::EffectsPlugin,
                 events::WorldEventsPlugin,
@@ -44,6 +45,7 @@ impl Plugin for LevelPlugin {
             .register_type::<[std::path::PathBuf; 2]>()
             .register_type::<BlockName>()
             .register_type::<UsableBlock>()
+            .register_type::<DynamicBlock>()
             .register_type::<BlockCoord>()
             .register_type::<NamedBlockMesh>()
             .register_type::<NamedBlockMeshShape>();
@@ -72,6 +74,13 @@ pub struct ChunkBoundaryCrossedEvent {
     pub new_position: ChunkCoord,
 }
 
+//removed dynamic blocks live until the end of the tick so systems reading BlockBrokenEvent can still query them
+fn despawn_removed_blocks(query: Query<Entity, With<RemovedBlock>>, mut commands: Commands) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 fn check_chunk_boundary(
     mut notifiers: Query<(Entity, &mut ChunkBoundaryNotifier, &GlobalTransform)>,
     mut writer: EventWriter<ChunkBoundaryCrossedEvent>,
@@ -161,3 +161,74 @@ fn test_create_fat_palette() {
 
     app.update();
 }
+
+//a pillar standing on the chunk below (which isn't loaded, so it counts as ground) with an arm off the top.
+//the pillar is missing the block at broken_y
+fn pillar_level(broken_y: i32, arm_block: Entity) -> crate::level::LevelData {
+    let level = crate::level::LevelData::new("integrity", 0);
+    let stone = Entity::from_raw(1);
+    let mut chunk = ArrayChunk::new(ChunkCoord::new(0, 0, 0), Entity::PLACEHOLDER);
+    let mut set = |coord: BlockCoord, block: Entity| {
+        ChunkTrait::set_block(
+            &mut chunk,
+            ChunkIdx::from(coord).into(),
+            BlockType::Filled(block),
+        )
+    };
+    for y in (0..5).filter(|y| *y != broken_y) {
+        set(BlockCoord::new(5, y, 5), stone);
+    }
+    for x in 6..9 {
+        set(BlockCoord::new(x, 4, 5), stone);
+    }
+    set(BlockCoord::new(8, 3, 5), arm_block);
+    level.add_chunk(chunk.position, ChunkType::Full(chunk));
+    level
+}
+
+#[test]
+fn test_find_unsupported_group() {
+    let level = pillar_level(2, Entity::from_raw(1));
+    let mut supported = bevy::utils::hashbrown::HashSet::new();
+    //the bottom of the pillar is still on the ground
+    assert!(level
+        .find_unsupported_group(BlockCoord::new(5, 1, 5), 64, &mut supported, |_| false)
+        .is_none());
+    assert!(supported.contains(&BlockCoord::new(5, 0, 5)));
+    //the top of the pillar and the arm come loose together
+    let group = level
+        .find_unsupported_group(BlockCoord::new(5, 3, 5), 64, &mut supported, |_| false)
+        .unwrap();
+    assert_eq!(group.len(), 6);
+    assert!(group.contains(&(BlockCoord::new(8, 3, 5), Entity::from_raw(1))));
+    //too big to check is treated as supported
+    assert!(level
+        .find_unsupported_group(BlockCoord::new(5, 3, 5), 4, &mut Default::default(), |_| {
+            false
+        })
+        .is_none());
+}
+
+#[test]
+fn test_anchor_supports_group() {
+    let anchor = Entity::from_raw(2);
+    let level = pillar_level(2, anchor);
+    assert!(level
+        .find_unsupported_group(
+            BlockCoord::new(5, 3, 5),
+            64,
+            &mut Default::default(),
+            |entity| entity == anchor
+        )
+        .is_none());
+    //an intact pillar holds everything up
+    let level = pillar_level(-1, anchor);
+    assert!(level
+        .find_unsupported_group(
+            BlockCoord::new(7, 4, 5),
+            64,
+            &mut Default::default(),
+            |_| { false }
+        )
+        .is_none());
+}
@@ -25,6 +25,7 @@ pub const FUNKY: usize = 2;
 pub type UsedBiomeMap = BiomeMap<{ TEMP }, { HUMID }, { FUNKY }>;
 
 pub struct Biome {
+    pub name: &'static str,
     pub topsoil: BlockId,
     pub midsoil: BlockId,
     pub soil_depth: u8, //must be less than CHUNK_SIZE
@@ -107,6 +108,7 @@ impl UsedBiomeMap {
         };
 
         let meadow = Biome {
+            name: "meadow",
             topsoil: registry.get_id(&BlockName::core("grass")),
             midsoil: registry.get_id(&BlockName::core("dirt")),
             soil_depth: 4,
@@ -129,34 +131,60 @@ impl UsedBiomeMap {
                         }),
                         rolls_per_chunk: 100,
                     },
+                    BiomeStructure {
+                        gen: Box::new(FauanaGenerator {
+                            to_spawn: registry.get_id(&BlockName::core("supply_chest")),
+                            spawn_on: registry.get_id(&BlockName::core("grass")),
+                        }),
+                        rolls_per_chunk: 1,
+                    },
                 ],
             }),
         };
         let desert = Biome {
+            name: "desert",
             topsoil: registry.get_id(&BlockName::core("sand")),
             midsoil: registry.get_id(&BlockName::core("sand")),
             soil_depth: 15,
             fallback_generator: Some(BiomeStructureGenerator {
-                structures: vec![BiomeStructure {
-                    gen: get_cactus(
-                        get_next_seed(&mut seed),
-                        Range { start: 3, end: 8 },
-                        0.5,
-                        2,
-                        4,
-                        registry,
-                    ),
-                    rolls_per_chunk: 5,
-                }],
+                structures: vec![
+                    BiomeStructure {
+                        gen: get_cactus(
+                            get_next_seed(&mut seed),
+                            Range { start: 3, end: 8 },
+                            0.5,
+                            2,
+                            4,
+                            registry,
+                        ),
+                        rolls_per_chunk: 5,
+                    },
+                    BiomeStructure {
+                        gen: Box::new(FauanaGenerator {
+                            to_spawn: registry.get_id(&BlockName::core("supply_chest")),
+                            spawn_on: registry.get_id(&BlockName::core("sand")),
+                        }),
+                        rolls_per_chunk: 1,
+                    },
+                    BiomeStructure {
+                        gen: Box::new(FauanaGenerator {
+                            to_spawn: registry.get_id(&BlockName::core("updraft_vent")),
+                            spawn_on: registry.get_id(&BlockName::core("sand")),
+                        }),
+                        rolls_per_chunk: 1,
+                    },
+                ],
             }),
         };
         let rocks = Biome {
+            name: "rocks",
             topsoil: registry.get_id(&BlockName::core("stone")),
             midsoil: registry.get_id(&BlockName::core("stone")),
             soil_depth: 0,
             fallback_generator: None,
         };
         let snowy_mountains = Biome {
+            name: "snowy_mountains",
             topsoil: registry.get_id(&BlockName::core("snow_sheet")),
             midsoil: registry.get_id(&BlockName::core("snow")),
             soil_depth: 2,
@@ -19,10 +19,7 @@ pub fn shape_chunk<
     block_id: BlockId,
 ) -> Heightmap<CHUNK_SIZE> {
     let _my_span = info_span!("shape_chunk", name = "shape_chunk").entered();
-    let heightmap_noise = &settings.heightmap_noise;
     let density_noise = &settings.density_noise;
-    let landmass_noise = &settings.landmass_noise;
-    let squish_noise = &settings.squish_noise;
 
     const LERP_DISTANCE: u8 = 4;
     const SAMPLE_INTERVAL: usize = (CHUNK_SIZE_U8 / LERP_DISTANCE) as usize;
@@ -48,9 +45,7 @@ pub fn shape_chunk<
     for x in 0..CHUNK_SIZE_U8 {
         for z in 0..CHUNK_SIZE_U8 {
             let column_pos = chunk.get_block_pos(ChunkIdx::new(x, 0, z));
-            let squish = squish_noise.get_noise2d(column_pos.x, column_pos.z);
-            let height = squish * heightmap_noise.get_noise2d(column_pos.x, column_pos.z)
-                + landmass_noise.get_noise2d(column_pos.x, column_pos.z);
+            let (squish, height) = settings.sample_column(column_pos.x, column_pos.z);
             heightmap.0[x as usize][z as usize] = settings.lower_density.x + height;
             let density_map = ClampedSpline::new([
                 Vec2::new(settings.lower_density.x + height, settings.lower_density.y),
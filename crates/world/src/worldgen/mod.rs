@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 use bracket_noise::prelude::*;
 
 use crate::{level::Level, BlockId, BlockName, BlockResources, LevelLoadState, LevelSystemSet};
@@ -73,6 +73,23 @@ pub struct DecorationSettings {
     pub ores: Vec<OreGenerator>,
 }
 
+//looks up which biome a position is in after the world has been generated
+#[derive(SystemParam)]
+pub struct BiomeSampler<'w> {
+    shaper: Option<Res<'w, UsedShaperResources>>,
+    decoration: Option<Res<'w, DecorationResources>>,
+}
+
+impl BiomeSampler<'_> {
+    pub fn biome_name_at(&self, pos: Vec3) -> Option<&'static str> {
+        let (Some(shaper), Some(decoration)) = (&self.shaper, &self.decoration) else {
+            return None;
+        };
+        let height = shaper.0.target_height(pos.x, pos.z);
+        Some(decoration.0.biomes.sample(height, pos).name)
+    }
+}
+
 fn create_shaper_settings(mut commands: Commands, level: Res<Level>) {
     let mut seed = level.seed ^ 0xABDFACDFAEDFA0DF;
     let settings = ShaperSettings {
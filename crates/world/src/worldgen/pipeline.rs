@@ -103,6 +103,22 @@ pub struct ShaperSettings<
     pub lower_density: Vec2,
 }
 
+impl<const NOISE: usize, const HEIGHTMAP: usize, const LANDMASS: usize, const SQUISH: usize>
+    ShaperSettings<NOISE, HEIGHTMAP, LANDMASS, SQUISH>
+{
+    //returns (squish, height) for the column
+    pub fn sample_column(&self, x: f32, z: f32) -> (f32, f32) {
+        let squish = self.squish_noise.get_noise2d(x, z);
+        let height =
+            squish * self.heightmap_noise.get_noise2d(x, z) + self.landmass_noise.get_noise2d(x, z);
+        (squish, height)
+    }
+    //the value stored in the heightmap for the column, used to pick biomes
+    pub fn target_height(&self, x: f32, z: f32) -> f32 {
+        self.lower_density.x + self.sample_column(x, z).1
+    }
+}
+
 pub struct OreGenerator {
     pub ore_block: BlockId,
     pub can_replace: Vec<BlockId>,